@@ -0,0 +1,544 @@
+use anyhow::{Context, Result};
+use ethers::signers::LocalWallet;
+use ethers::types::Address;
+use ethers::utils::to_checksum;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::ethereum::{
+    create_wallet, create_wallet_from_mnemonic, parse_chain, parse_price_sources, ChainlinkFeeds,
+    CoinGecko, PriceOracles, PriceSource, TokenDenylist, WeiRounding, DEFAULT_COINGECKO_BASE_URL,
+    DEFAULT_PRICE_SOURCES, DEFAULT_USER_AGENT,
+};
+use crate::types::ResponseFormat;
+
+/// Default BIP-44 path for the first Ethereum account
+const DEFAULT_DERIVATION_PATH: &str = "m/44'/60'/0'/0/0";
+
+/// The burn address, which holds ETH and most tokens, so swap gas estimates from it rarely revert
+const DEFAULT_SIMULATION_FROM: &str = "0x000000000000000000000000000000000000dEaD";
+
+/// Server configuration loaded from environment variables
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Swaps whose price impact exceeds this many basis points are rejected
+    pub max_price_impact_bps: u32,
+    /// Swaps whose price impact exceeds this many basis points need `confirm_high_impact`
+    pub confirm_impact_bps: u32,
+    /// Longest swap path allowed, in hops (pools traversed)
+    pub max_hops: usize,
+    /// Swap slippage below this many basis points is raised to it
+    pub min_slippage_bps: u32,
+    /// Swap slippage above this many basis points is flagged as dangerous
+    pub slippage_warn_bps: u32,
+    /// Swap gas estimates above this are flagged as abnormally expensive (0 = no limit)
+    pub max_gas_estimate: u64,
+    /// Wallet `swap_tokens` estimates gas from when the call omits `wallet_address`
+    pub simulation_from: Address,
+    /// Uniswap prices from pools worth less than this many USD are rejected (0 = accept any pool)
+    pub min_pool_liquidity_usd: Decimal,
+    /// Rounding of swap input amounts to whole wei. Truncation (the default) never spends more than requested
+    pub wei_rounding: WeiRounding,
+    /// Decimals used instead of the token's own `decimals()`, for tokens that misreport or revert it
+    pub token_decimals_overrides: HashMap<Address, u8>,
+    /// Directory token metadata is persisted to across restarts; in-memory only when unset
+    pub cache_dir: Option<String>,
+    /// Chainlink USD feeds by token: the built-in majors plus any from `CHAINLINK_FEEDS_PATH`
+    pub chainlink_feeds: ChainlinkFeeds,
+    /// Order `auto` pricing tries sources in, until one prices the token
+    pub price_sources: Vec<PriceSource>,
+    /// Opt-in switch for trade execution; also requires a configured wallet
+    pub enable_trading: bool,
+    /// Answer tool calls with canned mock data instead of contacting an RPC node
+    pub offline_mode: bool,
+    /// Most client requests handled at once; 1 processes them strictly in order
+    pub max_inflight_requests: usize,
+    /// Tool text output longer than this is truncated (0 = unlimited)
+    pub max_result_chars: usize,
+    /// Content blocks tool results carry, unless a call asks for another `format`
+    pub response_format: ResponseFormat,
+    /// Log a warning when a client reuses a request id within the session
+    pub warn_duplicate_ids: bool,
+    /// Log every outbound JSON-RPC call at trace level
+    pub log_rpc_calls: bool,
+    /// Redact addresses, amounts and calldata from logged RPC calls
+    pub log_rpc_redact: bool,
+    /// Most JSON-RPC calls in flight at once across all tools (0 = unlimited)
+    pub rpc_max_in_flight: usize,
+    /// Most JSON-RPC calls started per second (0 = unlimited)
+    pub rpc_rate_limit: u32,
+    /// How often to poll the RPC for new blocks and transaction receipts, in milliseconds
+    pub rpc_poll_interval_ms: u64,
+    /// Rate-limit responses from the RPC within `rpc_busy_window_secs` that make the server
+    /// reject tool calls as busy (0 = never)
+    pub rpc_busy_threshold: u32,
+    pub rpc_busy_window_secs: u64,
+    /// How long tool calls are rejected once the RPC is rate limiting
+    pub rpc_busy_cooldown_secs: u64,
+    /// How often the RPC endpoint's chain id is re-checked against the startup one (0 = never)
+    pub chain_check_interval_secs: u64,
+    /// Connect + request timeout for CoinGecko and other external HTTP APIs
+    pub coingecko_timeout_ms: u64,
+    /// CoinGecko API root, for deployments that reach it through a proxy or caching mirror
+    pub coingecko_base_url: String,
+    /// Optional proxy for outbound HTTPS requests to external APIs
+    pub https_proxy: Option<String>,
+    /// User-Agent sent to the RPC endpoint and external APIs
+    pub http_user_agent: String,
+    /// API key for the 0x Swap API, used to cross-check swap quotes
+    pub zerox_api_key: Option<String>,
+    /// API key for the Etherscan gas oracle
+    pub etherscan_api_key: Option<String>,
+    /// API key for the Blocknative gas price API
+    pub blocknative_api_key: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            max_price_impact_bps: 1500, // 15%
+            confirm_impact_bps: 300,    // 3%
+            max_hops: 3,
+            min_slippage_bps: 10,   // 0.1%
+            slippage_warn_bps: 300, // 3%
+            max_gas_estimate: 1_000_000,
+            simulation_from: DEFAULT_SIMULATION_FROM.parse().unwrap(),
+            min_pool_liquidity_usd: Decimal::from(10_000),
+            wei_rounding: WeiRounding::Truncate,
+            token_decimals_overrides: HashMap::new(),
+            cache_dir: None,
+            chainlink_feeds: ChainlinkFeeds::mainnet(),
+            price_sources: DEFAULT_PRICE_SOURCES.to_vec(),
+            enable_trading: false,
+            offline_mode: false,
+            max_inflight_requests: 8,
+            max_result_chars: 50_000,
+            response_format: ResponseFormat::Both,
+            warn_duplicate_ids: true,
+            log_rpc_calls: false,
+            log_rpc_redact: true,
+            rpc_max_in_flight: 32,
+            rpc_rate_limit: 0,
+            rpc_poll_interval_ms: 500,
+            rpc_busy_threshold: 5,
+            rpc_busy_window_secs: 10,
+            rpc_busy_cooldown_secs: 30,
+            chain_check_interval_secs: 60,
+            coingecko_timeout_ms: 10_000,
+            coingecko_base_url: DEFAULT_COINGECKO_BASE_URL.to_string(),
+            https_proxy: None,
+            http_user_agent: DEFAULT_USER_AGENT.to_string(),
+            zerox_api_key: None,
+            etherscan_api_key: None,
+            blocknative_api_key: None,
+        }
+    }
+}
+
+impl Config {
+    /// Load configuration, falling back to defaults for unset variables
+    pub fn from_env() -> Result<Self> {
+        let defaults = Self::default();
+
+        Ok(Self {
+            max_price_impact_bps: env_or("MAX_PRICE_IMPACT_BPS", defaults.max_price_impact_bps)?,
+            confirm_impact_bps: env_or("CONFIRM_IMPACT_BPS", defaults.confirm_impact_bps)?,
+            max_hops: env_or("MAX_HOPS", defaults.max_hops)?,
+            min_slippage_bps: env_or("MIN_SLIPPAGE_BPS", defaults.min_slippage_bps)?,
+            slippage_warn_bps: env_or("SLIPPAGE_WARN_BPS", defaults.slippage_warn_bps)?,
+            max_gas_estimate: env_or("MAX_GAS_ESTIMATE", defaults.max_gas_estimate)?,
+            simulation_from: env_or("SIMULATION_FROM", defaults.simulation_from)?,
+            min_pool_liquidity_usd: env_or(
+                "MIN_POOL_LIQUIDITY_USD",
+                defaults.min_pool_liquidity_usd,
+            )?,
+            wei_rounding: env_or("WEI_ROUNDING", defaults.wei_rounding)?,
+            token_decimals_overrides: match env_opt("TOKEN_DECIMALS_OVERRIDES") {
+                Some(overrides) => parse_decimals_overrides(&overrides)
+                    .context("Invalid TOKEN_DECIMALS_OVERRIDES")?,
+                None => defaults.token_decimals_overrides,
+            },
+            cache_dir: env_opt("CACHE_DIR"),
+            chainlink_feeds: match env_opt("CHAINLINK_FEEDS_PATH") {
+                Some(path) => {
+                    let mut feeds = defaults.chainlink_feeds;
+                    feeds.extend(load_chainlink_feeds(&path)?);
+                    feeds
+                }
+                None => defaults.chainlink_feeds,
+            },
+            price_sources: match env_opt("PRICE_SOURCES") {
+                Some(sources) => parse_price_sources(&sources).context("Invalid PRICE_SOURCES")?,
+                None => defaults.price_sources,
+            },
+            enable_trading: env_or("ENABLE_TRADING", defaults.enable_trading)?,
+            offline_mode: env_or("OFFLINE_MODE", defaults.offline_mode)?,
+            max_inflight_requests: env_or("MAX_INFLIGHT_REQUESTS", defaults.max_inflight_requests)?,
+            max_result_chars: env_or("MAX_RESULT_CHARS", defaults.max_result_chars)?,
+            response_format: env_or("RESPONSE_FORMAT", defaults.response_format)?,
+            warn_duplicate_ids: env_or("WARN_DUPLICATE_IDS", defaults.warn_duplicate_ids)?,
+            log_rpc_calls: env_or("LOG_RPC_CALLS", defaults.log_rpc_calls)?,
+            log_rpc_redact: env_or("LOG_RPC_REDACT", defaults.log_rpc_redact)?,
+            rpc_max_in_flight: env_or("RPC_MAX_IN_FLIGHT", defaults.rpc_max_in_flight)?,
+            rpc_rate_limit: env_or("RPC_RATE_LIMIT", defaults.rpc_rate_limit)?,
+            rpc_poll_interval_ms: env_or("RPC_POLL_INTERVAL_MS", defaults.rpc_poll_interval_ms)?,
+            rpc_busy_threshold: env_or("RPC_BUSY_THRESHOLD", defaults.rpc_busy_threshold)?,
+            rpc_busy_window_secs: env_or("RPC_BUSY_WINDOW_SECS", defaults.rpc_busy_window_secs)?,
+            rpc_busy_cooldown_secs: env_or(
+                "RPC_BUSY_COOLDOWN_SECS",
+                defaults.rpc_busy_cooldown_secs,
+            )?,
+            chain_check_interval_secs: env_or(
+                "CHAIN_CHECK_INTERVAL_SECS",
+                defaults.chain_check_interval_secs,
+            )?,
+            coingecko_timeout_ms: env_or("COINGECKO_TIMEOUT_MS", defaults.coingecko_timeout_ms)?,
+            coingecko_base_url: match env_opt("COINGECKO_BASE_URL") {
+                Some(url) => parse_base_url(&url).context("Invalid COINGECKO_BASE_URL")?,
+                None => defaults.coingecko_base_url,
+            },
+            https_proxy: std::env::var("HTTPS_PROXY")
+                .or_else(|_| std::env::var("https_proxy"))
+                .ok()
+                .filter(|proxy| !proxy.trim().is_empty()),
+            http_user_agent: env_opt("HTTP_USER_AGENT").unwrap_or(defaults.http_user_agent),
+            zerox_api_key: env_opt("ZEROX_API_KEY"),
+            etherscan_api_key: env_opt("ETHERSCAN_API_KEY"),
+            blocknative_api_key: env_opt("BLOCKNATIVE_API_KEY"),
+        })
+    }
+
+    /// CoinGecko client sending requests through `http` to the configured base URL
+    pub fn coingecko(&self, http: &reqwest::Client) -> CoinGecko {
+        CoinGecko::new(http, &self.coingecko_base_url)
+    }
+
+    /// Price oracles as configured, with CoinGecko reached through `http`
+    pub fn price_oracles(&self, http: &reqwest::Client) -> PriceOracles<'_> {
+        PriceOracles {
+            coingecko: self.coingecko(http),
+            feeds: &self.chainlink_feeds,
+            sources: &self.price_sources,
+            min_liquidity_usd: self.min_pool_liquidity_usd,
+        }
+    }
+}
+
+/// Load the signing wallet from `PRIVATE_KEY` or `MNEMONIC`, if either is set.
+/// Kept out of `Config` so secrets never end up in its `Debug` output.
+pub fn load_wallet() -> Result<Option<LocalWallet>> {
+    let private_key = std::env::var("PRIVATE_KEY")
+        .ok()
+        .filter(|key| !key.trim().is_empty());
+    let mnemonic = std::env::var("MNEMONIC")
+        .ok()
+        .filter(|phrase| !phrase.trim().is_empty());
+
+    match (private_key, mnemonic) {
+        (Some(_), Some(_)) => anyhow::bail!("Set only one of PRIVATE_KEY or MNEMONIC"),
+        (Some(key), None) => Ok(Some(create_wallet(key.trim())?)),
+        (None, Some(phrase)) => {
+            let path = std::env::var("MNEMONIC_DERIVATION_PATH")
+                .unwrap_or_else(|_| DEFAULT_DERIVATION_PATH.to_string());
+            Ok(Some(create_wallet_from_mnemonic(phrase.trim(), &path)?))
+        }
+        (None, None) => Ok(None),
+    }
+}
+
+/// Load the wallets listed in the file at `WATCH_WALLETS_PATH`, one address per line.
+/// Blank lines and `#` comments are skipped; invalid or non-checksummed entries are logged and dropped.
+pub fn load_watched_wallets() -> Result<Vec<Address>> {
+    let path = match env_opt("WATCH_WALLETS_PATH") {
+        Some(path) => path,
+        None => return Ok(Vec::new()),
+    };
+
+    let contents = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read WATCH_WALLETS_PATH {}", path))?;
+
+    Ok(parse_watched_wallets(&contents))
+}
+
+/// Load the key that signs tool results when `SIGN_RESPONSES=true`, from `RESPONSE_SIGNING_KEY`.
+/// A separate key from `PRIVATE_KEY`, so audit signatures never come from a funded wallet.
+pub fn load_response_signer() -> Result<Option<LocalWallet>> {
+    if !env_or("SIGN_RESPONSES", false)? {
+        return Ok(None);
+    }
+    let key = env_opt("RESPONSE_SIGNING_KEY")
+        .context("SIGN_RESPONSES is enabled but RESPONSE_SIGNING_KEY is not set")?;
+    create_wallet(key.trim())
+        .map(Some)
+        .context("Invalid RESPONSE_SIGNING_KEY")
+}
+
+/// Load the tokens listed in the file at `TOKEN_DENYLIST_PATH`, which every tool refuses to touch
+pub fn load_token_denylist() -> Result<TokenDenylist> {
+    let path = match env_opt("TOKEN_DENYLIST_PATH") {
+        Some(path) => path,
+        None => return Ok(TokenDenylist::default()),
+    };
+
+    let contents = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read TOKEN_DENYLIST_PATH {}", path))?;
+
+    Ok(TokenDenylist::parse(&contents))
+}
+
+/// Read the token -> Chainlink feed JSON object at `path`
+fn load_chainlink_feeds(path: &str) -> Result<ChainlinkFeeds> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read CHAINLINK_FEEDS_PATH {}", path))?;
+    ChainlinkFeeds::parse_json(&contents)
+        .with_context(|| format!("Invalid CHAINLINK_FEEDS_PATH {}", path))
+}
+
+/// Load extra headers for external API requests from `HTTP_HEADERS`, as `Name: value` pairs
+/// separated by `;`. Kept out of `Config` since values are often API keys; they are never sent
+/// to the RPC endpoint.
+pub fn load_http_headers() -> Result<HeaderMap> {
+    match env_opt("HTTP_HEADERS") {
+        Some(headers) => parse_http_headers(&headers).context("Invalid HTTP_HEADERS"),
+        None => Ok(HeaderMap::new()),
+    }
+}
+
+fn parse_http_headers(contents: &str) -> Result<HeaderMap> {
+    let mut headers = HeaderMap::new();
+    for entry in contents.split(';').map(str::trim) {
+        if entry.is_empty() {
+            continue;
+        }
+        let (name, value) = entry
+            .split_once(':')
+            .with_context(|| format!("Expected `Name: value`, got {}", entry))?;
+        let name = HeaderName::from_bytes(name.trim().as_bytes())
+            .with_context(|| format!("Invalid header name {}", name.trim()))?;
+        let mut value = HeaderValue::from_str(value.trim())
+            .with_context(|| format!("Invalid value for header {}", name))?;
+        // Keeps the value out of reqwest's Debug output
+        value.set_sensitive(true);
+        headers.insert(name, value);
+    }
+    Ok(headers)
+}
+
+/// An http(s) URL that API paths are appended to, without a trailing slash
+fn parse_base_url(url: &str) -> Result<String> {
+    let url = url.trim();
+    let parsed = reqwest::Url::parse(url).with_context(|| format!("{} is not a URL", url))?;
+    if !matches!(parsed.scheme(), "http" | "https") || !parsed.has_host() {
+        anyhow::bail!("{} is not an http(s) URL", url);
+    }
+    if parsed.query().is_some() || parsed.fragment().is_some() {
+        anyhow::bail!("{} must not have a query or fragment", url);
+    }
+    Ok(url.trim_end_matches('/').to_string())
+}
+
+/// Load RPC endpoints for chains other than `ETH_RPC_URL`'s from `RPC_URLS`, as `chain=url`
+/// pairs separated by `;`, where `chain` is a chain ID or name. Kept out of `Config` since URLs
+/// often embed API keys.
+pub fn load_rpc_urls() -> Result<HashMap<u64, String>> {
+    match env_opt("RPC_URLS") {
+        Some(urls) => parse_rpc_urls(&urls).context("Invalid RPC_URLS"),
+        None => Ok(HashMap::new()),
+    }
+}
+
+fn parse_rpc_urls(contents: &str) -> Result<HashMap<u64, String>> {
+    let mut urls = HashMap::new();
+    for entry in contents.split(';').map(str::trim) {
+        if entry.is_empty() {
+            continue;
+        }
+        let (chain, url) = entry
+            .split_once('=')
+            .with_context(|| format!("Expected `chain=url`, got {}", entry))?;
+        let chain_id = parse_chain(chain)?;
+        let url = url.trim();
+        reqwest::Url::parse(url)
+            .with_context(|| format!("Invalid URL for chain ID {}", chain_id))?;
+        if urls.insert(chain_id, url.to_string()).is_some() {
+            anyhow::bail!("Chain ID {} is listed more than once", chain_id);
+        }
+    }
+    Ok(urls)
+}
+
+/// Parse `address=decimals` pairs separated by `,`
+fn parse_decimals_overrides(contents: &str) -> Result<HashMap<Address, u8>> {
+    let mut overrides = HashMap::new();
+    for entry in contents.split(',').map(str::trim) {
+        if entry.is_empty() {
+            continue;
+        }
+        let (address, decimals) = entry
+            .split_once('=')
+            .with_context(|| format!("Expected `address=decimals`, got {}", entry))?;
+        let address = address
+            .trim()
+            .parse::<Address>()
+            .with_context(|| format!("Invalid token address {}", address.trim()))?;
+        let decimals = decimals
+            .trim()
+            .parse::<u8>()
+            .with_context(|| format!("Invalid decimals for {:?}: {}", address, decimals.trim()))?;
+        overrides.insert(address, decimals);
+    }
+    Ok(overrides)
+}
+
+fn parse_watched_wallets(contents: &str) -> Vec<Address> {
+    let mut wallets = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let entry = line.trim();
+        if entry.is_empty() || entry.starts_with('#') {
+            continue;
+        }
+
+        let address = match entry.parse::<Address>() {
+            Ok(address) => address,
+            Err(e) => {
+                tracing::warn!(
+                    "Skipping watched wallet on line {}: {} ({})",
+                    index + 1,
+                    entry,
+                    e
+                );
+                continue;
+            }
+        };
+        // Checksums catch typos that would otherwise silently watch the wrong address
+        if to_checksum(&address, None) != entry {
+            tracing::warn!(
+                "Skipping watched wallet on line {}: {} is not checksummed",
+                index + 1,
+                entry
+            );
+            continue;
+        }
+        if !wallets.contains(&address) {
+            wallets.push(address);
+        }
+    }
+    wallets
+}
+
+/// Read an optional environment variable, treating blank values as unset
+fn env_opt(key: &str) -> Option<String> {
+    std::env::var(key)
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+}
+
+/// Parse an environment variable, using `default` when it is unset
+fn env_or<T>(key: &str, default: T) -> Result<T>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    match std::env::var(key) {
+        Ok(value) => value
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid value for {}: {}", key, value)),
+        Err(_) => Ok(default),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_http_headers() {
+        let headers =
+            parse_http_headers("x-cg-demo-api-key: abc123; Accept:application/json;").unwrap();
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers["x-cg-demo-api-key"], "abc123");
+        assert_eq!(headers["accept"], "application/json");
+
+        assert!(parse_http_headers("no-colon").is_err());
+        assert!(parse_http_headers("bad name: value").is_err());
+    }
+
+    #[test]
+    fn test_parse_base_url() {
+        assert_eq!(
+            parse_base_url(" https://cg-proxy.internal/coingecko/ ").unwrap(),
+            "https://cg-proxy.internal/coingecko"
+        );
+        assert_eq!(
+            parse_base_url("http://127.0.0.1:8080").unwrap(),
+            "http://127.0.0.1:8080"
+        );
+        assert!(parse_base_url("api.coingecko.com").is_err());
+        assert!(parse_base_url("ftp://mirror.example").is_err());
+        assert!(parse_base_url("https://mirror.example/?key=1").is_err());
+    }
+
+    #[test]
+    fn test_parse_rpc_urls() {
+        let urls = parse_rpc_urls("10=https://opt.example/v2/key?a=b; base = https://base.example")
+            .unwrap();
+        assert_eq!(urls.len(), 2);
+        assert_eq!(urls[&10], "https://opt.example/v2/key?a=b");
+        assert_eq!(urls[&8453], "https://base.example");
+
+        assert!(parse_rpc_urls("https://opt.example").is_err());
+        assert!(parse_rpc_urls("nowhere=https://opt.example").is_err());
+        assert!(parse_rpc_urls("10=not a url").is_err());
+        assert!(parse_rpc_urls("10=https://a.example;optimism=https://b.example").is_err());
+    }
+
+    #[test]
+    fn test_parse_decimals_overrides() {
+        let overrides = parse_decimals_overrides(
+            "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48=6, 0x0000000000000000000000000000000000000001 = 9,",
+        )
+        .unwrap();
+        assert_eq!(overrides.len(), 2);
+        assert_eq!(
+            overrides[&"0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"
+                .parse::<Address>()
+                .unwrap()],
+            6
+        );
+        assert_eq!(overrides[&Address::from_low_u64_be(1)], 9);
+
+        assert!(parse_decimals_overrides("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48").is_err());
+        assert!(parse_decimals_overrides("not-an-address=6").is_err());
+        assert!(
+            parse_decimals_overrides("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48=256").is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_watched_wallets() {
+        let contents = "\
+# treasury
+0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045
+
+0xd8da6bf26964af9d7eed9e03e53415d37aa96045
+not-an-address
+0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045
+0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48
+";
+        let wallets = parse_watched_wallets(contents);
+        assert_eq!(
+            wallets,
+            vec![
+                "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+                    .parse::<Address>()
+                    .unwrap(),
+                "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"
+                    .parse::<Address>()
+                    .unwrap(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use ethers::prelude::*;
+
+// ERC20: balances and metadata, plus allowance and approval for swaps
+abigen!(
+    ERC20,
+    r#"[
+        function balanceOf(address account) external view returns (uint256)
+        function decimals() external view returns (uint8)
+        function symbol() external view returns (string)
+        function name() external view returns (string)
+        function approve(address spender, uint256 amount) external returns (bool)
+        function allowance(address owner, address spender) external view returns (uint256)
+    ]"#,
+);
+
+// ERC-4626 tokenized vault extension
+abigen!(
+    ERC4626,
+    r#"[
+        function asset() external view returns (address)
+        function convertToAssets(uint256 shares) external view returns (uint256)
+    ]"#,
+);
+
+// Uniswap V2 Router ABI
+abigen!(
+    UniswapV2Router,
+    r#"[
+        function swapExactTokensForTokens(uint amountIn, uint amountOutMin, address[] calldata path, address to, uint deadline) external returns (uint[] memory amounts)
+        function swapExactETHForTokens(uint amountOutMin, address[] calldata path, address to, uint deadline) external payable returns (uint[] memory amounts)
+        function swapExactTokensForETH(uint amountIn, uint amountOutMin, address[] calldata path, address to, uint deadline) external returns (uint[] memory amounts)
+        function getAmountsOut(uint amountIn, address[] calldata path) external view returns (uint[] memory amounts)
+        function WETH() external pure returns (address)
+        function factory() external pure returns (address)
+    ]"#,
+);
+
+// Uniswap V2 Pair ABI
+abigen!(
+    UniswapV2Pair,
+    r#"[
+        function getReserves() external view returns (uint112 reserve0, uint112 reserve1, uint32 blockTimestampLast)
+        function token0() external view returns (address)
+        function token1() external view returns (address)
+    ]"#,
+);
+
+// Uniswap V2 Factory ABI
+abigen!(
+    UniswapV2Factory,
+    r#"[
+        function getPair(address tokenA, address tokenB) external view returns (address pair)
+    ]"#,
+);
+
+// Uniswap V3 Factory ABI
+abigen!(
+    UniswapV3Factory,
+    r#"[
+        function getPool(address tokenA, address tokenB, uint24 fee) external view returns (address pool)
+    ]"#,
+);
+
+// Uniswap V3 Quoter ABI
+abigen!(
+    UniswapV3Quoter,
+    r#"[
+        function quoteExactInputSingle(address tokenIn, address tokenOut, uint24 fee, uint256 amountIn, uint160 sqrtPriceLimitX96) external returns (uint256 amountOut)
+    ]"#,
+);
+
+// Uniswap V3 SwapRouter ABI
+abigen!(
+    UniswapV3Router,
+    r#"[
+        struct ExactInputSingleParams { address tokenIn; address tokenOut; uint24 fee; address recipient; uint256 deadline; uint256 amountIn; uint256 amountOutMinimum; uint160 sqrtPriceLimitX96; }
+        function exactInputSingle(ExactInputSingleParams calldata params) external payable returns (uint256 amountOut)
+    ]"#,
+);
+
+// Uniswap V3 Pool oracle ABI
+abigen!(
+    UniswapV3Pool,
+    r#"[
+        function slot0() external view returns (uint160 sqrtPriceX96, int24 tick, uint16 observationIndex, uint16 observationCardinality, uint16 observationCardinalityNext, uint8 feeProtocol, bool unlocked)
+        function observe(uint32[] calldata secondsAgos) external view returns (int56[] memory tickCumulatives, uint160[] memory secondsPerLiquidityCumulativeX128s)
+        function observations(uint256 index) external view returns (uint32 blockTimestamp, int56 tickCumulative, uint160 secondsPerLiquidityCumulativeX128, bool initialized)
+        function token0() external view returns (address)
+        function token1() external view returns (address)
+    ]"#,
+);
+
+// Chainlink Price Feed ABI
+abigen!(
+    ChainlinkAggregator,
+    r#"[
+        function latestRoundData() external view returns (uint80 roundId, int256 answer, uint256 startedAt, uint256 updatedAt, uint80 answeredInRound)
+        function decimals() external view returns (uint8)
+    ]"#,
+);
+
+// OP-stack GasPriceOracle predeploy
+abigen!(
+    GasPriceOracle,
+    r#"[
+        function getL1Fee(bytes memory data) external view returns (uint256)
+    ]"#,
+);
@@ -0,0 +1,67 @@
+use anyhow::{Context, Result};
+use ethers::prelude::*;
+use rust_decimal::Decimal;
+
+use super::swap::wei_to_decimal;
+
+/// Chains served by the 0x Swap API
+const ZEROX_SUPPORTED_CHAINS: [u64; 8] = [1, 10, 56, 137, 8453, 42161, 43114, 59144];
+
+/// 0x represents native ETH with this sentinel rather than the zero address
+const ZEROX_NATIVE_TOKEN: &str = "0xEeeeeEeeeEeEeeEeEeEeeEEEeeeeEeeeeeeeEEeE";
+
+/// Fetch an indicative output amount from the 0x Swap API for the same pair and input
+pub async fn get_aggregator_quote(
+    http: &reqwest::Client,
+    api_key: &str,
+    chain_id: u64,
+    sell_token: Address,
+    buy_token: Address,
+    sell_amount: U256,
+    buy_decimals: u8,
+) -> Result<Decimal> {
+    if !ZEROX_SUPPORTED_CHAINS.contains(&chain_id) {
+        anyhow::bail!("0x does not support chain {}", chain_id);
+    }
+
+    let token_param = |token: Address| {
+        if token == Address::zero() {
+            ZEROX_NATIVE_TOKEN.to_string()
+        } else {
+            format!("{:?}", token)
+        }
+    };
+
+    let url = format!(
+        "https://api.0x.org/swap/permit2/price?chainId={}&sellToken={}&buyToken={}&sellAmount={}",
+        chain_id,
+        token_param(sell_token),
+        token_param(buy_token),
+        sell_amount
+    );
+
+    let data: serde_json::Value = http
+        .get(&url)
+        .header("0x-api-key", api_key)
+        .header("0x-version", "v2")
+        .send()
+        .await
+        .context("Failed to reach 0x API")?
+        .error_for_status()
+        .context("0x API returned an error")?
+        .json()
+        .await
+        .context("Invalid 0x API response")?;
+
+    if data.get("liquidityAvailable").and_then(|v| v.as_bool()) == Some(false) {
+        anyhow::bail!("0x reports no liquidity for this pair");
+    }
+
+    let buy_amount = data
+        .get("buyAmount")
+        .and_then(|v| v.as_str())
+        .context("0x response missing buyAmount")?;
+    let buy_amount = U256::from_dec_str(buy_amount).context("Invalid buyAmount from 0x")?;
+
+    wei_to_decimal(buy_amount, buy_decimals)
+}
@@ -0,0 +1,197 @@
+use anyhow::Result;
+use ethers::prelude::*;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Mutex, MutexGuard};
+
+/// Upper bound on pending alerts per server, so a runaway client can't grow state without limit
+pub const MAX_PRICE_ALERTS: usize = 100;
+
+/// Which side of the threshold triggers an alert
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertDirection {
+    /// Price at or above the threshold
+    Above,
+    /// Price at or below the threshold
+    Below,
+}
+
+impl AlertDirection {
+    pub fn is_met(self, price: Decimal, threshold: Decimal) -> bool {
+        match self {
+            AlertDirection::Above => price >= threshold,
+            AlertDirection::Below => price <= threshold,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Unknown alert direction {0} (expected above or below)")]
+pub struct ParseAlertDirectionError(String);
+
+impl FromStr for AlertDirection {
+    type Err = ParseAlertDirectionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "above" => Ok(AlertDirection::Above),
+            "below" => Ok(AlertDirection::Below),
+            _ => Err(ParseAlertDirectionError(s.to_string())),
+        }
+    }
+}
+
+/// A registered price condition on one token
+#[derive(Debug, Clone)]
+pub struct PriceAlert {
+    pub id: u64,
+    pub token: Address,
+    pub direction: AlertDirection,
+    pub threshold_usd: Decimal,
+}
+
+/// An alert whose condition held at the last check, with the price that triggered it
+#[derive(Debug, Clone)]
+pub struct TriggeredAlert {
+    pub alert: PriceAlert,
+    pub price_usd: Decimal,
+}
+
+#[derive(Debug, Default)]
+struct AlertState {
+    next_id: u64,
+    pending: Vec<PriceAlert>,
+}
+
+/// Session-scoped price alerts; each alert fires once and is then removed
+#[derive(Debug, Default)]
+pub struct PriceAlerts {
+    state: Mutex<AlertState>,
+}
+
+impl PriceAlerts {
+    /// Lock the alert list, carrying on past a panic in another tool call
+    fn state(&self) -> MutexGuard<'_, AlertState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register an alert, returning it with its assigned id
+    pub fn add(
+        &self,
+        token: Address,
+        direction: AlertDirection,
+        threshold_usd: Decimal,
+    ) -> Result<PriceAlert> {
+        if threshold_usd <= Decimal::ZERO {
+            anyhow::bail!("Alert threshold must be positive");
+        }
+
+        let mut state = self.state();
+        if state.pending.len() >= MAX_PRICE_ALERTS {
+            anyhow::bail!(
+                "Too many pending alerts (max {}); check or wait for some to trigger",
+                MAX_PRICE_ALERTS
+            );
+        }
+
+        state.next_id += 1;
+        let alert = PriceAlert {
+            id: state.next_id,
+            token,
+            direction,
+            threshold_usd,
+        };
+        state.pending.push(alert.clone());
+        Ok(alert)
+    }
+
+    /// Distinct tokens with pending alerts, in registration order
+    pub fn pending_tokens(&self) -> Vec<Address> {
+        let state = self.state();
+        let mut tokens = Vec::new();
+        for alert in &state.pending {
+            if !tokens.contains(&alert.token) {
+                tokens.push(alert.token);
+            }
+        }
+        tokens
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.state().pending.len()
+    }
+
+    /// Remove and return alerts whose condition holds at `prices`; unpriced tokens stay pending
+    pub fn take_triggered(&self, prices: &HashMap<Address, Decimal>) -> Vec<TriggeredAlert> {
+        let mut state = self.state();
+        let mut triggered = Vec::new();
+        state
+            .pending
+            .retain(|alert| match prices.get(&alert.token) {
+                Some(&price_usd) if alert.direction.is_met(price_usd, alert.threshold_usd) => {
+                    triggered.push(TriggeredAlert {
+                        alert: alert.clone(),
+                        price_usd,
+                    });
+                    false
+                }
+                _ => true,
+            });
+        triggered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alerts_trigger_once() {
+        let alerts = PriceAlerts::new();
+        let eth = Address::zero();
+        let usdc = Address::from_low_u64_be(1);
+
+        let above = alerts
+            .add(eth, AlertDirection::Above, Decimal::from(3000))
+            .unwrap();
+        alerts
+            .add(eth, AlertDirection::Below, Decimal::from(1500))
+            .unwrap();
+        alerts
+            .add(usdc, AlertDirection::Below, Decimal::from(1))
+            .unwrap();
+        assert_eq!(alerts.pending_tokens(), vec![eth, usdc]);
+        assert!(alerts
+            .add(eth, AlertDirection::Above, Decimal::ZERO)
+            .is_err());
+
+        // USDC unpriced: stays pending; ETH crosses only the upper threshold
+        let prices = HashMap::from([(eth, Decimal::from(3100))]);
+        let triggered = alerts.take_triggered(&prices);
+        assert_eq!(triggered.len(), 1);
+        assert_eq!(triggered[0].alert.id, above.id);
+        assert_eq!(triggered[0].price_usd, Decimal::from(3100));
+        assert_eq!(alerts.pending_count(), 2);
+
+        // Already reported, so the same price triggers nothing new
+        assert!(alerts.take_triggered(&prices).is_empty());
+    }
+
+    #[test]
+    fn test_alert_direction() {
+        assert_eq!(
+            "ABOVE".parse::<AlertDirection>().unwrap(),
+            AlertDirection::Above
+        );
+        assert!("sideways".parse::<AlertDirection>().is_err());
+        assert!(AlertDirection::Below.is_met(Decimal::from(10), Decimal::from(10)));
+        assert!(!AlertDirection::Above.is_met(Decimal::from(9), Decimal::from(10)));
+    }
+}
@@ -0,0 +1,170 @@
+use ethers::providers::RpcError;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// JSON-RPC error codes providers use for rate limiting: HTTP-style 429 and EIP-1474 "limit exceeded"
+const RATE_LIMIT_CODES: [i64; 2] = [429, -32005];
+
+/// How providers phrase rate limiting when the code alone doesn't say
+const RATE_LIMIT_MESSAGES: [&str; 4] = [
+    "too many requests",
+    "rate limit",
+    "rate-limit",
+    "compute units per second",
+];
+
+/// Whether an RPC transport error is the endpoint rate limiting us
+pub fn is_rate_limited<E: RpcError>(error: &E) -> bool {
+    let message = match error.as_error_response() {
+        Some(response) if RATE_LIMIT_CODES.contains(&response.code) => return true,
+        Some(response) => response.message.to_lowercase(),
+        // Non-JSON 429 bodies surface as decode errors quoting the response text
+        None => error.to_string().to_lowercase(),
+    };
+    RATE_LIMIT_MESSAGES.iter().any(|m| message.contains(m))
+}
+
+/// Circuit breaker that trips after repeated rate-limit responses from the RPC endpoint
+///
+/// Once `threshold` rate-limit responses arrive within `window`, the breaker stays open for
+/// `cooldown`, during which new tool calls are turned away instead of piling more load on the
+/// endpoint. A threshold of 0 never trips.
+#[derive(Debug)]
+pub struct Backpressure {
+    threshold: u32,
+    window: Duration,
+    cooldown: Duration,
+    state: Mutex<BreakerState>,
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    /// When recent rate-limit responses arrived, oldest first
+    hits: VecDeque<Instant>,
+    open_until: Option<Instant>,
+}
+
+impl Backpressure {
+    pub fn new(threshold: u32, window: Duration, cooldown: Duration) -> Self {
+        Self {
+            threshold,
+            window,
+            cooldown,
+            state: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    pub fn record_rate_limited(&self) {
+        self.record_rate_limited_at(Instant::now());
+    }
+
+    /// How long callers should wait before retrying, while the breaker is open
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.retry_after_at(Instant::now())
+    }
+
+    fn record_rate_limited_at(&self, now: Instant) {
+        if self.threshold == 0 {
+            return;
+        }
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        while state
+            .hits
+            .front()
+            .is_some_and(|hit| now.duration_since(*hit) > self.window)
+        {
+            state.hits.pop_front();
+        }
+        state.hits.push_back(now);
+
+        if state.hits.len() >= self.threshold as usize {
+            state.hits.clear();
+            state.open_until = Some(now + self.cooldown);
+            tracing::warn!(
+                "RPC endpoint rate limited {} calls within {:?}; rejecting tool calls for {:?}",
+                self.threshold,
+                self.window,
+                self.cooldown
+            );
+        }
+    }
+
+    fn retry_after_at(&self, now: Instant) -> Option<Duration> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state
+            .open_until
+            .and_then(|until| until.checked_duration_since(now))
+            .filter(|remaining| !remaining.is_zero())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::providers::{HttpClientError, JsonRpcError};
+
+    fn rpc_error(code: i64, message: &str) -> HttpClientError {
+        HttpClientError::JsonRpcError(JsonRpcError {
+            code,
+            message: message.to_string(),
+            data: None,
+        })
+    }
+
+    #[test]
+    fn test_is_rate_limited() {
+        assert!(is_rate_limited(&rpc_error(429, "slow down")));
+        assert!(is_rate_limited(&rpc_error(-32005, "limit exceeded")));
+        assert!(is_rate_limited(&rpc_error(
+            -32000,
+            "Your app has exceeded its compute units per second capacity"
+        )));
+        assert!(!is_rate_limited(&rpc_error(3, "execution reverted")));
+
+        let body = HttpClientError::SerdeJson {
+            err: serde_json::from_str::<serde_json::Value>("Too Many Requests").unwrap_err(),
+            text: "Too Many Requests".to_string(),
+        };
+        assert!(is_rate_limited(&body));
+    }
+
+    #[test]
+    fn test_breaker_trips_after_threshold_within_window() {
+        let breaker = Backpressure::new(3, Duration::from_secs(10), Duration::from_secs(30));
+        let start = Instant::now();
+
+        breaker.record_rate_limited_at(start);
+        breaker.record_rate_limited_at(start + Duration::from_secs(1));
+        assert_eq!(breaker.retry_after_at(start + Duration::from_secs(1)), None);
+
+        breaker.record_rate_limited_at(start + Duration::from_secs(2));
+        assert_eq!(
+            breaker.retry_after_at(start + Duration::from_secs(12)),
+            Some(Duration::from_secs(20))
+        );
+        assert_eq!(
+            breaker.retry_after_at(start + Duration::from_secs(32)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_breaker_forgets_hits_outside_window() {
+        let breaker = Backpressure::new(2, Duration::from_secs(10), Duration::from_secs(30));
+        let start = Instant::now();
+
+        breaker.record_rate_limited_at(start);
+        breaker.record_rate_limited_at(start + Duration::from_secs(11));
+        assert_eq!(
+            breaker.retry_after_at(start + Duration::from_secs(11)),
+            None
+        );
+
+        let disabled = Backpressure::new(0, Duration::from_secs(10), Duration::from_secs(30));
+        for _ in 0..10 {
+            disabled.record_rate_limited_at(start);
+        }
+        assert_eq!(disabled.retry_after_at(start), None);
+    }
+}
@@ -1,20 +1,13 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 use ethers::prelude::*;
 use rust_decimal::Decimal;
-use std::str::FromStr;
 
+use super::abi::{ERC20, ERC4626};
+use super::cache::MetadataCache;
 use super::client::EthClient;
-
-// ERC20 ABI for balanceOf and decimals
-abigen!(
-    ERC20,
-    r#"[
-        function balanceOf(address account) external view returns (uint256)
-        function decimals() external view returns (uint8)
-        function symbol() external view returns (string)
-        function name() external view returns (string)
-    ]"#,
-);
+use super::error::{is_revert, revert_reason, RpcCallContext};
+use super::swap::wei_to_decimal;
+use super::tokens::is_native_eth;
 
 #[derive(Debug)]
 pub struct BalanceInfo {
@@ -22,6 +15,16 @@ pub struct BalanceInfo {
     pub symbol: String,
     pub decimals: u8,
     pub raw_balance: U256,
+    /// Underlying assets the balance redeems for, when the token is an ERC-4626 vault
+    pub underlying: Option<UnderlyingInfo>,
+}
+
+/// Value of ERC-4626 vault shares in the vault's underlying asset
+#[derive(Debug)]
+pub struct UnderlyingInfo {
+    pub asset: Address,
+    pub amount: Decimal,
+    pub symbol: String,
 }
 
 /// Get ETH balance for an address
@@ -29,7 +32,7 @@ pub async fn get_eth_balance(provider: &EthClient, address: Address) -> Result<B
     let balance = provider
         .get_balance(address, None)
         .await
-        .context("Failed to get ETH balance")?;
+        .rpc_context("eth_getBalance", None)?;
 
     let decimals = 18u8;
     let balance_decimal = wei_to_decimal(balance, decimals)?;
@@ -39,60 +42,155 @@ pub async fn get_eth_balance(provider: &EthClient, address: Address) -> Result<B
         symbol: "ETH".to_string(),
         decimals,
         raw_balance: balance,
+        underlying: None,
     })
 }
 
 /// Get ERC20 token balance for an address
+///
+/// Native ETH has no `balanceOf`, so either ETH sentinel reads the wallet's ETH balance instead.
 pub async fn get_token_balance(
     provider: &EthClient,
+    cache: &MetadataCache,
     token_address: Address,
     wallet_address: Address,
 ) -> Result<BalanceInfo> {
+    if is_native_eth(token_address) {
+        return get_eth_balance(provider, wallet_address).await;
+    }
+
     let contract = ERC20::new(token_address, provider.clone());
 
-    // Get balance
-    let balance = contract
+    // Get balance. Some tokens revert for particular holders, such as the zero address
+    let balance = match contract
         .balance_of(wallet_address)
         .call()
         .await
-        .context("Failed to call balanceOf")?;
+        .rpc_context("balanceOf", Some(token_address))
+    {
+        Err(e) if is_revert(&e) => {
+            let reason = revert_reason(&e)
+                .map(|reason| format!(" (reverted: {})", reason))
+                .unwrap_or_default();
+            return Err(e.context(format!(
+                "Token {:?} does not support balance query for address {:?}{}",
+                token_address, wallet_address, reason
+            )));
+        }
+        result => result?,
+    };
 
     // Get decimals
-    let decimals = contract
-        .decimals()
-        .call()
-        .await
-        .context("Failed to get token decimals")?;
+    let decimals = cache
+        .token_decimals(token_address, || async {
+            contract
+                .decimals()
+                .call()
+                .await
+                .rpc_context("decimals", Some(token_address))
+        })
+        .await?;
 
     // Get symbol
-    let symbol = contract
-        .symbol()
-        .call()
+    let symbol = cache
+        .token_symbol(token_address, || async {
+            contract
+                .symbol()
+                .call()
+                .await
+                .rpc_context("symbol", Some(token_address))
+        })
         .await
         .unwrap_or_else(|_| "UNKNOWN".to_string());
 
     let balance_decimal = wei_to_decimal(balance, decimals)?;
+    let underlying = get_vault_underlying(provider, cache, token_address, balance).await;
 
     Ok(BalanceInfo {
         balance: balance_decimal,
         symbol,
         decimals,
         raw_balance: balance,
+        underlying,
     })
 }
 
-/// Convert wei amount to decimal with given decimals
-fn wei_to_decimal(amount: U256, decimals: u8) -> Result<Decimal> {
-    let amount_str = amount.to_string();
-    let amount_decimal = Decimal::from_str(&amount_str)?;
-    let divisor = Decimal::from(10u64.pow(decimals as u32));
+/// Convert vault shares to underlying assets, or `None` if the token isn't an ERC-4626 vault
+async fn get_vault_underlying(
+    provider: &EthClient,
+    cache: &MetadataCache,
+    token_address: Address,
+    shares: U256,
+) -> Option<UnderlyingInfo> {
+    let vault = ERC4626::new(token_address, provider.clone());
+
+    // Plain ERC20s revert (or return garbage) on these calls; treat any failure as "not a vault"
+    let asset = vault.asset().call().await.ok()?;
+    let assets = vault.convert_to_assets(shares).call().await.ok()?;
+
+    let asset_token = ERC20::new(asset, provider.clone());
+    let decimals = cache
+        .token_decimals(asset, || async {
+            asset_token
+                .decimals()
+                .call()
+                .await
+                .rpc_context("decimals", Some(asset))
+        })
+        .await
+        .ok()?;
+    let symbol = cache
+        .token_symbol(asset, || async {
+            asset_token
+                .symbol()
+                .call()
+                .await
+                .rpc_context("symbol", Some(asset))
+        })
+        .await
+        .unwrap_or_else(|_| "UNKNOWN".to_string());
 
-    Ok(amount_decimal / divisor)
+    Some(UnderlyingInfo {
+        asset,
+        amount: wei_to_decimal(assets, decimals).ok()?,
+        symbol,
+    })
+}
+
+/// Balances this small are shown in scientific notation in human-readable text
+const SCIENTIFIC_BELOW: Decimal = Decimal::from_parts(1, 0, 0, false, 4); // 0.0001
+
+/// Format an amount for display, using scientific notation for tiny non-zero values
+///
+/// `0.000000000123456789` becomes `1.23456789e-10`, so long runs of zeros don't hide the digits.
+pub fn display_amount(amount: Decimal) -> String {
+    let amount = amount.normalize();
+    if amount.is_zero() || amount.abs() >= SCIENTIFIC_BELOW {
+        return amount.to_string();
+    }
+
+    let digits = amount.mantissa().unsigned_abs().to_string();
+    let exponent = digits.len() as i64 - 1 - amount.scale() as i64;
+    let (lead, rest) = digits.split_at(1);
+    format!(
+        "{}{}{}{}e{}",
+        if amount.is_sign_negative() { "-" } else { "" },
+        lead,
+        if rest.is_empty() { "" } else { "." },
+        rest,
+        exponent
+    )
+}
+
+/// Whether `display_amount` switches to scientific notation for this amount
+pub fn is_tiny_amount(amount: Decimal) -> bool {
+    !amount.is_zero() && amount.abs() < SCIENTIFIC_BELOW
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::str::FromStr;
 
     #[test]
     fn test_wei_to_decimal() {
@@ -106,4 +204,28 @@ mod tests {
         let result = wei_to_decimal(thousand_usdc, 6).unwrap();
         assert_eq!(result, Decimal::from(1000));
     }
+
+    #[test]
+    fn test_display_amount() {
+        let dust = wei_to_decimal(U256::from(123_456_789u64), 18).unwrap();
+        assert!(is_tiny_amount(dust));
+        assert_eq!(display_amount(dust), "1.23456789e-10");
+        assert_eq!(
+            display_amount(Decimal::from_str("0.00005").unwrap()),
+            "5e-5"
+        );
+        assert_eq!(
+            display_amount(Decimal::from_str("-0.0000012").unwrap()),
+            "-1.2e-6"
+        );
+
+        // At or above the cutoff, and zero, stay in plain notation
+        assert_eq!(
+            display_amount(Decimal::from_str("0.0001").unwrap()),
+            "0.0001"
+        );
+        assert_eq!(display_amount(Decimal::from_str("1.50").unwrap()), "1.5");
+        assert_eq!(display_amount(Decimal::ZERO), "0");
+        assert!(!is_tiny_amount(Decimal::ZERO));
+    }
 }
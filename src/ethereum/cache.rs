@@ -0,0 +1,302 @@
+use anyhow::{Context, Result};
+use ethers::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Lock a cache map for reading; entries are inserted whole, so a poisoned lock is still usable
+fn read_map<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(|e| e.into_inner())
+}
+
+fn write_map<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    lock.write().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Addresses a Uniswap V2 router is bound to; constant for a given router
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RouterInfo {
+    pub weth: Address,
+    pub factory: Address,
+}
+
+/// In-memory cache of on-chain metadata that never changes for a given contract
+///
+/// With a backing file it is loaded from disk at startup and saved after every update, so a
+/// restarted server doesn't look the same tokens up again.
+#[derive(Debug, Default)]
+pub struct MetadataCache {
+    routers: RwLock<HashMap<Address, RouterInfo>>,
+    decimals: RwLock<HashMap<Address, u8>>,
+    symbols: RwLock<HashMap<Address, String>>,
+    /// Operator-supplied decimals that take precedence over the token's `decimals()`
+    decimals_overrides: HashMap<Address, u8>,
+    /// JSON file the cache is persisted to, when `CACHE_DIR` is set
+    path: Option<PathBuf>,
+}
+
+/// On-disk form of the cache; sorted so the file diffs cleanly between saves
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    #[serde(default)]
+    routers: BTreeMap<Address, RouterInfo>,
+    #[serde(default)]
+    decimals: BTreeMap<Address, u8>,
+    #[serde(default)]
+    symbols: BTreeMap<Address, String>,
+}
+
+impl MetadataCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A cache that answers `token_decimals` from `overrides` for the listed tokens
+    pub fn with_decimals_overrides(overrides: HashMap<Address, u8>) -> Self {
+        Self {
+            decimals_overrides: overrides,
+            ..Self::new()
+        }
+    }
+
+    /// Persist the cache to `metadata-<chain_id>.json` in `dir`, loading what an earlier run saved
+    ///
+    /// Metadata differs per chain even at the same address, hence one file per chain. A missing
+    /// file starts the cache empty; an unreadable one is replaced on the next save.
+    pub fn persisted_in(mut self, dir: &Path, chain_id: u64) -> Result<Self> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create CACHE_DIR {}", dir.display()))?;
+        let path = dir.join(format!("metadata-{}.json", chain_id));
+
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => match serde_json::from_str::<CacheFile>(&contents) {
+                Ok(file) => {
+                    tracing::info!(
+                        "Loaded metadata cache from {}: {} tokens, {} routers",
+                        path.display(),
+                        file.decimals.len().max(file.symbols.len()),
+                        file.routers.len()
+                    );
+                    self.routers = RwLock::new(file.routers.into_iter().collect());
+                    self.decimals = RwLock::new(file.decimals.into_iter().collect());
+                    self.symbols = RwLock::new(file.symbols.into_iter().collect());
+                }
+                Err(e) => tracing::warn!(
+                    "Ignoring unreadable metadata cache {}: {}",
+                    path.display(),
+                    e
+                ),
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        }
+
+        self.path = Some(path);
+        Ok(self)
+    }
+
+    /// Write the cache to its file, if it has one. A failed save only costs a lookup after the
+    /// next restart, so it is logged rather than failing the call that triggered it
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let file = CacheFile {
+            routers: read_map(&self.routers).clone().into_iter().collect(),
+            decimals: read_map(&self.decimals).clone().into_iter().collect(),
+            symbols: read_map(&self.symbols).clone().into_iter().collect(),
+        };
+        // Written beside the target and renamed over it, so a crash never leaves half a file
+        let tmp = path.with_extension("json.tmp");
+        let result = serde_json::to_vec_pretty(&file)
+            .map_err(anyhow::Error::from)
+            .and_then(|contents| Ok(std::fs::write(&tmp, contents)?))
+            .and_then(|()| Ok(std::fs::rename(&tmp, path)?));
+        if let Err(e) = result {
+            tracing::warn!(
+                "Failed to save metadata cache to {}: {:#}",
+                path.display(),
+                e
+            );
+        }
+    }
+
+    /// Forget a token's cached decimals and symbol so the next lookup reads them from the chain
+    pub fn invalidate(&self, token: Address) {
+        let removed_decimals = write_map(&self.decimals).remove(&token).is_some();
+        let removed_symbol = write_map(&self.symbols).remove(&token).is_some();
+        if removed_decimals || removed_symbol {
+            tracing::debug!("Invalidated cached metadata for {:?}", token);
+            self.save();
+        }
+    }
+
+    /// Router WETH/factory addresses, fetched on first use per router
+    pub async fn router_info<F, Fut>(&self, router: Address, fetch: F) -> Result<RouterInfo>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<RouterInfo>>,
+    {
+        if let Some(info) = read_map(&self.routers).get(&router) {
+            return Ok(*info);
+        }
+
+        let info = fetch().await?;
+        write_map(&self.routers).insert(router, info);
+        self.save();
+        Ok(info)
+    }
+
+    /// Token decimals, from the overrides or fetched on first use per token. Failed lookups are
+    /// not cached
+    pub async fn token_decimals<F, Fut>(&self, token: Address, fetch: F) -> Result<u8>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<u8>>,
+    {
+        if let Some(decimals) = self.decimals_overrides.get(&token) {
+            tracing::debug!(
+                "Using TOKEN_DECIMALS_OVERRIDES decimals {} for {:?}",
+                decimals,
+                token
+            );
+            return Ok(*decimals);
+        }
+        if let Some(decimals) = read_map(&self.decimals).get(&token) {
+            return Ok(*decimals);
+        }
+
+        let decimals = fetch().await?;
+        write_map(&self.decimals).insert(token, decimals);
+        self.save();
+        Ok(decimals)
+    }
+
+    /// Token symbol, fetched on first use per token. Failed lookups are not cached
+    pub async fn token_symbol<F, Fut>(&self, token: Address, fetch: F) -> Result<String>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<String>>,
+    {
+        if let Some(symbol) = read_map(&self.symbols).get(&token) {
+            return Ok(symbol.clone());
+        }
+
+        let symbol = fetch().await?;
+        write_map(&self.symbols).insert(token, symbol.clone());
+        self.save();
+        Ok(symbol)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_router_info_fetched_once_per_router() {
+        let cache = MetadataCache::new();
+        let calls = AtomicUsize::new(0);
+        let router = Address::from_low_u64_be(1);
+        let expected = RouterInfo {
+            weth: Address::from_low_u64_be(2),
+            factory: Address::from_low_u64_be(3),
+        };
+
+        for _ in 0..3 {
+            let info = cache
+                .router_info(router, || async {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    Ok(expected)
+                })
+                .await
+                .unwrap();
+            assert_eq!(info, expected);
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_decimals_lookup_not_cached() {
+        let cache = MetadataCache::new();
+        let token = Address::from_low_u64_be(1);
+
+        let failed = cache
+            .token_decimals(token, || async { anyhow::bail!("rpc down") })
+            .await;
+        assert!(failed.is_err());
+
+        let decimals = cache
+            .token_decimals(token, || async { Ok(6) })
+            .await
+            .unwrap();
+        assert_eq!(decimals, 6);
+    }
+
+    #[tokio::test]
+    async fn test_persisted_cache_survives_restart() {
+        let dir = std::env::temp_dir().join(format!("metadata-cache-test-{}", std::process::id()));
+        let token = Address::from_low_u64_be(1);
+
+        let cache = MetadataCache::new().persisted_in(&dir, 1).unwrap();
+        cache
+            .token_decimals(token, || async { Ok(6) })
+            .await
+            .unwrap();
+        cache
+            .token_symbol(token, || async { Ok("USDC".to_string()) })
+            .await
+            .unwrap();
+
+        // A fresh cache for the same chain answers without fetching
+        let restarted = MetadataCache::new().persisted_in(&dir, 1).unwrap();
+        let decimals = restarted
+            .token_decimals(token, || async { anyhow::bail!("should be cached") })
+            .await
+            .unwrap();
+        assert_eq!(decimals, 6);
+
+        // Other chains keep their own file
+        let other_chain = MetadataCache::new().persisted_in(&dir, 10).unwrap();
+        let decimals = other_chain
+            .token_decimals(token, || async { Ok(18) })
+            .await
+            .unwrap();
+        assert_eq!(decimals, 18);
+
+        // Invalidation is persisted too
+        restarted.invalidate(token);
+        let reloaded = MetadataCache::new().persisted_in(&dir, 1).unwrap();
+        let symbol = reloaded
+            .token_symbol(token, || async { Ok("USDC.e".to_string()) })
+            .await
+            .unwrap();
+        assert_eq!(symbol, "USDC.e");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_decimals_override_skips_lookup() {
+        let token = Address::from_low_u64_be(1);
+        let cache = MetadataCache::with_decimals_overrides(HashMap::from([(token, 9)]));
+
+        let decimals = cache
+            .token_decimals(token, || async { anyhow::bail!("decimals() reverted") })
+            .await
+            .unwrap();
+        assert_eq!(decimals, 9);
+
+        // Other tokens are still looked up
+        let other = Address::from_low_u64_be(2);
+        let decimals = cache
+            .token_decimals(other, || async { Ok(18) })
+            .await
+            .unwrap();
+        assert_eq!(decimals, 18);
+    }
+}
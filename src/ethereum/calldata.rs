@@ -0,0 +1,313 @@
+use anyhow::Result;
+use ethers::abi::{Function, Token};
+use ethers::prelude::*;
+
+use super::contract_call::{param_types, parse_signature};
+use super::swap::{SUSHISWAP_ROUTER_ADDRESS, V2_ROUTER_ADDRESS, V3_ROUTER_ADDRESS};
+
+/// Uniswap SwapRouter02 and Universal Router, which only appear here as call targets
+const SWAP_ROUTER_02_ADDRESS: &str = "0x68b3465833fb72A70ecDF485E0e4C7bD8665Fc45";
+const UNIVERSAL_ROUTER_ADDRESS: &str = "0x3fC91A3afd70395Cd496C647d5a6CC9D4B2b7FAD";
+
+/// Deepest `multicall` nesting whose inner calls are decoded
+const MAX_MULTICALL_DEPTH: usize = 2;
+
+/// Functions recognized by selector, with the ABI each comes from
+///
+/// Parameter names are kept so decoded arguments read like the contract's own source.
+const KNOWN_FUNCTIONS: &[(&str, &str)] = &[
+    ("ERC20", "transfer(address to, uint256 amount)"),
+    ("ERC20", "approve(address spender, uint256 amount)"),
+    ("ERC20", "transferFrom(address from, address to, uint256 amount)"),
+    ("ERC20", "increaseAllowance(address spender, uint256 addedValue)"),
+    ("ERC20", "decreaseAllowance(address spender, uint256 subtractedValue)"),
+    ("ERC721", "setApprovalForAll(address operator, bool approved)"),
+    ("ERC721", "safeTransferFrom(address from, address to, uint256 tokenId)"),
+    ("WETH", "deposit()"),
+    ("WETH", "withdraw(uint256 wad)"),
+    ("Uniswap V2 Router", "swapExactTokensForTokens(uint256 amountIn, uint256 amountOutMin, address[] path, address to, uint256 deadline)"),
+    ("Uniswap V2 Router", "swapTokensForExactTokens(uint256 amountOut, uint256 amountInMax, address[] path, address to, uint256 deadline)"),
+    ("Uniswap V2 Router", "swapExactETHForTokens(uint256 amountOutMin, address[] path, address to, uint256 deadline)"),
+    ("Uniswap V2 Router", "swapETHForExactTokens(uint256 amountOut, address[] path, address to, uint256 deadline)"),
+    ("Uniswap V2 Router", "swapExactTokensForETH(uint256 amountIn, uint256 amountOutMin, address[] path, address to, uint256 deadline)"),
+    ("Uniswap V2 Router", "swapTokensForExactETH(uint256 amountOut, uint256 amountInMax, address[] path, address to, uint256 deadline)"),
+    ("Uniswap V2 Router", "swapExactTokensForTokensSupportingFeeOnTransferTokens(uint256 amountIn, uint256 amountOutMin, address[] path, address to, uint256 deadline)"),
+    ("Uniswap V2 Router", "swapExactETHForTokensSupportingFeeOnTransferTokens(uint256 amountOutMin, address[] path, address to, uint256 deadline)"),
+    ("Uniswap V2 Router", "swapExactTokensForETHSupportingFeeOnTransferTokens(uint256 amountIn, uint256 amountOutMin, address[] path, address to, uint256 deadline)"),
+    ("Uniswap V2 Router", "addLiquidity(address tokenA, address tokenB, uint256 amountADesired, uint256 amountBDesired, uint256 amountAMin, uint256 amountBMin, address to, uint256 deadline)"),
+    ("Uniswap V2 Router", "addLiquidityETH(address token, uint256 amountTokenDesired, uint256 amountTokenMin, uint256 amountETHMin, address to, uint256 deadline)"),
+    ("Uniswap V2 Router", "removeLiquidity(address tokenA, address tokenB, uint256 liquidity, uint256 amountAMin, uint256 amountBMin, address to, uint256 deadline)"),
+    ("Uniswap V2 Router", "removeLiquidityETH(address token, uint256 liquidity, uint256 amountTokenMin, uint256 amountETHMin, address to, uint256 deadline)"),
+    ("Uniswap V3 Router", "exactInputSingle((address,address,uint24,address,uint256,uint256,uint256,uint160) params)"),
+    ("Uniswap V3 Router", "exactInput((bytes,address,uint256,uint256,uint256) params)"),
+    ("Uniswap V3 Router", "exactOutputSingle((address,address,uint24,address,uint256,uint256,uint256,uint160) params)"),
+    ("Uniswap V3 Router", "exactOutput((bytes,address,uint256,uint256,uint256) params)"),
+    ("Uniswap SwapRouter02", "exactInputSingle((address,address,uint24,address,uint256,uint256,uint160) params)"),
+    ("Uniswap SwapRouter02", "exactInput((bytes,address,uint256,uint256) params)"),
+    ("Uniswap Router", "multicall(bytes[] data)"),
+    ("Uniswap Router", "multicall(uint256 deadline, bytes[] data)"),
+    ("Uniswap Router", "unwrapWETH9(uint256 amountMinimum, address recipient)"),
+    ("Uniswap Router", "refundETH()"),
+    ("Uniswap Universal Router", "execute(bytes commands, bytes[] inputs, uint256 deadline)"),
+    ("Uniswap Universal Router", "execute(bytes commands, bytes[] inputs)"),
+];
+
+/// A decoded function argument
+#[derive(Debug, Clone)]
+pub struct DecodedArg {
+    pub name: String,
+    pub kind: String,
+    pub token: Token,
+}
+
+/// What a piece of calldata calls, as far as the known ABIs tell
+#[derive(Debug, Clone)]
+pub struct DecodedCall {
+    /// `0x`-prefixed 4-byte selector; empty for a plain transfer without calldata
+    pub selector: String,
+    /// `name(types)`, when the selector is known and its arguments decode
+    pub function: Option<String>,
+    /// ABI the function was matched in, e.g. `ERC20`
+    pub abi: Option<&'static str>,
+    pub args: Vec<DecodedArg>,
+    /// Calls bundled by a `multicall`, decoded the same way
+    pub inner_calls: Vec<DecodedCall>,
+    /// Effects worth a second look before signing, such as unlimited approvals, including those
+    /// of inner calls
+    pub warnings: Vec<String>,
+}
+
+/// Name of a well-known router at `address`
+pub fn known_contract(address: Address) -> Option<&'static str> {
+    [
+        (V2_ROUTER_ADDRESS, "Uniswap V2 Router"),
+        (SUSHISWAP_ROUTER_ADDRESS, "Sushiswap Router"),
+        (V3_ROUTER_ADDRESS, "Uniswap V3 Router"),
+        (SWAP_ROUTER_02_ADDRESS, "Uniswap SwapRouter02"),
+        (UNIVERSAL_ROUTER_ADDRESS, "Uniswap Universal Router"),
+    ]
+    .into_iter()
+    .find(|(known, _)| known.parse::<Address>().ok() == Some(address))
+    .map(|(_, name)| name)
+}
+
+fn known_functions() -> impl Iterator<Item = (&'static str, Function)> {
+    KNOWN_FUNCTIONS.iter().map(|(abi, signature)| {
+        let function = parse_signature(signature).expect("KNOWN_FUNCTIONS signatures are valid");
+        (*abi, function)
+    })
+}
+
+/// Decode calldata against the known ABIs
+///
+/// Selectors shared by several ABIs are tried in turn, and the first whose argument types fit
+/// the data wins. Unknown selectors come back with no `function`.
+pub fn decode_calldata(data: &[u8]) -> Result<DecodedCall> {
+    decode_at_depth(data, 0)
+}
+
+fn decode_at_depth(data: &[u8], depth: usize) -> Result<DecodedCall> {
+    if data.is_empty() {
+        return Ok(DecodedCall {
+            selector: String::new(),
+            function: None,
+            abi: None,
+            args: Vec::new(),
+            inner_calls: Vec::new(),
+            warnings: Vec::new(),
+        });
+    }
+    if data.len() < 4 {
+        anyhow::bail!(
+            "Calldata is {} bytes, shorter than a 4-byte selector",
+            data.len()
+        );
+    }
+
+    let selector = Bytes::from(data[..4].to_vec()).to_string();
+    let candidates: Vec<_> = known_functions()
+        .filter(|(_, function)| function.short_signature() == data[..4])
+        .collect();
+    let matched = candidates.iter().find_map(|(abi, function)| {
+        let tokens = function.decode_input(&data[4..]).ok()?;
+        Some((*abi, function, tokens))
+    });
+    let Some((abi, function, tokens)) = matched else {
+        // A known selector with arguments that don't fit is as suspect as an unknown one
+        let warnings = candidates
+            .iter()
+            .map(|(abi, function)| {
+                format!(
+                    "Selector matches {}({}) from {}, but the arguments don't decode",
+                    function.name,
+                    param_types(&function.inputs),
+                    abi
+                )
+            })
+            .collect();
+        return Ok(DecodedCall {
+            selector,
+            function: None,
+            abi: None,
+            args: Vec::new(),
+            inner_calls: Vec::new(),
+            warnings,
+        });
+    };
+
+    let args: Vec<DecodedArg> = function
+        .inputs
+        .iter()
+        .zip(tokens)
+        .map(|(param, token)| DecodedArg {
+            name: param.name.clone(),
+            kind: param.kind.to_string(),
+            token,
+        })
+        .collect();
+
+    let mut inner_calls = Vec::new();
+    let mut warnings = call_warnings(&function.name, &args);
+    if function.name == "multicall" {
+        if depth >= MAX_MULTICALL_DEPTH {
+            warnings.push("Nested multicall too deep; inner calls not decoded".to_string());
+        } else if let Some(Token::Array(calls)) = args.last().map(|arg| &arg.token) {
+            for call in calls {
+                if let Token::Bytes(call) = call {
+                    let inner = decode_at_depth(call, depth + 1)?;
+                    warnings.extend(inner.warnings.iter().cloned());
+                    inner_calls.push(inner);
+                }
+            }
+        }
+    }
+
+    Ok(DecodedCall {
+        selector,
+        function: Some(format!(
+            "{}({})",
+            function.name,
+            param_types(&function.inputs)
+        )),
+        abi: Some(abi),
+        args,
+        inner_calls,
+        warnings,
+    })
+}
+
+/// Flag calls that hand control of funds to another address
+fn call_warnings(name: &str, args: &[DecodedArg]) -> Vec<String> {
+    let arg = |index: usize| args.get(index).map(|arg| &arg.token);
+    match (name, arg(0), arg(1)) {
+        ("approve", Some(Token::Address(spender)), Some(Token::Uint(amount)))
+            if *amount == U256::MAX =>
+        {
+            vec![format!(
+                "Unlimited approval: {:?} can move every token of this type the signer holds",
+                spender
+            )]
+        }
+        ("setApprovalForAll", Some(Token::Address(operator)), Some(Token::Bool(true))) => {
+            vec![format!(
+                "Approval for all: {:?} can move every NFT of this collection the signer holds",
+                operator
+            )]
+        }
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holder() -> Address {
+        "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+            .parse()
+            .unwrap()
+    }
+
+    fn calldata(signature: &str, tokens: &[Token]) -> Vec<u8> {
+        parse_signature(signature)
+            .unwrap()
+            .encode_input(tokens)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_known_signatures_parse() {
+        assert_eq!(known_functions().count(), KNOWN_FUNCTIONS.len());
+        let selector = |name: &str| {
+            known_functions()
+                .find(|(_, function)| function.name == name)
+                .map(|(_, function)| Bytes::from(function.short_signature().to_vec()).to_string())
+                .unwrap()
+        };
+        assert_eq!(selector("transfer"), "0xa9059cbb");
+        assert_eq!(selector("swapExactTokensForTokens"), "0x38ed1739");
+        assert_eq!(selector("exactInputSingle"), "0x414bf389");
+        assert_eq!(selector("multicall"), "0xac9650d8");
+    }
+
+    #[test]
+    fn test_decode_erc20_approve() {
+        let data = calldata(
+            "approve(address,uint256)",
+            &[Token::Address(holder()), Token::Uint(U256::MAX)],
+        );
+        let decoded = decode_calldata(&data).unwrap();
+        assert_eq!(decoded.selector, "0x095ea7b3");
+        assert_eq!(
+            decoded.function.as_deref(),
+            Some("approve(address,uint256)")
+        );
+        assert_eq!(decoded.abi, Some("ERC20"));
+        assert_eq!(decoded.args[0].name, "spender");
+        assert_eq!(decoded.args[1].token, Token::Uint(U256::MAX));
+        assert!(decoded.warnings[0].contains("Unlimited approval"));
+    }
+
+    #[test]
+    fn test_decode_multicall_inner_calls() {
+        let transfer = calldata(
+            "transfer(address,uint256)",
+            &[Token::Address(holder()), Token::Uint(U256::from(5))],
+        );
+        let data = calldata(
+            "multicall(uint256,bytes[])",
+            &[
+                Token::Uint(U256::from(1_700_000_000u64)),
+                Token::Array(vec![Token::Bytes(transfer)]),
+            ],
+        );
+        let decoded = decode_calldata(&data).unwrap();
+        assert_eq!(decoded.selector, "0x5ae401dc");
+        assert_eq!(decoded.inner_calls.len(), 1);
+        assert_eq!(
+            decoded.inner_calls[0].function.as_deref(),
+            Some("transfer(address,uint256)")
+        );
+    }
+
+    #[test]
+    fn test_unknown_and_malformed_calldata() {
+        let unknown = decode_calldata(&[0xde, 0xad, 0xbe, 0xef, 0x00]).unwrap();
+        assert_eq!(unknown.selector, "0xdeadbeef");
+        assert!(unknown.function.is_none());
+
+        // Known selector whose arguments are cut short
+        let truncated = decode_calldata(&[0xa9, 0x05, 0x9c, 0xbb, 0x01]).unwrap();
+        assert!(truncated.function.is_none());
+        assert!(truncated.warnings[0].contains("transfer(address,uint256)"));
+
+        assert!(decode_calldata(&[0xa9, 0x05]).is_err());
+        assert!(decode_calldata(&[]).unwrap().selector.is_empty());
+
+        assert_eq!(
+            known_contract(V2_ROUTER_ADDRESS.parse().unwrap()),
+            Some("Uniswap V2 Router")
+        );
+        assert_eq!(known_contract(holder()), None);
+    }
+}
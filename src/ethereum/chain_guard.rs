@@ -0,0 +1,116 @@
+use ethers::prelude::*;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use super::client::EthClient;
+
+#[derive(Debug)]
+struct GuardState {
+    checked_at: Instant,
+    /// Chain id last reported by the endpoint, when it differs from the startup one
+    mismatch: Option<u64>,
+}
+
+/// Periodically re-reads the endpoint's chain id, catching RPC proxies that silently fail over
+/// to a different network after startup
+#[derive(Debug)]
+pub struct ChainGuard {
+    expected_chain_id: u64,
+    /// How long a check stays fresh (zero = never re-check)
+    max_age: Duration,
+    state: Mutex<GuardState>,
+}
+
+impl ChainGuard {
+    /// Guard for the chain id read at startup, which counts as the first check
+    pub fn new(expected_chain_id: u64, max_age: Duration) -> Self {
+        Self {
+            expected_chain_id,
+            max_age,
+            state: Mutex::new(GuardState {
+                checked_at: Instant::now(),
+                mismatch: None,
+            }),
+        }
+    }
+
+    /// The guard's state, even if a panic poisoned the lock; both fields are always valid
+    fn state(&self) -> MutexGuard<'_, GuardState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Chain id the endpoint moved to, if it no longer serves the startup chain
+    pub fn mismatch(&self) -> Option<u64> {
+        self.state().mismatch
+    }
+
+    fn is_stale(&self) -> bool {
+        !self.max_age.is_zero() && self.state().checked_at.elapsed() >= self.max_age
+    }
+
+    /// Re-read the chain id if the last check is older than `max_age`
+    ///
+    /// A failed lookup keeps the previous verdict, so a flaky endpoint neither blocks nor unblocks tools.
+    pub async fn refresh(&self, provider: &EthClient) {
+        if !self.is_stale() {
+            return;
+        }
+
+        match provider.get_chainid().await {
+            Ok(chain_id) => self.record(chain_id.as_u64()),
+            Err(e) => {
+                tracing::warn!("Chain ID re-check failed: {}", e);
+                self.state().checked_at = Instant::now();
+            }
+        }
+    }
+
+    /// Record a freshly observed chain id, logging when the endpoint switches networks
+    pub fn record(&self, chain_id: u64) {
+        let mut state = self.state();
+        state.checked_at = Instant::now();
+
+        let mismatch = (chain_id != self.expected_chain_id).then_some(chain_id);
+        if mismatch != state.mismatch {
+            match mismatch {
+                Some(chain_id) => tracing::error!(
+                    "RPC endpoint switched from chain ID {} to {}; swap and price tools are disabled",
+                    self.expected_chain_id,
+                    chain_id
+                ),
+                None => tracing::info!(
+                    "RPC endpoint is back on chain ID {}; swap and price tools re-enabled",
+                    self.expected_chain_id
+                ),
+            }
+        }
+        state.mismatch = mismatch;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_mismatch_and_recovery() {
+        let guard = ChainGuard::new(1, Duration::from_secs(60));
+        assert_eq!(guard.mismatch(), None);
+
+        guard.record(10);
+        assert_eq!(guard.mismatch(), Some(10));
+
+        guard.record(1);
+        assert_eq!(guard.mismatch(), None);
+    }
+
+    #[test]
+    fn test_is_stale() {
+        assert!(!ChainGuard::new(1, Duration::ZERO).is_stale());
+        assert!(!ChainGuard::new(1, Duration::from_secs(60)).is_stale());
+
+        let guard = ChainGuard::new(1, Duration::from_millis(1));
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(guard.is_stale());
+    }
+}
@@ -0,0 +1,199 @@
+use anyhow::{Context, Result};
+use ethers::types::Chain;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, OnceCell};
+
+use super::cache::MetadataCache;
+use super::client::{create_provider, fetch_chain_info, ChainInfo, EthClient, DEFAULT_USER_AGENT};
+use super::pool::RpcLimits;
+use super::rpc_log::RpcLogging;
+
+/// Parse a chain given by ID (`10`) or by name (`optimism`, `base`, `mainnet`)
+pub fn parse_chain(chain: &str) -> Result<u64> {
+    let chain = chain.trim();
+    if let Ok(chain_id) = chain.parse::<u64>() {
+        return Ok(chain_id);
+    }
+    Chain::from_str(&chain.to_lowercase())
+        .map(u64::from)
+        .map_err(|_| anyhow::anyhow!("Unknown chain {:?}: expected a chain ID or name", chain))
+}
+
+/// Symbol and CoinGecko coin id of a chain's native gas token; ETH unless known otherwise
+pub fn native_currency(chain_id: u64) -> (&'static str, &'static str) {
+    match chain_id {
+        56 | 97 => ("BNB", "binancecoin"),
+        137 | 80002 => ("POL", "polygon-ecosystem-token"),
+        43114 | 43113 => ("AVAX", "avalanche-2"),
+        100 => ("xDAI", "xdai"),
+        250 => ("FTM", "fantom"),
+        _ => ("ETH", "ethereum"),
+    }
+}
+
+/// A connected provider for one chain, with its own metadata cache
+#[derive(Debug, Clone)]
+pub struct ChainClient {
+    pub provider: EthClient,
+    pub info: ChainInfo,
+    /// Token and router metadata differ per chain even at the same address
+    pub cache: Arc<MetadataCache>,
+}
+
+/// Providers for the extra chains configured in `RPC_URLS`, connected on first use
+///
+/// Each endpoint is checked to serve the chain ID it is configured for before it is used; an
+/// endpoint that fails the check is retried on the next call rather than cached. Chains connect
+/// independently, so a slow endpoint doesn't hold up calls to the others.
+#[derive(Debug)]
+pub struct ChainProviders {
+    urls: HashMap<u64, String>,
+    logging: RpcLogging,
+    limits: RpcLimits,
+    user_agent: String,
+    poll_interval: Duration,
+    /// Where each chain's metadata cache is persisted, as for the `ETH_RPC_URL` chain
+    cache_dir: Option<PathBuf>,
+    clients: Mutex<HashMap<u64, Arc<OnceCell<ChainClient>>>>,
+}
+
+impl Default for ChainProviders {
+    fn default() -> Self {
+        Self::new(
+            HashMap::new(),
+            RpcLogging::default(),
+            RpcLimits::default(),
+            DEFAULT_USER_AGENT,
+            Duration::from_millis(500),
+        )
+    }
+}
+
+impl ChainProviders {
+    pub fn new(
+        urls: HashMap<u64, String>,
+        logging: RpcLogging,
+        limits: RpcLimits,
+        user_agent: &str,
+        poll_interval: Duration,
+    ) -> Self {
+        Self {
+            urls,
+            logging,
+            limits,
+            user_agent: user_agent.to_string(),
+            poll_interval,
+            cache_dir: None,
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Persist each chain's token metadata in `cache_dir`
+    pub fn with_cache_dir(mut self, cache_dir: Option<PathBuf>) -> Self {
+        self.cache_dir = cache_dir;
+        self
+    }
+
+    /// Configured chain IDs, ascending
+    pub fn chain_ids(&self) -> Vec<u64> {
+        let mut chain_ids: Vec<u64> = self.urls.keys().copied().collect();
+        chain_ids.sort_unstable();
+        chain_ids
+    }
+
+    /// The client for `chain_id`, connecting and checking its endpoint on first use
+    pub async fn get(&self, chain_id: u64) -> Result<ChainClient> {
+        let url = self.urls.get(&chain_id).with_context(|| {
+            format!(
+                "Chain ID {} is not configured; add it to RPC_URLS (configured: {:?})",
+                chain_id,
+                self.chain_ids()
+            )
+        })?;
+
+        // Concurrent first calls for a chain share one connection attempt
+        let client = self
+            .clients
+            .lock()
+            .await
+            .entry(chain_id)
+            .or_default()
+            .clone();
+        client
+            .get_or_try_init(|| self.connect(chain_id, url))
+            .await
+            .cloned()
+    }
+
+    /// Create the provider for `chain_id`, checking the endpoint serves that chain
+    async fn connect(&self, chain_id: u64, url: &str) -> Result<ChainClient> {
+        let provider = create_provider(
+            url,
+            self.logging,
+            self.limits,
+            &self.user_agent,
+            self.poll_interval,
+        )
+        .await
+        .with_context(|| format!("Failed to create provider for chain ID {}", chain_id))?;
+        let info = fetch_chain_info(&provider, url)
+            .await
+            .with_context(|| format!("Failed to query chain ID {} endpoint", chain_id))?;
+        if info.chain_id != chain_id {
+            anyhow::bail!(
+                "RPC_URLS endpoint for chain ID {} ({}) serves chain ID {}",
+                chain_id,
+                info.rpc_url,
+                info.chain_id
+            );
+        }
+        tracing::info!(
+            "Connected to chain ID {} ({}) at block {}",
+            info.chain_id,
+            info.chain_name,
+            info.block_number
+        );
+
+        let cache = match &self.cache_dir {
+            Some(dir) => MetadataCache::new().persisted_in(dir, chain_id)?,
+            None => MetadataCache::new(),
+        };
+        Ok(ChainClient {
+            provider,
+            info,
+            cache: Arc::new(cache),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_chain() {
+        assert_eq!(parse_chain("10").unwrap(), 10);
+        assert_eq!(parse_chain(" Base ").unwrap(), 8453);
+        assert_eq!(parse_chain("mainnet").unwrap(), 1);
+        assert!(parse_chain("not-a-chain").is_err());
+    }
+
+    #[test]
+    fn test_native_currency() {
+        assert_eq!(native_currency(1).0, "ETH");
+        assert_eq!(native_currency(8453).0, "ETH");
+        assert_eq!(native_currency(56), ("BNB", "binancecoin"));
+        assert_eq!(native_currency(137).0, "POL");
+    }
+
+    #[tokio::test]
+    async fn test_unconfigured_chain() {
+        let providers = ChainProviders::default();
+        let error = providers.get(10).await.unwrap_err();
+        assert!(error.to_string().contains("not configured"));
+    }
+}
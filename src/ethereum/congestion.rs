@@ -0,0 +1,246 @@
+use anyhow::{Context, Result};
+use ethers::prelude::*;
+use rust_decimal::Decimal;
+use tokio::task::JoinSet;
+
+use super::client::EthClient;
+use super::error::{require_found, RpcCallContext};
+use super::swap::wei_to_decimal;
+
+/// Most blocks one congestion lookup may fetch
+pub const MAX_CONGESTION_BLOCKS: u64 = 50;
+
+/// Percentage-point change in average gas utilization that counts as a trend
+const UTILIZATION_TREND_POINTS: u32 = 5;
+
+/// Relative change in average base fee, in percent, that counts as a trend
+const BASE_FEE_TREND_PCT: u32 = 5;
+
+/// EIP-1559 base fee change denominator: the fee moves at most 1/8 per block
+const BASE_FEE_MAX_CHANGE_DENOMINATOR: u64 = 8;
+
+/// Direction of a metric between the older and newer half of the sampled blocks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    Rising,
+    Falling,
+    Steady,
+}
+
+impl Trend {
+    pub fn name(self) -> &'static str {
+        match self {
+            Trend::Rising => "rising",
+            Trend::Falling => "falling",
+            Trend::Steady => "steady",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BlockActivity {
+    pub number: u64,
+    pub timestamp: u64,
+    pub transaction_count: usize,
+    pub gas_used: U256,
+    pub gas_limit: U256,
+    /// gasUsed / gasLimit, in percent
+    pub utilization_pct: Decimal,
+    /// None before London and on chains without EIP-1559
+    pub base_fee_gwei: Option<Decimal>,
+}
+
+#[derive(Debug)]
+pub struct CongestionReport {
+    /// Oldest block first
+    pub blocks: Vec<BlockActivity>,
+    pub average_utilization_pct: Decimal,
+    pub utilization_trend: Trend,
+    pub base_fee_trend: Option<Trend>,
+    /// Base fee the next block will charge, implied by the latest block's gas usage
+    pub next_base_fee_gwei: Option<Decimal>,
+}
+
+/// Transaction counts and gas utilization of the latest `block_count` blocks
+pub async fn get_block_congestion(
+    provider: &EthClient,
+    block_count: u64,
+) -> Result<CongestionReport> {
+    if block_count == 0 || block_count > MAX_CONGESTION_BLOCKS {
+        anyhow::bail!(
+            "block_count must be between 1 and {}",
+            MAX_CONGESTION_BLOCKS
+        );
+    }
+
+    let latest = provider
+        .get_block_number()
+        .await
+        .rpc_context("eth_blockNumber", None)?
+        .as_u64();
+    let first = latest.saturating_sub(block_count - 1);
+
+    let mut tasks = JoinSet::new();
+    for number in first..=latest {
+        let provider = provider.clone();
+        tasks.spawn(async move {
+            let block = provider
+                .get_block(number)
+                .await
+                .rpc_context("eth_getBlockByNumber", None);
+            (number, block)
+        });
+    }
+
+    let mut blocks = Vec::with_capacity(block_count as usize);
+    while let Some(joined) = tasks.join_next().await {
+        let (number, block) = joined.context("Block lookup task failed")?;
+        let block = require_found(block?, "Block", number)?;
+        blocks.push(BlockActivity {
+            number,
+            timestamp: block.timestamp.as_u64(),
+            transaction_count: block.transactions.len(),
+            gas_used: block.gas_used,
+            gas_limit: block.gas_limit,
+            utilization_pct: utilization_pct(block.gas_used, block.gas_limit)?,
+            base_fee_gwei: block
+                .base_fee_per_gas
+                .map(|fee| wei_to_decimal(fee, 9))
+                .transpose()?,
+        });
+    }
+    blocks.sort_by_key(|block| block.number);
+
+    let utilization: Vec<Decimal> = blocks.iter().map(|block| block.utilization_pct).collect();
+    let average_utilization_pct = average(&utilization);
+    let utilization_trend = trend(&utilization, |older, newer| {
+        newer - older > Decimal::from(UTILIZATION_TREND_POINTS)
+    });
+
+    let base_fees: Option<Vec<Decimal>> = blocks.iter().map(|block| block.base_fee_gwei).collect();
+    let base_fee_trend = base_fees.map(|fees| {
+        trend(&fees, |older, newer| {
+            newer - older > older * Decimal::from(BASE_FEE_TREND_PCT) / Decimal::from(100)
+        })
+    });
+
+    let latest_block = blocks.last().context("No blocks fetched")?;
+    let next_base_fee_gwei = latest_block
+        .base_fee_gwei
+        .map(|base_fee| next_base_fee(base_fee, latest_block.gas_used, latest_block.gas_limit));
+
+    Ok(CongestionReport {
+        blocks,
+        average_utilization_pct,
+        utilization_trend,
+        base_fee_trend,
+        next_base_fee_gwei,
+    })
+}
+
+fn utilization_pct(gas_used: U256, gas_limit: U256) -> Result<Decimal> {
+    if gas_limit.is_zero() {
+        return Ok(Decimal::ZERO);
+    }
+    let used = wei_to_decimal(gas_used, 0)?;
+    let limit = wei_to_decimal(gas_limit, 0)?;
+    Ok((used / limit * Decimal::from(100)).round_dp(2))
+}
+
+fn average(values: &[Decimal]) -> Decimal {
+    if values.is_empty() {
+        return Decimal::ZERO;
+    }
+    (values.iter().sum::<Decimal>() / Decimal::from(values.len())).round_dp(2)
+}
+
+/// Compare the average of the older half of `values` with the newer half
+///
+/// `exceeds(a, b)` tells whether moving from `a` to `b` is a significant rise; the same test in
+/// reverse detects a fall. One sample has no direction.
+fn trend(values: &[Decimal], exceeds: impl Fn(Decimal, Decimal) -> bool) -> Trend {
+    if values.len() < 2 {
+        return Trend::Steady;
+    }
+    let (older, newer) = values.split_at(values.len() / 2);
+    let (older, newer) = (average(older), average(newer));
+    if exceeds(older, newer) {
+        Trend::Rising
+    } else if exceeds(newer, older) {
+        Trend::Falling
+    } else {
+        Trend::Steady
+    }
+}
+
+/// Next block's base fee under EIP-1559: up to 12.5% higher when the block was over its gas
+/// target (half the limit), up to 12.5% lower when under it
+fn next_base_fee(base_fee: Decimal, gas_used: U256, gas_limit: U256) -> Decimal {
+    let target = gas_limit / 2;
+    if target.is_zero() || gas_used == target {
+        return base_fee;
+    }
+    let (used, target) = match (wei_to_decimal(gas_used, 0), wei_to_decimal(target, 0)) {
+        (Ok(used), Ok(target)) => (used, target),
+        _ => return base_fee,
+    };
+    let change =
+        base_fee * (used - target) / target / Decimal::from(BASE_FEE_MAX_CHANGE_DENOMINATOR);
+    (base_fee + change).round_dp(9)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_utilization_and_next_base_fee() {
+        let limit = U256::from(30_000_000u64);
+        assert_eq!(
+            utilization_pct(U256::from(15_000_000u64), limit).unwrap(),
+            Decimal::from(50)
+        );
+        assert_eq!(
+            utilization_pct(U256::zero(), U256::zero()).unwrap(),
+            Decimal::ZERO
+        );
+
+        let base_fee = Decimal::from(16);
+        // Full block: +12.5%; empty block: -12.5%; on target: unchanged
+        assert_eq!(next_base_fee(base_fee, limit, limit), Decimal::from(18));
+        assert_eq!(
+            next_base_fee(base_fee, U256::zero(), limit),
+            Decimal::from(14)
+        );
+        assert_eq!(
+            next_base_fee(base_fee, U256::from(15_000_000u64), limit),
+            base_fee
+        );
+    }
+
+    #[test]
+    fn test_trend() {
+        let points = |values: &[&str]| -> Vec<Decimal> {
+            values
+                .iter()
+                .map(|v| Decimal::from_str(v).unwrap())
+                .collect()
+        };
+        let by_points = |older: Decimal, newer: Decimal| newer - older > Decimal::from(5);
+
+        assert_eq!(
+            trend(&points(&["40", "45", "60", "70"]), by_points),
+            Trend::Rising
+        );
+        assert_eq!(
+            trend(&points(&["70", "60", "45", "40"]), by_points),
+            Trend::Falling
+        );
+        assert_eq!(
+            trend(&points(&["50", "52", "49", "51"]), by_points),
+            Trend::Steady
+        );
+        assert_eq!(trend(&points(&["90"]), by_points), Trend::Steady);
+    }
+}
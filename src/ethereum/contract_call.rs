@@ -0,0 +1,237 @@
+use anyhow::{Context, Result};
+use ethers::abi::token::{LenientTokenizer, Tokenizer};
+use ethers::abi::{Function, HumanReadableParser, Token};
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use serde_json::Value;
+
+use super::client::EthClient;
+use super::error::RpcCallContext;
+
+/// Raw and decoded result of a read-only contract call
+#[derive(Debug)]
+pub struct ContractCallResult {
+    pub function: Function,
+    pub raw: Bytes,
+    /// One entry per declared output; empty when the signature declares no return types
+    pub decoded: Vec<Token>,
+}
+
+/// How the call arguments are supplied
+#[derive(Debug)]
+pub enum CallArgs {
+    /// Human-readable values, tokenized against the signature's input types
+    Values(Vec<Value>),
+    /// Already ABI-encoded arguments, appended to the selector as-is
+    Encoded(Bytes),
+}
+
+/// Parse a function signature into an ABI function
+///
+/// Accepts `balanceOf(address)`, the `balanceOf(address)(uint256)` shorthand, or a full
+/// `function balanceOf(address) view returns (uint256)` declaration. Results can only be
+/// decoded when return types are given.
+pub fn parse_signature(signature: &str) -> Result<Function> {
+    let signature = signature.trim();
+    let signature = signature
+        .strip_prefix("function ")
+        .unwrap_or(signature)
+        .trim();
+
+    let declaration = match split_shorthand_returns(signature) {
+        Some((inputs, outputs)) => format!("function {} returns {}", inputs, outputs),
+        None => format!("function {}", signature),
+    };
+
+    // The lexer-based parser, unlike AbiParser, accepts tuple parameters such as V3 swap structs
+    HumanReadableParser::parse_function(&declaration)
+        .map_err(|e| anyhow::anyhow!("Invalid function signature {:?}: {}", signature, e))
+}
+
+/// Split `name(inputs)(outputs)` after the input list, if a bare output list follows it
+fn split_shorthand_returns(signature: &str) -> Option<(&str, &str)> {
+    let open = signature.find('(')?;
+    let mut depth = 0;
+    for (i, c) in signature[open..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    let end = open + i + 1;
+                    let rest = signature[end..].trim();
+                    return rest.starts_with('(').then_some((&signature[..end], rest));
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Build calldata (selector followed by encoded arguments) for a call
+pub fn encode_call(function: &Function, args: &CallArgs) -> Result<Bytes> {
+    let calldata = match args {
+        CallArgs::Values(values) => {
+            if values.len() != function.inputs.len() {
+                anyhow::bail!(
+                    "{} expects {} arguments, got {}",
+                    function.name,
+                    function.inputs.len(),
+                    values.len()
+                );
+            }
+            let tokens = function
+                .inputs
+                .iter()
+                .zip(values)
+                .map(|(param, value)| {
+                    let text = match value {
+                        Value::String(text) => text.clone(),
+                        other => other.to_string(),
+                    };
+                    LenientTokenizer::tokenize(&param.kind, &text).map_err(|e| {
+                        anyhow::anyhow!("Invalid {} argument {:?}: {}", param.kind, text, e)
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            function
+                .encode_input(&tokens)
+                .context("Failed to encode arguments")?
+        }
+        CallArgs::Encoded(data) => {
+            let mut calldata = function.short_signature().to_vec();
+            calldata.extend_from_slice(data);
+            calldata
+        }
+    };
+
+    Ok(calldata.into())
+}
+
+/// `eth_call` an arbitrary function and decode the result against the signature's return types
+pub async fn call_contract(
+    provider: &EthClient,
+    contract: Address,
+    function: Function,
+    args: CallArgs,
+) -> Result<ContractCallResult> {
+    let calldata = encode_call(&function, &args)?;
+    let tx: TypedTransaction = TransactionRequest::new().to(contract).data(calldata).into();
+
+    let code = provider
+        .get_code(contract, None)
+        .await
+        .rpc_context("eth_getCode", Some(contract))?;
+    if code.is_empty() {
+        anyhow::bail!("No contract deployed at {:?}", contract);
+    }
+
+    let raw = provider
+        .call(&tx, None)
+        .await
+        .rpc_context(&function.name, Some(contract))?;
+
+    let decoded = if function.outputs.is_empty() {
+        Vec::new()
+    } else {
+        function.decode_output(&raw).map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to decode {} bytes returned by {} as ({}): {}",
+                raw.len(),
+                function.name,
+                param_types(&function.outputs),
+                e
+            )
+        })?
+    };
+
+    Ok(ContractCallResult {
+        function,
+        raw,
+        decoded,
+    })
+}
+
+/// Comma-separated parameter types, e.g. `address,uint256`
+pub fn param_types(params: &[ethers::abi::Param]) -> String {
+    params
+        .iter()
+        .map(|param| param.kind.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Render a decoded token as JSON; integers become decimal strings so they never lose precision
+pub fn token_to_json(token: &Token) -> Value {
+    match token {
+        Token::Address(address) => Value::String(format!("{:?}", address)),
+        Token::FixedBytes(bytes) | Token::Bytes(bytes) => {
+            Value::String(Bytes::from(bytes.clone()).to_string())
+        }
+        Token::Int(value) => Value::String(I256::from_raw(*value).to_string()),
+        Token::Uint(value) => Value::String(value.to_string()),
+        Token::Bool(value) => Value::Bool(*value),
+        Token::String(value) => Value::String(value.clone()),
+        Token::FixedArray(tokens) | Token::Array(tokens) | Token::Tuple(tokens) => {
+            Value::Array(tokens.iter().map(token_to_json).collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_signature_forms() {
+        let bare = parse_signature("balanceOf(address)").unwrap();
+        assert_eq!(bare.name, "balanceOf");
+        assert_eq!(param_types(&bare.inputs), "address");
+        assert!(bare.outputs.is_empty());
+
+        let shorthand = parse_signature("balanceOf(address)(uint256)").unwrap();
+        assert_eq!(param_types(&shorthand.outputs), "uint256");
+
+        let full =
+            parse_signature("function getReserves() view returns (uint112, uint112, uint32)")
+                .unwrap();
+        assert_eq!(param_types(&full.outputs), "uint112,uint112,uint32");
+
+        assert!(parse_signature("not a signature").is_err());
+    }
+
+    #[test]
+    fn test_encode_call() {
+        let function = parse_signature("balanceOf(address)(uint256)").unwrap();
+        let holder = json!("0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045");
+
+        let calldata = encode_call(&function, &CallArgs::Values(vec![holder])).unwrap();
+        assert_eq!(&calldata[..4], &[0x70, 0xa0, 0x82, 0x31]);
+        assert_eq!(calldata.len(), 36);
+
+        let encoded = Bytes::from(calldata[4..].to_vec());
+        assert_eq!(
+            encode_call(&function, &CallArgs::Encoded(encoded)).unwrap(),
+            calldata
+        );
+
+        assert!(encode_call(&function, &CallArgs::Values(Vec::new())).is_err());
+        assert!(encode_call(&function, &CallArgs::Values(vec![json!("0x12")])).is_err());
+    }
+
+    #[test]
+    fn test_token_to_json() {
+        let minus_one = Token::Int(U256::MAX);
+        assert_eq!(token_to_json(&minus_one), json!("-1"));
+        assert_eq!(
+            token_to_json(&Token::Tuple(vec![
+                Token::Uint(U256::from(42)),
+                Token::Bool(true),
+                Token::Bytes(vec![0xab, 0xcd]),
+            ])),
+            json!(["42", true, "0xabcd"])
+        );
+    }
+}
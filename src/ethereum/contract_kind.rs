@@ -0,0 +1,271 @@
+use anyhow::Result;
+use ethers::abi::{self, Token};
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use serde::Serialize;
+
+use super::client::EthClient;
+use super::error::{is_revert, RpcCallContext};
+use super::storage::{get_storage, parse_slot};
+
+/// Selector of ERC-165 `supportsInterface(bytes4)`
+const SUPPORTS_INTERFACE_SELECTOR: [u8; 4] = [0x01, 0xff, 0xc9, 0xa7];
+/// ERC-165 interface IDs of the NFT standards
+const ERC721_INTERFACE_ID: [u8; 4] = [0x80, 0xac, 0x58, 0xcd];
+const ERC1155_INTERFACE_ID: [u8; 4] = [0xd9, 0xb6, 0x7a, 0x26];
+/// Selectors of ERC20 `decimals()` and `totalSupply()`
+const DECIMALS_SELECTOR: [u8; 4] = [0x31, 0x3c, 0xe5, 0x67];
+const TOTAL_SUPPLY_SELECTOR: [u8; 4] = [0x18, 0x16, 0x0d, 0xdd];
+
+/// EIP-7702 delegation designator prefix: an EOA whose code is `0xef0100 || delegate`
+const EIP7702_PREFIX: [u8; 3] = [0xef, 0x01, 0x00];
+/// EIP-1167 minimal proxy runtime code, around the 20-byte implementation address
+const EIP1167_PREFIX: [u8; 10] = [0x36, 0x3d, 0x3d, 0x37, 0x3d, 0x3d, 0x3d, 0x36, 0x3d, 0x73];
+const EIP1167_SUFFIX: [u8; 15] = [
+    0x5a, 0xf4, 0x3d, 0x82, 0x80, 0x3e, 0x90, 0x3d, 0x91, 0x60, 0x2b, 0x57, 0xfd, 0x5b, 0xf3,
+];
+
+/// Best guess at what an address is, from its code and the interfaces it answers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContractKind {
+    /// No code: a plain externally owned account (or an address nobody has deployed to)
+    Eoa,
+    /// An EOA that delegated its code to a contract with EIP-7702
+    DelegatedEoa,
+    Erc20,
+    Erc721,
+    Erc1155,
+    /// A proxy whose implementation answered none of the probed standards
+    Proxy,
+    /// Code that answered none of the probed standards
+    Unknown,
+}
+
+impl ContractKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            ContractKind::Eoa => "EOA",
+            ContractKind::DelegatedEoa => "EOA with EIP-7702 delegation",
+            ContractKind::Erc20 => "ERC20 token",
+            ContractKind::Erc721 => "ERC721 NFT",
+            ContractKind::Erc1155 => "ERC1155 multi-token",
+            ContractKind::Proxy => "proxy",
+            ContractKind::Unknown => "unknown contract",
+        }
+    }
+}
+
+/// How a proxy finds its implementation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProxyKind {
+    /// Implementation address in the EIP-1967 implementation slot
+    Eip1967,
+    /// Beacon address in the EIP-1967 beacon slot; the beacon names the implementation
+    Eip1967Beacon,
+    /// EIP-1167 minimal proxy ("clone") with the implementation in its bytecode
+    Eip1167,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ProxyInfo {
+    pub kind: ProxyKind,
+    /// The implementation, or the beacon for `Eip1967Beacon`
+    pub target: Address,
+}
+
+#[derive(Debug, Clone)]
+pub struct AddressInfo {
+    pub address: Address,
+    pub code_size: usize,
+    pub kind: ContractKind,
+    /// Standards the address answered for, e.g. `ERC20`
+    pub interfaces: Vec<&'static str>,
+    pub proxy: Option<ProxyInfo>,
+    /// Contract an EIP-7702 EOA delegates to
+    pub delegate: Option<Address>,
+}
+
+impl AddressInfo {
+    /// Whether the address has code; EIP-7702 EOAs have a delegation designator, not a contract
+    pub fn is_contract(&self) -> bool {
+        !matches!(self.kind, ContractKind::Eoa | ContractKind::DelegatedEoa)
+    }
+}
+
+/// Check whether `address` holds code, and guess its type from the standard interfaces it answers
+///
+/// Probes are `eth_call`s of ERC-165 `supportsInterface` for ERC721 and ERC1155, and of ERC20
+/// `decimals()` and `totalSupply()`, plus the EIP-1967 proxy slots. A probe that reverts or
+/// returns nothing counts as unsupported; transport failures are errors.
+pub async fn inspect_address(provider: &EthClient, address: Address) -> Result<AddressInfo> {
+    let code = provider
+        .get_code(address, None)
+        .await
+        .rpc_context("eth_getCode", Some(address))?;
+
+    let mut info = AddressInfo {
+        address,
+        code_size: code.len(),
+        kind: ContractKind::Eoa,
+        interfaces: Vec::new(),
+        proxy: None,
+        delegate: None,
+    };
+    if code.is_empty() {
+        return Ok(info);
+    }
+    if let Some(delegate) = eip7702_delegate(&code) {
+        info.kind = ContractKind::DelegatedEoa;
+        info.delegate = Some(delegate);
+        return Ok(info);
+    }
+
+    let (erc721, erc1155, decimals, total_supply, proxy) = tokio::join!(
+        supports_interface(provider, address, ERC721_INTERFACE_ID),
+        supports_interface(provider, address, ERC1155_INTERFACE_ID),
+        probe(provider, address, "decimals", DECIMALS_SELECTOR.to_vec()),
+        probe(
+            provider,
+            address,
+            "totalSupply",
+            TOTAL_SUPPLY_SELECTOR.to_vec()
+        ),
+        detect_proxy(provider, address, &code)
+    );
+    // ERC721 collections often have totalSupply too, but never decimals
+    let erc20 =
+        decimals?.is_some_and(|word| word <= U256::from(u8::MAX)) && total_supply?.is_some();
+    for (supported, name) in [(erc20, "ERC20"), (erc721?, "ERC721"), (erc1155?, "ERC1155")] {
+        if supported {
+            info.interfaces.push(name);
+        }
+    }
+    info.proxy = proxy?;
+    info.kind = guess_kind(&info.interfaces, info.proxy.is_some());
+    Ok(info)
+}
+
+/// The most specific kind for what answered; calls through a proxy reach its implementation
+fn guess_kind(interfaces: &[&str], is_proxy: bool) -> ContractKind {
+    if interfaces.contains(&"ERC721") {
+        ContractKind::Erc721
+    } else if interfaces.contains(&"ERC1155") {
+        ContractKind::Erc1155
+    } else if interfaces.contains(&"ERC20") {
+        ContractKind::Erc20
+    } else if is_proxy {
+        ContractKind::Proxy
+    } else {
+        ContractKind::Unknown
+    }
+}
+
+/// ERC-165 `supportsInterface(interface_id)`, false when the contract doesn't implement ERC-165
+async fn supports_interface(
+    provider: &EthClient,
+    address: Address,
+    interface_id: [u8; 4],
+) -> Result<bool> {
+    let mut calldata = SUPPORTS_INTERFACE_SELECTOR.to_vec();
+    calldata.extend(abi::encode(&[Token::FixedBytes(interface_id.to_vec())]));
+    let word = probe(provider, address, "supportsInterface", calldata).await?;
+    Ok(word == Some(U256::one()))
+}
+
+/// First return word of a view call, or `None` when it reverts or returns too little
+async fn probe(
+    provider: &EthClient,
+    address: Address,
+    method: &str,
+    calldata: Vec<u8>,
+) -> Result<Option<U256>> {
+    let tx: TypedTransaction = TransactionRequest::new().to(address).data(calldata).into();
+    match provider
+        .call(&tx, None)
+        .await
+        .rpc_context(method, Some(address))
+    {
+        Ok(output) if output.len() >= 32 => Ok(Some(U256::from_big_endian(&output[..32]))),
+        Ok(_) => Ok(None),
+        Err(e) if is_revert(&e) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+async fn detect_proxy(
+    provider: &EthClient,
+    address: Address,
+    code: &[u8],
+) -> Result<Option<ProxyInfo>> {
+    if let Some(target) = eip1167_implementation(code) {
+        return Ok(Some(ProxyInfo {
+            kind: ProxyKind::Eip1167,
+            target,
+        }));
+    }
+    for (slot, kind) in [
+        ("eip1967.implementation", ProxyKind::Eip1967),
+        ("eip1967.beacon", ProxyKind::Eip1967Beacon),
+    ] {
+        let value = get_storage(provider, address, parse_slot(slot)?, None).await?;
+        if !value.as_address.is_zero() {
+            return Ok(Some(ProxyInfo {
+                kind,
+                target: value.as_address,
+            }));
+        }
+    }
+    Ok(None)
+}
+
+/// The delegate of an EIP-7702 delegation designator
+fn eip7702_delegate(code: &[u8]) -> Option<Address> {
+    match code.strip_prefix(&EIP7702_PREFIX[..]) {
+        Some(delegate) if delegate.len() == 20 => Some(Address::from_slice(delegate)),
+        _ => None,
+    }
+}
+
+/// The implementation baked into EIP-1167 minimal proxy code
+fn eip1167_implementation(code: &[u8]) -> Option<Address> {
+    let implementation = code
+        .strip_prefix(&EIP1167_PREFIX[..])?
+        .strip_suffix(&EIP1167_SUFFIX[..])?;
+    (implementation.len() == 20).then(|| Address::from_slice(implementation))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_patterns() {
+        let target = Address::from_low_u64_be(0xabc);
+
+        let mut designator = EIP7702_PREFIX.to_vec();
+        designator.extend_from_slice(target.as_bytes());
+        assert_eq!(eip7702_delegate(&designator), Some(target));
+        assert_eq!(eip7702_delegate(&designator[..22]), None);
+
+        let mut clone = EIP1167_PREFIX.to_vec();
+        clone.extend_from_slice(target.as_bytes());
+        clone.extend_from_slice(&EIP1167_SUFFIX);
+        assert_eq!(eip1167_implementation(&clone), Some(target));
+        assert_eq!(eip1167_implementation(&clone[1..]), None);
+        assert_eq!(eip1167_implementation(&designator), None);
+    }
+
+    #[test]
+    fn test_guess_kind() {
+        assert_eq!(guess_kind(&["ERC20"], true), ContractKind::Erc20);
+        assert_eq!(
+            guess_kind(&["ERC20", "ERC721"], false),
+            ContractKind::Erc721
+        );
+        assert_eq!(guess_kind(&["ERC1155"], false), ContractKind::Erc1155);
+        assert_eq!(guess_kind(&[], true), ContractKind::Proxy);
+        assert_eq!(guess_kind(&[], false), ContractKind::Unknown);
+    }
+}
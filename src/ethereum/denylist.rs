@@ -0,0 +1,105 @@
+use ethers::prelude::*;
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Tokens operators have blocked from every tool, e.g. known scams
+#[derive(Debug, Clone, Default)]
+pub struct TokenDenylist {
+    tokens: HashSet<Address>,
+}
+
+impl TokenDenylist {
+    /// Parse one address per line; blank lines and `#` comments are skipped, invalid entries logged
+    ///
+    /// Unlike watched wallets, checksums aren't required: published scam lists are often lowercase.
+    pub fn parse(contents: &str) -> Self {
+        let mut tokens = HashSet::new();
+        for (index, line) in contents.lines().enumerate() {
+            let entry = line.split('#').next().unwrap_or_default().trim();
+            if entry.is_empty() {
+                continue;
+            }
+            match entry.parse::<Address>() {
+                Ok(address) => {
+                    tokens.insert(address);
+                }
+                Err(e) => tracing::warn!(
+                    "Skipping denylisted token on line {}: {} ({})",
+                    index + 1,
+                    entry,
+                    e
+                ),
+            }
+        }
+        Self { tokens }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    pub fn contains(&self, token: &Address) -> bool {
+        self.tokens.contains(token)
+    }
+
+    /// First denylisted address anywhere in a tool's arguments, including nested arrays such as swap paths
+    pub fn find_in_args(&self, args: &Value) -> Option<Address> {
+        if self.is_empty() {
+            return None;
+        }
+        match args {
+            Value::String(value) => value
+                .trim()
+                .parse::<Address>()
+                .ok()
+                .filter(|address| self.contains(address)),
+            Value::Array(values) => values.iter().find_map(|value| self.find_in_args(value)),
+            Value::Object(fields) => fields.values().find_map(|value| self.find_in_args(value)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const SCAM: &str = "0x1111111111111111111111111111111111111111";
+
+    #[test]
+    fn test_parse_denylist() {
+        let denylist = TokenDenylist::parse(&format!(
+            "# scams\n{}\n\n0x2222222222222222222222222222222222222222 # airdrop bait\nnot-an-address\n",
+            SCAM
+        ));
+        assert_eq!(denylist.len(), 2);
+        assert!(denylist.contains(&SCAM.parse().unwrap()));
+    }
+
+    #[test]
+    fn test_find_in_args() {
+        let denylist = TokenDenylist::parse(SCAM);
+        let scam: Address = SCAM.parse().unwrap();
+
+        assert_eq!(
+            denylist.find_in_args(&json!({"token_address": SCAM})),
+            Some(scam)
+        );
+        assert_eq!(
+            denylist.find_in_args(&json!({
+                "from_token": "0x0000000000000000000000000000000000000000",
+                "path": ["0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2", SCAM.to_uppercase().replace("0X", "0x")]
+            })),
+            Some(scam)
+        );
+        assert_eq!(
+            denylist.find_in_args(&json!({"token_address": "USDC", "amount": "1"})),
+            None
+        );
+    }
+}
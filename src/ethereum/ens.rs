@@ -0,0 +1,60 @@
+use anyhow::Result;
+use ethers::prelude::*;
+
+use super::client::EthClient;
+use super::error::RpcCallContext;
+
+/// Check an ENS name is dotted and lowercase it, the ASCII part of ENS normalization
+pub fn normalize_name(name: &str) -> Result<String> {
+    let name = name.trim().to_lowercase();
+    if name.split('.').count() < 2 || name.split('.').any(str::is_empty) {
+        anyhow::bail!(
+            "Invalid ENS name {:?}: expected a dotted name like vitalik.eth",
+            name
+        );
+    }
+    Ok(name)
+}
+
+/// Resolve an ENS name to its address
+///
+/// `None` means the name has no resolver or no address record; network and RPC failures are
+/// errors.
+pub async fn resolve_ens_name(provider: &EthClient, name: &str) -> Result<Option<Address>> {
+    match provider.resolve_name(name).await {
+        Err(ProviderError::EnsError(_)) => Ok(None),
+        result => result
+            .map(|address| (!address.is_zero()).then_some(address))
+            .rpc_context("ens_resolve", None),
+    }
+}
+
+/// Look up an address's primary ENS name
+///
+/// `None` means no reverse record, or a reverse record whose name doesn't resolve back to the
+/// address; ENS treats such a name as unverified, so it isn't returned.
+pub async fn lookup_ens_name(provider: &EthClient, address: Address) -> Result<Option<String>> {
+    match provider.lookup_address(address).await {
+        Err(ProviderError::EnsError(_)) | Err(ProviderError::EnsNotOwned(_)) => Ok(None),
+        result => result
+            .map(|name| (!name.is_empty()).then_some(name))
+            .rpc_context("ens_reverse", None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_name() {
+        assert_eq!(normalize_name(" Vitalik.ETH ").unwrap(), "vitalik.eth");
+        assert_eq!(
+            normalize_name("pay.vitalik.eth").unwrap(),
+            "pay.vitalik.eth"
+        );
+        assert!(normalize_name("vitalik").is_err());
+        assert!(normalize_name("vitalik..eth").is_err());
+        assert!(normalize_name(".eth").is_err());
+    }
+}
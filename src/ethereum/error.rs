@@ -0,0 +1,324 @@
+use ethers::abi::{self, ParamType, Token};
+use ethers::prelude::{Address, Bytes, ContractError, ProviderError};
+use ethers::providers::RpcError;
+use serde::Serialize;
+use std::fmt;
+
+use super::client::EthProvider;
+
+/// Broad category of an RPC failure, so callers can decide whether to retry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RpcErrorKind {
+    /// The node could not be reached or the connection failed
+    Transport,
+    /// The node answered with a JSON-RPC error
+    RpcError,
+    /// The call reached the contract and it reverted
+    Revert,
+    /// The response could not be decoded into the expected type
+    Decode,
+    /// No contract code at the target address
+    NotDeployed,
+    /// The node has pruned the state for the requested block
+    MissingState,
+    Other,
+}
+
+impl RpcErrorKind {
+    /// Only transport failures are worth retrying as-is
+    pub fn is_retryable(self) -> bool {
+        matches!(self, RpcErrorKind::Transport)
+    }
+}
+
+/// A failed RPC or contract call, carrying enough context for JSON-RPC error `data`
+#[derive(Debug, thiserror::Error, Serialize)]
+#[error("Failed to call {method}{}: {message}", .contract.map(|c| format!(" on {:?}", c)).unwrap_or_default())]
+pub struct RpcCallError {
+    pub method: String,
+    pub contract: Option<Address>,
+    pub kind: RpcErrorKind,
+    pub retryable: bool,
+    pub message: String,
+    /// Decoded `Error(string)`, `Panic(uint256)` or custom error selector, when the call reverted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+}
+
+impl RpcCallError {
+    fn new(method: &str, contract: Option<Address>, kind: RpcErrorKind, message: String) -> Self {
+        Self {
+            method: method.to_string(),
+            contract,
+            kind,
+            retryable: kind.is_retryable(),
+            message,
+            revert_reason: None,
+        }
+    }
+
+    fn with_revert_data(mut self, data: Option<Bytes>) -> Self {
+        self.revert_reason = data.and_then(|data| decode_revert_reason(&data));
+        if let Some(reason) = &self.revert_reason {
+            self.message = format!("execution reverted: {}", reason);
+        }
+        self
+    }
+}
+
+/// A block, transaction or receipt the node answered with null for
+///
+/// This is an answer rather than a failure: the thing doesn't exist, or doesn't exist yet.
+#[derive(Debug, thiserror::Error)]
+#[error("{what} {id} not found")]
+pub struct NotFound {
+    pub what: &'static str,
+    pub id: String,
+}
+
+/// Turn the null of an RPC lookup into a `NotFound` naming what was queried
+pub fn require_found<T>(
+    value: Option<T>,
+    what: &'static str,
+    id: impl fmt::Debug,
+) -> Result<T, NotFound> {
+    value.ok_or_else(|| NotFound {
+        what,
+        id: format!("{:?}", id),
+    })
+}
+
+/// Find the decoded revert reason of the first `RpcCallError` in an error chain
+pub fn revert_reason(error: &anyhow::Error) -> Option<&str> {
+    error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<RpcCallError>())
+        .and_then(|e| e.revert_reason.as_deref())
+}
+
+/// Whether an error chain contains a contract call that reverted
+pub fn is_revert(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .filter_map(|cause| cause.downcast_ref::<RpcCallError>())
+        .any(|e| e.kind == RpcErrorKind::Revert)
+}
+
+/// Whether an error chain contains an RPC failure caused by pruned historical state
+pub fn is_missing_state(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .filter_map(|cause| cause.downcast_ref::<RpcCallError>())
+        .any(|e| e.kind == RpcErrorKind::MissingState)
+}
+
+/// Messages full nodes return for state outside their pruning window
+const MISSING_STATE_MESSAGES: [&str; 5] = [
+    "missing trie node",
+    "header not found",
+    "pruned",
+    "historical state",
+    "state is not available",
+];
+
+/// Selector of Solidity's `Error(string)`
+const ERROR_STRING_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// Selector of Solidity's `Panic(uint256)`
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Decode revert data into a human-readable reason
+fn decode_revert_reason(data: &[u8]) -> Option<String> {
+    if data.len() < 4 {
+        return None;
+    }
+    let (selector, payload) = data.split_at(4);
+
+    if selector == ERROR_STRING_SELECTOR {
+        match abi::decode(&[ParamType::String], payload).ok()?.pop()? {
+            Token::String(reason) => Some(reason),
+            _ => None,
+        }
+    } else if selector == PANIC_SELECTOR {
+        match abi::decode(&[ParamType::Uint(256)], payload).ok()?.pop()? {
+            Token::Uint(code) => Some(format!("panic code {:#x}", code)),
+            _ => None,
+        }
+    } else {
+        Some(format!(
+            "custom error 0x{}",
+            ethers::utils::hex::encode(selector)
+        ))
+    }
+}
+
+/// Attach the failing method and target to an RPC error
+pub trait RpcCallContext<T> {
+    fn rpc_context(self, method: &str, contract: Option<Address>) -> anyhow::Result<T>;
+}
+
+impl<T> RpcCallContext<T> for Result<T, ContractError<EthProvider>> {
+    fn rpc_context(self, method: &str, contract: Option<Address>) -> anyhow::Result<T> {
+        self.map_err(|e| {
+            RpcCallError::new(method, contract, classify_contract_error(&e), e.to_string())
+                .with_revert_data(contract_revert_data(&e))
+                .into()
+        })
+    }
+}
+
+impl<T> RpcCallContext<T> for Result<T, ProviderError> {
+    fn rpc_context(self, method: &str, contract: Option<Address>) -> anyhow::Result<T> {
+        self.map_err(|e| {
+            RpcCallError::new(method, contract, classify_provider_error(&e), e.to_string())
+                .with_revert_data(provider_revert_data(&e))
+                .into()
+        })
+    }
+}
+
+fn contract_revert_data(error: &ContractError<EthProvider>) -> Option<Bytes> {
+    match error {
+        ContractError::Revert(data) => Some(data.clone()),
+        ContractError::MiddlewareError { e } | ContractError::ProviderError { e } => {
+            provider_revert_data(e)
+        }
+        _ => None,
+    }
+}
+
+fn provider_revert_data(error: &ProviderError) -> Option<Bytes> {
+    error
+        .as_error_response()
+        .and_then(|response| response.as_revert_data())
+}
+
+fn classify_contract_error(error: &ContractError<EthProvider>) -> RpcErrorKind {
+    match error {
+        ContractError::Revert(_) => RpcErrorKind::Revert,
+        ContractError::DecodingError(_)
+        | ContractError::AbiError(_)
+        | ContractError::DetokenizationError(_) => RpcErrorKind::Decode,
+        ContractError::MiddlewareError { e } | ContractError::ProviderError { e } => {
+            classify_provider_error(e)
+        }
+        ContractError::ContractNotDeployed => RpcErrorKind::NotDeployed,
+        _ => RpcErrorKind::Other,
+    }
+}
+
+fn classify_provider_error(error: &ProviderError) -> RpcErrorKind {
+    if let Some(response) = error.as_error_response() {
+        // eth_call reverts come back as JSON-RPC errors carrying revert data
+        if response.as_revert_data().is_some() || response.message.contains("revert") {
+            return RpcErrorKind::Revert;
+        }
+        let message = response.message.to_lowercase();
+        if MISSING_STATE_MESSAGES.iter().any(|m| message.contains(m)) {
+            return RpcErrorKind::MissingState;
+        }
+        return RpcErrorKind::RpcError;
+    }
+    if error.as_serde_error().is_some() {
+        return RpcErrorKind::Decode;
+    }
+
+    match error {
+        ProviderError::JsonRpcClientError(_) | ProviderError::HTTPError(_) => {
+            RpcErrorKind::Transport
+        }
+        ProviderError::SerdeJson(_) | ProviderError::HexError(_) => RpcErrorKind::Decode,
+        _ => RpcErrorKind::Other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::providers::{HttpClientError, JsonRpcError};
+    use ethers::types::H256;
+
+    #[test]
+    fn test_rpc_call_error_message_and_data() {
+        let contract = Address::from_low_u64_be(1);
+        let err = RpcCallError::new(
+            "balanceOf",
+            Some(contract),
+            RpcErrorKind::Revert,
+            "execution reverted".to_string(),
+        );
+
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Failed to call balanceOf on {:?}: execution reverted",
+                contract
+            )
+        );
+
+        let data = serde_json::to_value(&err).unwrap();
+        assert_eq!(data["method"], "balanceOf");
+        assert_eq!(data["kind"], "revert");
+        assert_eq!(data["retryable"], false);
+    }
+
+    #[test]
+    fn test_require_found() {
+        assert_eq!(require_found(Some(7), "Block", 7u64).unwrap(), 7);
+
+        let hash = H256::from_low_u64_be(1);
+        let error = require_found(None::<()>, "Transaction", hash).unwrap_err();
+        assert_eq!(error.id, format!("{:?}", hash));
+        assert!(error.to_string().starts_with("Transaction 0x"));
+    }
+
+    #[test]
+    fn test_decode_revert_reason() {
+        let mut data = ERROR_STRING_SELECTOR.to_vec();
+        data.extend(abi::encode(&[Token::String(
+            "UniswapV2Library: INSUFFICIENT_LIQUIDITY".to_string(),
+        )]));
+        assert_eq!(
+            decode_revert_reason(&data).as_deref(),
+            Some("UniswapV2Library: INSUFFICIENT_LIQUIDITY")
+        );
+
+        let mut panic = PANIC_SELECTOR.to_vec();
+        panic.extend(abi::encode(&[Token::Uint(0x11.into())]));
+        assert_eq!(
+            decode_revert_reason(&panic).as_deref(),
+            Some("panic code 0x11")
+        );
+
+        assert_eq!(
+            decode_revert_reason(&[0xde, 0xad, 0xbe, 0xef]).as_deref(),
+            Some("custom error 0xdeadbeef")
+        );
+        assert_eq!(decode_revert_reason(&[]), None);
+    }
+
+    #[test]
+    fn test_classify_provider_error() {
+        let decode = ProviderError::SerdeJson(serde_json::from_str::<u8>("x").unwrap_err());
+        assert_eq!(classify_provider_error(&decode), RpcErrorKind::Decode);
+
+        let custom = ProviderError::CustomError("boom".to_string());
+        assert_eq!(classify_provider_error(&custom), RpcErrorKind::Other);
+
+        let pruned = ProviderError::JsonRpcClientError(Box::new(HttpClientError::JsonRpcError(
+            JsonRpcError {
+                code: -32000,
+                message: "missing trie node 8f3a... (path ) state 0x... is not available"
+                    .to_string(),
+                data: None,
+            },
+        )));
+        assert_eq!(classify_provider_error(&pruned), RpcErrorKind::MissingState);
+
+        let err: anyhow::Error = Err::<(), _>(pruned)
+            .rpc_context("latestRoundData", None)
+            .unwrap_err();
+        let err = err.context("Chainlink latestRoundData failed");
+        assert!(is_missing_state(&err));
+    }
+}
@@ -0,0 +1,135 @@
+use anyhow::{Context, Result};
+use ethers::prelude::*;
+use std::collections::HashMap;
+
+/// Chainlink ETH/USD aggregator on Ethereum mainnet
+pub(super) const ETH_USD_FEED: &str = "0x5f4eC3Df9cbd43714FE2740f5E3616155c5b8419";
+
+/// Token -> Chainlink USD aggregator, for pricing tokens from on-chain oracles
+#[derive(Debug, Clone, Default)]
+pub struct ChainlinkFeeds {
+    feeds: HashMap<Address, Address>,
+}
+
+impl ChainlinkFeeds {
+    /// Built-in USD feeds for Ethereum mainnet majors
+    pub fn mainnet() -> Self {
+        let majors = [
+            // WETH
+            ("0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2", ETH_USD_FEED),
+            // USDC
+            (
+                "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+                "0x8fFfFfd4AfB6115b954Bd326cbe7B4BA576818f6",
+            ),
+            // USDT
+            (
+                "0xdAC17F958D2ee523a2206206994597C13D831ec7",
+                "0x3E7d1eAB13ad0104d2750B8863b489D65364e32D",
+            ),
+            // DAI
+            (
+                "0x6B175474E89094C44Da98b954EedeAC495271d0F",
+                "0xAed0c38402a5d19df6E4c03F4E2DceD6e29c1ee9",
+            ),
+            // LINK
+            (
+                "0x514910771AF9Ca656af840dff83E8264EcF986CA",
+                "0x2c1d072e956AFFC0D435Cb7AC38EF18d24d9127c",
+            ),
+            // UNI
+            (
+                "0x1f9840a85d5aF5bf1D1762F925BDADdC4201F984",
+                "0x553303d460EE0afB37EdFf9bE42922D8FF63220e",
+            ),
+        ];
+
+        Self {
+            feeds: majors
+                .iter()
+                .map(|(token, feed)| (token.parse().unwrap(), feed.parse().unwrap()))
+                .collect(),
+        }
+    }
+
+    /// Parse a JSON object of token address -> aggregator address
+    pub fn parse_json(contents: &str) -> Result<Self> {
+        let entries: HashMap<String, String> =
+            serde_json::from_str(contents).context("Expected a JSON object of token -> feed")?;
+        let mut feeds = HashMap::with_capacity(entries.len());
+        for (token, feed) in entries {
+            let token = token
+                .trim()
+                .parse::<Address>()
+                .with_context(|| format!("Invalid token address {}", token))?;
+            let feed = feed
+                .trim()
+                .parse::<Address>()
+                .with_context(|| format!("Invalid feed address {} for {:?}", feed, token))?;
+            feeds.insert(token, feed);
+        }
+        Ok(Self { feeds })
+    }
+
+    /// Add `other`'s feeds, replacing any entry for the same token
+    pub fn extend(&mut self, other: ChainlinkFeeds) {
+        self.feeds.extend(other.feeds);
+    }
+
+    pub fn len(&self) -> usize {
+        self.feeds.len()
+    }
+
+    /// USD aggregator for `token`, if one is registered
+    pub fn get(&self, token: Address) -> Option<Address> {
+        self.feeds.get(&token).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINK: &str = "0x514910771AF9Ca656af840dff83E8264EcF986CA";
+
+    #[test]
+    fn test_mainnet_feeds() {
+        let feeds = ChainlinkFeeds::mainnet();
+        let weth: Address = "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"
+            .parse()
+            .unwrap();
+        assert_eq!(feeds.get(weth), Some(ETH_USD_FEED.parse().unwrap()));
+        assert_eq!(feeds.get(Address::zero()), None);
+    }
+
+    #[test]
+    fn test_parse_json_overrides_builtin() {
+        let custom = "0x1111111111111111111111111111111111111111";
+        let mut feeds = ChainlinkFeeds::mainnet();
+        let builtin = feeds.len();
+        feeds.extend(
+            ChainlinkFeeds::parse_json(&format!(
+                r#"{{"{}": "{}", "0x2222222222222222222222222222222222222222": "{}"}}"#,
+                LINK.to_lowercase(),
+                custom,
+                custom
+            ))
+            .unwrap(),
+        );
+
+        assert_eq!(feeds.len(), builtin + 1);
+        assert_eq!(
+            feeds.get(LINK.parse().unwrap()),
+            Some(custom.parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_parse_json_rejects_invalid() {
+        assert!(
+            ChainlinkFeeds::parse_json(r#"["0x1111111111111111111111111111111111111111"]"#)
+                .is_err()
+        );
+        assert!(ChainlinkFeeds::parse_json(&format!(r#"{{"{}": "not-a-feed"}}"#, LINK)).is_err());
+    }
+}
@@ -0,0 +1,438 @@
+use anyhow::{Context, Result};
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+use super::abi::GasPriceOracle;
+use super::client::EthClient;
+use super::error::RpcCallContext;
+use super::swap::wei_to_decimal;
+
+/// GasPriceOracle predeploy address, identical on every OP-stack chain
+const GAS_PRICE_ORACLE: &str = "0x420000000000000000000000000000000000000F";
+
+/// OP-stack L2s (Optimism, Base, Zora, Mode and the OP/Base Sepolia testnets), where
+/// transactions also pay an L1 data fee on top of L2 execution gas
+const OP_STACK_CHAIN_IDS: &[u64] = &[10, 8453, 7777777, 34443, 11155420, 84532];
+
+/// Where a gas price estimate comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GasSource {
+    Node,
+    Etherscan,
+    Blocknative,
+}
+
+impl GasSource {
+    pub fn name(self) -> &'static str {
+        match self {
+            GasSource::Node => "node",
+            GasSource::Etherscan => "etherscan",
+            GasSource::Blocknative => "blocknative",
+        }
+    }
+}
+
+impl FromStr for GasSource {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "node" => Ok(GasSource::Node),
+            "etherscan" => Ok(GasSource::Etherscan),
+            "blocknative" => Ok(GasSource::Blocknative),
+            other => anyhow::bail!(
+                "Unknown gas source: {} (expected node, etherscan or blocknative)",
+                other
+            ),
+        }
+    }
+}
+
+/// Gas price estimate from one source, in gwei
+#[derive(Debug, Default)]
+pub struct GasEstimate {
+    pub gas_price: Option<Decimal>,
+    pub base_fee: Option<Decimal>,
+    pub max_fee_per_gas: Option<Decimal>,
+    pub max_priority_fee_per_gas: Option<Decimal>,
+    pub safe: Option<Decimal>,
+    pub fast: Option<Decimal>,
+}
+
+/// API keys for external gas oracles
+#[derive(Debug, Clone, Copy)]
+pub struct GasOracleKeys<'a> {
+    pub etherscan: Option<&'a str>,
+    pub blocknative: Option<&'a str>,
+}
+
+/// Fetch a gas estimate from the requested source
+pub async fn get_gas_estimate(
+    provider: &EthClient,
+    http: &reqwest::Client,
+    keys: GasOracleKeys<'_>,
+    source: GasSource,
+) -> Result<GasEstimate> {
+    match source {
+        GasSource::Node => get_node_gas(provider).await,
+        GasSource::Etherscan => {
+            let key = keys
+                .etherscan
+                .context("ETHERSCAN_API_KEY is not configured")?;
+            get_etherscan_gas(http, key).await
+        }
+        GasSource::Blocknative => {
+            let key = keys
+                .blocknative
+                .context("BLOCKNATIVE_API_KEY is not configured")?;
+            get_blocknative_gas(http, key).await
+        }
+    }
+}
+
+/// Whether the chain charges an OP-stack L1 data fee
+pub fn is_op_stack(chain_id: u64) -> bool {
+    OP_STACK_CHAIN_IDS.contains(&chain_id)
+}
+
+/// L1 data fee in wei for posting `tx` to L1, as quoted by the GasPriceOracle predeploy
+///
+/// `estimate_gas` only covers L2 execution, so this must be added for the true cost.
+pub async fn get_l1_data_fee(provider: &EthClient, tx: &TypedTransaction) -> Result<U256> {
+    let oracle_address = GAS_PRICE_ORACLE.parse::<Address>().unwrap();
+    GasPriceOracle::new(oracle_address, provider.clone())
+        .get_l1_fee(tx.rlp())
+        .call()
+        .await
+        .rpc_context("getL1Fee", Some(oracle_address))
+}
+
+/// Gas used by a plain native-token transfer to an EOA
+pub const TRANSFER_GAS: u64 = 21_000;
+
+/// What a plain transfer costs on a chain right now, in its native token
+#[derive(Debug)]
+pub struct TransferCost {
+    /// eth_gasPrice, in gwei
+    pub gas_price: Decimal,
+    /// OP-stack L1 data fee on top of execution gas; `None` elsewhere or when it couldn't be read
+    pub l1_data_fee: Option<Decimal>,
+    /// Execution gas at `gas_price` plus any L1 data fee
+    pub cost: Decimal,
+}
+
+/// Price a `TRANSFER_GAS` transfer at the chain's current gas price
+pub async fn get_transfer_cost(provider: &EthClient, chain_id: u64) -> Result<TransferCost> {
+    let gas_price = provider
+        .get_gas_price()
+        .await
+        .rpc_context("eth_gasPrice", None)?;
+    let execution = gas_price.saturating_mul(U256::from(TRANSFER_GAS));
+
+    let l1_data_fee = if is_op_stack(chain_id) {
+        let tx: TypedTransaction = Eip1559TransactionRequest::new()
+            .to(Address::repeat_byte(0x11))
+            .value(U256::one())
+            .gas(TRANSFER_GAS)
+            .max_fee_per_gas(gas_price)
+            .chain_id(chain_id)
+            .into();
+        match get_l1_data_fee(provider, &tx).await {
+            Ok(fee) => Some(fee),
+            Err(e) => {
+                tracing::debug!("L1 data fee unavailable on chain ID {}: {:#}", chain_id, e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    Ok(TransferCost {
+        gas_price: to_gwei(gas_price)?,
+        l1_data_fee: l1_data_fee.map(|fee| wei_to_decimal(fee, 18)).transpose()?,
+        cost: wei_to_decimal(
+            execution.saturating_add(l1_data_fee.unwrap_or_default()),
+            18,
+        )?,
+    })
+}
+
+/// Most blocks one eth_feeHistory call may span; nodes cap it at 1024
+pub const MAX_FEE_HISTORY_BLOCKS: u64 = 1024;
+
+/// One block of EIP-1559 fee history, in gwei
+#[derive(Debug)]
+pub struct FeeHistoryBlock {
+    pub number: u64,
+    pub base_fee: Decimal,
+    /// Fraction of the block's gas limit used, 0 to 1
+    pub gas_used_ratio: f64,
+    /// Priority fee paid at each requested percentile of the block's gas
+    pub priority_fees: Vec<Decimal>,
+}
+
+/// EIP-1559 fee history over recent blocks, with a suggested bid, in gwei
+#[derive(Debug)]
+pub struct Eip1559FeeHistory {
+    pub blocks: Vec<FeeHistoryBlock>,
+    /// Base fee of the block after the newest, which the protocol already fixes
+    pub next_base_fee: Decimal,
+    pub suggested_max_fee_per_gas: Decimal,
+    pub suggested_max_priority_fee_per_gas: Decimal,
+}
+
+/// Fetch fee history for the latest `block_count` blocks at the given reward percentiles
+///
+/// The suggested priority fee is the median across blocks of the highest requested percentile,
+/// so the percentile list also sets how aggressive the bid is. The max fee covers two full
+/// base-fee increases on top of it.
+pub async fn get_fee_history(
+    provider: &EthClient,
+    block_count: u64,
+    percentiles: &[f64],
+) -> Result<Eip1559FeeHistory> {
+    if block_count == 0 || block_count > MAX_FEE_HISTORY_BLOCKS {
+        anyhow::bail!(
+            "block_count must be between 1 and {}",
+            MAX_FEE_HISTORY_BLOCKS
+        );
+    }
+    validate_percentiles(percentiles)?;
+
+    let history = provider
+        .fee_history(block_count, BlockNumber::Latest, percentiles)
+        .await
+        .rpc_context("eth_feeHistory", None)?;
+
+    // base_fee_per_gas has one extra entry: the block after the newest
+    let next_base_fee = *history
+        .base_fee_per_gas
+        .last()
+        .context("Fee history returned no base fees; the chain may predate EIP-1559")?;
+    let oldest = history.oldest_block.as_u64();
+    let blocks = history
+        .base_fee_per_gas
+        .iter()
+        .zip(&history.gas_used_ratio)
+        .enumerate()
+        .map(|(i, (base_fee, gas_used_ratio))| {
+            let rewards = history.reward.get(i).map(Vec::as_slice).unwrap_or_default();
+            Ok(FeeHistoryBlock {
+                number: oldest + i as u64,
+                base_fee: to_gwei(*base_fee)?,
+                gas_used_ratio: *gas_used_ratio,
+                priority_fees: rewards
+                    .iter()
+                    .copied()
+                    .map(to_gwei)
+                    .collect::<Result<_>>()?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let (max_fee, max_priority_fee) = suggest_fees(next_base_fee, &history.reward);
+    Ok(Eip1559FeeHistory {
+        blocks,
+        next_base_fee: to_gwei(next_base_fee)?,
+        suggested_max_fee_per_gas: to_gwei(max_fee)?,
+        suggested_max_priority_fee_per_gas: to_gwei(max_priority_fee)?,
+    })
+}
+
+/// eth_feeHistory wants at least one percentile, each within 0-100 and in ascending order
+fn validate_percentiles(percentiles: &[f64]) -> Result<()> {
+    if percentiles.is_empty() {
+        anyhow::bail!("percentiles must list at least one value");
+    }
+    if let Some(p) = percentiles.iter().find(|p| !(0.0..=100.0).contains(*p)) {
+        anyhow::bail!("Percentile {} is outside 0-100", p);
+    }
+    if percentiles.windows(2).any(|pair| pair[0] > pair[1]) {
+        anyhow::bail!("percentiles must be in ascending order");
+    }
+    Ok(())
+}
+
+/// Suggested (maxFeePerGas, maxPriorityFeePerGas) in wei from a fee history
+fn suggest_fees(next_base_fee: U256, rewards: &[Vec<U256>]) -> (U256, U256) {
+    let mut tips: Vec<U256> = rewards
+        .iter()
+        .filter_map(|block| block.last().copied())
+        .collect();
+    tips.sort();
+    let max_priority_fee = tips.get(tips.len() / 2).copied().unwrap_or_default();
+    (next_base_fee * 2 + max_priority_fee, max_priority_fee)
+}
+
+fn to_gwei(wei: U256) -> Result<Decimal> {
+    wei_to_decimal(wei, 9)
+}
+
+/// eth_gasPrice plus an EIP-1559 estimate derived from fee history
+async fn get_node_gas(provider: &EthClient) -> Result<GasEstimate> {
+    let gas_price = provider
+        .get_gas_price()
+        .await
+        .rpc_context("eth_gasPrice", None)?;
+
+    let base_fee = provider
+        .get_block(BlockNumber::Latest)
+        .await
+        .rpc_context("eth_getBlockByNumber", None)?
+        .and_then(|block| block.base_fee_per_gas);
+
+    // Pre-London chains have no fee history; eth_gasPrice alone is still useful
+    let (max_fee, max_priority_fee) = match provider.estimate_eip1559_fees(None).await {
+        Ok((max_fee, max_priority_fee)) => (Some(max_fee), Some(max_priority_fee)),
+        Err(e) => {
+            tracing::debug!("EIP-1559 fee estimate unavailable: {}", e);
+            (None, None)
+        }
+    };
+
+    Ok(GasEstimate {
+        gas_price: Some(to_gwei(gas_price)?),
+        base_fee: base_fee.map(to_gwei).transpose()?,
+        max_fee_per_gas: max_fee.map(to_gwei).transpose()?,
+        max_priority_fee_per_gas: max_priority_fee.map(to_gwei).transpose()?,
+        safe: None,
+        fast: None,
+    })
+}
+
+/// Etherscan gas oracle (values already in gwei)
+async fn get_etherscan_gas(http: &reqwest::Client, api_key: &str) -> Result<GasEstimate> {
+    let url = format!(
+        "https://api.etherscan.io/api?module=gastracker&action=gasoracle&apikey={}",
+        api_key
+    );
+
+    let data: serde_json::Value = http
+        .get(&url)
+        .send()
+        .await
+        .context("Failed to reach Etherscan")?
+        .error_for_status()
+        .context("Etherscan returned an error")?
+        .json()
+        .await
+        .context("Invalid Etherscan response")?;
+
+    if data.get("status").and_then(|v| v.as_str()) != Some("1") {
+        anyhow::bail!(
+            "Etherscan gas oracle error: {}",
+            data.get("result").cloned().unwrap_or_default()
+        );
+    }
+
+    let field = |name: &str| {
+        data["result"]
+            .get(name)
+            .and_then(|v| v.as_str())
+            .and_then(|v| Decimal::from_str(v).ok())
+    };
+
+    Ok(GasEstimate {
+        gas_price: field("ProposeGasPrice"),
+        base_fee: field("suggestBaseFee"),
+        max_fee_per_gas: None,
+        max_priority_fee_per_gas: None,
+        safe: field("SafeGasPrice"),
+        fast: field("FastGasPrice"),
+    })
+}
+
+/// Blocknative block prices (values already in gwei), keyed by inclusion confidence
+async fn get_blocknative_gas(http: &reqwest::Client, api_key: &str) -> Result<GasEstimate> {
+    let data: serde_json::Value = http
+        .get("https://api.blocknative.com/gasprices/blockprices")
+        .header("Authorization", api_key)
+        .send()
+        .await
+        .context("Failed to reach Blocknative")?
+        .error_for_status()
+        .context("Blocknative returned an error")?
+        .json()
+        .await
+        .context("Invalid Blocknative response")?;
+
+    let block = data["blockPrices"]
+        .get(0)
+        .context("Blocknative response missing blockPrices")?;
+    let estimates = block["estimatedPrices"]
+        .as_array()
+        .context("Blocknative response missing estimatedPrices")?;
+
+    let decimal = |value: &serde_json::Value| value.as_f64().and_then(Decimal::from_f64_retain);
+    let at_confidence = |confidence: u64, key: &str| {
+        estimates
+            .iter()
+            .find(|estimate| estimate["confidence"].as_u64() == Some(confidence))
+            .and_then(|estimate| decimal(&estimate[key]))
+    };
+
+    Ok(GasEstimate {
+        gas_price: at_confidence(90, "price"),
+        base_fee: decimal(&block["baseFeePerGas"]),
+        max_fee_per_gas: at_confidence(90, "maxFeePerGas"),
+        max_priority_fee_per_gas: at_confidence(90, "maxPriorityFeePerGas"),
+        safe: at_confidence(70, "price"),
+        fast: at_confidence(99, "price"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_gas_source() {
+        assert_eq!("Node".parse::<GasSource>().unwrap(), GasSource::Node);
+        assert_eq!(
+            "etherscan".parse::<GasSource>().unwrap(),
+            GasSource::Etherscan
+        );
+        assert!("oracle".parse::<GasSource>().is_err());
+    }
+
+    #[test]
+    fn test_validate_percentiles() {
+        assert!(validate_percentiles(&[10.0, 50.0, 90.0]).is_ok());
+        assert!(validate_percentiles(&[50.0, 50.0]).is_ok());
+        assert!(validate_percentiles(&[]).is_err());
+        assert!(validate_percentiles(&[50.0, 101.0]).is_err());
+        assert!(validate_percentiles(&[90.0, 10.0]).is_err());
+    }
+
+    #[test]
+    fn test_suggest_fees() {
+        let gwei = |n: u64| U256::from(n) * U256::exp10(9);
+        let rewards = vec![
+            vec![gwei(1), gwei(3)],
+            vec![gwei(1), gwei(2)],
+            vec![gwei(1), gwei(10)],
+        ];
+        // Median of the top percentile column: 2, 3, 10 -> 3
+        assert_eq!(suggest_fees(gwei(20), &rewards), (gwei(43), gwei(3)));
+        assert_eq!(suggest_fees(gwei(20), &[]), (gwei(40), U256::zero()));
+    }
+
+    #[test]
+    fn test_is_op_stack() {
+        assert!(is_op_stack(10));
+        assert!(is_op_stack(8453));
+        assert!(!is_op_stack(1));
+        assert!(!is_op_stack(42161));
+    }
+
+    #[test]
+    fn test_to_gwei() {
+        assert_eq!(
+            to_gwei(U256::from(1_500_000_000u64)).unwrap(),
+            Decimal::from_str("1.5").unwrap()
+        );
+    }
+}
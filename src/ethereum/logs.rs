@@ -0,0 +1,247 @@
+use anyhow::Result;
+use ethers::prelude::*;
+use ethers::utils::keccak256;
+use serde_json::{json, Value};
+
+use super::client::EthClient;
+use super::error::RpcCallContext;
+
+/// Largest block span a single log query may cover
+pub const MAX_LOG_BLOCK_RANGE: u64 = 5_000;
+
+#[derive(Debug)]
+pub struct LogEntry {
+    pub address: Address,
+    pub topics: Vec<H256>,
+    pub data: Bytes,
+    pub block_number: Option<u64>,
+    pub transaction_hash: Option<H256>,
+    pub log_index: Option<U256>,
+    /// Event name when topic0 matches a known signature
+    pub event: Option<String>,
+    /// Decoded fields for events whose layout is known
+    pub decoded: Option<Value>,
+}
+
+/// The RPC endpoint refused a log query as too large; fewer blocks per query may succeed
+#[derive(Debug, thiserror::Error)]
+#[error("RPC rejected the log query for blocks {from_block}..={to_block}: {message}. Retry with a smaller block range (chunk the query)")]
+pub struct LogRangeLimited {
+    pub from_block: u64,
+    pub to_block: u64,
+    pub message: String,
+}
+
+/// Query logs for an inclusive block range, decoding well-known events
+pub async fn query_logs(
+    provider: &EthClient,
+    address: Option<Address>,
+    topics: Vec<Option<H256>>,
+    from_block: u64,
+    to_block: u64,
+) -> Result<Vec<LogEntry>> {
+    if to_block < from_block {
+        anyhow::bail!(
+            "from_block ({}) must not be greater than to_block ({})",
+            from_block,
+            to_block
+        );
+    }
+    if to_block - from_block + 1 > MAX_LOG_BLOCK_RANGE {
+        anyhow::bail!(
+            "Block range {}..={} spans {} blocks, exceeding the maximum of {}. Split the query into smaller chunks",
+            from_block,
+            to_block,
+            to_block - from_block + 1,
+            MAX_LOG_BLOCK_RANGE
+        );
+    }
+    if topics.len() > 4 {
+        anyhow::bail!("At most 4 topics are supported, got {}", topics.len());
+    }
+
+    let mut filter = Filter::new().from_block(from_block).to_block(to_block);
+    if let Some(address) = address {
+        filter = filter.address(address);
+    }
+    for (i, topic) in topics.into_iter().enumerate() {
+        // A null topic is a wildcard for that position
+        if let Some(topic) = topic {
+            filter.topics[i] = Some(ValueOrArray::Value(Some(topic)));
+        }
+    }
+
+    let logs = match provider.get_logs(&filter).await {
+        Ok(logs) => logs,
+        Err(e) if is_range_limit_error(&e.to_string()) => {
+            return Err(LogRangeLimited {
+                from_block,
+                to_block,
+                message: e.to_string(),
+            }
+            .into())
+        }
+        Err(e) => return Err::<Vec<LogEntry>, _>(e).rpc_context("eth_getLogs", address),
+    };
+
+    Ok(logs.into_iter().map(decode_log).collect())
+}
+
+/// Query logs for a block range of any length, oldest first, in chunks of at most
+/// `MAX_LOG_BLOCK_RANGE` blocks
+///
+/// A chunk the endpoint rejects as too large is retried in halves, down to single blocks.
+pub async fn query_logs_chunked(
+    provider: &EthClient,
+    address: Option<Address>,
+    topics: Vec<Option<H256>>,
+    from_block: u64,
+    to_block: u64,
+) -> Result<Vec<LogEntry>> {
+    let mut entries = Vec::new();
+    let mut chunk = MAX_LOG_BLOCK_RANGE;
+    let mut start = from_block;
+    while start <= to_block {
+        let end = to_block.min(start.saturating_add(chunk - 1));
+        match query_logs(provider, address, topics.clone(), start, end).await {
+            Ok(logs) => {
+                entries.extend(logs);
+                start = end + 1;
+            }
+            Err(e) if chunk > 1 && e.downcast_ref::<LogRangeLimited>().is_some() => {
+                chunk = (end - start + 1).div_ceil(2);
+                tracing::debug!("{}; retrying {} blocks at a time", e, chunk);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(entries)
+}
+
+/// Providers phrase "too many results / range too large" differently
+fn is_range_limit_error(message: &str) -> bool {
+    let message = message.to_lowercase();
+    [
+        "block range",
+        "range too",
+        "too many",
+        "limit exceeded",
+        "query returned more than",
+    ]
+    .iter()
+    .any(|needle| message.contains(needle))
+}
+
+pub(super) fn event_topic(signature: &str) -> H256 {
+    H256::from(keccak256(signature.as_bytes()))
+}
+
+fn decode_log(log: Log) -> LogEntry {
+    let (event, decoded) = match log.topics.first() {
+        Some(topic0) => decode_known_event(*topic0, &log.topics, &log.data),
+        None => (None, None),
+    };
+
+    LogEntry {
+        address: log.address,
+        topics: log.topics,
+        data: log.data,
+        block_number: log.block_number.map(|n| n.as_u64()),
+        transaction_hash: log.transaction_hash,
+        log_index: log.log_index,
+        event,
+        decoded,
+    }
+}
+
+/// Best-effort decoding of the ERC20 and Uniswap V2 events this server deals with
+fn decode_known_event(
+    topic0: H256,
+    topics: &[H256],
+    data: &Bytes,
+) -> (Option<String>, Option<Value>) {
+    let word =
+        |i: usize| -> Option<U256> { data.get(i * 32..(i + 1) * 32).map(U256::from_big_endian) };
+    let topic_address = |i: usize| -> Option<Address> { topics.get(i).map(|t| Address::from(*t)) };
+
+    if topic0 == event_topic("Transfer(address,address,uint256)") {
+        let decoded = match (topic_address(1), topic_address(2), word(0)) {
+            (Some(from), Some(to), Some(value)) => Some(json!({
+                "from": format!("{:?}", from),
+                "to": format!("{:?}", to),
+                "value": value.to_string(),
+            })),
+            _ => None,
+        };
+        (Some("Transfer".to_string()), decoded)
+    } else if topic0 == event_topic("Approval(address,address,uint256)") {
+        let decoded = match (topic_address(1), topic_address(2), word(0)) {
+            (Some(owner), Some(spender), Some(value)) => Some(json!({
+                "owner": format!("{:?}", owner),
+                "spender": format!("{:?}", spender),
+                "value": value.to_string(),
+            })),
+            _ => None,
+        };
+        (Some("Approval".to_string()), decoded)
+    } else if topic0 == event_topic("Swap(address,uint256,uint256,uint256,uint256,address)") {
+        let decoded = match (word(0), word(1), word(2), word(3)) {
+            (Some(amount0_in), Some(amount1_in), Some(amount0_out), Some(amount1_out)) => {
+                Some(json!({
+                    "amount0In": amount0_in.to_string(),
+                    "amount1In": amount1_in.to_string(),
+                    "amount0Out": amount0_out.to_string(),
+                    "amount1Out": amount1_out.to_string(),
+                }))
+            }
+            _ => None,
+        };
+        (Some("Swap".to_string()), decoded)
+    } else if topic0 == event_topic("Sync(uint112,uint112)") {
+        let decoded = match (word(0), word(1)) {
+            (Some(reserve0), Some(reserve1)) => Some(json!({
+                "reserve0": reserve0.to_string(),
+                "reserve1": reserve1.to_string(),
+            })),
+            _ => None,
+        };
+        (Some("Sync".to_string()), decoded)
+    } else {
+        (None, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_transfer() {
+        let from = Address::from_low_u64_be(1);
+        let to = Address::from_low_u64_be(2);
+        let mut data = [0u8; 32];
+        U256::from(1_000u64).to_big_endian(&mut data);
+
+        let topics = vec![
+            event_topic("Transfer(address,address,uint256)"),
+            H256::from(from),
+            H256::from(to),
+        ];
+        let (event, decoded) = decode_known_event(topics[0], &topics, &Bytes::from(data.to_vec()));
+
+        assert_eq!(event.as_deref(), Some("Transfer"));
+        let decoded = decoded.unwrap();
+        assert_eq!(decoded["from"], format!("{:?}", from));
+        assert_eq!(decoded["to"], format!("{:?}", to));
+        assert_eq!(decoded["value"], "1000");
+    }
+
+    #[test]
+    fn test_range_limit_detection() {
+        assert!(is_range_limit_error(
+            "query returned more than 10000 results"
+        ));
+        assert!(is_range_limit_error("eth_getLogs block range too large"));
+        assert!(!is_range_limit_error("connection refused"));
+    }
+}
@@ -0,0 +1,284 @@
+use ethers::abi::{self, ParamType, Token};
+use ethers::prelude::*;
+use ethers::utils::id;
+use rust_decimal::Decimal;
+use std::time::Duration;
+
+use super::cache::MetadataCache;
+use super::client::EthClient;
+use super::price::WETH_ADDRESS;
+use super::swap::{token_decimals, wei_to_decimal, V2_ROUTER_ADDRESS, V3_ROUTER_ADDRESS};
+
+/// How long pending transaction hashes are collected when `txpool_content` is unavailable
+const PENDING_FILTER_WINDOW: Duration = Duration::from_secs(3);
+
+/// Most pending transactions fetched one by one after a filter sample
+const MAX_FILTER_TRANSACTIONS: usize = 200;
+
+/// Router swaps whose tokens and amounts can be read from calldata
+const SWAP_EXACT_TOKENS_FOR_TOKENS: &str =
+    "swapExactTokensForTokens(uint256,uint256,address[],address,uint256)";
+const SWAP_EXACT_ETH_FOR_TOKENS: &str = "swapExactETHForTokens(uint256,address[],address,uint256)";
+const SWAP_EXACT_TOKENS_FOR_ETH: &str =
+    "swapExactTokensForETH(uint256,uint256,address[],address,uint256)";
+const EXACT_INPUT_SINGLE: &str =
+    "exactInputSingle((address,address,uint24,address,uint256,uint256,uint256,uint160))";
+
+/// Which side of the market a pending swap is on, with its volume in the token's base units
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapSide {
+    /// Buys are sized by their minimum output, a lower bound on the tokens bought
+    Buy(U256),
+    /// Sells are sized by their exact input
+    Sell(U256),
+}
+
+/// Pending Uniswap router swaps involving one token, with volumes in token units
+#[derive(Debug, Clone)]
+pub struct MempoolFlow {
+    /// How the pending transactions were read
+    pub source: &'static str,
+    /// Pending transactions inspected, across all tokens
+    pub inspected: usize,
+    pub buys: usize,
+    pub sells: usize,
+    /// Sum of the buys' minimum outputs
+    pub buy_volume: Decimal,
+    pub sell_volume: Decimal,
+}
+
+/// Summarize pending buy and sell swaps of `token` on the Uniswap V2 and V3 routers
+///
+/// Reads the whole pool via `txpool_content` where the node exposes it, otherwise samples new
+/// pending transactions through `eth_newPendingTransactionFilter` for a few seconds. Fails with
+/// the reason when the endpoint offers neither, as most public RPCs don't.
+pub async fn sample_mempool_flow(
+    provider: &EthClient,
+    cache: &MetadataCache,
+    token: Address,
+) -> Result<MempoolFlow, String> {
+    let token = if token == Address::zero() {
+        WETH_ADDRESS.parse().unwrap()
+    } else {
+        token
+    };
+
+    let (source, transactions) = match provider.txpool_content().await {
+        Ok(content) => {
+            let transactions = content
+                .pending
+                .into_values()
+                .flat_map(|by_nonce| by_nonce.into_values())
+                .collect::<Vec<_>>();
+            ("txpool_content", transactions)
+        }
+        Err(txpool_error) => match sample_pending_filter(provider).await {
+            Ok(transactions) => ("pending transaction filter", transactions),
+            Err(filter_error) => {
+                return Err(format!(
+                    "txpool_content: {}; eth_newPendingTransactionFilter: {}",
+                    txpool_error, filter_error
+                ))
+            }
+        },
+    };
+
+    let routers = [
+        V2_ROUTER_ADDRESS.parse::<Address>().unwrap(),
+        V3_ROUTER_ADDRESS.parse::<Address>().unwrap(),
+    ];
+    let (mut buys, mut sells) = (0, 0);
+    let (mut buy_volume, mut sell_volume) = (U256::zero(), U256::zero());
+    for tx in &transactions {
+        if !tx.to.is_some_and(|to| routers.contains(&to)) {
+            continue;
+        }
+        match classify_swap(&tx.input, tx.value, token) {
+            Some(SwapSide::Buy(amount)) => {
+                buys += 1;
+                buy_volume = buy_volume.saturating_add(amount);
+            }
+            Some(SwapSide::Sell(amount)) => {
+                sells += 1;
+                sell_volume = sell_volume.saturating_add(amount);
+            }
+            None => {}
+        }
+    }
+
+    let decimals = token_decimals(provider, cache, token).await;
+    let to_units = |amount: U256| wei_to_decimal(amount, decimals).map_err(|e| e.to_string());
+    Ok(MempoolFlow {
+        source,
+        inspected: transactions.len(),
+        buys,
+        sells,
+        buy_volume: to_units(buy_volume)?,
+        sell_volume: to_units(sell_volume)?,
+    })
+}
+
+/// Collect pending transactions announced during `PENDING_FILTER_WINDOW`
+async fn sample_pending_filter(provider: &EthClient) -> Result<Vec<Transaction>, ProviderError> {
+    let filter_id = provider.new_filter(FilterKind::PendingTransactions).await?;
+    tokio::time::sleep(PENDING_FILTER_WINDOW).await;
+    let hashes = provider.get_filter_changes::<_, H256>(filter_id).await;
+    if let Err(e) = provider.uninstall_filter(filter_id).await {
+        tracing::debug!("Failed to uninstall pending transaction filter: {}", e);
+    }
+
+    let mut transactions = Vec::new();
+    for hash in hashes?.into_iter().take(MAX_FILTER_TRANSACTIONS) {
+        // Transactions mined or dropped since they were announced are simply skipped
+        if let Ok(Some(tx)) = provider.get_transaction(hash).await {
+            transactions.push(tx);
+        }
+    }
+    Ok(transactions)
+}
+
+/// Decode a router call and tell whether it buys or sells `token`
+fn classify_swap(input: &[u8], value: U256, token: Address) -> Option<SwapSide> {
+    let (token_in, token_out, amount_in, amount_out_min) = decode_swap(input, value)?;
+    if token_in == token {
+        Some(SwapSide::Sell(amount_in))
+    } else if token_out == token {
+        Some(SwapSide::Buy(amount_out_min))
+    } else {
+        None
+    }
+}
+
+/// Input token, output token, input amount and minimum output of a supported router swap
+fn decode_swap(input: &[u8], value: U256) -> Option<(Address, Address, U256, U256)> {
+    if input.len() < 4 {
+        return None;
+    }
+    let (selector, args) = input.split_at(4);
+    let uint = || ParamType::Uint(256);
+    let path = || ParamType::Array(Box::new(ParamType::Address));
+
+    if selector == id(SWAP_EXACT_TOKENS_FOR_TOKENS) || selector == id(SWAP_EXACT_TOKENS_FOR_ETH) {
+        let types = [uint(), uint(), path(), ParamType::Address, uint()];
+        let mut tokens = abi::decode(&types, args).ok()?.into_iter();
+        let amount_in = tokens.next()?.into_uint()?;
+        let amount_out_min = tokens.next()?.into_uint()?;
+        let (first, last) = path_ends(tokens.next()?)?;
+        return Some((first, last, amount_in, amount_out_min));
+    }
+
+    // The input is the ETH attached to the transaction
+    if selector == id(SWAP_EXACT_ETH_FOR_TOKENS) {
+        let types = [uint(), path(), ParamType::Address, uint()];
+        let mut tokens = abi::decode(&types, args).ok()?.into_iter();
+        let amount_out_min = tokens.next()?.into_uint()?;
+        let (first, last) = path_ends(tokens.next()?)?;
+        return Some((first, last, value, amount_out_min));
+    }
+
+    if selector == id(EXACT_INPUT_SINGLE) {
+        let params = ParamType::Tuple(vec![
+            ParamType::Address,
+            ParamType::Address,
+            ParamType::Uint(24),
+            ParamType::Address,
+            uint(),
+            uint(),
+            uint(),
+            ParamType::Uint(160),
+        ]);
+        let fields = abi::decode(&[params], args).ok()?.pop()?.into_tuple()?;
+        return Some((
+            fields.first()?.clone().into_address()?,
+            fields.get(1)?.clone().into_address()?,
+            fields.get(5)?.clone().into_uint()?,
+            fields.get(6)?.clone().into_uint()?,
+        ));
+    }
+
+    None
+}
+
+/// First and last token of a decoded V2 swap path
+fn path_ends(path: Token) -> Option<(Address, Address)> {
+    let path = path.into_array()?;
+    let first = path.first()?.clone().into_address()?;
+    let last = path.last()?.clone().into_address()?;
+    Some((first, last))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn calldata(signature: &str, args: &[Token]) -> Vec<u8> {
+        [&id(signature)[..], &abi::encode(args)].concat()
+    }
+
+    #[test]
+    fn test_classify_swap() {
+        let usdc: Address = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"
+            .parse()
+            .unwrap();
+        let weth: Address = WETH_ADDRESS.parse().unwrap();
+        let path = |tokens: &[Address]| {
+            Token::Array(tokens.iter().map(|token| Token::Address(*token)).collect())
+        };
+
+        let sell = calldata(
+            SWAP_EXACT_TOKENS_FOR_ETH,
+            &[
+                Token::Uint(U256::from(1_000)),
+                Token::Uint(U256::from(1)),
+                path(&[usdc, weth]),
+                Token::Address(Address::zero()),
+                Token::Uint(U256::zero()),
+            ],
+        );
+        assert_eq!(
+            classify_swap(&sell, U256::zero(), usdc),
+            Some(SwapSide::Sell(U256::from(1_000)))
+        );
+
+        let buy = calldata(
+            SWAP_EXACT_ETH_FOR_TOKENS,
+            &[
+                Token::Uint(U256::from(500)),
+                path(&[weth, usdc]),
+                Token::Address(Address::zero()),
+                Token::Uint(U256::zero()),
+            ],
+        );
+        assert_eq!(
+            classify_swap(&buy, U256::exp10(18), usdc),
+            Some(SwapSide::Buy(U256::from(500)))
+        );
+        // Seen from WETH, the same swap sells the attached ETH
+        assert_eq!(
+            classify_swap(&buy, U256::exp10(18), weth),
+            Some(SwapSide::Sell(U256::exp10(18)))
+        );
+
+        let v3_buy = calldata(
+            EXACT_INPUT_SINGLE,
+            &[Token::Tuple(vec![
+                Token::Address(weth),
+                Token::Address(usdc),
+                Token::Uint(U256::from(500)),
+                Token::Address(Address::zero()),
+                Token::Uint(U256::zero()),
+                Token::Uint(U256::exp10(18)),
+                Token::Uint(U256::from(2_000)),
+                Token::Uint(U256::zero()),
+            ])],
+        );
+        assert_eq!(
+            classify_swap(&v3_buy, U256::zero(), usdc),
+            Some(SwapSide::Buy(U256::from(2_000)))
+        );
+
+        let unrelated = Address::from_low_u64_be(1);
+        assert_eq!(classify_swap(&buy, U256::exp10(18), unrelated), None);
+        assert_eq!(classify_swap(&[0xde, 0xad], U256::zero(), usdc), None);
+    }
+}
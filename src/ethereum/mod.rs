@@ -1,9 +1,78 @@
+pub mod abi;
+pub mod aggregator;
+pub mod alerts;
+pub mod backpressure;
 pub mod balance;
+pub mod cache;
+pub mod calldata;
+pub mod chain_guard;
+pub mod chains;
 pub mod client;
+pub mod congestion;
+pub mod contract_call;
+pub mod contract_kind;
+pub mod denylist;
+pub mod ens;
+pub mod error;
+pub mod feeds;
+pub mod gas;
+pub mod logs;
+pub mod mempool;
+pub mod pool;
+pub mod pool_swaps;
+pub mod pools;
+pub mod portfolio;
 pub mod price;
+pub mod rpc_log;
+pub mod slippage;
+pub mod storage;
 pub mod swap;
+pub mod swap_effects;
+pub mod tokens;
+pub mod twap;
+pub mod tx;
 
-pub use balance::{get_eth_balance, get_token_balance, BalanceInfo};
-pub use client::{create_provider, create_signer, create_wallet, EthClient};
-pub use price::{get_token_price, PriceInfo};
-pub use swap::{simulate_swap, SwapSimulation};
+pub use aggregator::get_aggregator_quote;
+pub use alerts::{AlertDirection, PriceAlerts};
+pub use balance::{display_amount, get_eth_balance, get_token_balance, is_tiny_amount};
+pub use cache::MetadataCache;
+pub use calldata::{decode_calldata, known_contract, DecodedCall};
+pub use chain_guard::ChainGuard;
+pub use chains::{native_currency, parse_chain, ChainClient, ChainProviders};
+pub use client::{
+    create_http_client, create_provider, create_wallet, create_wallet_from_mnemonic,
+    fetch_chain_info, rpc_backpressure, user_agent_header, ChainInfo, EthClient,
+    DEFAULT_USER_AGENT,
+};
+pub use congestion::{get_block_congestion, MAX_CONGESTION_BLOCKS};
+pub use contract_call::{call_contract, param_types, parse_signature, token_to_json, CallArgs};
+pub use contract_kind::{inspect_address, ContractKind, ProxyKind};
+pub use denylist::TokenDenylist;
+pub use ens::{lookup_ens_name, normalize_name, resolve_ens_name};
+pub use error::{NotFound, RpcCallError};
+pub use feeds::ChainlinkFeeds;
+pub use gas::{
+    get_fee_history, get_gas_estimate, get_transfer_cost, GasEstimate, GasOracleKeys, GasSource,
+    TransferCost, MAX_FEE_HISTORY_BLOCKS, TRANSFER_GAS,
+};
+pub use logs::{query_logs, MAX_LOG_BLOCK_RANGE};
+pub use mempool::sample_mempool_flow;
+pub use pool::RpcLimits;
+pub use pool_swaps::{get_pool_swaps, summarize_swaps, MAX_POOL_SWAP_BLOCKS};
+pub use pools::get_pool_addresses;
+pub use portfolio::{discover_tokens, get_portfolio, Holding, MAX_PORTFOLIO_TOKENS};
+pub use price::{
+    get_coin_price_usd, get_coingecko_prices, get_eth_price_from_chainlink, get_token_price,
+    get_token_price_at_block, parse_price_sources, CoinGecko, PriceOracles, PriceSource,
+    QuoteCurrency, DEFAULT_COINGECKO_BASE_URL, DEFAULT_PRICE_SOURCES,
+};
+pub use rpc_log::RpcLogging;
+pub use storage::{get_storage, mapping_slot, parse_slot};
+pub use swap::{
+    build_swap_transaction, compare_v2_venues, explore_routes, max_trade_size, simulate_swap,
+    SwapError, SwapRequest, UniswapVersion, VenueQuote, WeiRounding,
+};
+pub use swap_effects::simulate_swap_effects;
+pub use tokens::{normalize_eth, TokenRegistry};
+pub use twap::get_twap;
+pub use tx::{wait_for_confirmations, MAX_CONFIRMATION_WAIT_SECS};
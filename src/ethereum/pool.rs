@@ -0,0 +1,223 @@
+use async_trait::async_trait;
+use ethers::providers::JsonRpcClient;
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::time::Instant;
+
+use super::backpressure::{is_rate_limited, Backpressure};
+
+/// Bounds on outbound RPC traffic; 0 disables a limit
+#[derive(Debug, Clone, Copy)]
+pub struct RpcLimits {
+    /// Most JSON-RPC calls allowed in flight at once
+    pub max_in_flight: usize,
+    /// Most calls started per second against the RPC host
+    pub max_requests_per_sec: u32,
+    /// Rate-limit responses within `busy_window` that mark the server busy
+    pub busy_threshold: u32,
+    pub busy_window: Duration,
+    /// How long the server stays busy once `busy_threshold` is reached
+    pub busy_cooldown: Duration,
+}
+
+impl Default for RpcLimits {
+    fn default() -> Self {
+        Self {
+            max_in_flight: 32,
+            max_requests_per_sec: 0,
+            busy_threshold: 5,
+            busy_window: Duration::from_secs(10),
+            busy_cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Spaces call starts evenly so bursts never exceed the configured rate
+#[derive(Debug)]
+struct RateLimiter {
+    interval: Duration,
+    next_slot: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    fn new(per_sec: u32) -> Self {
+        Self {
+            interval: Duration::from_secs(1) / per_sec,
+            next_slot: Mutex::new(Instant::now()),
+        }
+    }
+
+    async fn wait(&self) {
+        let slot = {
+            let mut next_slot = self.next_slot.lock().unwrap_or_else(|e| e.into_inner());
+            let slot = (*next_slot).max(Instant::now());
+            *next_slot = slot + self.interval;
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
+/// Transport wrapper that caps in-flight calls and optionally rate-limits them
+///
+/// Every contract instance and tool shares the provider, so fan-outs such as portfolio
+/// valuation queue here instead of opening an unbounded number of requests. Rate-limit
+/// responses from the endpoint feed the shared `Backpressure` breaker.
+#[derive(Debug, Clone)]
+pub struct PooledClient<C> {
+    inner: C,
+    permits: Option<Arc<Semaphore>>,
+    rate: Option<Arc<RateLimiter>>,
+    backpressure: Arc<Backpressure>,
+}
+
+impl<C> PooledClient<C> {
+    pub fn new(inner: C, limits: RpcLimits) -> Self {
+        Self {
+            inner,
+            permits: (limits.max_in_flight > 0)
+                .then(|| Arc::new(Semaphore::new(limits.max_in_flight))),
+            rate: (limits.max_requests_per_sec > 0)
+                .then(|| Arc::new(RateLimiter::new(limits.max_requests_per_sec))),
+            backpressure: Arc::new(Backpressure::new(
+                limits.busy_threshold,
+                limits.busy_window,
+                limits.busy_cooldown,
+            )),
+        }
+    }
+
+    pub fn backpressure(&self) -> &Backpressure {
+        &self.backpressure
+    }
+}
+
+#[async_trait]
+impl<C: JsonRpcClient> JsonRpcClient for PooledClient<C> {
+    type Error = C::Error;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        // Wait for a rate slot before taking a permit, so queued calls don't hold permits idle
+        if let Some(rate) = &self.rate {
+            rate.wait().await;
+        }
+        let _permit = match &self.permits {
+            // The semaphore is never closed
+            Some(permits) => Some(permits.acquire().await.expect("RPC semaphore closed")),
+            None => None,
+        };
+
+        let result = self.inner.request(method, params).await;
+        if let Err(e) = &result {
+            if is_rate_limited(e) {
+                self.backpressure.record_rate_limited();
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::providers::HttpClientError;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Debug, Default)]
+    struct CallStats {
+        in_flight: AtomicUsize,
+        peak: AtomicUsize,
+        calls: AtomicUsize,
+    }
+
+    /// Fake transport that records the peak number of concurrent calls
+    #[derive(Debug, Default)]
+    struct CountingClient {
+        stats: Arc<CallStats>,
+    }
+
+    #[async_trait]
+    impl JsonRpcClient for CountingClient {
+        type Error = HttpClientError;
+
+        async fn request<T, R>(&self, _method: &str, _params: T) -> Result<R, Self::Error>
+        where
+            T: Debug + Serialize + Send + Sync,
+            R: DeserializeOwned + Send,
+        {
+            let stats = &self.stats;
+            let now = stats.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            stats.peak.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            stats.in_flight.fetch_sub(1, Ordering::SeqCst);
+            stats.calls.fetch_add(1, Ordering::SeqCst);
+
+            Ok(serde_json::from_value(serde_json::json!("0x1")).unwrap())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_in_flight_cap_under_200_concurrent_calls() {
+        let stats = Arc::new(CallStats::default());
+        let counting = CountingClient {
+            stats: stats.clone(),
+        };
+        let client = Arc::new(PooledClient::new(
+            counting,
+            RpcLimits {
+                max_in_flight: 8,
+                max_requests_per_sec: 0,
+                ..Default::default()
+            },
+        ));
+
+        let mut tasks = tokio::task::JoinSet::new();
+        for _ in 0..200 {
+            let client = client.clone();
+            tasks.spawn(async move {
+                client
+                    .request::<_, String>("eth_blockNumber", ())
+                    .await
+                    .unwrap()
+            });
+        }
+        while let Some(result) = tasks.join_next().await {
+            assert_eq!(result.unwrap(), "0x1");
+        }
+
+        assert_eq!(stats.calls.load(Ordering::SeqCst), 200);
+        let peak = stats.peak.load(Ordering::SeqCst);
+        assert!(peak <= 8, "peak in-flight {} exceeded the cap", peak);
+        assert!(peak > 1, "calls never overlapped");
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_spaces_calls() {
+        let client = PooledClient::new(
+            CountingClient::default(),
+            RpcLimits {
+                max_in_flight: 0,
+                max_requests_per_sec: 100,
+                ..Default::default()
+            },
+        );
+
+        let started = std::time::Instant::now();
+        for _ in 0..11 {
+            client
+                .request::<_, String>("eth_chainId", ())
+                .await
+                .unwrap();
+        }
+
+        // The first call goes immediately, the next ten are spaced 10ms apart
+        assert!(started.elapsed() >= Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,328 @@
+use anyhow::{Context, Result};
+use ethers::prelude::*;
+use rust_decimal::Decimal;
+
+use super::abi::{UniswapV2Pair, UniswapV3Pool};
+use super::cache::MetadataCache;
+use super::client::EthClient;
+use super::error::{is_revert, RpcCallContext};
+use super::logs::{event_topic, query_logs_chunked, LogEntry};
+use super::swap::{token_decimals, wei_to_decimal, UniswapVersion};
+
+/// Largest block window `get_pool_swaps` reads, about 7 hours of mainnet blocks
+pub const MAX_POOL_SWAP_BLOCKS: u64 = 2_000;
+
+const V2_SWAP_EVENT: &str = "Swap(address,uint256,uint256,uint256,uint256,address)";
+const V3_SWAP_EVENT: &str = "Swap(address,address,int256,int256,uint160,uint128,int24)";
+
+/// One swap through the pool, in base units; V3's signed deltas are split into in and out
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolSwap {
+    pub block_number: Option<u64>,
+    pub transaction_hash: Option<H256>,
+    pub amount0_in: U256,
+    pub amount1_in: U256,
+    pub amount0_out: U256,
+    pub amount1_out: U256,
+}
+
+/// Buy and sell volume of the base token, priced in the quote token
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwapVolume {
+    /// Swaps taking the base token out of the pool
+    pub buys: usize,
+    /// Swaps putting the base token into the pool
+    pub sells: usize,
+    pub buy_volume: Decimal,
+    pub sell_volume: Decimal,
+    /// Quote token paid and received for those volumes
+    pub quote_volume: Decimal,
+    /// Quote token per base token, weighted by volume; `None` when nothing traded
+    pub average_price: Option<Decimal>,
+}
+
+#[derive(Debug)]
+pub struct PoolSwaps {
+    pub pool: Address,
+    pub version: UniswapVersion,
+    pub token0: Address,
+    pub token1: Address,
+    pub decimals0: u8,
+    pub decimals1: u8,
+    pub from_block: u64,
+    pub to_block: u64,
+    /// Oldest first
+    pub swaps: Vec<PoolSwap>,
+}
+
+impl PoolSwaps {
+    /// A swap's amounts in token units, in `PoolSwap` field order
+    pub fn token_amounts(&self, swap: &PoolSwap) -> Result<[Decimal; 4]> {
+        Ok([
+            wei_to_decimal(swap.amount0_in, self.decimals0)?,
+            wei_to_decimal(swap.amount1_in, self.decimals1)?,
+            wei_to_decimal(swap.amount0_out, self.decimals0)?,
+            wei_to_decimal(swap.amount1_out, self.decimals1)?,
+        ])
+    }
+}
+
+/// Read the `Swap` events of a Uniswap V2 pair or V3 pool over the latest `blocks` blocks
+///
+/// The pool's version is told apart by `slot0()`, which only V3 pools have.
+pub async fn get_pool_swaps(
+    provider: &EthClient,
+    cache: &MetadataCache,
+    pool: Address,
+    blocks: u64,
+) -> Result<PoolSwaps> {
+    if blocks == 0 || blocks > MAX_POOL_SWAP_BLOCKS {
+        anyhow::bail!(
+            "blocks must be between 1 and {}, got {}",
+            MAX_POOL_SWAP_BLOCKS,
+            blocks
+        );
+    }
+
+    let version = match UniswapV3Pool::new(pool, provider.clone())
+        .slot_0()
+        .call()
+        .await
+        .rpc_context("slot0", Some(pool))
+    {
+        Ok(_) => UniswapVersion::V3,
+        Err(e) if is_revert(&e) => UniswapVersion::V2,
+        Err(e) => return Err(e),
+    };
+    let pair = UniswapV2Pair::new(pool, provider.clone());
+    let (token0, token1) = tokio::try_join!(
+        async {
+            pair.token_0()
+                .call()
+                .await
+                .rpc_context("token0", Some(pool))
+        },
+        async {
+            pair.token_1()
+                .call()
+                .await
+                .rpc_context("token1", Some(pool))
+        }
+    )
+    .with_context(|| format!("{:?} is not a Uniswap V2 pair or V3 pool", pool))?;
+    let (decimals0, decimals1) = tokio::join!(
+        token_decimals(provider, cache, token0),
+        token_decimals(provider, cache, token1)
+    );
+
+    let to_block = provider
+        .get_block_number()
+        .await
+        .context("Failed to get latest block number")?
+        .as_u64();
+    let from_block = to_block.saturating_sub(blocks - 1);
+    let signature = match version {
+        UniswapVersion::V2 => V2_SWAP_EVENT,
+        UniswapVersion::V3 => V3_SWAP_EVENT,
+    };
+    let topics = vec![Some(event_topic(signature))];
+    let logs = query_logs_chunked(provider, Some(pool), topics, from_block, to_block).await?;
+
+    let swaps = logs
+        .iter()
+        .filter_map(|log| decode_swap(version, log))
+        .collect();
+    Ok(PoolSwaps {
+        pool,
+        version,
+        token0,
+        token1,
+        decimals0,
+        decimals1,
+        from_block,
+        to_block,
+        swaps,
+    })
+}
+
+/// The amounts of a V2 or V3 `Swap` log; `None` when the data is too short
+fn decode_swap(version: UniswapVersion, log: &LogEntry) -> Option<PoolSwap> {
+    let word = |i: usize| -> Option<U256> {
+        log.data
+            .get(i * 32..(i + 1) * 32)
+            .map(U256::from_big_endian)
+    };
+    // V3 reports signed pool balance changes: positive amounts were paid in
+    let split = |delta: U256| {
+        let delta = I256::from_raw(delta);
+        if delta.is_negative() {
+            (U256::zero(), delta.unsigned_abs())
+        } else {
+            (delta.into_raw(), U256::zero())
+        }
+    };
+
+    let (amount0_in, amount1_in, amount0_out, amount1_out) = match version {
+        UniswapVersion::V2 => (word(0)?, word(1)?, word(2)?, word(3)?),
+        UniswapVersion::V3 => {
+            let ((in0, out0), (in1, out1)) = (split(word(0)?), split(word(1)?));
+            (in0, in1, out0, out1)
+        }
+    };
+    Some(PoolSwap {
+        block_number: log.block_number,
+        transaction_hash: log.transaction_hash,
+        amount0_in,
+        amount1_in,
+        amount0_out,
+        amount1_out,
+    })
+}
+
+/// Total buys and sells of the base token (token0 unless `base_is_token1`)
+pub fn summarize_swaps(
+    swaps: &[PoolSwap],
+    base_is_token1: bool,
+    decimals0: u8,
+    decimals1: u8,
+) -> Result<SwapVolume> {
+    let (mut buys, mut sells) = (0, 0);
+    let (mut buy_volume, mut sell_volume) = (U256::zero(), U256::zero());
+    let mut quote_volume = U256::zero();
+    for swap in swaps {
+        let (base_in, base_out, quote_in, quote_out) = if base_is_token1 {
+            (
+                swap.amount1_in,
+                swap.amount1_out,
+                swap.amount0_in,
+                swap.amount0_out,
+            )
+        } else {
+            (
+                swap.amount0_in,
+                swap.amount0_out,
+                swap.amount1_in,
+                swap.amount1_out,
+            )
+        };
+        // A swap nets one side in and the other out; the larger base leg decides its direction
+        if base_out > base_in {
+            buys += 1;
+            buy_volume = buy_volume.saturating_add(base_out - base_in);
+            quote_volume = quote_volume.saturating_add(quote_in.saturating_sub(quote_out));
+        } else if base_in > base_out {
+            sells += 1;
+            sell_volume = sell_volume.saturating_add(base_in - base_out);
+            quote_volume = quote_volume.saturating_add(quote_out.saturating_sub(quote_in));
+        }
+    }
+
+    let (base_decimals, quote_decimals) = if base_is_token1 {
+        (decimals1, decimals0)
+    } else {
+        (decimals0, decimals1)
+    };
+    let buy_volume = wei_to_decimal(buy_volume, base_decimals)?;
+    let sell_volume = wei_to_decimal(sell_volume, base_decimals)?;
+    let quote_volume = wei_to_decimal(quote_volume, quote_decimals)?;
+    let average_price = buy_volume
+        .checked_add(sell_volume)
+        .and_then(|base_volume| quote_volume.checked_div(base_volume))
+        .map(|price| price.round_dp(18).normalize());
+
+    Ok(SwapVolume {
+        buys,
+        sells,
+        buy_volume,
+        sell_volume,
+        quote_volume,
+        average_price,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn log(words: &[U256]) -> LogEntry {
+        let mut data = Vec::new();
+        for word in words {
+            let mut bytes = [0u8; 32];
+            word.to_big_endian(&mut bytes);
+            data.extend_from_slice(&bytes);
+        }
+        LogEntry {
+            address: Address::zero(),
+            topics: Vec::new(),
+            data: Bytes::from(data),
+            block_number: Some(100),
+            transaction_hash: None,
+            log_index: None,
+            event: None,
+            decoded: None,
+        }
+    }
+
+    #[test]
+    fn test_decode_swap() {
+        let v2 = log(&[U256::from(5), U256::zero(), U256::zero(), U256::from(7)]);
+        let swap = decode_swap(UniswapVersion::V2, &v2).unwrap();
+        assert_eq!(
+            (swap.amount0_in, swap.amount1_out),
+            (U256::from(5), U256::from(7))
+        );
+
+        // token0 paid in, token1 taken out
+        let v3 = log(&[
+            I256::from(5).into_raw(),
+            I256::from(-7).into_raw(),
+            U256::one(),
+            U256::one(),
+            U256::zero(),
+        ]);
+        let swap = decode_swap(UniswapVersion::V3, &v3).unwrap();
+        assert_eq!(swap.amount0_in, U256::from(5));
+        assert_eq!(swap.amount0_out, U256::zero());
+        assert_eq!(swap.amount1_in, U256::zero());
+        assert_eq!(swap.amount1_out, U256::from(7));
+
+        assert!(decode_swap(UniswapVersion::V2, &log(&[U256::one()])).is_none());
+    }
+
+    #[test]
+    fn test_summarize_swaps() {
+        let swap =
+            |amount0_in: u64, amount1_in: u64, amount0_out: u64, amount1_out: u64| PoolSwap {
+                block_number: None,
+                transaction_hash: None,
+                amount0_in: U256::from(amount0_in),
+                amount1_in: U256::from(amount1_in),
+                amount0_out: U256::from(amount0_out),
+                amount1_out: U256::from(amount1_out),
+            };
+        // Buy 2 token0 for 4 token1, then sell 1 token0 for 3 token1
+        let swaps = [swap(0, 4_000, 2_000, 0), swap(1_000, 0, 0, 3_000)];
+
+        let volume = summarize_swaps(&swaps, false, 3, 3).unwrap();
+        assert_eq!((volume.buys, volume.sells), (1, 1));
+        assert_eq!(volume.buy_volume, Decimal::from(2));
+        assert_eq!(volume.sell_volume, Decimal::from(1));
+        assert_eq!(volume.quote_volume, Decimal::from(7));
+        assert_eq!(
+            volume.average_price,
+            Some(Decimal::from_str("2.333333333333333333").unwrap())
+        );
+
+        // From token1's side the same swaps are a sell of 4 and a buy of 3
+        let flipped = summarize_swaps(&swaps, true, 3, 3).unwrap();
+        assert_eq!((flipped.buys, flipped.sells), (1, 1));
+        assert_eq!(flipped.buy_volume, Decimal::from(3));
+        assert_eq!(flipped.sell_volume, Decimal::from(4));
+
+        assert_eq!(
+            summarize_swaps(&[], false, 18, 6).unwrap().average_price,
+            None
+        );
+    }
+}
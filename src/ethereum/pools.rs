@@ -0,0 +1,100 @@
+use anyhow::Result;
+use ethers::prelude::*;
+
+use super::abi::{UniswapV2Factory, UniswapV3Factory};
+use super::client::EthClient;
+use super::error::RpcCallContext;
+use super::price::{UNISWAP_V2_FACTORY, WETH_ADDRESS};
+use super::swap::V3_FEE_TIERS;
+
+/// Uniswap V3 factory on Ethereum mainnet
+const UNISWAP_V3_FACTORY: &str = "0x1F98431c8aD98523631AE4a59f267346ea31F984";
+
+/// Uniswap V2 pair and V3 pool addresses for a token pair; `None` where no pool exists
+#[derive(Debug)]
+pub struct PoolAddresses {
+    pub token_a: Address,
+    pub token_b: Address,
+    pub v2_pair: Option<Address>,
+    pub v3_pools: Vec<(u32, Option<Address>)>,
+}
+
+/// Look up the pools for a pair via the factories, mapping ETH (zero address) to WETH
+pub async fn get_pool_addresses(
+    provider: &EthClient,
+    token_a: Address,
+    token_b: Address,
+    fee_tier: Option<u32>,
+) -> Result<PoolAddresses> {
+    if let Some(fee) = fee_tier {
+        if !V3_FEE_TIERS.contains(&fee) {
+            anyhow::bail!(
+                "Unsupported fee tier {}. Expected one of {:?}",
+                fee,
+                V3_FEE_TIERS
+            );
+        }
+    }
+
+    let weth = WETH_ADDRESS.parse::<Address>().unwrap();
+    let normalize = |token: Address| {
+        if token == Address::zero() {
+            weth
+        } else {
+            token
+        }
+    };
+    let (token_a, token_b) = (normalize(token_a), normalize(token_b));
+    if token_a == token_b {
+        anyhow::bail!("Both tokens resolve to the same address {:?}", token_a);
+    }
+
+    let v2_factory_address = UNISWAP_V2_FACTORY.parse::<Address>().unwrap();
+    let v2_factory = UniswapV2Factory::new(v2_factory_address, provider.clone());
+    let v2_pair = v2_factory
+        .get_pair(token_a, token_b)
+        .call()
+        .await
+        .rpc_context("getPair", Some(v2_factory_address))?;
+
+    let v3_factory_address = UNISWAP_V3_FACTORY.parse::<Address>().unwrap();
+    let v3_factory = UniswapV3Factory::new(v3_factory_address, provider.clone());
+    let tiers = match fee_tier {
+        Some(fee) => vec![fee],
+        None => V3_FEE_TIERS.to_vec(),
+    };
+
+    let mut v3_pools = Vec::with_capacity(tiers.len());
+    for fee in tiers {
+        let pool = v3_factory
+            .get_pool(token_a, token_b, fee)
+            .call()
+            .await
+            .rpc_context("getPool", Some(v3_factory_address))?;
+        v3_pools.push((fee, non_zero(pool)));
+    }
+
+    Ok(PoolAddresses {
+        token_a,
+        token_b,
+        v2_pair: non_zero(v2_pair),
+        v3_pools,
+    })
+}
+
+/// Factories return the zero address when no pool exists
+fn non_zero(address: Address) -> Option<Address> {
+    (!address.is_zero()).then_some(address)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_non_zero() {
+        assert_eq!(non_zero(Address::zero()), None);
+        let pool = Address::from_low_u64_be(7);
+        assert_eq!(non_zero(pool), Some(pool));
+    }
+}
@@ -0,0 +1,193 @@
+use anyhow::{Context, Result};
+use ethers::prelude::*;
+use rust_decimal::Decimal;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::task::JoinSet;
+
+use super::balance::{get_eth_balance, get_token_balance};
+use super::cache::MetadataCache;
+use super::client::EthClient;
+use super::logs::{event_topic, query_logs, MAX_LOG_BLOCK_RANGE};
+use super::price::{get_token_price, PriceOracles, PriceSource};
+
+/// Upper bound on tokens valued per call, to keep the concurrent RPC fan-out bounded
+pub const MAX_PORTFOLIO_TOKENS: usize = 50;
+
+/// A non-zero token position, valued when a USD price is available
+#[derive(Debug)]
+pub struct Holding {
+    pub token: Address,
+    pub symbol: String,
+    pub balance: Decimal,
+    pub price_usd: Option<Decimal>,
+    pub value_usd: Option<Decimal>,
+    pub price_source: Option<String>,
+}
+
+/// A token whose balance could not be read (e.g. not an ERC20)
+#[derive(Debug)]
+pub struct FailedToken {
+    pub token: Address,
+    pub error: String,
+}
+
+#[derive(Debug)]
+pub struct Portfolio {
+    pub priced: Vec<Holding>,
+    pub unpriced: Vec<Holding>,
+    pub failed: Vec<FailedToken>,
+    pub total_usd: Decimal,
+}
+
+/// Value a wallet's holdings of `tokens` (`Address::zero()` for ETH), fetching concurrently
+pub async fn get_portfolio(
+    provider: &EthClient,
+    oracles: &PriceOracles<'_>,
+    cache: &Arc<MetadataCache>,
+    wallet: Address,
+    tokens: &[Address],
+) -> Result<Portfolio> {
+    if tokens.len() > MAX_PORTFOLIO_TOKENS {
+        anyhow::bail!(
+            "Too many tokens ({}); at most {} can be valued per call",
+            tokens.len(),
+            MAX_PORTFOLIO_TOKENS
+        );
+    }
+
+    // Tasks outlive the borrow, so each gets its own handle on the oracles
+    let feeds = Arc::new(oracles.feeds.clone());
+    let sources: Arc<[PriceSource]> = oracles.sources.into();
+    let mut tasks = JoinSet::new();
+    for (index, token) in tokens.iter().copied().enumerate() {
+        let provider = provider.clone();
+        let coingecko = oracles.coingecko.clone();
+        let min_liquidity_usd = oracles.min_liquidity_usd;
+        let cache = cache.clone();
+        let feeds = feeds.clone();
+        let sources = sources.clone();
+        tasks.spawn(async move {
+            let oracles = PriceOracles {
+                coingecko,
+                feeds: &feeds,
+                sources: &sources,
+                min_liquidity_usd,
+            };
+            let result = fetch_holding(&provider, &oracles, &cache, wallet, token).await;
+            (index, token, result)
+        });
+    }
+
+    let mut results = Vec::with_capacity(tokens.len());
+    while let Some(joined) = tasks.join_next().await {
+        results.push(joined.context("Portfolio lookup task failed")?);
+    }
+    // Report in the caller's order regardless of completion order
+    results.sort_by_key(|(index, _, _)| *index);
+
+    let mut portfolio = Portfolio {
+        priced: Vec::new(),
+        unpriced: Vec::new(),
+        failed: Vec::new(),
+        total_usd: Decimal::ZERO,
+    };
+    for (_, token, result) in results {
+        match result {
+            Ok(Some(holding)) => match holding.value_usd {
+                Some(value) => {
+                    portfolio.total_usd += value;
+                    portfolio.priced.push(holding);
+                }
+                None => portfolio.unpriced.push(holding),
+            },
+            Ok(None) => {}
+            Err(e) => portfolio.failed.push(FailedToken {
+                token,
+                error: format!("{:#}", e),
+            }),
+        }
+    }
+
+    Ok(portfolio)
+}
+
+/// Balance and price for one token; `None` when the wallet holds none of it
+async fn fetch_holding(
+    provider: &EthClient,
+    oracles: &PriceOracles<'_>,
+    cache: &MetadataCache,
+    wallet: Address,
+    token: Address,
+) -> Result<Option<Holding>> {
+    let balance = async {
+        if token == Address::zero() {
+            get_eth_balance(provider, wallet).await
+        } else {
+            get_token_balance(provider, cache, token, wallet).await
+        }
+    };
+    let (balance, price) = tokio::join!(
+        balance,
+        get_token_price(provider, oracles, token, PriceSource::Auto)
+    );
+
+    let balance = balance?;
+    if balance.raw_balance.is_zero() {
+        return Ok(None);
+    }
+
+    // A missing price is not fatal: the holding is reported as unpriced
+    let price = match price {
+        Ok(price) => Some(price),
+        Err(e) => {
+            tracing::debug!("No price for {:?}: {}", token, e);
+            None
+        }
+    };
+    let price_usd = price.as_ref().and_then(|p| p.price_usd);
+
+    Ok(Some(Holding {
+        token,
+        symbol: balance.symbol,
+        balance: balance.balance,
+        price_usd,
+        value_usd: price_usd.map(|p| p * balance.balance),
+        price_source: price.map(|p| p.source),
+    }))
+}
+
+/// Find tokens the wallet received via ERC20 Transfer logs in the most recent blocks
+pub async fn discover_tokens(provider: &EthClient, wallet: Address) -> Result<Vec<Address>> {
+    let to_block = provider
+        .get_block_number()
+        .await
+        .context("Failed to get latest block number")?
+        .as_u64();
+    let from_block = to_block.saturating_sub(MAX_LOG_BLOCK_RANGE - 1);
+
+    let topics = vec![
+        Some(event_topic("Transfer(address,address,uint256)")),
+        None,
+        Some(H256::from(wallet)),
+    ];
+    let logs = query_logs(provider, None, topics, from_block, to_block).await?;
+
+    let mut seen = HashSet::new();
+    let tokens: Vec<Address> = logs
+        .iter()
+        .map(|log| log.address)
+        .filter(|address| seen.insert(*address))
+        .collect();
+
+    if tokens.len() > MAX_PORTFOLIO_TOKENS {
+        tracing::warn!(
+            "Discovered {} tokens for {:?}; valuing the first {}",
+            tokens.len(),
+            wallet,
+            MAX_PORTFOLIO_TOKENS
+        );
+    }
+
+    Ok(tokens.into_iter().take(MAX_PORTFOLIO_TOKENS).collect())
+}
@@ -2,34 +2,155 @@ use anyhow::{Context, Result};
 use ethers::prelude::*;
 use rust_decimal::Decimal;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::str::FromStr;
+use std::time::Duration;
 
-use super::client::EthClient;
-
-// Uniswap V2 Pair ABI
-abigen!(
-    UniswapV2Pair,
-    r#"[
-        function getReserves() external view returns (uint112 reserve0, uint112 reserve1, uint32 blockTimestampLast)
-        function token0() external view returns (address)
-        function token1() external view returns (address)
-    ]"#,
-);
-
-// Chainlink Price Feed ABI
-abigen!(
-    ChainlinkAggregator,
-    r#"[
-        function latestRoundData() external view returns (uint80 roundId, int256 answer, uint256 startedAt, uint256 updatedAt, uint80 answeredInRound)
-        function decimals() external view returns (uint8)
-    ]"#,
-);
+use super::abi::{ChainlinkAggregator, UniswapV2Factory, UniswapV2Pair, ERC20};
+use super::client::{EthClient, EthProvider};
+use super::error::{is_missing_state, RpcCallContext};
+use super::feeds::{ChainlinkFeeds, ETH_USD_FEED};
+use super::swap::wei_to_decimal;
+use super::tokens::normalize_eth;
+
+/// WETH on Ethereum mainnet
+pub(super) const WETH_ADDRESS: &str = "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2";
+
+/// Uniswap V2 factory on Ethereum mainnet
+pub(super) const UNISWAP_V2_FACTORY: &str = "0x5C69bEe701ef814a2B6a3EDD4B1652CB9cc5aA6f";
+
+/// Decimals assumed when a feed's `decimals()` call fails; what every Chainlink USD feed uses
+const DEFAULT_FEED_DECIMALS: u8 = 8;
+
+/// Attempts per CoinGecko request when failures are transient
+const COINGECKO_MAX_ATTEMPTS: u32 = 3;
+
+/// Delay before the first CoinGecko retry; doubles after each attempt
+const COINGECKO_RETRY_BACKOFF: Duration = Duration::from_millis(250);
+
+/// Public CoinGecko API, used unless `COINGECKO_BASE_URL` points at a proxy or mirror
+pub const DEFAULT_COINGECKO_BASE_URL: &str = "https://api.coingecko.com";
+
+/// Failures talking to external price APIs
+#[derive(Debug, thiserror::Error)]
+pub enum PriceError {
+    #[error("Price API request timed out")]
+    Timeout,
+    #[error("Price API rate limited the request (HTTP 429)")]
+    RateLimited,
+    #[error("Price API returned HTTP {0}")]
+    Status(reqwest::StatusCode),
+    #[error("Price API request failed: {0}")]
+    Request(reqwest::Error),
+    #[error("Token not found in CoinGecko")]
+    NotFound,
+}
+
+impl PriceError {
+    fn from_status(status: reqwest::StatusCode) -> Self {
+        match status {
+            reqwest::StatusCode::NOT_FOUND => PriceError::NotFound,
+            reqwest::StatusCode::TOO_MANY_REQUESTS => PriceError::RateLimited,
+            status => PriceError::Status(status),
+        }
+    }
+
+    /// Timeouts, rate limits and server errors may succeed on retry; a missing token never will
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            PriceError::Timeout | PriceError::RateLimited => true,
+            PriceError::Status(status) => status.is_server_error(),
+            PriceError::Request(_) | PriceError::NotFound => false,
+        }
+    }
+}
+
+impl From<reqwest::Error> for PriceError {
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            PriceError::Timeout
+        } else {
+            PriceError::Request(error)
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct PriceInfo {
     pub price_usd: Option<Decimal>,
     pub price_eth: Option<Decimal>,
     pub source: String,
+    /// Set on the hardcoded ETH price used when no source answers; it is not a market price
+    pub fallback: bool,
+    /// Unix timestamp of the oracle round the price came from, when the source reports one
+    pub updated_at: Option<u64>,
+    /// Block the price was read at, for historical lookups
+    pub block_number: Option<u64>,
+    /// USD value of both sides of the pool a DEX price was derived from
+    pub pool_liquidity_usd: Option<Decimal>,
+    /// Chainlink round the USD price was read from
+    pub round: Option<FeedRound>,
+    /// Assumptions the price rests on, such as a feed's decimals that couldn't be read
+    pub warnings: Vec<String>,
+}
+
+/// The Chainlink aggregator and round behind a price
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedRound {
+    pub feed: Address,
+    pub round_id: u128,
+    /// Round the answer was computed in; behind `round_id` when the answer was carried over
+    pub answered_in_round: u128,
+    pub updated_at: u64,
+}
+
+impl PriceInfo {
+    /// The USD price, unless it is missing, not positive or the hardcoded fallback
+    pub fn market_price_usd(&self) -> Option<Decimal> {
+        self.price_usd
+            .filter(|price| !self.fallback && *price > Decimal::ZERO)
+    }
+}
+
+/// A feed answer that can't be a price, as opposed to a feed that couldn't be reached
+#[derive(Debug, thiserror::Error)]
+#[error("Chainlink feed {feed:?} returned a non-positive answer {answer}")]
+pub struct InvalidFeedAnswer {
+    pub feed: Address,
+    pub answer: I256,
+}
+
+/// CoinGecko API client: the shared HTTP client and the base URL requests go to
+#[derive(Debug, Clone)]
+pub struct CoinGecko {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl CoinGecko {
+    pub fn new(http: &reqwest::Client, base_url: &str) -> Self {
+        Self {
+            http: http.clone(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// GET an `/api/v3` endpoint, retrying transient failures
+    async fn get(&self, path_and_query: &str) -> Result<serde_json::Value, PriceError> {
+        let url = format!("{}/api/v3/{}", self.base_url, path_and_query);
+        fetch_json_with_retry(&self.http, &url).await
+    }
+}
+
+/// The oracles `get_token_price` consults, and the order `Auto` tries them in
+#[derive(Debug, Clone)]
+pub struct PriceOracles<'a> {
+    pub coingecko: CoinGecko,
+    pub feeds: &'a ChainlinkFeeds,
+    /// Sources `Auto` tries, first to last; never contains `Auto` itself
+    pub sources: &'a [PriceSource],
+    /// Uniswap prices from pools holding less than this many USD are rejected as unreliable
+    pub min_liquidity_usd: Decimal,
 }
 
 /// CoinGecko API response
@@ -43,130 +164,1070 @@ struct EthereumPrice {
     usd: Option<f64>,
 }
 
-/// Get token price using multiple sources
+/// Price oracle to query; `Auto` tries them in the configured priority order
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PriceSource {
+    /// The configured sources in order, until one prices the token
+    #[default]
+    Auto,
+    Chainlink,
+    CoinGecko,
+    Uniswap,
+}
+
+impl PriceSource {
+    fn name(self) -> &'static str {
+        match self {
+            PriceSource::Auto => "auto",
+            PriceSource::Chainlink => "chainlink",
+            PriceSource::CoinGecko => "coingecko",
+            PriceSource::Uniswap => "uniswap",
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Unknown price source {0} (expected chainlink, coingecko, uniswap or auto)")]
+pub struct ParsePriceSourceError(String);
+
+impl FromStr for PriceSource {
+    type Err = ParsePriceSourceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(PriceSource::Auto),
+            "chainlink" => Ok(PriceSource::Chainlink),
+            "coingecko" => Ok(PriceSource::CoinGecko),
+            "uniswap" => Ok(PriceSource::Uniswap),
+            _ => Err(ParsePriceSourceError(s.to_string())),
+        }
+    }
+}
+
+/// Order `Auto` tries sources in unless `PRICE_SOURCES` says otherwise
+pub const DEFAULT_PRICE_SOURCES: [PriceSource; 3] = [
+    PriceSource::Chainlink,
+    PriceSource::CoinGecko,
+    PriceSource::Uniswap,
+];
+
+/// Parse a comma-separated source priority such as `chainlink,coingecko,uniswap`
+pub fn parse_price_sources(s: &str) -> Result<Vec<PriceSource>> {
+    let mut sources = Vec::new();
+    for name in s.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+        let source = name.parse::<PriceSource>()?;
+        if source == PriceSource::Auto {
+            anyhow::bail!("auto is not a price source; list the sources to try, in order");
+        }
+        if sources.contains(&source) {
+            anyhow::bail!("Price source {} is listed twice", source.name());
+        }
+        sources.push(source);
+    }
+    if sources.is_empty() {
+        anyhow::bail!("No price sources listed");
+    }
+    Ok(sources)
+}
+
+/// Currency a balance or position can be valued in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteCurrency {
+    Usd,
+    Eth,
+}
+
+impl QuoteCurrency {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            QuoteCurrency::Usd => "USD",
+            QuoteCurrency::Eth => "ETH",
+        }
+    }
+
+    /// Price of one token in this currency, when the source quoted it
+    pub fn unit_price(self, price: &PriceInfo) -> Option<Decimal> {
+        match self {
+            QuoteCurrency::Usd => price.price_usd,
+            QuoteCurrency::Eth => price.price_eth,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Unknown quote currency {0} (expected USD or ETH)")]
+pub struct ParseQuoteCurrencyError(String);
+
+impl FromStr for QuoteCurrency {
+    type Err = ParseQuoteCurrencyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "usd" => Ok(QuoteCurrency::Usd),
+            "eth" => Ok(QuoteCurrency::Eth),
+            _ => Err(ParseQuoteCurrencyError(s.to_string())),
+        }
+    }
+}
+
+/// Get token price from the given source
+///
+/// `Auto` tries `oracles.sources` in order and returns the first price found; a pinned source
+/// either serves the token or fails, naming the source, so callers never get a price from an
+/// oracle they didn't ask for.
 pub async fn get_token_price(
     provider: &EthClient,
+    oracles: &PriceOracles<'_>,
     token_address: Address,
+    source: PriceSource,
 ) -> Result<PriceInfo> {
-    // Special case for ETH
-    if token_address == Address::zero() {
-        return get_eth_price_from_chainlink(provider).await;
+    let token_address = normalize_eth(token_address);
+    if source != PriceSource::Auto {
+        return price_from_source(provider, oracles, token_address, source)
+            .await
+            .with_context(|| {
+                format!(
+                    "Price source {} cannot price {:?}",
+                    source.name(),
+                    token_address
+                )
+            });
     }
 
-    // Try to get price from CoinGecko
-    match get_price_from_coingecko(&token_address).await {
-        Ok(price_info) => Ok(price_info),
-        Err(_) => {
-            // Fallback: estimate from Uniswap pool if available
-            get_price_from_uniswap(provider, token_address).await
+    let mut failures = Vec::new();
+    for &source in oracles.sources {
+        match price_from_source(provider, oracles, token_address, source).await {
+            Ok(price_info) => return Ok(price_info),
+            Err(e) => {
+                tracing::debug!("{} price unavailable: {:#}", source.name(), e);
+                failures.push((source, e));
+            }
+        }
+    }
+
+    // ETH falls back to a default price when every source is unreachable, but not past a
+    // broken feed
+    let broken_feed = failures
+        .iter()
+        .find(|(_, e)| e.downcast_ref::<InvalidFeedAnswer>().is_some());
+    match broken_feed {
+        None if token_address == Address::zero() => {
+            tracing::debug!("No ETH price source available, using the default");
+            Ok(default_eth_price())
+        }
+        _ => {
+            let reasons = failures
+                .iter()
+                .map(|(source, e)| format!("{}: {:#}", source.name(), e))
+                .collect::<Vec<_>>()
+                .join("; ");
+            anyhow::bail!(
+                "No price source could price {:?} ({})",
+                token_address,
+                reasons
+            )
         }
     }
 }
 
-/// Get ETH price from Chainlink price feed
-async fn get_eth_price_from_chainlink(provider: &EthClient) -> Result<PriceInfo> {
-    // ETH/USD Chainlink feed on Ethereum mainnet
-    let eth_usd_feed = "0x5f4eC3Df9cbd43714FE2740f5E3616155c5b8419"
-        .parse::<Address>()
-        .unwrap();
+/// Price a token (`Address::zero()` for ETH) from a single source
+async fn price_from_source(
+    provider: &EthClient,
+    oracles: &PriceOracles<'_>,
+    token_address: Address,
+    source: PriceSource,
+) -> Result<PriceInfo> {
+    let is_eth = token_address == Address::zero();
+    match source {
+        PriceSource::Auto => anyhow::bail!("auto is not a single price source"),
+        PriceSource::Chainlink if is_eth => read_chainlink_eth_price(provider, None).await,
+        PriceSource::Chainlink => match oracles.feeds.get(token_address) {
+            Some(feed) => read_chainlink_token_price(provider, feed, None).await,
+            None => {
+                anyhow::bail!("no Chainlink feed for this token; add one via CHAINLINK_FEEDS_PATH")
+            }
+        },
+        PriceSource::CoinGecko if is_eth => {
+            Ok(get_eth_price_from_coingecko(&oracles.coingecko).await?)
+        }
+        PriceSource::CoinGecko => {
+            Ok(get_price_from_coingecko(&oracles.coingecko, &token_address).await?)
+        }
+        // Uniswap quotes tokens against WETH, so ETH itself has no pool price
+        PriceSource::Uniswap if is_eth => anyhow::bail!("ETH is the quote asset"),
+        PriceSource::Uniswap => {
+            get_price_from_uniswap(provider, token_address, None, oracles.min_liquidity_usd).await
+        }
+    }
+}
 
-    let aggregator = ChainlinkAggregator::new(eth_usd_feed, provider.clone());
+/// Get the price as of a past block, reading on-chain sources at that block
+///
+/// Only Chainlink (ETH and tokens with a feed) and Uniswap V2 (tokens) can be read historically;
+/// CoinGecko serves current prices only. Blocks the node has pruned fail with an archive-node hint.
+pub async fn get_token_price_at_block(
+    provider: &EthClient,
+    feeds: &ChainlinkFeeds,
+    token_address: Address,
+    source: PriceSource,
+    block_number: u64,
+    min_liquidity_usd: Decimal,
+) -> Result<PriceInfo> {
+    let latest = provider
+        .get_block_number()
+        .await
+        .rpc_context("eth_blockNumber", None)?
+        .as_u64();
+    if block_number > latest {
+        anyhow::bail!(
+            "Block {} is ahead of the chain head ({})",
+            block_number,
+            latest
+        );
+    }
 
-    match aggregator.latest_round_data().call().await {
-        Ok((_, answer, _, _, _)) => {
-            let decimals = aggregator.decimals().call().await.unwrap_or(8);
-            let price = Decimal::from(answer.as_u128()) / Decimal::from(10u64.pow(decimals as u32));
+    let token_address = normalize_eth(token_address);
+    let is_eth = token_address == Address::zero();
+    let block = Some(BlockId::from(block_number));
+    let result = match (source, feeds.get(token_address)) {
+        (PriceSource::Auto | PriceSource::Chainlink, _) if is_eth => {
+            read_chainlink_eth_price(provider, block).await
+        }
+        (PriceSource::Auto | PriceSource::Chainlink, Some(feed)) => {
+            read_chainlink_token_price(provider, feed, block).await
+        }
+        (PriceSource::Auto | PriceSource::Uniswap, _) if !is_eth => {
+            get_price_from_uniswap(provider, token_address, block, min_liquidity_usd).await
+        }
+        _ => anyhow::bail!(
+            "Price source {} cannot price {:?} at a past block (historical prices come from Chainlink feeds and Uniswap V2)",
+            source.name(),
+            token_address
+        ),
+    };
 
-            Ok(PriceInfo {
-                price_usd: Some(price),
-                price_eth: Some(Decimal::from(1)),
-                source: "Chainlink".to_string(),
-            })
+    let mut price_info = result.map_err(|e| {
+        if is_missing_state(&e) {
+            e.context(format!(
+                "Block {} is not available on this RPC node; historical prices need an archive node",
+                block_number
+            ))
+        } else {
+            e
         }
-        Err(_) => {
-            // Fallback to a default ETH price
-            Ok(PriceInfo {
-                price_usd: Some(Decimal::from(2000)), // Default fallback
-                price_eth: Some(Decimal::from(1)),
-                source: "Default".to_string(),
-            })
+    })?;
+    price_info.block_number = Some(block_number);
+    Ok(price_info)
+}
+
+/// Get ETH price from Chainlink price feed, falling back to a default when the feed is unreachable
+///
+/// A feed that answers with a non-positive price is broken rather than unreachable, so that is
+/// an error instead of a fallback.
+pub async fn get_eth_price_from_chainlink(provider: &EthClient) -> Result<PriceInfo> {
+    match read_chainlink_eth_price(provider, None).await {
+        Ok(price_info) => Ok(price_info),
+        Err(e) if e.downcast_ref::<InvalidFeedAnswer>().is_some() => Err(e),
+        Err(e) => {
+            tracing::debug!("Chainlink ETH/USD unavailable, using the default: {:#}", e);
+            Ok(default_eth_price())
         }
     }
 }
 
-/// Get price from CoinGecko API
-async fn get_price_from_coingecko(token_address: &Address) -> Result<PriceInfo> {
-    let url = format!(
-        "https://api.coingecko.com/api/v3/simple/token_price/ethereum?contract_addresses={}&vs_currencies=usd,eth",
-        format!("{:?}", token_address).to_lowercase()
-    );
+/// Fallback ETH price for when no source can be reached
+fn default_eth_price() -> PriceInfo {
+    PriceInfo {
+        price_usd: Some(Decimal::from(2000)), // Default fallback
+        price_eth: Some(Decimal::from(1)),
+        source: "Default".to_string(),
+        fallback: true,
+        updated_at: None,
+        block_number: None,
+        pool_liquidity_usd: None,
+        round: None,
+        warnings: Vec::new(),
+    }
+}
 
-    let client = reqwest::Client::new();
-    let response = client
-        .get(&url)
-        .header("accept", "application/json")
-        .send()
-        .await
-        .context("Failed to fetch from CoinGecko")?;
+/// Pin a contract read to `block`, or leave it on latest
+fn at_block<D: abi::Detokenize>(
+    call: ContractCall<EthProvider, D>,
+    block: Option<BlockId>,
+) -> ContractCall<EthProvider, D> {
+    match block {
+        Some(block) => call.block(block),
+        None => call,
+    }
+}
 
-    let data: serde_json::Value = response.json().await?;
+/// Read the Chainlink ETH/USD feed, at `block` or latest
+async fn read_chainlink_eth_price(
+    provider: &EthClient,
+    block: Option<BlockId>,
+) -> Result<PriceInfo> {
+    let (price, round, warning) =
+        read_chainlink_feed(provider, ETH_USD_FEED.parse().unwrap(), block).await?;
 
-    // CoinGecko returns {address: {usd: price, eth: price}}
-    let token_key = format!("{:?}", token_address).to_lowercase();
-    let token_data = data
-        .get(&token_key)
-        .context("Token not found in CoinGecko")?;
-
-    let price_usd = token_data
-        .get("usd")
-        .and_then(|v| v.as_f64())
-        .map(Decimal::from_f64_retain)
-        .flatten();
-
-    let price_eth = token_data
-        .get("eth")
-        .and_then(|v| v.as_f64())
-        .map(Decimal::from_f64_retain)
-        .flatten();
+    Ok(PriceInfo {
+        price_usd: Some(price),
+        price_eth: Some(Decimal::from(1)),
+        source: "Chainlink".to_string(),
+        fallback: false,
+        updated_at: Some(round.updated_at),
+        block_number: None,
+        pool_liquidity_usd: None,
+        round: Some(round),
+        warnings: warning.into_iter().collect(),
+    })
+}
+
+/// Read a token's Chainlink USD feed, at `block` or latest, with its ETH price derived from
+/// the ETH/USD feed at the same block
+async fn read_chainlink_token_price(
+    provider: &EthClient,
+    feed: Address,
+    block: Option<BlockId>,
+) -> Result<PriceInfo> {
+    let (token_price, eth_price) = tokio::join!(
+        read_chainlink_feed(provider, feed, block),
+        read_chainlink_feed(provider, ETH_USD_FEED.parse().unwrap(), block),
+    );
+    let (price_usd, round, warning) = token_price?;
+    let mut warnings: Vec<String> = warning.into_iter().collect();
+    // The USD price stands on its own; ETH is a courtesy conversion
+    let price_eth = match eth_price {
+        Ok((eth_usd, _, eth_warning)) => {
+            warnings.extend(eth_warning);
+            Some(price_usd / eth_usd)
+        }
+        Err(e) => {
+            tracing::debug!("Chainlink ETH/USD unavailable: {:#}", e);
+            None
+        }
+    };
 
     Ok(PriceInfo {
-        price_usd,
+        price_usd: Some(price_usd),
         price_eth,
+        source: "Chainlink".to_string(),
+        fallback: false,
+        updated_at: Some(round.updated_at),
+        block_number: None,
+        pool_liquidity_usd: None,
+        round: Some(round),
+        warnings,
+    })
+}
+
+/// Latest answer of a Chainlink aggregator, scaled by its decimals, the round it came from, and
+/// a warning when the decimals had to be assumed
+async fn read_chainlink_feed(
+    provider: &EthClient,
+    feed: Address,
+    block: Option<BlockId>,
+) -> Result<(Decimal, FeedRound, Option<String>)> {
+    let aggregator = ChainlinkAggregator::new(feed, provider.clone());
+
+    let (round_id, answer, _, updated_at, answered_in_round) =
+        at_block(aggregator.latest_round_data(), block)
+            .call()
+            .await
+            .rpc_context("latestRoundData", Some(feed))
+            .context("Chainlink latestRoundData failed")?;
+    let (decimals, warning) = feed_decimals(feed, aggregator.decimals().call().await);
+    let price = scale_feed_answer(feed, answer, decimals)?;
+
+    Ok((
+        price,
+        FeedRound {
+            feed,
+            round_id,
+            answered_in_round,
+            updated_at: updated_at.as_u64(),
+        },
+        warning,
+    ))
+}
+
+/// The feed's decimals, or `DEFAULT_FEED_DECIMALS` and a warning saying so when the call failed
+fn feed_decimals<E: std::fmt::Display>(
+    feed: Address,
+    decimals: Result<u8, E>,
+) -> (u8, Option<String>) {
+    match decimals {
+        Ok(decimals) => (decimals, None),
+        Err(e) => {
+            tracing::warn!(
+                "Chainlink feed {:?} decimals() failed, assuming {}: {}",
+                feed,
+                DEFAULT_FEED_DECIMALS,
+                e
+            );
+            let warning = format!(
+                "Chainlink feed {:?} did not report its decimals; assumed {}, so the price is wrong if the feed uses another precision",
+                feed, DEFAULT_FEED_DECIMALS
+            );
+            (DEFAULT_FEED_DECIMALS, Some(warning))
+        }
+    }
+}
+
+/// Scale a raw aggregator answer to a price, refusing answers that can't be one
+fn scale_feed_answer(feed: Address, answer: I256, decimals: u8) -> Result<Decimal> {
+    if answer <= I256::zero() {
+        return Err(InvalidFeedAnswer { feed, answer }.into());
+    }
+    let mut price = Decimal::from_str(&answer.to_string())
+        .with_context(|| format!("Chainlink feed {:?} answer {} overflows", feed, answer))?;
+    price.set_scale(u32::from(decimals)).map_err(|e| {
+        anyhow::anyhow!(
+            "Chainlink feed {:?} reports {} decimals: {}",
+            feed,
+            decimals,
+            e
+        )
+    })?;
+    Ok(price.normalize())
+}
+
+/// USD price of a coin by its CoinGecko id (e.g. `binancecoin`), for chains' native tokens
+pub async fn get_coin_price_usd(coingecko: &CoinGecko, coin_id: &str) -> Result<Decimal> {
+    let data = coingecko
+        .get(&format!("simple/price?ids={}&vs_currencies=usd", coin_id))
+        .await?;
+    data[coin_id]["usd"]
+        .as_f64()
+        .and_then(Decimal::from_f64_retain)
+        .filter(|price| *price > Decimal::ZERO)
+        .with_context(|| format!("CoinGecko has no USD price for {}", coin_id))
+}
+
+/// Get the ETH/USD price from CoinGecko
+async fn get_eth_price_from_coingecko(coingecko: &CoinGecko) -> Result<PriceInfo, PriceError> {
+    let data = coingecko
+        .get("simple/price?ids=ethereum&vs_currencies=usd")
+        .await?;
+
+    let price_usd = serde_json::from_value::<CoinGeckoResponse>(data)
+        .ok()
+        .and_then(|response| response.ethereum)
+        .and_then(|ethereum| ethereum.usd)
+        .and_then(Decimal::from_f64_retain)
+        .ok_or(PriceError::NotFound)?;
+
+    Ok(PriceInfo {
+        price_usd: Some(price_usd),
+        price_eth: Some(Decimal::from(1)),
+        source: "CoinGecko".to_string(),
+        fallback: false,
+        updated_at: None,
+        block_number: None,
+        pool_liquidity_usd: None,
+        round: None,
+        warnings: Vec::new(),
+    })
+}
+
+/// Get price from CoinGecko API
+async fn get_price_from_coingecko(
+    coingecko: &CoinGecko,
+    token_address: &Address,
+) -> Result<PriceInfo, PriceError> {
+    let prices = fetch_coingecko_prices(coingecko, *token_address, &["usd", "eth"]).await?;
+
+    Ok(PriceInfo {
+        price_usd: prices.get("usd").copied(),
+        price_eth: prices.get("eth").copied(),
         source: "CoinGecko".to_string(),
+        fallback: false,
+        updated_at: None,
+        block_number: None,
+        pool_liquidity_usd: None,
+        round: None,
+        warnings: Vec::new(),
     })
 }
 
-/// Estimate price from Uniswap V2 pool
+/// Price of a token (ETH for `Address::zero()`) in each currency of the comma-separated
+/// `vs_currencies`, e.g. `eur,gbp,btc`, in the order requested
+///
+/// Codes are checked against CoinGecko's supported list first, so a typo fails clearly instead
+/// of coming back without a price. A currency CoinGecko has no price for maps to `None`.
+pub async fn get_coingecko_prices(
+    coingecko: &CoinGecko,
+    token_address: Address,
+    vs_currencies: &str,
+) -> Result<Vec<(String, Option<Decimal>)>> {
+    let token_address = normalize_eth(token_address);
+    let supported = coingecko
+        .get("simple/supported_vs_currencies")
+        .await
+        .context("Failed to fetch CoinGecko's supported currencies")?;
+    let supported: Vec<String> = serde_json::from_value(supported)
+        .context("Unexpected CoinGecko supported currencies response")?;
+    let currencies = parse_vs_currencies(vs_currencies, &supported)?;
+
+    let codes: Vec<&str> = currencies.iter().map(String::as_str).collect();
+    let prices = fetch_coingecko_prices(coingecko, token_address, &codes).await?;
+
+    Ok(currencies
+        .into_iter()
+        .map(|currency| {
+            let price = prices.get(&currency).copied();
+            (currency, price)
+        })
+        .collect())
+}
+
+/// Lowercase, deduplicated currency codes from a comma-separated list, all of them `supported`
+fn parse_vs_currencies(vs_currencies: &str, supported: &[String]) -> Result<Vec<String>> {
+    let mut currencies: Vec<String> = Vec::new();
+    for code in vs_currencies.split(',').map(str::trim) {
+        let code = code.to_lowercase();
+        if !code.is_empty() && !currencies.contains(&code) {
+            currencies.push(code);
+        }
+    }
+    if currencies.is_empty() {
+        anyhow::bail!("vs_currencies must list at least one currency code");
+    }
+
+    let unsupported: Vec<&str> = currencies
+        .iter()
+        .filter(|code| !supported.contains(code))
+        .map(String::as_str)
+        .collect();
+    if !unsupported.is_empty() {
+        anyhow::bail!(
+            "Unsupported vs_currencies: {}. CoinGecko supports: {}",
+            unsupported.join(", "),
+            supported.join(", ")
+        );
+    }
+    Ok(currencies)
+}
+
+/// Prices keyed by currency code from CoinGecko's simple price endpoints; currencies CoinGecko
+/// has no price for are left out
+async fn fetch_coingecko_prices(
+    coingecko: &CoinGecko,
+    token_address: Address,
+    currencies: &[&str],
+) -> Result<HashMap<String, Decimal>, PriceError> {
+    let vs_currencies = currencies.join(",");
+    let (path, key) = if token_address == Address::zero() {
+        (
+            format!("simple/price?ids=ethereum&vs_currencies={}", vs_currencies),
+            "ethereum".to_string(),
+        )
+    } else {
+        let key = format!("{:?}", token_address).to_lowercase();
+        (
+            format!(
+                "simple/token_price/ethereum?contract_addresses={}&vs_currencies={}",
+                key, vs_currencies
+            ),
+            key,
+        )
+    };
+
+    let data = coingecko.get(&path).await?;
+
+    // CoinGecko returns {address: {usd: price, eth: price}}
+    let prices = data
+        .get(&key)
+        .and_then(|prices| prices.as_object())
+        .ok_or(PriceError::NotFound)?;
+
+    Ok(prices
+        .iter()
+        .filter_map(|(currency, price)| {
+            let price = price.as_f64().and_then(Decimal::from_f64_retain)?;
+            Some((currency.clone(), price))
+        })
+        .collect())
+}
+
+/// GET a JSON document from an external API using the shared client
+async fn fetch_json(http: &reqwest::Client, url: &str) -> Result<serde_json::Value, PriceError> {
+    let response = http
+        .get(url)
+        .header("accept", "application/json")
+        .send()
+        .await?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(PriceError::from_status(status));
+    }
+
+    Ok(response.json().await?)
+}
+
+/// `fetch_json`, retrying transient failures with exponential backoff
+async fn fetch_json_with_retry(
+    http: &reqwest::Client,
+    url: &str,
+) -> Result<serde_json::Value, PriceError> {
+    let mut backoff = COINGECKO_RETRY_BACKOFF;
+    let mut attempt = 1;
+    loop {
+        match fetch_json(http, url).await {
+            Err(e) if e.is_retryable() && attempt < COINGECKO_MAX_ATTEMPTS => {
+                tracing::debug!(
+                    "Price API attempt {} failed, retrying in {:?}: {}",
+                    attempt,
+                    backoff,
+                    e
+                );
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Price a token in ETH from its Uniswap V2 WETH pair reserves, at `block` or latest
+///
+/// The pair's liquidity is valued with the Chainlink ETH/USD feed at the same block; pairs worth
+/// less than `min_liquidity_usd` (zero disables the check) are rejected, since a few dollars of
+/// reserves can be pushed to any price.
 async fn get_price_from_uniswap(
     provider: &EthClient,
     token_address: Address,
+    block: Option<BlockId>,
+    min_liquidity_usd: Decimal,
 ) -> Result<PriceInfo> {
-    // WETH address on mainnet
-    let weth = "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"
-        .parse::<Address>()
-        .unwrap();
+    let weth = WETH_ADDRESS.parse::<Address>().unwrap();
+    let factory_address = UNISWAP_V2_FACTORY.parse::<Address>().unwrap();
+    let factory = UniswapV2Factory::new(factory_address, provider.clone());
 
-    // Common Uniswap V2 factory
-    let factory = "0x5C69bEe701ef814a2B6a3EDD4B1652CB9cc5aA6f"
-        .parse::<Address>()
-        .unwrap();
+    let pair_address = at_block(factory.get_pair(token_address, weth), block)
+        .call()
+        .await
+        .rpc_context("getPair", Some(factory_address))?;
+    if pair_address == Address::zero() {
+        anyhow::bail!("No Uniswap V2 WETH pair for {:?}", token_address);
+    }
+
+    let pair = UniswapV2Pair::new(pair_address, provider.clone());
+    let token0 = pair
+        .token_0()
+        .call()
+        .await
+        .rpc_context("token0", Some(pair_address))?;
+    let (reserve0, reserve1, _) = at_block(pair.get_reserves(), block)
+        .call()
+        .await
+        .rpc_context("getReserves", Some(pair_address))?;
+    let (token_reserve, weth_reserve) = if token0 == token_address {
+        (reserve0, reserve1)
+    } else {
+        (reserve1, reserve0)
+    };
+
+    let decimals = ERC20::new(token_address, provider.clone())
+        .decimals()
+        .call()
+        .await
+        .rpc_context("decimals", Some(token_address))?;
+
+    let pool_liquidity_usd = match read_chainlink_eth_price(provider, block).await {
+        Ok(eth_price) => eth_price
+            .price_usd
+            .map(|eth_usd| pool_liquidity_usd(U256::from(weth_reserve), eth_usd))
+            .transpose()?,
+        // Without a threshold to enforce, an unvalued pool still gives a usable price
+        Err(e) if min_liquidity_usd.is_zero() => {
+            tracing::debug!("Could not value Uniswap V2 pair liquidity: {}", e);
+            None
+        }
+        Err(e) => {
+            return Err(e.context(format!(
+                "Could not value the liquidity of Uniswap V2 pair {:?}",
+                pair_address
+            )))
+        }
+    };
+    if let Some(liquidity) = pool_liquidity_usd {
+        if liquidity < min_liquidity_usd {
+            anyhow::bail!(
+                "Uniswap V2 pair {:?} holds only ${} of liquidity, below the ${} minimum (MIN_POOL_LIQUIDITY_USD); its price is unreliable",
+                pair_address,
+                liquidity.round_dp(2),
+                min_liquidity_usd
+            );
+        }
+    }
 
-    // Calculate pair address (simplified - in production, use factory.getPair)
-    // For now, return an estimate
     Ok(PriceInfo {
         price_usd: None,
-        price_eth: Some(Decimal::from_str("0.001")?), // Placeholder
-        source: "Uniswap V2 (estimated)".to_string(),
+        price_eth: Some(reserves_price(
+            U256::from(token_reserve),
+            decimals,
+            U256::from(weth_reserve),
+        )?),
+        source: "Uniswap V2".to_string(),
+        fallback: false,
+        updated_at: None,
+        block_number: None,
+        pool_liquidity_usd,
+        round: None,
+        warnings: Vec::new(),
     })
 }
 
+/// USD value of a WETH pair, counting the token side as worth the same as the WETH side
+fn pool_liquidity_usd(weth_reserve: U256, eth_usd: Decimal) -> Result<Decimal> {
+    Ok(wei_to_decimal(weth_reserve, 18)? * eth_usd * Decimal::TWO)
+}
+
+/// ETH per token implied by a WETH pair's reserves
+fn reserves_price(token_reserve: U256, token_decimals: u8, weth_reserve: U256) -> Result<Decimal> {
+    let token_amount = wei_to_decimal(token_reserve, token_decimals)?;
+    if token_amount.is_zero() {
+        anyhow::bail!("Uniswap V2 pair has no liquidity");
+    }
+    Ok(wei_to_decimal(weth_reserve, 18)? / token_amount)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use reqwest::header::HeaderMap;
+
+    #[test]
+    fn test_parse_price_source() {
+        assert_eq!("auto".parse::<PriceSource>().unwrap(), PriceSource::Auto);
+        assert_eq!(
+            "CoinGecko".parse::<PriceSource>().unwrap(),
+            PriceSource::CoinGecko
+        );
+        assert_eq!(
+            "chainlink".parse::<PriceSource>().unwrap(),
+            PriceSource::Chainlink
+        );
+        assert!("pyth".parse::<PriceSource>().is_err());
+    }
+
+    #[test]
+    fn test_parse_price_sources() {
+        assert_eq!(
+            parse_price_sources("chainlink,coingecko,uniswap").unwrap(),
+            DEFAULT_PRICE_SOURCES
+        );
+        assert_eq!(
+            parse_price_sources(" Uniswap , chainlink ").unwrap(),
+            [PriceSource::Uniswap, PriceSource::Chainlink]
+        );
+
+        assert!(parse_price_sources("").is_err());
+        assert!(parse_price_sources("auto").is_err());
+        assert!(parse_price_sources("coingecko,pyth").is_err());
+        assert!(parse_price_sources("uniswap,coingecko,uniswap").is_err());
+    }
+
+    #[test]
+    fn test_scale_feed_answer() {
+        let feed: Address = ETH_USD_FEED.parse().unwrap();
+        assert_eq!(
+            scale_feed_answer(feed, I256::from(250_012_345_678i64), 8).unwrap(),
+            Decimal::from_str("2500.12345678").unwrap()
+        );
+
+        let negative = scale_feed_answer(feed, I256::from(-1), 8).unwrap_err();
+        assert!(negative.downcast_ref::<InvalidFeedAnswer>().is_some());
+        assert!(scale_feed_answer(feed, I256::zero(), 8).is_err());
+        // Would have overflowed `as_u128` and `10u64.pow`
+        assert!(scale_feed_answer(feed, I256::MAX, 8).is_err());
+        assert!(scale_feed_answer(feed, I256::from(1), 40).is_err());
+    }
+
+    #[test]
+    fn test_feed_decimals_fallback() {
+        let feed: Address = ETH_USD_FEED.parse().unwrap();
+        assert_eq!(feed_decimals::<&str>(feed, Ok(18)), (18, None));
+
+        let (decimals, warning) = feed_decimals(feed, Err("execution reverted"));
+        assert_eq!(decimals, DEFAULT_FEED_DECIMALS);
+        assert!(warning.unwrap().contains("assumed 8"));
+    }
+
+    #[test]
+    fn test_quote_currency() {
+        assert_eq!("usd".parse::<QuoteCurrency>().unwrap(), QuoteCurrency::Usd);
+        assert_eq!("ETH".parse::<QuoteCurrency>().unwrap(), QuoteCurrency::Eth);
+        assert!("EUR".parse::<QuoteCurrency>().is_err());
+
+        let price = PriceInfo {
+            price_usd: None,
+            price_eth: Some(Decimal::from_str("0.0005").unwrap()),
+            source: "Uniswap V2".to_string(),
+            fallback: false,
+            updated_at: None,
+            block_number: None,
+            pool_liquidity_usd: None,
+            round: None,
+            warnings: Vec::new(),
+        };
+        assert_eq!(QuoteCurrency::Usd.unit_price(&price), None);
+        assert_eq!(
+            QuoteCurrency::Eth.unit_price(&price),
+            Some(Decimal::from_str("0.0005").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_reserves_price() {
+        // 2,000,000 USDC (6 decimals) against 1,000 WETH: 0.0005 ETH per USDC
+        let price = reserves_price(U256::from(2_000_000_000_000u64), 6, U256::exp10(21)).unwrap();
+        assert_eq!(price, Decimal::from_str("0.0005").unwrap());
+
+        assert!(reserves_price(U256::zero(), 18, U256::exp10(18)).is_err());
+    }
+
+    #[test]
+    fn test_pool_liquidity_usd() {
+        // 5 WETH at $2,000 on each side
+        let liquidity = pool_liquidity_usd(U256::exp10(18) * 5, Decimal::from(2_000)).unwrap();
+        assert_eq!(liquidity, Decimal::from(20_000));
+    }
 
     #[test]
     fn test_price_calculation() {
         let price = Decimal::from(100_000_000u64) / Decimal::from(100_000_000u64);
         assert_eq!(price, Decimal::from(1));
     }
+
+    #[tokio::test]
+    async fn test_fetch_json_timeout_is_typed() {
+        // Accept connections but never answer, so only the request timeout can end the call
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut sockets = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                sockets.push(socket);
+            }
+        });
+
+        let http = crate::ethereum::create_http_client(
+            std::time::Duration::from_millis(100),
+            None,
+            HeaderMap::new(),
+        )
+        .unwrap();
+        let result = fetch_json(&http, &format!("http://{}/", addr)).await;
+
+        assert!(matches!(result, Err(PriceError::Timeout)));
+    }
+
+    /// Serve one scripted HTTP response per connection, counting the requests received
+    async fn scripted_server(
+        responses: Vec<(u16, &'static str)>,
+    ) -> (String, std::sync::Arc<std::sync::atomic::AtomicUsize>) {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let hits = std::sync::Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        tokio::spawn(async move {
+            for (status, body) in responses {
+                let mut socket = match listener.accept().await {
+                    Ok((socket, _)) => socket,
+                    Err(_) => return,
+                };
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match socket.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                counter.fetch_add(1, Ordering::SeqCst);
+                let response = format!(
+                    "HTTP/1.1 {} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        (url, hits)
+    }
+
+    #[tokio::test]
+    async fn test_fetch_retries_transient_errors() {
+        use std::sync::atomic::Ordering;
+
+        let (url, hits) = scripted_server(vec![
+            (503, "{}"),
+            (429, "{}"),
+            (200, r#"{"ethereum":{"usd":3000}}"#),
+        ])
+        .await;
+        let http = crate::ethereum::create_http_client(
+            std::time::Duration::from_secs(1),
+            None,
+            HeaderMap::new(),
+        )
+        .unwrap();
+
+        let data = fetch_json_with_retry(&http, &url).await.unwrap();
+        assert_eq!(data["ethereum"]["usd"], 3000);
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_gives_up_after_max_attempts() {
+        use std::sync::atomic::Ordering;
+
+        let (url, hits) = scripted_server(vec![(500, "{}"); 5]).await;
+        let http = crate::ethereum::create_http_client(
+            std::time::Duration::from_secs(1),
+            None,
+            HeaderMap::new(),
+        )
+        .unwrap();
+
+        let result = fetch_json_with_retry(&http, &url).await;
+        assert!(matches!(result, Err(PriceError::Status(status)) if status.as_u16() == 500));
+        assert_eq!(hits.load(Ordering::SeqCst), COINGECKO_MAX_ATTEMPTS as usize);
+    }
+
+    #[tokio::test]
+    async fn test_coingecko_base_url() {
+        let (url, _) = scripted_server(vec![(200, r#"{"ethereum":{"usd":3000}}"#)]).await;
+        let http = crate::ethereum::create_http_client(
+            std::time::Duration::from_secs(1),
+            None,
+            HeaderMap::new(),
+        )
+        .unwrap();
+
+        // The scripted server's URL ends in a slash, which must not double up
+        let coingecko = CoinGecko::new(&http, &url);
+        assert!(!coingecko.base_url.ends_with('/'));
+        let price = get_eth_price_from_coingecko(&coingecko).await.unwrap();
+        assert_eq!(price.price_usd, Some(Decimal::from(3000)));
+    }
+
+    #[tokio::test]
+    async fn test_auto_follows_source_order() {
+        let (url, _) = scripted_server(vec![(200, r#"{"ethereum":{"usd":3000}}"#)]).await;
+        let http = crate::ethereum::create_http_client(
+            std::time::Duration::from_secs(1),
+            None,
+            HeaderMap::new(),
+        )
+        .unwrap();
+        // None of these lookups reach the node
+        let provider = crate::ethereum::create_provider(
+            "http://127.0.0.1:1",
+            Default::default(),
+            Default::default(),
+            crate::ethereum::DEFAULT_USER_AGENT,
+            Duration::from_millis(500),
+        )
+        .await
+        .unwrap();
+        let feeds = ChainlinkFeeds::default();
+        let oracles = |sources| PriceOracles {
+            coingecko: CoinGecko::new(&http, &url),
+            feeds: &feeds,
+            sources,
+            min_liquidity_usd: Decimal::ZERO,
+        };
+
+        // Uniswap can't price ETH, so CoinGecko answers
+        let order = [PriceSource::Uniswap, PriceSource::CoinGecko];
+        let price = get_token_price(
+            &provider,
+            &oracles(&order),
+            Address::zero(),
+            PriceSource::Auto,
+        )
+        .await
+        .unwrap();
+        assert_eq!(price.source, "CoinGecko");
+        assert_eq!(price.price_usd, Some(Decimal::from(3000)));
+
+        let only_uniswap = [PriceSource::Uniswap];
+        let price = get_token_price(
+            &provider,
+            &oracles(&only_uniswap),
+            Address::zero(),
+            PriceSource::Auto,
+        )
+        .await
+        .unwrap();
+        assert_eq!(price.source, "Default");
+        assert!(price.fallback);
+        assert_eq!(price.market_price_usd(), None);
+
+        let only_chainlink = [PriceSource::Chainlink];
+        let token = Address::repeat_byte(0x11);
+        let error = get_token_price(
+            &provider,
+            &oracles(&only_chainlink),
+            token,
+            PriceSource::Auto,
+        )
+        .await
+        .unwrap_err()
+        .to_string();
+        assert!(error.contains("chainlink: no Chainlink feed"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_not_found_is_not_retried() {
+        use std::sync::atomic::Ordering;
+
+        let (url, hits) = scripted_server(vec![(404, "{}"), (200, "{}")]).await;
+        let http = crate::ethereum::create_http_client(
+            std::time::Duration::from_secs(1),
+            None,
+            HeaderMap::new(),
+        )
+        .unwrap();
+
+        let result = fetch_json_with_retry(&http, &url).await;
+        assert!(matches!(result, Err(PriceError::NotFound)));
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_parse_vs_currencies() {
+        let supported: Vec<String> = ["usd", "eur", "gbp", "btc"]
+            .iter()
+            .map(|code| code.to_string())
+            .collect();
+
+        assert_eq!(
+            parse_vs_currencies(" EUR,gbp,,eur, btc", &supported).unwrap(),
+            vec!["eur", "gbp", "btc"]
+        );
+
+        let error = parse_vs_currencies("eur,xyz,abc", &supported).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("Unsupported vs_currencies: xyz, abc."));
+        assert!(parse_vs_currencies(" , ", &supported).is_err());
+    }
+
+    #[test]
+    fn test_price_error_is_retryable() {
+        assert!(PriceError::Timeout.is_retryable());
+        assert!(PriceError::from_status(reqwest::StatusCode::TOO_MANY_REQUESTS).is_retryable());
+        assert!(PriceError::from_status(reqwest::StatusCode::BAD_GATEWAY).is_retryable());
+        assert!(!PriceError::from_status(reqwest::StatusCode::NOT_FOUND).is_retryable());
+        assert!(!PriceError::from_status(reqwest::StatusCode::BAD_REQUEST).is_retryable());
+    }
 }
@@ -0,0 +1,96 @@
+use async_trait::async_trait;
+use ethers::providers::{Http, HttpClientError, JsonRpcClient};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::fmt::Debug;
+
+/// Param fields that carry addresses, amounts or calldata
+const REDACTED_KEYS: &[&str] = &["from", "to", "value", "data", "input", "address", "topics"];
+
+/// Outbound RPC call logging options
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RpcLogging {
+    /// Log every JSON-RPC call at `trace` level
+    pub enabled: bool,
+    /// Replace addresses, amounts and calldata in logged params
+    pub redact: bool,
+}
+
+/// HTTP transport that optionally logs each JSON-RPC call before sending it
+#[derive(Debug, Clone)]
+pub struct LoggedHttp {
+    inner: Http,
+    logging: RpcLogging,
+}
+
+impl LoggedHttp {
+    pub fn new(inner: Http, logging: RpcLogging) -> Self {
+        Self { inner, logging }
+    }
+}
+
+#[async_trait]
+impl JsonRpcClient for LoggedHttp {
+    type Error = HttpClientError;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        if self.logging.enabled {
+            let mut params_json = serde_json::to_value(&params).unwrap_or(Value::Null);
+            if self.logging.redact {
+                redact_params(&mut params_json);
+            }
+            tracing::trace!(target: "rpc", "{} {}", method, params_json);
+        }
+
+        self.inner.request(method, params).await
+    }
+}
+
+/// Blank out sensitive fields in-place, keeping the call's shape readable
+fn redact_params(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                if REDACTED_KEYS.contains(&key.as_str()) {
+                    *field = Value::String("<redacted>".to_string());
+                } else {
+                    redact_params(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_params),
+        Value::String(s) if is_address(s) => *s = "<address>".to_string(),
+        _ => {}
+    }
+}
+
+fn is_address(s: &str) -> bool {
+    s.len() == 42 && s.starts_with("0x") && s[2..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redact_params() {
+        let mut params = json!(["0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045", "latest"]);
+        redact_params(&mut params);
+        assert_eq!(params, json!(["<address>", "latest"]));
+
+        let mut params = json!([
+            {"to": "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48", "data": "0x70a08231", "gas": "0x5208"},
+            "0x12a05f2"
+        ]);
+        redact_params(&mut params);
+        assert_eq!(
+            params,
+            json!([{"to": "<redacted>", "data": "<redacted>", "gas": "0x5208"}, "0x12a05f2"])
+        );
+    }
+}
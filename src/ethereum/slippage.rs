@@ -0,0 +1,126 @@
+use rust_decimal::Decimal;
+
+/// How many blocks back the route is re-quoted when suggesting slippage
+///
+/// Kept within the 128 recent blocks whose state full nodes retain, so no archive node is needed.
+pub(super) const AUTO_SLIPPAGE_SAMPLE_OFFSETS: [u64; 6] = [0, 10, 20, 30, 40, 50];
+
+/// Lowest suggestion, even for a pair whose rate never moved
+const AUTO_SLIPPAGE_FLOOR_BPS: u32 = 10;
+
+/// Highest suggestion; beyond this the trade needs a human to pick a tolerance
+const AUTO_SLIPPAGE_CAP_BPS: u32 = 300;
+
+/// Rate movement assumed when the recent blocks couldn't be sampled
+const ASSUMED_MOVEMENT_BPS: u32 = 25;
+
+/// A slippage tolerance suggested from the pair's recent behaviour
+#[derive(Debug, Clone)]
+pub struct SlippageSuggestion {
+    pub slippage_bps: u32,
+    /// How far the route's rate moved across the sampled blocks, when at least two were sampled
+    pub movement_bps: Option<Decimal>,
+    pub reasoning: String,
+}
+
+/// Spread between the highest and lowest sampled rate, relative to their mean, in bps
+fn rate_movement_bps(samples: &[Decimal]) -> Option<Decimal> {
+    if samples.len() < 2 {
+        return None;
+    }
+    let max = samples.iter().max()?;
+    let min = samples.iter().min()?;
+    let mean = samples.iter().sum::<Decimal>() / Decimal::from(samples.len());
+    if mean.is_zero() {
+        return None;
+    }
+    Some(((max - min) / mean * Decimal::from(10_000)).round_dp(1))
+}
+
+/// Suggest slippage as twice the recent rate movement plus half the price impact
+///
+/// Recent movement stands in for how far the price drifts before the swap lands, and a high
+/// price impact marks a thin pool that other trades move just as easily. `samples` are the
+/// route's output for a fixed input at recent blocks; `price_impact` is in percent.
+pub fn suggest_slippage(samples: &[Decimal], price_impact: Decimal) -> SlippageSuggestion {
+    let movement_bps = rate_movement_bps(samples);
+    let impact_bps = price_impact * Decimal::from(100);
+    let movement = movement_bps.unwrap_or(Decimal::from(ASSUMED_MOVEMENT_BPS));
+
+    let raw = movement * Decimal::from(2) + impact_bps / Decimal::from(2);
+    // Rounded up to a multiple of 5 bps
+    let rounded = (raw / Decimal::from(5)).ceil() * Decimal::from(5);
+    let slippage_bps = u32::try_from(rounded)
+        .unwrap_or(u32::MAX)
+        .clamp(AUTO_SLIPPAGE_FLOOR_BPS, AUTO_SLIPPAGE_CAP_BPS);
+
+    let observed = match movement_bps {
+        Some(movement) => format!(
+            "the rate moved {} bps across {} samples over the last {} blocks",
+            movement,
+            samples.len(),
+            AUTO_SLIPPAGE_SAMPLE_OFFSETS[AUTO_SLIPPAGE_SAMPLE_OFFSETS.len() - 1]
+        ),
+        None => format!(
+            "recent blocks couldn't be sampled, so {} bps of movement is assumed",
+            ASSUMED_MOVEMENT_BPS
+        ),
+    };
+    let bound = if slippage_bps == AUTO_SLIPPAGE_CAP_BPS {
+        format!(", capped at {} bps", AUTO_SLIPPAGE_CAP_BPS)
+    } else if slippage_bps == AUTO_SLIPPAGE_FLOOR_BPS {
+        format!(", raised to the {} bps floor", AUTO_SLIPPAGE_FLOOR_BPS)
+    } else {
+        String::new()
+    };
+    let reasoning = format!(
+        "Suggested {} bps: {} and price impact is {}%; twice the movement plus half the impact, rounded up to 5 bps{}",
+        slippage_bps,
+        observed,
+        price_impact.round_dp(2),
+        bound
+    );
+
+    SlippageSuggestion {
+        slippage_bps,
+        movement_bps,
+        reasoning,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rates(values: &[i64]) -> Vec<Decimal> {
+        values.iter().copied().map(Decimal::from).collect()
+    }
+
+    #[test]
+    fn test_stable_pair_gets_floor() {
+        let suggestion = suggest_slippage(&rates(&[10_000, 10_001, 10_000]), Decimal::new(1, 2));
+        assert_eq!(suggestion.movement_bps, Some(Decimal::ONE));
+        assert_eq!(suggestion.slippage_bps, 10);
+        assert!(suggestion.reasoning.contains("floor"));
+    }
+
+    #[test]
+    fn test_volatile_thin_pair_gets_more() {
+        // 40 bps of movement and 1% impact: 80 + 50 = 130
+        let suggestion = suggest_slippage(&rates(&[9_980, 10_000, 10_020]), Decimal::ONE);
+        assert_eq!(suggestion.slippage_bps, 130);
+
+        let extreme = suggest_slippage(&rates(&[9_000, 11_000]), Decimal::from(5));
+        assert_eq!(extreme.slippage_bps, 300);
+        assert!(extreme.reasoning.contains("capped"));
+    }
+
+    #[test]
+    fn test_unsampled_assumes_movement() {
+        let suggestion = suggest_slippage(&rates(&[10_000]), Decimal::new(3, 1));
+        assert_eq!(suggestion.movement_bps, None);
+        // 2 x 25 + 30 / 2 = 65
+        assert_eq!(suggestion.slippage_bps, 65);
+        assert!(suggestion.reasoning.contains("assumed"));
+    }
+}
@@ -0,0 +1,163 @@
+use anyhow::{Context, Result};
+use ethers::prelude::*;
+use ethers::utils::keccak256;
+
+use super::client::EthClient;
+use super::error::RpcCallContext;
+
+/// EIP-1967 proxy slots, addressable by name instead of their hashed position
+const NAMED_SLOTS: [(&str, &str); 3] = [
+    ("eip1967.implementation", "eip1967.proxy.implementation"),
+    ("eip1967.admin", "eip1967.proxy.admin"),
+    ("eip1967.beacon", "eip1967.proxy.beacon"),
+];
+
+/// A raw storage word with the common ways of reading it
+#[derive(Debug, Clone, Copy)]
+pub struct StorageValue {
+    pub raw: H256,
+    pub as_uint: U256,
+    /// Low 20 bytes, where Solidity stores an `address`
+    pub as_address: Address,
+    /// Only when the word is exactly 0 or 1
+    pub as_bool: Option<bool>,
+}
+
+impl StorageValue {
+    pub fn new(raw: H256) -> Self {
+        let as_uint = U256::from_big_endian(raw.as_bytes());
+        Self {
+            raw,
+            as_uint,
+            as_address: Address::from_slice(&raw.as_bytes()[12..]),
+            as_bool: (as_uint <= U256::one()).then_some(!as_uint.is_zero()),
+        }
+    }
+
+    /// A field packed into this word: `size` bytes starting `offset` bytes from the right, the
+    /// way Solidity packs consecutive small variables into one slot
+    pub fn packed(&self, offset: usize, size: usize) -> Result<U256> {
+        if size == 0 || offset + size > 32 {
+            anyhow::bail!(
+                "Packed field of {} bytes at offset {} does not fit in a 32-byte slot",
+                size,
+                offset
+            );
+        }
+        let end = 32 - offset;
+        Ok(U256::from_big_endian(&self.raw.as_bytes()[end - size..end]))
+    }
+}
+
+/// Parse a slot given as hex (`0x...`), decimal, or an EIP-1967 slot name
+pub fn parse_slot(slot: &str) -> Result<H256> {
+    let slot = slot.trim();
+    if let Some((_, label)) = NAMED_SLOTS.iter().find(|(name, _)| *name == slot) {
+        return Ok(eip1967_slot(label));
+    }
+    let position = parse_word(slot).with_context(|| format!("Invalid storage slot {}", slot))?;
+    Ok(word_to_h256(position))
+}
+
+/// Slot of `mapping[key]` for a mapping declared at `base_slot`: keccak256(key . base_slot)
+pub fn mapping_slot(key: &str, base_slot: H256) -> Result<H256> {
+    let key = parse_word(key.trim()).with_context(|| format!("Invalid mapping key {}", key))?;
+    let mut preimage = [0u8; 64];
+    key.to_big_endian(&mut preimage[..32]);
+    preimage[32..].copy_from_slice(base_slot.as_bytes());
+    Ok(H256::from(keccak256(preimage)))
+}
+
+/// Read one storage slot of `contract`, optionally as of a past block
+pub async fn get_storage(
+    provider: &EthClient,
+    contract: Address,
+    slot: H256,
+    block_number: Option<u64>,
+) -> Result<StorageValue> {
+    let raw = provider
+        .get_storage_at(contract, slot, block_number.map(BlockId::from))
+        .await
+        .rpc_context("eth_getStorageAt", Some(contract))?;
+    Ok(StorageValue::new(raw))
+}
+
+/// A 32-byte word from hex (`0x...`, which also covers addresses) or decimal
+fn parse_word(value: &str) -> Result<U256> {
+    match value.strip_prefix("0x") {
+        Some(hex) => U256::from_str_radix(hex, 16).map_err(|e| anyhow::anyhow!("{}", e)),
+        None => U256::from_dec_str(value).map_err(|e| anyhow::anyhow!("{}", e)),
+    }
+}
+
+fn word_to_h256(word: U256) -> H256 {
+    let mut bytes = [0u8; 32];
+    word.to_big_endian(&mut bytes);
+    H256::from(bytes)
+}
+
+/// keccak256(label) - 1, which no compiler-assigned slot can collide with
+fn eip1967_slot(label: &str) -> H256 {
+    word_to_h256(U256::from_big_endian(&keccak256(label.as_bytes())) - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_slot() {
+        assert_eq!(parse_slot("0").unwrap(), H256::zero());
+        assert_eq!(parse_slot("0x5").unwrap(), H256::from_low_u64_be(5));
+        assert_eq!(parse_slot("10").unwrap(), H256::from_low_u64_be(10));
+        assert_eq!(
+            parse_slot("eip1967.implementation").unwrap(),
+            "0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc"
+                .parse::<H256>()
+                .unwrap()
+        );
+        assert_eq!(
+            parse_slot("eip1967.admin").unwrap(),
+            "0xb53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d6103"
+                .parse::<H256>()
+                .unwrap()
+        );
+        assert!(parse_slot("0xzz").is_err());
+        assert!(parse_slot("slot").is_err());
+    }
+
+    #[test]
+    fn test_mapping_slot_pads_key_and_slot() {
+        let wallet = "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045";
+        let mut preimage = [0u8; 64];
+        preimage[12..32].copy_from_slice(wallet.parse::<Address>().unwrap().as_bytes());
+        preimage[63] = 3;
+        assert_eq!(
+            mapping_slot(wallet, H256::from_low_u64_be(3)).unwrap(),
+            H256::from(keccak256(preimage))
+        );
+    }
+
+    #[test]
+    fn test_storage_value_decoding() {
+        let owner = Address::from_low_u64_be(0xabcd);
+        // An address in the low 20 bytes, with a bool packed right above it
+        let mut raw = [0u8; 32];
+        raw[12..].copy_from_slice(owner.as_bytes());
+        raw[11] = 1;
+        let value = StorageValue::new(H256::from(raw));
+
+        assert_eq!(value.as_address, owner);
+        assert_eq!(value.as_bool, None);
+        assert_eq!(value.packed(0, 20).unwrap(), U256::from(0xabcd));
+        assert_eq!(value.packed(20, 1).unwrap(), U256::one());
+        assert!(value.packed(31, 2).is_err());
+        assert!(value.packed(0, 0).is_err());
+
+        assert_eq!(
+            StorageValue::new(H256::from_low_u64_be(1)).as_bool,
+            Some(true)
+        );
+        assert_eq!(StorageValue::new(H256::zero()).as_bool, Some(false));
+    }
+}
@@ -1,230 +1,1792 @@
 use anyhow::{Context, Result};
 use ethers::prelude::*;
-use rust_decimal::Decimal;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use rust_decimal::{Decimal, RoundingStrategy};
 use std::str::FromStr;
 
-use super::client::EthClient;
-
-// Uniswap V2 Router ABI
-abigen!(
-    UniswapV2Router,
-    r#"[
-        function swapExactTokensForTokens(uint amountIn, uint amountOutMin, address[] calldata path, address to, uint deadline) external returns (uint[] memory amounts)
-        function swapExactETHForTokens(uint amountOutMin, address[] calldata path, address to, uint deadline) external payable returns (uint[] memory amounts)
-        function swapExactTokensForETH(uint amountIn, uint amountOutMin, address[] calldata path, address to, uint deadline) external returns (uint[] memory amounts)
-        function getAmountsOut(uint amountIn, address[] calldata path) external view returns (uint[] memory amounts)
-        function WETH() external pure returns (address)
-    ]"#,
-);
-
-// ERC20 for approvals
-abigen!(
-    IERC20,
-    r#"[
-        function approve(address spender, uint256 amount) external returns (bool)
-        function allowance(address owner, address spender) external view returns (uint256)
-        function decimals() external view returns (uint8)
-    ]"#,
-);
+use super::abi::{
+    ExactInputSingleParams, UniswapV2Factory, UniswapV2Pair, UniswapV2Router, UniswapV3Quoter,
+    UniswapV3Router, ERC20,
+};
+use super::balance::{get_eth_balance, get_token_balance};
+use super::cache::{MetadataCache, RouterInfo};
+use super::client::{EthClient, EthProvider};
+use super::error::{revert_reason, RpcCallContext};
+use super::slippage::{suggest_slippage, SlippageSuggestion, AUTO_SLIPPAGE_SAMPLE_OFFSETS};
+use super::tokens::normalize_eth;
+
+/// Uniswap V2 Router on Ethereum mainnet
+pub(super) const V2_ROUTER_ADDRESS: &str = "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D";
+
+/// Sushiswap's router on Ethereum mainnet, a Uniswap V2 fork with the same interface
+pub(super) const SUSHISWAP_ROUTER_ADDRESS: &str = "0xd9e1cE17f2641f24aE83637ab66a2cca9C378B9F";
+
+/// Uniswap V3 SwapRouter on Ethereum mainnet
+pub(super) const V3_ROUTER_ADDRESS: &str = "0xE592427A0AEce92De3Edee1F18E0157C05861564";
+
+/// Uniswap V3 Quoter on Ethereum mainnet
+const V3_QUOTER_ADDRESS: &str = "0xb27308f9F90D607463bb33eA1BeBb41C27CE5AB6";
+
+/// Fraction of the input used as a probe trade to measure the marginal rate
+const PRICE_IMPACT_PROBE_DIVISOR: u64 = 10_000;
+
+/// Shares of the input (in percent) routed through V2 when evaluating a split; the rest goes to V3
+const SPLIT_V2_SHARES: [u32; 5] = [0, 25, 50, 75, 100];
+
+/// Gas assumed for a swap transaction whose estimate failed, when `allow_gas_fallback` is set
+const FALLBACK_SWAP_GAS: u64 = 300_000;
+
+/// Gas assumed for an ERC20 approve whose estimate failed
+const FALLBACK_APPROVAL_GAS: u64 = 60_000;
+
+/// Standard Uniswap V3 fee tiers (in hundredths of a basis point)
+pub const V3_FEE_TIERS: [u32; 4] = [100, 500, 3000, 10000];
+
+/// Middle hops tried besides WETH when exploring routes: USDC, USDT and DAI
+const ROUTE_CONNECTORS: [&str; 3] = [
+    "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+    "0xdAC17F958D2ee523a2206206994597C13D831ec7",
+    "0x6B175474E89094C44Da98b954EedeAC495271d0F",
+];
+
+/// Uniswap protocol version used to quote a swap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UniswapVersion {
+    V2,
+    V3,
+}
+
+/// Parameters for a swap simulation
+#[derive(Debug, Clone)]
+pub struct SwapRequest {
+    pub from_token: Address,
+    pub to_token: Address,
+    pub amount_in: Decimal,
+    pub slippage_bps: u32, // basis points (e.g., 50 = 0.5%)
+    /// Ignore `slippage_bps` and suggest one from the route's recent rate movement and price impact
+    pub auto_slippage: bool,
+    pub wallet_address: Address,
+    /// Account the router sends the output to; `wallet_address` when None
+    pub recipient: Option<Address>,
+    pub version: UniswapVersion,
+    /// Pin a V3 fee tier instead of searching all standard tiers
+    pub fee_tier: Option<u32>,
+    /// Reject quotes whose price impact exceeds this many basis points
+    pub max_price_impact_bps: u32,
+    /// Quotes whose price impact exceeds this many basis points need `high_impact_confirmed`
+    pub confirm_impact_bps: u32,
+    /// The caller acknowledged a price impact above `confirm_impact_bps`
+    pub high_impact_confirmed: bool,
+    /// Reject paths longer than this many hops
+    pub max_hops: usize,
+    /// How to round `amount_in` to whole wei
+    pub rounding: WeiRounding,
+    /// Quote exactly this route instead of building one
+    pub path: Option<Vec<Address>>,
+    /// Evaluate splitting the input across V2 and V3 to minimize impact
+    pub split: bool,
+    /// Quote V2 swaps on this V2-compatible router instead of Uniswap's
+    pub v2_router: Option<Address>,
+    /// Fail (rather than warn) when the wallet holds less than `amount_in`
+    pub strict_balance: bool,
+    /// Slippage below this many basis points is raised to it
+    pub min_slippage_bps: u32,
+    /// Slippage above this many basis points is quoted with a warning
+    pub slippage_warn_bps: u32,
+    /// Quote with a flat gas figure when estimation fails, instead of failing the quote
+    pub allow_gas_fallback: bool,
+    /// Estimated gas above this is quoted with a warning (0 = no limit)
+    pub max_gas_estimate: u64,
+}
+
+/// One venue's portion of a split swap
+#[derive(Debug, Clone)]
+pub struct SplitLeg {
+    pub version: UniswapVersion,
+    pub fee_tier: Option<u32>,
+    pub share_pct: u32,
+    pub amount_in: Decimal,
+    pub estimated_output: Decimal,
+}
+
+/// Swap simulation failures that callers may want to tell apart
+#[derive(Debug, thiserror::Error)]
+pub enum SwapError {
+    #[error("No liquidity: the route returned zero output")]
+    NoLiquidity,
+    #[error("Excessive price impact: {impact}% exceeds the {max}% limit")]
+    ExcessiveImpact { impact: Decimal, max: Decimal },
+    #[error("Confirmation required: price impact {impact}% exceeds the {threshold}% high-impact threshold; re-call with confirm_high_impact set to true to proceed")]
+    ConfirmationRequired { impact: Decimal, threshold: Decimal },
+    #[error("Insufficient balance: wallet holds {held} {symbol} but the swap requires {required} {symbol}")]
+    InsufficientBalance {
+        held: Decimal,
+        required: Decimal,
+        symbol: String,
+    },
+}
 
 #[derive(Debug)]
 pub struct SwapSimulation {
     pub estimated_output: Decimal,
     pub estimated_gas: U256,
+    /// Gas for the approve transactions the routers need first, when the allowance is short
+    pub approval_gas: Option<U256>,
+    /// Total cost in ETH: swap and approval gas at the current gas price
+    pub estimated_cost_eth: Decimal,
     pub minimum_output: Decimal,
     pub price_impact: Decimal,
+    /// Slippage tolerance actually applied, after clamping to the configured floor
+    pub slippage_bps: u32,
+    /// How `slippage_bps` was picked, when it was suggested rather than given
+    pub slippage_suggestion: Option<SlippageSuggestion>,
     pub route: Vec<Address>,
+    pub version: UniswapVersion,
+    /// V3 fee tier the quote was taken from (None for V2)
+    pub fee_tier: Option<u32>,
+    /// Per-venue allocation when the swap was split
+    pub split: Option<Vec<SplitLeg>>,
+    /// Input amount in the from-token's base units
+    pub amount_in_raw: U256,
+    /// Minimum output in the to-token's base units, as used for `amountOutMin`
+    pub minimum_output_raw: U256,
+    pub to_decimals: u8,
+    /// Non-fatal issues the caller should surface alongside the quote
+    pub warnings: Vec<String>,
 }
 
-/// Simulate a token swap on Uniswap V2
+/// Simulate a token swap on Uniswap V2 or V3
 pub async fn simulate_swap(
     provider: &EthClient,
-    from_token: Address,
-    to_token: Address,
-    amount_in: Decimal,
-    slippage_bps: u32, // basis points (e.g., 50 = 0.5%)
-    wallet_address: Address,
+    cache: &MetadataCache,
+    request: SwapRequest,
 ) -> Result<SwapSimulation> {
-    // Uniswap V2 Router on Ethereum mainnet
-    let router_address = "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D"
-        .parse::<Address>()
-        .unwrap();
+    let SwapRequest {
+        from_token,
+        to_token,
+        amount_in,
+        slippage_bps,
+        auto_slippage,
+        wallet_address,
+        recipient,
+        version,
+        fee_tier,
+        max_price_impact_bps,
+        confirm_impact_bps,
+        high_impact_confirmed,
+        max_hops,
+        rounding,
+        path: explicit_path,
+        split,
+        v2_router,
+        strict_balance,
+        min_slippage_bps,
+        slippage_warn_bps,
+        allow_gas_fallback,
+        max_gas_estimate,
+    } = request;
+    let (from_token, to_token) = (normalize_eth(from_token), normalize_eth(to_token));
+    let recipient = recipient.unwrap_or(wallet_address);
+    let explicit_path =
+        explicit_path.map(|path| path.into_iter().map(normalize_eth).collect::<Vec<_>>());
+
+    // A suggested slippage needs the route quoted first
+    let explicit_slippage = (!auto_slippage)
+        .then(|| apply_slippage_policy(slippage_bps, min_slippage_bps, slippage_warn_bps))
+        .transpose()?;
+
+    if let Some(fee) = fee_tier {
+        if !V3_FEE_TIERS.contains(&fee) {
+            anyhow::bail!(
+                "Unsupported fee tier {}. Expected one of {:?}",
+                fee,
+                V3_FEE_TIERS
+            );
+        }
+    }
+
+    // Other routers only share Uniswap's V2 interface; V3 quotes always go to Uniswap
+    if v2_router.is_some() && (split || version == UniswapVersion::V3) {
+        anyhow::bail!("A custom V2 router can only quote V2 swaps");
+    }
+    let router_address = v2_router.unwrap_or_else(|| V2_ROUTER_ADDRESS.parse::<Address>().unwrap());
 
     let router = UniswapV2Router::new(router_address, provider.clone());
-    let weth = router.weth().call().await?;
+    let weth = router_weth(cache, &router).await?;
 
-    // Build the swap path
-    let path = build_swap_path(from_token, to_token, weth);
+    // Build the swap path, or validate the caller's explicit route
+    let path = match explicit_path {
+        Some(explicit) => {
+            let path = resolve_explicit_path(&explicit, from_token, to_token, weth, max_hops)?;
+            // The V3 quoter and router calls here are single-hop
+            if path.len() > 2 && (split || version == UniswapVersion::V3) {
+                anyhow::bail!("Multi-hop paths are only supported on Uniswap V2");
+            }
+            path
+        }
+        None => build_swap_path(from_token, to_token, weth, &[], max_hops)?,
+    };
 
     // Get decimals for from_token
-    let from_decimals = if from_token == Address::zero() {
-        18u8
-    } else {
-        let token = IERC20::new(from_token, provider.clone());
-        token.decimals().call().await.unwrap_or(18)
-    };
+    let from_decimals = token_decimals(provider, cache, from_token).await;
 
     // Convert amount to wei
-    let amount_in_wei = decimal_to_wei(amount_in, from_decimals)?;
+    let amount_in_wei = decimal_to_wei(amount_in, from_decimals, rounding)?;
 
     // Get estimated output amounts
-    let amounts_out = router
-        .get_amounts_out(amount_in_wei, path.clone())
-        .call()
-        .await
-        .context("Failed to get amounts out from router")?;
+    let (estimated_output_wei, chosen_fee_tier, legs) = if split {
+        let (output, fee, legs) =
+            quote_split(provider, &router, &path, amount_in_wei, fee_tier).await?;
+        (output, fee, Some(legs))
+    } else {
+        let (output, fee) =
+            quote_route(provider, &router, version, &path, amount_in_wei, fee_tier).await?;
+        (output, fee, None)
+    };
+
+    if estimated_output_wei.is_zero() {
+        return Err(SwapError::NoLiquidity.into());
+    }
 
-    let estimated_output_wei = amounts_out
-        .last()
-        .copied()
-        .context("No output amount")?;
+    // Whether built or supplied, the quoted route must run between the requested tokens
+    check_route_endpoints(&path, from_token, to_token, weth)?;
 
-    // Get decimals for to_token
-    let to_decimals = if to_token == Address::zero() {
-        18u8
+    // Get decimals of the token the quoted route actually ends at
+    let output_token = path[path.len() - 1];
+    let to_decimals = token_decimals(provider, cache, output_token).await;
+
+    let estimated_output = wei_to_decimal(estimated_output_wei, to_decimals)?;
+
+    // Calculate price impact against the marginal rate of a tiny probe trade
+    let probe_in_wei = amount_in_wei / U256::from(PRICE_IMPACT_PROBE_DIVISOR);
+    let price_impact = if probe_in_wei.is_zero() {
+        Decimal::ZERO
     } else {
-        let token = IERC20::new(to_token, provider.clone());
-        token.decimals().call().await.unwrap_or(18)
+        // A split is measured against the better of the venues' marginal rates
+        let probe_versions = match &legs {
+            Some(legs) => legs.iter().map(|leg| leg.version).collect(),
+            None => vec![version],
+        };
+
+        let mut probe_out_wei = U256::zero();
+        for probe_version in probe_versions {
+            let (out, _) = quote_route(
+                provider,
+                &router,
+                probe_version,
+                &path,
+                probe_in_wei,
+                chosen_fee_tier,
+            )
+            .await?;
+            probe_out_wei = probe_out_wei.max(out);
+        }
+
+        compute_price_impact(
+            amount_in_wei,
+            estimated_output_wei,
+            probe_in_wei,
+            probe_out_wei,
+        )?
     };
 
-    let estimated_output = wei_to_decimal(estimated_output_wei, to_decimals)?;
+    let max_impact = Decimal::from(max_price_impact_bps) / Decimal::from(100);
+    if price_impact > max_impact {
+        return Err(SwapError::ExcessiveImpact {
+            impact: price_impact.round_dp(2),
+            max: max_impact,
+        }
+        .into());
+    }
+
+    let confirm_threshold = Decimal::from(confirm_impact_bps) / Decimal::from(100);
+    if price_impact > confirm_threshold && !high_impact_confirmed {
+        return Err(SwapError::ConfirmationRequired {
+            impact: price_impact.round_dp(2),
+            threshold: confirm_threshold,
+        }
+        .into());
+    }
+
+    let mut slippage_suggestion = None;
+    let (slippage_bps, slippage_warning) = match explicit_slippage {
+        Some(policy) => policy,
+        None => {
+            // Sample the venue carrying most of the trade, at the probe size when there is one
+            let (sample_version, sample_fee_tier) = match &legs {
+                Some(legs) => legs
+                    .iter()
+                    .max_by_key(|leg| leg.share_pct)
+                    .map(|leg| (leg.version, leg.fee_tier))
+                    .unwrap_or((version, chosen_fee_tier)),
+                None => (version, chosen_fee_tier),
+            };
+            let sample_in_wei = if probe_in_wei.is_zero() {
+                amount_in_wei
+            } else {
+                probe_in_wei
+            };
+            let samples = sample_route_rates(
+                provider,
+                &router,
+                sample_version,
+                &path,
+                sample_in_wei,
+                sample_fee_tier,
+            )
+            .await?
+            .into_iter()
+            .map(|out| wei_to_decimal(out, to_decimals))
+            .collect::<Result<Vec<_>>>()?;
+
+            let suggestion = suggest_slippage(&samples, price_impact);
+            let policy = apply_slippage_policy(
+                suggestion.slippage_bps,
+                min_slippage_bps,
+                slippage_warn_bps,
+            )?;
+            slippage_suggestion = Some(suggestion);
+            policy
+        }
+    };
 
     // Calculate minimum output with slippage
-    let slippage_multiplier = Decimal::from(10000 - slippage_bps) / Decimal::from(10000);
-    let minimum_output = estimated_output * slippage_multiplier;
-    let min_output_wei = decimal_to_wei(minimum_output, to_decimals)?;
+    let slippage_multiplier = slippage_factor(slippage_bps)?;
+    let minimum_output = slippage_floor(estimated_output, slippage_bps)?;
+    // Always truncate the floor so dust can never make the minimum unreachable
+    let min_output_wei = decimal_to_wei(minimum_output, to_decimals, WeiRounding::Truncate)?;
+    if min_output_wei.is_zero() {
+        anyhow::bail!(
+            "Minimum output {} rounds to zero base units; the swap would have no slippage protection",
+            minimum_output
+        );
+    }
 
-    // Estimate gas by simulating the transaction
-    let estimated_gas = estimate_swap_gas(
-        provider,
-        &router,
-        from_token,
-        to_token,
-        amount_in_wei,
-        min_output_wei,
-        path.clone(),
-        wallet_address,
-    )
-    .await?;
+    let mut warnings: Vec<String> = slippage_warning.into_iter().collect();
+
+    // Check the wallet can fund the swap before gas estimation fails opaquely on it
+    let held = if from_token == Address::zero() {
+        get_eth_balance(provider, wallet_address).await?
+    } else {
+        get_token_balance(provider, cache, from_token, wallet_address).await?
+    };
+    if held.raw_balance < amount_in_wei {
+        let error = SwapError::InsufficientBalance {
+            held: held.balance,
+            required: amount_in,
+            symbol: held.symbol,
+        };
+        if strict_balance {
+            return Err(error.into());
+        }
+        warnings.push(format!("{}; gas estimate may be unreliable", error));
+    }
+
+    // Estimate gas by simulating the transaction (one swap per venue used)
+    let gas_legs = match &legs {
+        Some(legs) => legs
+            .iter()
+            .map(|leg| (leg.version, leg.fee_tier, leg.amount_in, leg.amount_out))
+            .collect(),
+        None => vec![(
+            version,
+            chosen_fee_tier,
+            amount_in_wei,
+            estimated_output_wei,
+        )],
+    };
+
+    let mut approval_gas: Option<U256> = None;
+    let mut txs = Vec::new();
+    for (leg_version, leg_fee_tier, leg_in_wei, leg_out_wei) in gas_legs {
+        let leg_min_output_wei = if legs.is_some() {
+            decimal_to_wei(
+                wei_to_decimal(leg_out_wei, to_decimals)? * slippage_multiplier,
+                to_decimals,
+                WeiRounding::Truncate,
+            )?
+        } else {
+            min_output_wei
+        };
+
+        let tx = match (leg_version, leg_fee_tier) {
+            (UniswapVersion::V3, Some(fee)) => build_v3_swap_tx(
+                provider,
+                from_token,
+                &path,
+                fee,
+                leg_in_wei,
+                leg_min_output_wei,
+                recipient,
+            ),
+            _ => build_v2_swap_tx(
+                &router,
+                from_token,
+                to_token,
+                leg_in_wei,
+                leg_min_output_wei,
+                path.clone(),
+                recipient,
+            ),
+        };
+        txs.push((tx, leg_in_wei));
+    }
+
+    // Token inputs are pulled by the router, so each router used needs an allowance first
+    let mut approvals = Vec::new();
+    if from_token != Address::zero() {
+        let mut spend: Vec<(Address, U256)> = Vec::new();
+        for (tx, leg_in_wei) in &txs {
+            let spender = *tx.to_addr().context("Swap call has no router address")?;
+            match spend.iter_mut().find(|(router, _)| *router == spender) {
+                Some((_, amount)) => *amount += *leg_in_wei,
+                None => spend.push((spender, *leg_in_wei)),
+            }
+        }
+        let token = ERC20::new(from_token, provider.clone());
+        for (spender, amount) in spend {
+            let allowance = token
+                .allowance(wallet_address, spender)
+                .call()
+                .await
+                .rpc_context("allowance", Some(from_token))?;
+            if allowance < amount {
+                approvals.push(spender);
+                let gas = estimate_approval_gas(&token, wallet_address, spender, amount).await;
+                warnings.push(format!(
+                    "Approval required: router {:?} is not approved to spend this amount of {:?}; send an approve transaction (about {} gas) before the swap",
+                    spender, from_token, gas
+                ));
+                *approval_gas.get_or_insert_with(U256::zero) += gas;
+            }
+        }
+    }
+
+    let mut estimated_gas = U256::zero();
+    for (tx, _) in &txs {
+        // The router can't pull unapproved tokens, so estimating the swap would only revert
+        estimated_gas += if tx.to_addr().is_some_and(|to| approvals.contains(to)) {
+            warnings.push(format!(
+                "Swap gas can't be estimated until the router is approved; quoted with a flat {} gas",
+                FALLBACK_SWAP_GAS
+            ));
+            U256::from(FALLBACK_SWAP_GAS)
+        } else {
+            estimate_tx_gas(provider, tx, allow_gas_fallback, &mut warnings).await?
+        };
+    }
+    if max_gas_estimate > 0 && estimated_gas > U256::from(max_gas_estimate) {
+        warnings.push(format!(
+            "Estimated gas {} exceeds MAX_GAS_ESTIMATE ({}); the trade is abnormally expensive, which can mean a token with a malicious transfer hook",
+            estimated_gas, max_gas_estimate
+        ));
+    }
+
+    let gas_price = provider
+        .get_gas_price()
+        .await
+        .rpc_context("eth_gasPrice", None)?;
+    let estimated_cost_eth = wei_to_decimal(
+        (estimated_gas + approval_gas.unwrap_or_default()) * gas_price,
+        18,
+    )?;
 
-    // Calculate price impact (simplified)
-    let price_impact = Decimal::from(0); // Would need pool reserves for accurate calculation
+    let split = match legs {
+        Some(legs) => Some(
+            legs.into_iter()
+                .map(|leg| {
+                    Ok(SplitLeg {
+                        version: leg.version,
+                        fee_tier: leg.fee_tier,
+                        share_pct: leg.share_pct,
+                        amount_in: wei_to_decimal(leg.amount_in, from_decimals)?,
+                        estimated_output: wei_to_decimal(leg.amount_out, to_decimals)?,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?,
+        ),
+        None => None,
+    };
 
     Ok(SwapSimulation {
         estimated_output,
         estimated_gas,
+        approval_gas,
+        estimated_cost_eth,
         minimum_output,
         price_impact,
+        slippage_bps,
+        slippage_suggestion,
         route: path,
+        version,
+        fee_tier: chosen_fee_tier,
+        split,
+        amount_in_raw: amount_in_wei,
+        minimum_output_raw: min_output_wei,
+        to_decimals,
+        warnings,
+    })
+}
+
+/// A fully-populated unsigned swap transaction plus the quote it was built from
+#[derive(Debug)]
+pub struct UnsignedSwapTx {
+    pub tx: TypedTransaction,
+    pub simulation: SwapSimulation,
+}
+
+/// Gas limit headroom over the estimate, in percent
+const GAS_LIMIT_BUFFER_PCT: u64 = 20;
+
+/// Quote a swap and build the EIP-1559 transaction executing it, for signing elsewhere
+pub async fn build_swap_transaction(
+    provider: &EthClient,
+    cache: &MetadataCache,
+    chain_id: u64,
+    request: SwapRequest,
+) -> Result<UnsignedSwapTx> {
+    let SwapRequest {
+        from_token,
+        to_token,
+        wallet_address,
+        recipient,
+        v2_router,
+        ..
+    } = request;
+    let (from_token, to_token) = (normalize_eth(from_token), normalize_eth(to_token));
+    let recipient = recipient.unwrap_or(wallet_address);
+    if request.split {
+        anyhow::bail!("Split swaps need one transaction per venue; build without split");
+    }
+
+    let simulation = simulate_swap(provider, cache, request).await?;
+
+    let call = match (simulation.version, simulation.fee_tier) {
+        (UniswapVersion::V3, Some(fee)) => build_v3_swap_tx(
+            provider,
+            from_token,
+            &simulation.route,
+            fee,
+            simulation.amount_in_raw,
+            simulation.minimum_output_raw,
+            recipient,
+        ),
+        _ => {
+            let router = UniswapV2Router::new(
+                v2_router.unwrap_or_else(|| V2_ROUTER_ADDRESS.parse::<Address>().unwrap()),
+                provider.clone(),
+            );
+            build_v2_swap_tx(
+                &router,
+                from_token,
+                to_token,
+                simulation.amount_in_raw,
+                simulation.minimum_output_raw,
+                simulation.route.clone(),
+                recipient,
+            )
+        }
+    };
+    let router = *call.to_addr().context("Swap call has no router address")?;
+
+    let nonce = provider
+        .get_transaction_count(wallet_address, Some(BlockNumber::Pending.into()))
+        .await
+        .rpc_context("eth_getTransactionCount", None)?;
+    let (max_fee_per_gas, max_priority_fee_per_gas) = provider
+        .estimate_eip1559_fees(None)
+        .await
+        .rpc_context("eth_feeHistory", None)?;
+    let gas = simulation.estimated_gas * (100 + GAS_LIMIT_BUFFER_PCT) / 100;
+
+    let mut tx = Eip1559TransactionRequest::new()
+        .from(wallet_address)
+        .to(router)
+        .value(call.value().copied().unwrap_or_default())
+        .gas(gas)
+        .nonce(nonce)
+        .chain_id(chain_id)
+        .max_fee_per_gas(max_fee_per_gas)
+        .max_priority_fee_per_gas(max_priority_fee_per_gas);
+    if let Some(data) = call.data() {
+        tx = tx.data(data.clone());
+    }
+
+    Ok(UnsignedSwapTx {
+        tx: tx.into(),
+        simulation,
     })
 }
 
-/// Build swap path (direct or through WETH)
-fn build_swap_path(from_token: Address, to_token: Address, weth: Address) -> Vec<Address> {
-    let from = if from_token == Address::zero() {
-        weth
+/// One venue's quote of a swap in `compare_v2_venues`
+#[derive(Debug)]
+pub struct VenueQuote {
+    pub venue: &'static str,
+    pub router: Address,
+    pub simulation: Result<SwapSimulation>,
+}
+
+/// Quote the same V2 swap on Uniswap V2 and Sushiswap concurrently, best output first
+///
+/// Sushiswap is a Uniswap V2 fork, so `simulate_swap` quotes it by pointing at its router, which
+/// reports its own factory and WETH. A venue that can't quote the swap is listed last with its
+/// error.
+pub async fn compare_v2_venues(
+    provider: &EthClient,
+    cache: &MetadataCache,
+    request: SwapRequest,
+) -> Vec<VenueQuote> {
+    let quote = |venue: &'static str, router: &str| {
+        let router = router.parse::<Address>().unwrap();
+        let request = SwapRequest {
+            version: UniswapVersion::V2,
+            fee_tier: None,
+            split: false,
+            v2_router: Some(router),
+            ..request.clone()
+        };
+        async move {
+            VenueQuote {
+                venue,
+                router,
+                simulation: simulate_swap(provider, cache, request).await,
+            }
+        }
+    };
+
+    let (uniswap, sushiswap) = tokio::join!(
+        quote("Uniswap V2", V2_ROUTER_ADDRESS),
+        quote("Sushiswap", SUSHISWAP_ROUTER_ADDRESS)
+    );
+    let mut quotes = vec![uniswap, sushiswap];
+    quotes.sort_by_key(|quote| {
+        std::cmp::Reverse(
+            quote
+                .simulation
+                .as_ref()
+                .ok()
+                .map(|simulation| simulation.estimated_output),
+        )
+    });
+    quotes
+}
+
+/// A route considered by `explore_routes`, with its quote or the reason it failed
+#[derive(Debug, Clone)]
+pub struct RouteCandidate {
+    pub version: UniswapVersion,
+    pub fee_tier: Option<u32>,
+    pub path: Vec<Address>,
+    pub estimated_output: Option<Decimal>,
+    pub price_impact: Option<Decimal>,
+    pub error: Option<String>,
+}
+
+/// Quote every candidate route for a swap, best output first
+///
+/// Candidates are the direct V2 pair, two-hop V2 routes through WETH and the major stablecoins,
+/// and the direct V3 pool at each fee tier. Routes that fail to quote are kept, last, with the
+/// error, so callers can see why a venue was passed over.
+pub async fn explore_routes(
+    provider: &EthClient,
+    cache: &MetadataCache,
+    from_token: Address,
+    to_token: Address,
+    amount_in: Decimal,
+    max_hops: usize,
+    rounding: WeiRounding,
+) -> Result<Vec<RouteCandidate>> {
+    let (from_token, to_token) = (normalize_eth(from_token), normalize_eth(to_token));
+    let router_address = V2_ROUTER_ADDRESS.parse::<Address>().unwrap();
+    let router = UniswapV2Router::new(router_address, provider.clone());
+    let weth = router_weth(cache, &router).await?;
+
+    let direct = build_swap_path(from_token, to_token, weth, &[], max_hops)?;
+    let mut routes = vec![(UniswapVersion::V2, None, direct.clone())];
+    let connectors =
+        std::iter::once(weth).chain(ROUTE_CONNECTORS.iter().map(|token| token.parse().unwrap()));
+    for via in connectors {
+        // Connectors that are an endpoint, or would exceed max_hops, aren't valid routes
+        if let Ok(path) = build_swap_path(from_token, to_token, weth, &[via], max_hops) {
+            routes.push((UniswapVersion::V2, None, path));
+        }
+    }
+    for fee in V3_FEE_TIERS {
+        routes.push((UniswapVersion::V3, Some(fee), direct.clone()));
+    }
+
+    let from_decimals = token_decimals(provider, cache, from_token).await;
+    let to_decimals = token_decimals(provider, cache, to_token).await;
+    let amount_in_wei = decimal_to_wei(amount_in, from_decimals, rounding)?;
+    if amount_in_wei.is_zero() {
+        anyhow::bail!("Amount {} rounds to zero base units", amount_in);
+    }
+
+    let mut candidates = Vec::with_capacity(routes.len());
+    for (version, fee_tier, path) in routes {
+        let quote = quote_candidate(
+            provider,
+            &router,
+            version,
+            &path,
+            amount_in_wei,
+            fee_tier,
+            to_decimals,
+        )
+        .await;
+        let (estimated_output, price_impact, error) = match quote {
+            Ok((output, impact)) => (Some(output), Some(impact), None),
+            Err(e) => (None, None, Some(format!("{:#}", e))),
+        };
+        candidates.push(RouteCandidate {
+            version,
+            fee_tier,
+            path,
+            estimated_output,
+            price_impact,
+            error,
+        });
+    }
+
+    sort_route_candidates(&mut candidates);
+    Ok(candidates)
+}
+
+/// Quote one route and its price impact against a probe trade on the same route
+async fn quote_candidate(
+    provider: &EthClient,
+    router: &UniswapV2Router<EthProvider>,
+    version: UniswapVersion,
+    path: &[Address],
+    amount_in: U256,
+    fee_tier: Option<u32>,
+    to_decimals: u8,
+) -> Result<(Decimal, Decimal)> {
+    let (amount_out, _) = quote_route(provider, router, version, path, amount_in, fee_tier).await?;
+    if amount_out.is_zero() {
+        return Err(SwapError::NoLiquidity.into());
+    }
+
+    let probe_in = amount_in / U256::from(PRICE_IMPACT_PROBE_DIVISOR);
+    let price_impact = if probe_in.is_zero() {
+        Decimal::ZERO
     } else {
-        from_token
+        let (probe_out, _) =
+            quote_route(provider, router, version, path, probe_in, fee_tier).await?;
+        compute_price_impact(amount_in, amount_out, probe_in, probe_out)?
     };
 
-    let to = if to_token == Address::zero() {
-        weth
+    Ok((wei_to_decimal(amount_out, to_decimals)?, price_impact))
+}
+
+/// Order quoted routes by descending output; failed routes keep their order at the end
+fn sort_route_candidates(candidates: &mut [RouteCandidate]) {
+    candidates.sort_by_key(|candidate| std::cmp::Reverse(candidate.estimated_output));
+}
+
+/// Largest swap on a direct Uniswap V2 pair that keeps price impact within a limit
+#[derive(Debug, Clone)]
+pub struct TradeSize {
+    pub pair: Address,
+    pub amount_in: Decimal,
+    pub estimated_output: Decimal,
+    pub price_impact: Decimal,
+}
+
+/// Find the largest input on the direct V2 pair whose price impact stays within `max_impact_bps`
+///
+/// The pair's reserves are read once and the size is binary-searched locally, using the same
+/// probe-trade measure of impact that `simulate_swap` reports.
+pub async fn max_trade_size(
+    provider: &EthClient,
+    cache: &MetadataCache,
+    from_token: Address,
+    to_token: Address,
+    max_impact_bps: u32,
+) -> Result<TradeSize> {
+    if max_impact_bps == 0 || max_impact_bps >= 10_000 {
+        anyhow::bail!(
+            "Price impact limit of {} bps must be between 1 and 9999",
+            max_impact_bps
+        );
+    }
+    let (from_token, to_token) = (normalize_eth(from_token), normalize_eth(to_token));
+
+    let router_address = V2_ROUTER_ADDRESS.parse::<Address>().unwrap();
+    let router = UniswapV2Router::new(router_address, provider.clone());
+    let RouterInfo {
+        weth,
+        factory: factory_address,
+    } = router_info(cache, &router).await?;
+    let path = build_swap_path(from_token, to_token, weth, &[], 1)?;
+    let (token_in, token_out) = (path[0], path[1]);
+
+    let pair_address = UniswapV2Factory::new(factory_address, provider.clone())
+        .get_pair(token_in, token_out)
+        .call()
+        .await
+        .rpc_context("getPair", Some(factory_address))?;
+    if pair_address == Address::zero() {
+        anyhow::bail!("No Uniswap V2 pair for {:?} and {:?}", token_in, token_out);
+    }
+
+    let pair = UniswapV2Pair::new(pair_address, provider.clone());
+    let token0 = pair
+        .token_0()
+        .call()
+        .await
+        .rpc_context("token0", Some(pair_address))?;
+    let (reserve0, reserve1, _) = pair
+        .get_reserves()
+        .call()
+        .await
+        .rpc_context("getReserves", Some(pair_address))?;
+    let (reserve_in, reserve_out) = if token0 == token_in {
+        (U256::from(reserve0), U256::from(reserve1))
     } else {
-        to_token
+        (U256::from(reserve1), U256::from(reserve0))
     };
+    if reserve_in.is_zero() || reserve_out.is_zero() {
+        return Err(SwapError::NoLiquidity.into());
+    }
+
+    let amount_in = max_v2_input_within_impact(reserve_in, reserve_out, max_impact_bps);
+    let amount_out = v2_amount_out(amount_in, reserve_in, reserve_out);
+    if amount_out.is_zero() {
+        return Err(SwapError::NoLiquidity.into());
+    }
+    let probe_in = amount_in / U256::from(PRICE_IMPACT_PROBE_DIVISOR);
+    let price_impact = if probe_in.is_zero() {
+        Decimal::ZERO
+    } else {
+        let probe_out = v2_amount_out(probe_in, reserve_in, reserve_out);
+        compute_price_impact(amount_in, amount_out, probe_in, probe_out)?
+    };
+
+    let from_decimals = token_decimals(provider, cache, from_token).await;
+    let to_decimals = token_decimals(provider, cache, to_token).await;
+    Ok(TradeSize {
+        pair: pair_address,
+        amount_in: wei_to_decimal(amount_in, from_decimals)?,
+        estimated_output: wei_to_decimal(amount_out, to_decimals)?,
+        price_impact,
+    })
+}
+
+/// Output of a Uniswap V2 swap against the given reserves, including the 0.3% fee
+fn v2_amount_out(amount_in: U256, reserve_in: U256, reserve_out: U256) -> U256 {
+    let amount_in_with_fee = amount_in * U256::from(997);
+    let denominator = reserve_in * U256::from(1000) + amount_in_with_fee;
+    if denominator.is_zero() {
+        return U256::zero();
+    }
+    amount_in_with_fee * reserve_out / denominator
+}
+
+/// Whether a V2 swap's price impact, measured against a probe trade, is at most `max_impact_bps`
+fn v2_within_impact(
+    amount_in: U256,
+    reserve_in: U256,
+    reserve_out: U256,
+    max_impact_bps: u32,
+) -> bool {
+    let probe_in = amount_in / U256::from(PRICE_IMPACT_PROBE_DIVISOR);
+    let probe_out = v2_amount_out(probe_in, reserve_in, reserve_out);
+    if probe_out.is_zero() {
+        return true;
+    }
+    let amount_out = v2_amount_out(amount_in, reserve_in, reserve_out);
+
+    // execution rate >= (1 - max impact) * marginal rate, kept in integers to avoid overflow
+    amount_out * probe_in * U256::from(10_000)
+        >= probe_out * amount_in * U256::from(10_000 - max_impact_bps)
+}
+
+/// Binary-search the largest input within the impact limit; impact grows with size
+fn max_v2_input_within_impact(reserve_in: U256, reserve_out: U256, max_impact_bps: u32) -> U256 {
+    let mut low = U256::zero();
+    // Selling 1000x the reserve costs over 99.9% in impact, beyond any accepted limit
+    let mut high = reserve_in * U256::from(1000);
+    if v2_within_impact(high, reserve_in, reserve_out, max_impact_bps) {
+        return high;
+    }
+
+    while high - low > U256::one() {
+        let mid = low + (high - low) / 2;
+        if v2_within_impact(mid, reserve_in, reserve_out, max_impact_bps) {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    low
+}
+
+/// WETH address of the V2 router; it is fixed per router, so only looked up once
+async fn router_weth(
+    cache: &MetadataCache,
+    router: &UniswapV2Router<EthProvider>,
+) -> Result<Address> {
+    Ok(router_info(cache, router).await?.weth)
+}
+
+/// WETH and factory addresses of the V2 router, cached after the first lookup
+async fn router_info(
+    cache: &MetadataCache,
+    router: &UniswapV2Router<EthProvider>,
+) -> Result<RouterInfo> {
+    let router_address = router.address();
+    cache
+        .router_info(router_address, || async {
+            let weth = router
+                .weth()
+                .call()
+                .await
+                .rpc_context("WETH", Some(router_address))?;
+            let factory = router
+                .factory()
+                .call()
+                .await
+                .rpc_context("factory", Some(router_address))?;
+            Ok(RouterInfo { weth, factory })
+        })
+        .await
+}
+
+/// Decimals for a swap leg token (18 for native ETH), defaulting to 18 when the call fails
+pub(super) async fn token_decimals(
+    provider: &EthClient,
+    cache: &MetadataCache,
+    token: Address,
+) -> u8 {
+    if token == Address::zero() {
+        return 18;
+    }
+
+    cache
+        .token_decimals(token, || async {
+            ERC20::new(token, provider.clone())
+                .decimals()
+                .call()
+                .await
+                .rpc_context("decimals", Some(token))
+        })
+        .await
+        .unwrap_or(18)
+}
 
-    // Simple path: from -> to
-    // In production, could optimize routing
-    vec![from, to]
+/// A venue's share of a split, in raw token units
+#[derive(Debug, Clone, Copy)]
+struct RouteLeg {
+    version: UniswapVersion,
+    fee_tier: Option<u32>,
+    share_pct: u32,
+    amount_in: U256,
+    amount_out: U256,
 }
 
-/// Estimate gas for a swap transaction
-async fn estimate_swap_gas(
+/// Quote the input split across V2 and V3 at several ratios and keep the best combination
+async fn quote_split(
     provider: &EthClient,
-    router: &UniswapV2Router<Provider<Http>>,
+    router: &UniswapV2Router<EthProvider>,
+    path: &[Address],
+    amount_in: U256,
+    fee_tier: Option<u32>,
+) -> Result<(U256, Option<u32>, Vec<RouteLeg>)> {
+    // Choose the V3 tier once on the full amount; a missing V3 pool just means no split
+    let v3_fee = match quote_v3_best_tier(
+        provider,
+        path[0],
+        path[path.len() - 1],
+        amount_in,
+        fee_tier,
+    )
+    .await
+    {
+        Ok((_, fee)) => Some(fee),
+        Err(e) => {
+            tracing::debug!("V3 unavailable for split: {}", e);
+            None
+        }
+    };
+
+    let mut candidates = Vec::with_capacity(SPLIT_V2_SHARES.len());
+    for v2_share in SPLIT_V2_SHARES {
+        if v3_fee.is_none() && v2_share != 100 {
+            continue;
+        }
+
+        let v2_in = amount_in * U256::from(v2_share) / U256::from(100);
+        let v3_in = amount_in - v2_in;
+
+        let v2_out = quote_leg(provider, router, UniswapVersion::V2, path, v2_in, None).await;
+        let v3_out = quote_leg(provider, router, UniswapVersion::V3, path, v3_in, v3_fee).await;
+
+        candidates.push((v2_share, v2_out, v3_out));
+    }
+
+    let (v2_share, total) =
+        pick_best_split(&candidates).context("No liquidity on either V2 or V3 for this pair")?;
+    let (_, v2_out, v3_out) = candidates
+        .into_iter()
+        .find(|(share, _, _)| *share == v2_share)
+        .unwrap();
+
+    let v2_in = amount_in * U256::from(v2_share) / U256::from(100);
+    let mut legs = Vec::new();
+    if !v2_in.is_zero() {
+        legs.push(RouteLeg {
+            version: UniswapVersion::V2,
+            fee_tier: None,
+            share_pct: v2_share,
+            amount_in: v2_in,
+            amount_out: v2_out,
+        });
+    }
+    if v2_share < 100 {
+        legs.push(RouteLeg {
+            version: UniswapVersion::V3,
+            fee_tier: v3_fee,
+            share_pct: 100 - v2_share,
+            amount_in: amount_in - v2_in,
+            amount_out: v3_out,
+        });
+    }
+
+    let used_fee = legs.iter().find_map(|leg| leg.fee_tier);
+    Ok((total, used_fee, legs))
+}
+
+/// Quote one leg of a split; a failed or empty leg contributes nothing
+async fn quote_leg(
+    provider: &EthClient,
+    router: &UniswapV2Router<EthProvider>,
+    version: UniswapVersion,
+    path: &[Address],
+    amount_in: U256,
+    fee_tier: Option<u32>,
+) -> U256 {
+    if amount_in.is_zero() {
+        return U256::zero();
+    }
+
+    match quote_route(provider, router, version, path, amount_in, fee_tier).await {
+        Ok((amount_out, _)) => amount_out,
+        Err(e) => {
+            tracing::debug!("Split leg on {:?} failed: {}", version, e);
+            U256::zero()
+        }
+    }
+}
+
+/// Pick the V2 share whose combined output is largest
+fn pick_best_split(candidates: &[(u32, U256, U256)]) -> Option<(u32, U256)> {
+    candidates
+        .iter()
+        .map(|(share, v2_out, v3_out)| (*share, *v2_out + *v3_out))
+        .filter(|(_, total)| !total.is_zero())
+        .max_by_key(|(_, total)| *total)
+}
+
+/// Quote the output amount for a path on the given Uniswap version
+async fn quote_route(
+    provider: &EthClient,
+    router: &UniswapV2Router<EthProvider>,
+    version: UniswapVersion,
+    path: &[Address],
+    amount_in: U256,
+    fee_tier: Option<u32>,
+) -> Result<(U256, Option<u32>)> {
+    match version {
+        UniswapVersion::V2 => {
+            let amounts_out = router
+                .get_amounts_out(amount_in, path.to_vec())
+                .call()
+                .await
+                .rpc_context("getAmountsOut", Some(router.address()))
+                .map_err(with_uniswap_revert)?;
+
+            // One amount per token on the path, or the output isn't for the requested route
+            if amounts_out.len() != path.len() {
+                anyhow::bail!(
+                    "Router quoted {} amounts for the {}-token route {:?}",
+                    amounts_out.len(),
+                    path.len(),
+                    path
+                );
+            }
+            let output = amounts_out.last().copied().context("No output amount")?;
+
+            Ok((output, None))
+        }
+        UniswapVersion::V3 => {
+            let (output, fee) =
+                quote_v3_best_tier(provider, path[0], path[path.len() - 1], amount_in, fee_tier)
+                    .await?;
+
+            Ok((output, Some(fee)))
+        }
+    }
+}
+
+/// Quote `amount_in` along the route at recent blocks, for how much its rate has been moving
+///
+/// Blocks the node can't serve are skipped, so fewer samples come back on a lagging or pruning node.
+async fn sample_route_rates(
+    provider: &EthClient,
+    router: &UniswapV2Router<EthProvider>,
+    version: UniswapVersion,
+    path: &[Address],
+    amount_in: U256,
+    fee_tier: Option<u32>,
+) -> Result<Vec<U256>> {
+    let latest = provider
+        .get_block_number()
+        .await
+        .rpc_context("eth_blockNumber", None)?
+        .as_u64();
+    let quoter = UniswapV3Quoter::new(
+        V3_QUOTER_ADDRESS.parse::<Address>().unwrap(),
+        provider.clone(),
+    );
+
+    let mut samples = Vec::with_capacity(AUTO_SLIPPAGE_SAMPLE_OFFSETS.len());
+    for offset in AUTO_SLIPPAGE_SAMPLE_OFFSETS {
+        let block = BlockId::from(latest.saturating_sub(offset));
+        let quote = match (version, fee_tier) {
+            (UniswapVersion::V3, Some(fee)) => quoter
+                .quote_exact_input_single(
+                    path[0],
+                    path[path.len() - 1],
+                    fee,
+                    amount_in,
+                    U256::zero(),
+                )
+                .block(block)
+                .call()
+                .await
+                .rpc_context("quoteExactInputSingle", Some(quoter.address())),
+            _ => router
+                .get_amounts_out(amount_in, path.to_vec())
+                .block(block)
+                .call()
+                .await
+                .rpc_context("getAmountsOut", Some(router.address()))
+                .and_then(|amounts| amounts.last().copied().context("No output amount")),
+        };
+        match quote {
+            Ok(out) if !out.is_zero() => samples.push(out),
+            Ok(_) => {}
+            Err(e) => tracing::debug!("No rate sample {} blocks back: {:#}", offset, e),
+        }
+    }
+    Ok(samples)
+}
+
+/// Clamp slippage up to the configured floor, noting when that happened or when it is dangerously high
+///
+/// Very tight tolerances make swaps fail on ordinary price movement, which invites retries that
+/// sandwich bots can exploit; very loose ones hand the difference to them outright.
+fn apply_slippage_policy(
+    requested_bps: u32,
+    min_bps: u32,
+    warn_bps: u32,
+) -> Result<(u32, Option<String>)> {
+    if requested_bps >= 10_000 {
+        anyhow::bail!(
+            "Slippage of {} bps must be below 100% (10000 bps)",
+            requested_bps
+        );
+    }
+
+    if requested_bps < min_bps {
+        return Ok((
+            min_bps,
+            Some(format!(
+                "Slippage raised from {} bps to the {} bps minimum (MIN_SLIPPAGE_BPS)",
+                requested_bps, min_bps
+            )),
+        ));
+    }
+
+    let warning = (requested_bps > warn_bps).then(|| {
+        format!(
+            "Slippage of {} bps is above {} bps; the minimum output leaves room for front-running",
+            requested_bps, warn_bps
+        )
+    });
+
+    Ok((requested_bps, warning))
+}
+
+/// Fraction of the estimated output kept as the minimum after slippage
+fn slippage_factor(slippage_bps: u32) -> Result<Decimal> {
+    // Validation rejects this first; checked again so the subtraction can never underflow
+    if slippage_bps >= 10_000 {
+        anyhow::bail!(
+            "Slippage of {} bps leaves no minimum output (must be below 10000 bps)",
+            slippage_bps
+        );
+    }
+    Ok(Decimal::from(10_000 - slippage_bps) / Decimal::from(10_000))
+}
+
+/// Minimum acceptable output after slippage, which must stay strictly positive
+fn slippage_floor(estimated_output: Decimal, slippage_bps: u32) -> Result<Decimal> {
+    let minimum_output = estimated_output * slippage_factor(slippage_bps)?;
+    if minimum_output <= Decimal::ZERO {
+        anyhow::bail!(
+            "Minimum output {} is not positive; refusing a swap without slippage protection",
+            minimum_output
+        );
+    }
+    Ok(minimum_output)
+}
+
+/// Surface a decoded Uniswap revert (e.g. INSUFFICIENT_LIQUIDITY) ahead of the raw RPC error
+fn with_uniswap_revert(error: anyhow::Error) -> anyhow::Error {
+    match revert_reason(&error).map(str::to_owned) {
+        Some(reason) => error.context(format!("Uniswap revert: {}", reason)),
+        None => error,
+    }
+}
+
+/// Price impact in percent: how far the trade's rate falls below the probe's marginal rate
+fn compute_price_impact(
+    amount_in: U256,
+    amount_out: U256,
+    probe_in: U256,
+    probe_out: U256,
+) -> Result<Decimal> {
+    if probe_out.is_zero() {
+        return Ok(Decimal::ZERO);
+    }
+
+    let execution_rate = wei_to_decimal(amount_out, 0)? / wei_to_decimal(amount_in, 0)?;
+    let marginal_rate = wei_to_decimal(probe_out, 0)? / wei_to_decimal(probe_in, 0)?;
+
+    let impact = (Decimal::ONE - execution_rate / marginal_rate) * Decimal::from(100);
+    Ok(impact.max(Decimal::ZERO))
+}
+
+/// Quote a single-hop V3 swap, searching the standard fee tiers unless one is pinned
+async fn quote_v3_best_tier(
+    provider: &EthClient,
+    token_in: Address,
+    token_out: Address,
+    amount_in: U256,
+    fee_tier: Option<u32>,
+) -> Result<(U256, u32)> {
+    let quoter_address = V3_QUOTER_ADDRESS.parse::<Address>().unwrap();
+
+    let quoter = UniswapV3Quoter::new(quoter_address, provider.clone());
+
+    let tiers = match fee_tier {
+        Some(fee) => vec![fee],
+        None => V3_FEE_TIERS.to_vec(),
+    };
+
+    let mut quotes = Vec::with_capacity(tiers.len());
+    for fee in tiers {
+        // The quoter reverts internally to return its result, so it must be eth_call'd
+        match quoter
+            .quote_exact_input_single(token_in, token_out, fee, amount_in, U256::zero())
+            .call()
+            .await
+        {
+            Ok(amount_out) => quotes.push((fee, amount_out)),
+            Err(e) => {
+                let e = Err::<U256, _>(e)
+                    .rpc_context("quoteExactInputSingle", Some(quoter_address))
+                    .unwrap_err();
+                tracing::debug!("No V3 quote for fee tier {}: {:#}", fee, e)
+            }
+        }
+    }
+
+    select_best_quote(&quotes).context("No Uniswap V3 pool with liquidity found for this pair")
+}
+
+/// Pick the fee tier yielding the largest output
+fn select_best_quote(quotes: &[(u32, U256)]) -> Option<(U256, u32)> {
+    quotes
+        .iter()
+        .filter(|(_, amount_out)| !amount_out.is_zero())
+        .max_by_key(|(_, amount_out)| *amount_out)
+        .map(|(fee, amount_out)| (*amount_out, *fee))
+}
+
+/// Build swap path from -> via... -> to, with ETH mapped to WETH
+///
+/// Paths that revisit a token (a cycle) or exceed `max_hops` are rejected before quoting.
+fn build_swap_path(
+    from_token: Address,
+    to_token: Address,
+    weth: Address,
+    via: &[Address],
+    max_hops: usize,
+) -> Result<Vec<Address>> {
+    let normalize = |token: Address| {
+        if token == Address::zero() {
+            weth
+        } else {
+            token
+        }
+    };
+
+    let mut path = Vec::with_capacity(via.len() + 2);
+    path.push(normalize(from_token));
+    path.extend(via.iter().copied().map(normalize));
+    path.push(normalize(to_token));
+
+    if path[0] == path[path.len() - 1] {
+        anyhow::bail!(
+            "from_token and to_token resolve to the same token {:?}",
+            path[0]
+        );
+    }
+    for (i, token) in path.iter().enumerate() {
+        if path[..i].contains(token) {
+            anyhow::bail!("Swap path visits {:?} more than once", token);
+        }
+    }
+
+    let hops = path.len() - 1;
+    if hops > max_hops {
+        anyhow::bail!(
+            "Swap path has {} hops, more than the maximum of {}",
+            hops,
+            max_hops
+        );
+    }
+
+    Ok(path)
+}
+
+/// Check an explicit route runs from `from_token` to `to_token` (WETH standing in for ETH)
+fn resolve_explicit_path(
+    explicit: &[Address],
+    from_token: Address,
+    to_token: Address,
+    weth: Address,
+    max_hops: usize,
+) -> Result<Vec<Address>> {
+    let normalize = |token: Address| {
+        if token == Address::zero() {
+            weth
+        } else {
+            token
+        }
+    };
+
+    let (first, last) = match explicit {
+        [first, .., last] => (*first, *last),
+        _ => anyhow::bail!("path must contain at least two tokens"),
+    };
+    if normalize(first) != normalize(from_token) {
+        anyhow::bail!("path must start at from_token {:?}", normalize(from_token));
+    }
+    if normalize(last) != normalize(to_token) {
+        anyhow::bail!("path must end at to_token {:?}", normalize(to_token));
+    }
+
+    build_swap_path(
+        from_token,
+        to_token,
+        weth,
+        &explicit[1..explicit.len() - 1],
+        max_hops,
+    )
+}
+
+/// Check a quoted route runs from `from_token` to `to_token`
+///
+/// ETH is matched against WETH on the path, so a WETH substitution that lands on a different
+/// token than requested (or on the wrong end of the route) fails instead of quoting that token.
+fn check_route_endpoints(
+    path: &[Address],
+    from_token: Address,
+    to_token: Address,
+    weth: Address,
+) -> Result<()> {
+    let normalize = |token: Address| {
+        if token == Address::zero() {
+            weth
+        } else {
+            token
+        }
+    };
+
+    let (first, last) = match path {
+        [first, .., last] => (*first, *last),
+        _ => anyhow::bail!("Quoted route {:?} has fewer than two tokens", path),
+    };
+    if first != normalize(from_token) {
+        anyhow::bail!(
+            "Quoted route starts at {:?}, not from_token {:?}",
+            first,
+            normalize(from_token)
+        );
+    }
+    if last != normalize(to_token) {
+        anyhow::bail!(
+            "Quoted route ends at {:?}, not to_token {:?}",
+            last,
+            normalize(to_token)
+        );
+    }
+    Ok(())
+}
+
+/// Build the V2 router call for a swap, picking the ETH-in, ETH-out or token variant
+fn build_v2_swap_tx(
+    router: &UniswapV2Router<EthProvider>,
     from_token: Address,
     to_token: Address,
     amount_in: U256,
     amount_out_min: U256,
     path: Vec<Address>,
-    wallet_address: Address,
-) -> Result<U256> {
-    // Set deadline to 20 minutes from now (in Unix timestamp)
-    let deadline = U256::from(
-        std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs()
-            + 1200,
-    );
+    recipient: Address,
+) -> TypedTransaction {
+    let deadline = swap_deadline();
 
-    // Build the transaction based on token types
-    let tx = if from_token == Address::zero() {
+    if from_token == Address::zero() {
         // ETH -> Token
         router
-            .swap_exact_eth_for_tokens(amount_out_min, path, wallet_address, deadline)
+            .swap_exact_eth_for_tokens(amount_out_min, path, recipient, deadline)
             .value(amount_in)
             .tx
     } else if to_token == Address::zero() {
         // Token -> ETH
         router
-            .swap_exact_tokens_for_eth(amount_in, amount_out_min, path, wallet_address, deadline)
+            .swap_exact_tokens_for_eth(amount_in, amount_out_min, path, recipient, deadline)
             .tx
     } else {
         // Token -> Token
         router
-            .swap_exact_tokens_for_tokens(
-                amount_in,
-                amount_out_min,
-                path,
-                wallet_address,
-                deadline,
-            )
+            .swap_exact_tokens_for_tokens(amount_in, amount_out_min, path, recipient, deadline)
             .tx
+    }
+}
+
+/// Build a single-hop V3 SwapRouter call
+fn build_v3_swap_tx(
+    provider: &EthClient,
+    from_token: Address,
+    path: &[Address],
+    fee: u32,
+    amount_in: U256,
+    amount_out_min: U256,
+    recipient: Address,
+) -> TypedTransaction {
+    let router = UniswapV3Router::new(
+        V3_ROUTER_ADDRESS.parse::<Address>().unwrap(),
+        provider.clone(),
+    );
+
+    // Output is delivered as WETH for ETH-out swaps; unwrapping would need a multicall
+    let params = ExactInputSingleParams {
+        token_in: path[0],
+        token_out: path[path.len() - 1],
+        fee,
+        recipient,
+        deadline: swap_deadline(),
+        amount_in,
+        amount_out_minimum: amount_out_min,
+        sqrt_price_limit_x96: U256::zero(),
+    };
+
+    let mut call = router.exact_input_single(params);
+    if from_token == Address::zero() {
+        // The router wraps msg.value into WETH when paying with ETH
+        call = call.value(amount_in);
+    }
+    call.tx
+}
+
+/// Deadline 20 minutes from now (Unix timestamp)
+fn swap_deadline() -> U256 {
+    U256::from(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 1200,
+    )
+}
+
+/// Estimate an exact `approve` of `amount` to `spender`, falling back to `FALLBACK_APPROVAL_GAS`
+///
+/// Some tokens (USDT) revert when changing one non-zero allowance to another, so a failed
+/// estimate shouldn't fail the quote.
+async fn estimate_approval_gas(
+    token: &ERC20<EthProvider>,
+    owner: Address,
+    spender: Address,
+    amount: U256,
+) -> U256 {
+    match token
+        .approve(spender, amount)
+        .from(owner)
+        .estimate_gas()
+        .await
+    {
+        Ok(gas) => gas,
+        Err(e) => {
+            tracing::debug!("Approval gas estimation failed, using fallback: {}", e);
+            U256::from(FALLBACK_APPROVAL_GAS)
+        }
+    }
+}
+
+/// Estimate gas with eth_estimateGas
+///
+/// A failed estimate usually means the transaction would revert, so it fails the quote with the
+/// decoded reason. With `allow_fallback` the quote goes ahead on `FALLBACK_SWAP_GAS` and a warning.
+async fn estimate_tx_gas(
+    provider: &EthClient,
+    tx: &TypedTransaction,
+    allow_fallback: bool,
+    warnings: &mut Vec<String>,
+) -> Result<U256> {
+    let error = match provider
+        .estimate_gas(tx, None)
+        .await
+        .rpc_context("eth_estimateGas", tx.to_addr().copied())
+    {
+        Ok(gas) => return Ok(gas),
+        Err(e) => with_uniswap_revert(e),
     };
+    if !allow_fallback {
+        return Err(error.context(
+            "Gas estimation failed, so the swap would likely revert; set allow_gas_fallback to quote it anyway",
+        ));
+    }
+    tracing::debug!("Gas estimation failed, using fallback: {:#}", error);
+    warnings.push(format!(
+        "Gas estimation failed ({:#}); quoted with a flat {} gas, and the swap would likely revert",
+        error, FALLBACK_SWAP_GAS
+    ));
+    Ok(U256::from(FALLBACK_SWAP_GAS))
+}
+
+/// How sub-wei dust is handled when converting a decimal amount to wei
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WeiRounding {
+    /// Drop the dust. Safe for spending: the raw amount never exceeds what was asked for
+    #[default]
+    Truncate,
+    /// Round half away from zero
+    Round,
+    /// Round up to the next wei
+    Ceil,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Unknown rounding mode {0} (expected truncate, round or ceil)")]
+pub struct ParseWeiRoundingError(String);
 
-    // Estimate gas using eth_estimateGas
-    match provider.estimate_gas(&tx, None).await {
-        Ok(gas) => Ok(gas),
-        Err(_) => {
-            // Return a default estimate if simulation fails
-            Ok(U256::from(300000)) // Conservative default
+impl FromStr for WeiRounding {
+    type Err = ParseWeiRoundingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "truncate" => Ok(WeiRounding::Truncate),
+            "round" => Ok(WeiRounding::Round),
+            "ceil" => Ok(WeiRounding::Ceil),
+            _ => Err(ParseWeiRoundingError(s.to_string())),
         }
     }
 }
 
+/// Significant digits `Decimal` holds exactly (its 96-bit mantissa tops out near 7.9e28)
+const MAX_DECIMAL_DIGITS: usize = 28;
+
 /// Convert decimal to wei
-fn decimal_to_wei(amount: Decimal, decimals: u8) -> Result<U256> {
-    let multiplier = Decimal::from(10u64.pow(decimals as u32));
-    let amount_wei = amount * multiplier;
-    let amount_str = amount_wei.trunc().to_string();
+///
+/// The amount is rounded at `decimals` places in `Decimal`, then scaled up in `U256`, so large
+/// balances or high-decimal tokens fail with an error instead of overflowing `Decimal`.
+fn decimal_to_wei(amount: Decimal, decimals: u8, rounding: WeiRounding) -> Result<U256> {
+    let strategy = match rounding {
+        WeiRounding::Truncate => RoundingStrategy::ToZero,
+        WeiRounding::Round => RoundingStrategy::MidpointAwayFromZero,
+        WeiRounding::Ceil => RoundingStrategy::ToPositiveInfinity,
+    };
+    let rounded = amount.round_dp_with_strategy(u32::from(decimals), strategy);
+    let mantissa = u128::try_from(rounded.mantissa())
+        .map_err(|_| anyhow::anyhow!("Cannot convert negative amount {} to base units", amount))?;
 
-    U256::from_dec_str(&amount_str).context("Failed to convert decimal to U256")
+    // Rounding leaves at most `decimals` places, so the remaining scale-up is a whole power of ten
+    let exponent = u32::from(decimals) - rounded.scale();
+    U256::from(10u8)
+        .checked_pow(U256::from(exponent))
+        .and_then(|multiplier| U256::from(mantissa).checked_mul(multiplier))
+        .with_context(|| {
+            format!(
+                "Amount {} with {} decimals overflows 256-bit base units",
+                amount, decimals
+            )
+        })
 }
 
 /// Convert wei to decimal
-fn wei_to_decimal(amount: U256, decimals: u8) -> Result<Decimal> {
-    let amount_str = amount.to_string();
-    let amount_decimal = Decimal::from_str(&amount_str)?;
-    let divisor = Decimal::from(10u64.pow(decimals as u32));
+///
+/// The decimal point is placed in the raw digit string rather than dividing in `Decimal`, which
+/// overflows for huge raw amounts. Fractional digits past `Decimal`'s precision are truncated, and
+/// amounts whose whole part alone exceeds it are an error.
+pub(super) fn wei_to_decimal(amount: U256, decimals: u8) -> Result<Decimal> {
+    let digits = amount.to_string();
+    let decimals = usize::from(decimals);
+    let (whole, fraction) = if digits.len() > decimals {
+        digits.split_at(digits.len() - decimals)
+    } else {
+        ("0", digits.as_str())
+    };
+    if whole.len() > MAX_DECIMAL_DIGITS {
+        anyhow::bail!(
+            "Amount of {} base units with {} decimals is too large to represent",
+            amount,
+            decimals
+        );
+    }
 
-    Ok(amount_decimal / divisor)
+    // Leading zeros of an amount below one unit don't count against the precision
+    let fraction = format!("{:0>width$}", fraction, width = decimals);
+    let whole_digits = if whole == "0" { 0 } else { whole.len() };
+    let kept = &fraction[..fraction.len().min(MAX_DECIMAL_DIGITS - whole_digits)];
+    let text = if kept.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{}.{}", whole, kept)
+    };
+
+    Ok(Decimal::from_str(&text)?.normalize())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_apply_slippage_policy() {
+        assert_eq!(apply_slippage_policy(50, 10, 300).unwrap(), (50, None));
+        assert_eq!(apply_slippage_policy(10, 10, 300).unwrap(), (10, None));
+        assert_eq!(apply_slippage_policy(300, 10, 300).unwrap(), (300, None));
+
+        let (bps, warning) = apply_slippage_policy(0, 10, 300).unwrap();
+        assert_eq!(bps, 10);
+        assert!(warning.unwrap().contains("raised from 0 bps"));
+
+        let (bps, warning) = apply_slippage_policy(500, 10, 300).unwrap();
+        assert_eq!(bps, 500);
+        assert!(warning.unwrap().contains("above 300 bps"));
+
+        assert!(apply_slippage_policy(10_001, 10, 300).is_err());
+    }
+
+    #[test]
+    fn test_slippage_floor_edges() {
+        let output = Decimal::from(100);
+
+        // 9999 bps is allowed (with a warning) and keeps 0.01% of the output
+        let (bps, warning) = apply_slippage_policy(9999, 10, 300).unwrap();
+        assert_eq!(bps, 9999);
+        assert!(warning.is_some());
+        assert_eq!(
+            slippage_floor(output, 9999).unwrap(),
+            Decimal::from_str("0.01").unwrap()
+        );
+
+        // 10000 bps would make the minimum zero: rejected by validation and by the floor itself
+        assert!(apply_slippage_policy(10_000, 10, 300).is_err());
+        assert!(slippage_factor(10_000).is_err());
+        assert!(slippage_floor(output, 10_000).is_err());
+
+        assert!(slippage_floor(Decimal::ZERO, 50).is_err());
+    }
+
     #[test]
     fn test_decimal_conversions() {
         let amount = Decimal::from(1);
-        let wei = decimal_to_wei(amount, 18).unwrap();
+        let wei = decimal_to_wei(amount, 18, WeiRounding::Truncate).unwrap();
         assert_eq!(wei, U256::from_dec_str("1000000000000000000").unwrap());
 
         let back = wei_to_decimal(wei, 18).unwrap();
         assert_eq!(back, amount);
     }
 
+    #[test]
+    fn test_decimal_to_wei_rounding() {
+        // 1.0000015 USDC (6 decimals) is 1_000_001.5 raw units
+        let half = Decimal::from_str("1.0000015").unwrap();
+        assert_eq!(
+            decimal_to_wei(half, 6, WeiRounding::Truncate).unwrap(),
+            U256::from(1_000_001u64)
+        );
+        assert_eq!(
+            decimal_to_wei(half, 6, WeiRounding::Round).unwrap(),
+            U256::from(1_000_002u64)
+        );
+        assert_eq!(
+            decimal_to_wei(half, 6, WeiRounding::Ceil).unwrap(),
+            U256::from(1_000_002u64)
+        );
+
+        // Just under half a unit: only ceil rounds up
+        let dust = Decimal::from_str("1.0000014").unwrap();
+        assert_eq!(
+            decimal_to_wei(dust, 6, WeiRounding::Round).unwrap(),
+            U256::from(1_000_001u64)
+        );
+        assert_eq!(
+            decimal_to_wei(dust, 6, WeiRounding::Ceil).unwrap(),
+            U256::from(1_000_002u64)
+        );
+
+        // Exact amounts are unaffected by the mode
+        let exact = Decimal::from_str("1.000001").unwrap();
+        for mode in [WeiRounding::Truncate, WeiRounding::Round, WeiRounding::Ceil] {
+            assert_eq!(
+                decimal_to_wei(exact, 6, mode).unwrap(),
+                U256::from(1_000_001u64)
+            );
+        }
+
+        assert_eq!("CEIL".parse::<WeiRounding>().unwrap(), WeiRounding::Ceil);
+        assert!("floor".parse::<WeiRounding>().is_err());
+    }
+
+    #[test]
+    fn test_wei_conversion_at_extremes() {
+        // A trillion-supply 18-decimal token: 31 raw digits, past what Decimal can parse directly
+        let trillion = Decimal::from_str("1000000000000.5").unwrap();
+        let raw = decimal_to_wei(trillion, 18, WeiRounding::Truncate).unwrap();
+        assert_eq!(raw, U256::exp10(30) + U256::exp10(17) * 5);
+        assert_eq!(wei_to_decimal(raw, 18).unwrap(), trillion);
+
+        // U256::MAX only fits Decimal when nearly all of it is fractional, truncated to 28 digits
+        assert!(wei_to_decimal(U256::MAX, 18).is_err());
+        assert_eq!(
+            wei_to_decimal(U256::MAX, 77).unwrap(),
+            Decimal::from_str("1.157920892373161954235709850").unwrap()
+        );
+        assert_eq!(wei_to_decimal(U256::one(), 40).unwrap(), Decimal::ZERO);
+        assert_eq!(wei_to_decimal(U256::zero(), 18).unwrap(), Decimal::ZERO);
+
+        // Scaling Decimal::MAX by 10^18 would overflow Decimal, but fits U256
+        let max = decimal_to_wei(Decimal::MAX, 18, WeiRounding::Truncate).unwrap();
+        assert_eq!(
+            max,
+            U256::from_dec_str(&Decimal::MAX.to_string()).unwrap() * U256::exp10(18)
+        );
+        assert!(decimal_to_wei(Decimal::MAX, 60, WeiRounding::Truncate).is_err());
+        assert_eq!(
+            decimal_to_wei(Decimal::ONE, 77, WeiRounding::Truncate).unwrap(),
+            U256::exp10(77)
+        );
+        assert!(decimal_to_wei(Decimal::NEGATIVE_ONE, 18, WeiRounding::Ceil).is_err());
+    }
+
     #[test]
     fn test_slippage_calculation() {
         let output = Decimal::from(100);
@@ -234,4 +1796,157 @@ mod tests {
 
         assert_eq!(min_output, Decimal::from_str("99.5").unwrap());
     }
+
+    #[test]
+    fn test_compute_price_impact() {
+        // Probe gets 2 out per 1 in; the full trade only gets 1.7 per 1 in -> 15% impact
+        let impact = compute_price_impact(
+            U256::from(10_000u64),
+            U256::from(17_000u64),
+            U256::from(1u64),
+            U256::from(2u64),
+        )
+        .unwrap();
+        assert_eq!(impact, Decimal::from(15));
+
+        // A trade at the marginal rate has no impact
+        let impact = compute_price_impact(
+            U256::from(10_000u64),
+            U256::from(20_000u64),
+            U256::from(1u64),
+            U256::from(2u64),
+        )
+        .unwrap();
+        assert_eq!(impact, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_pick_best_split() {
+        let candidates = vec![
+            (0, U256::zero(), U256::from(900u64)),
+            (50, U256::from(480u64), U256::from(470u64)),
+            (100, U256::from(920u64), U256::zero()),
+        ];
+        assert_eq!(pick_best_split(&candidates), Some((50, U256::from(950u64))));
+
+        let dry = vec![(100, U256::zero(), U256::zero())];
+        assert_eq!(pick_best_split(&dry), None);
+    }
+
+    #[test]
+    fn test_max_v2_input_within_impact() {
+        let reserve_in = U256::exp10(24);
+        let reserve_out = U256::exp10(21);
+
+        let amount = max_v2_input_within_impact(reserve_in, reserve_out, 100);
+        assert!(v2_within_impact(amount, reserve_in, reserve_out, 100));
+        assert!(!v2_within_impact(
+            amount + U256::one(),
+            reserve_in,
+            reserve_out,
+            100
+        ));
+        // Impact is 997x / (1000R + 997x), so 1% is reached at about 1.013% of the reserve
+        let share = wei_to_decimal(amount, 0).unwrap() / wei_to_decimal(reserve_in, 0).unwrap();
+        assert_eq!(share.round_dp(4), Decimal::new(101, 4));
+
+        // A looser limit allows a larger trade
+        assert!(max_v2_input_within_impact(reserve_in, reserve_out, 500) > amount);
+    }
+
+    #[test]
+    fn test_sort_route_candidates() {
+        let candidate = |fee_tier: u32, output: Option<u64>| RouteCandidate {
+            version: UniswapVersion::V3,
+            fee_tier: Some(fee_tier),
+            path: Vec::new(),
+            estimated_output: output.map(Decimal::from),
+            price_impact: output.map(|_| Decimal::ZERO),
+            error: output.is_none().then(|| "no pool".to_string()),
+        };
+        let mut candidates = vec![
+            candidate(100, None),
+            candidate(500, Some(990)),
+            candidate(3000, None),
+            candidate(10000, Some(1000)),
+        ];
+
+        sort_route_candidates(&mut candidates);
+
+        let order: Vec<u32> = candidates.iter().filter_map(|c| c.fee_tier).collect();
+        assert_eq!(order, vec![10000, 500, 100, 3000]);
+    }
+
+    #[test]
+    fn test_build_swap_path() {
+        let weth = Address::from_low_u64_be(1);
+        let a = Address::from_low_u64_be(2);
+        let b = Address::from_low_u64_be(3);
+        let c = Address::from_low_u64_be(4);
+
+        assert_eq!(
+            build_swap_path(Address::zero(), a, weth, &[], 3).unwrap(),
+            vec![weth, a]
+        );
+        assert_eq!(
+            build_swap_path(a, b, weth, &[weth], 3).unwrap(),
+            vec![a, weth, b]
+        );
+
+        // Same token on both ends, including ETH vs WETH
+        assert!(build_swap_path(a, a, weth, &[], 3).is_err());
+        assert!(build_swap_path(Address::zero(), weth, weth, &[], 3).is_err());
+
+        // Intermediary equal to an endpoint is a cycle
+        assert!(build_swap_path(a, b, weth, &[a], 3).is_err());
+        assert!(build_swap_path(a, b, weth, &[weth, b], 3).is_err());
+
+        // Too many hops
+        assert!(build_swap_path(a, b, weth, &[weth, c], 2).is_err());
+        assert!(build_swap_path(a, b, weth, &[weth, c], 3).is_ok());
+    }
+
+    #[test]
+    fn test_resolve_explicit_path() {
+        let weth = Address::from_low_u64_be(1);
+        let a = Address::from_low_u64_be(2);
+        let b = Address::from_low_u64_be(3);
+        let exotic = Address::from_low_u64_be(4);
+
+        assert_eq!(
+            resolve_explicit_path(&[weth, exotic, b], Address::zero(), b, weth, 3).unwrap(),
+            vec![weth, exotic, b]
+        );
+        assert_eq!(
+            resolve_explicit_path(&[a, b], a, b, weth, 3).unwrap(),
+            vec![a, b]
+        );
+
+        // Wrong endpoints
+        assert!(resolve_explicit_path(&[b, a], a, b, weth, 3).is_err());
+        assert!(resolve_explicit_path(&[a, exotic], a, b, weth, 3).is_err());
+        assert!(resolve_explicit_path(&[a], a, b, weth, 3).is_err());
+
+        // Cycles and hop limits still apply
+        assert!(resolve_explicit_path(&[a, exotic, a, b], a, b, weth, 3).is_err());
+        assert!(resolve_explicit_path(&[a, weth, exotic, b], a, b, weth, 2).is_err());
+    }
+
+    #[test]
+    fn test_select_best_quote() {
+        let quotes = vec![
+            (500, U256::from(1_000u64)),
+            (3000, U256::from(1_200u64)),
+            (10000, U256::from(900u64)),
+        ];
+        assert_eq!(
+            select_best_quote(&quotes),
+            Some((U256::from(1_200u64), 3000))
+        );
+
+        // Zero outputs mean no usable liquidity in that tier
+        let empty = vec![(100, U256::zero())];
+        assert_eq!(select_best_quote(&empty), None);
+        assert_eq!(select_best_quote(&[]), None);
+    }
 }
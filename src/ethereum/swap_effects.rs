@@ -0,0 +1,175 @@
+use anyhow::Result;
+use ethers::prelude::*;
+use rust_decimal::Decimal;
+
+use super::balance::{get_eth_balance, get_token_balance, BalanceInfo};
+use super::cache::MetadataCache;
+use super::client::EthClient;
+use super::swap::{simulate_swap, SwapRequest, SwapSimulation};
+
+/// One asset's projected balance, before and after the swap
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceChange {
+    /// `Address::zero()` for ETH
+    pub token: Address,
+    pub symbol: String,
+    pub before: Decimal,
+    pub after: Decimal,
+}
+
+impl BalanceChange {
+    pub fn change(&self) -> Decimal {
+        self.after - self.before
+    }
+}
+
+#[derive(Debug)]
+pub struct SwapEffects {
+    pub simulation: SwapSimulation,
+    /// From-token, to-token, then ETH; ETH appears once when it is one side of the swap
+    pub changes: Vec<BalanceChange>,
+}
+
+/// Quote a swap and project the wallet's from-token, to-token and ETH balances after it
+///
+/// The to-token is credited the estimated output, unless a `recipient` other than the wallet
+/// receives it, and ETH is debited the estimated gas cost.
+/// Current balances are fetched alongside the quote.
+pub async fn simulate_swap_effects(
+    provider: &EthClient,
+    cache: &MetadataCache,
+    request: SwapRequest,
+) -> Result<SwapEffects> {
+    let (from_token, to_token, wallet) =
+        (request.from_token, request.to_token, request.wallet_address);
+    let amount_in = request.amount_in;
+    // Output sent to another account never reaches the wallet
+    let credited = request.recipient.unwrap_or(wallet) == wallet;
+
+    let (simulation, from_balance, to_balance, eth_balance) = tokio::join!(
+        simulate_swap(provider, cache, request),
+        balance_of(provider, cache, from_token, wallet),
+        balance_of(provider, cache, to_token, wallet),
+        get_eth_balance(provider, wallet),
+    );
+    let simulation = simulation?;
+
+    let changes = project_balances(
+        [
+            (from_token, from_balance?),
+            (to_token, to_balance?),
+            (Address::zero(), eth_balance?),
+        ],
+        amount_in,
+        if credited {
+            simulation.estimated_output
+        } else {
+            Decimal::ZERO
+        },
+        simulation.estimated_cost_eth,
+    );
+
+    Ok(SwapEffects {
+        simulation,
+        changes,
+    })
+}
+
+async fn balance_of(
+    provider: &EthClient,
+    cache: &MetadataCache,
+    token: Address,
+    wallet: Address,
+) -> Result<BalanceInfo> {
+    if token.is_zero() {
+        get_eth_balance(provider, wallet).await
+    } else {
+        get_token_balance(provider, cache, token, wallet).await
+    }
+}
+
+/// Apply the swap's deltas to the current from-token, to-token and ETH balances, in that order
+///
+/// When ETH is itself traded its deltas combine into the one ETH entry.
+fn project_balances(
+    current: [(Address, BalanceInfo); 3],
+    amount_in: Decimal,
+    output: Decimal,
+    gas_cost_eth: Decimal,
+) -> Vec<BalanceChange> {
+    let deltas = [-amount_in, output, -gas_cost_eth];
+    let mut changes: Vec<BalanceChange> = Vec::with_capacity(3);
+    for ((token, info), delta) in current.into_iter().zip(deltas) {
+        match changes.iter_mut().find(|change| change.token == token) {
+            Some(change) => change.after += delta,
+            None => changes.push(BalanceChange {
+                token,
+                symbol: info.symbol,
+                before: info.balance,
+                after: info.balance + delta,
+            }),
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn info(symbol: &str, balance: &str) -> BalanceInfo {
+        BalanceInfo {
+            balance: Decimal::from_str(balance).unwrap(),
+            symbol: symbol.to_string(),
+            decimals: 18,
+            raw_balance: U256::zero(),
+            underlying: None,
+        }
+    }
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn test_project_token_to_token_swap() {
+        let (usdc, dai) = (Address::from_low_u64_be(1), Address::from_low_u64_be(2));
+        let changes = project_balances(
+            [
+                (usdc, info("USDC", "1000")),
+                (dai, info("DAI", "5")),
+                (Address::zero(), info("ETH", "1")),
+            ],
+            dec("100"),
+            dec("99.5"),
+            dec("0.01"),
+        );
+
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[0].after, dec("900"));
+        assert_eq!(changes[1].after, dec("104.5"));
+        assert_eq!(changes[2].symbol, "ETH");
+        assert_eq!(changes[2].change(), dec("-0.01"));
+    }
+
+    #[test]
+    fn test_project_eth_swap_merges_gas_into_eth() {
+        let usdc = Address::from_low_u64_be(1);
+        let changes = project_balances(
+            [
+                (Address::zero(), info("ETH", "2")),
+                (usdc, info("USDC", "0")),
+                (Address::zero(), info("ETH", "2")),
+            ],
+            dec("1"),
+            dec("2000"),
+            dec("0.01"),
+        );
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].token, Address::zero());
+        assert_eq!(changes[0].after, dec("0.99"));
+        assert_eq!(changes[1].after, dec("2000"));
+    }
+}
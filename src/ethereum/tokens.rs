@@ -0,0 +1,160 @@
+use anyhow::Result;
+use ethers::prelude::*;
+
+/// Native-ETH placeholder used by many aggregators and wallets; this server's own sentinel is
+/// `Address::zero()`
+pub const ETH_SENTINEL_ADDRESS: &str = "0xEeeeeEeeeEeEeeEeEeEeeEEEeeeeEeeeeeeeEEeE";
+
+/// Whether `address` is either native-ETH sentinel
+pub fn is_native_eth(address: Address) -> bool {
+    address.is_zero() || address == eth_sentinel()
+}
+
+/// Map either native-ETH sentinel to `Address::zero()`, leaving token addresses untouched
+pub fn normalize_eth(address: Address) -> Address {
+    if is_native_eth(address) {
+        Address::zero()
+    } else {
+        address
+    }
+}
+
+fn eth_sentinel() -> Address {
+    ETH_SENTINEL_ADDRESS
+        .parse()
+        .expect("ETH_SENTINEL_ADDRESS is a valid address")
+}
+
+/// A well-known token the server can resolve by symbol
+#[derive(Debug, Clone)]
+pub struct TokenEntry {
+    pub symbol: String,
+    pub address: Address,
+}
+
+/// Symbol -> address lookup for common tokens
+#[derive(Debug, Clone, Default)]
+pub struct TokenRegistry {
+    tokens: Vec<TokenEntry>,
+}
+
+impl TokenRegistry {
+    pub fn new(tokens: Vec<TokenEntry>) -> Self {
+        Self { tokens }
+    }
+
+    /// Built-in registry of Ethereum mainnet majors
+    pub fn mainnet() -> Self {
+        let majors = [
+            ("WETH", "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"),
+            ("USDC", "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"),
+            ("USDT", "0xdAC17F958D2ee523a2206206994597C13D831ec7"),
+            ("DAI", "0x6B175474E89094C44Da98b954EedeAC495271d0F"),
+            ("WBTC", "0x2260FAC5E5542a773Aa44fBCfeDf7C193bc2C599"),
+            ("UNI", "0x1f9840a85d5aF5bf1D1762F925BDADdC4201F984"),
+            ("LINK", "0x514910771AF9Ca656af840dff83E8264EcF986CA"),
+        ];
+
+        Self::new(
+            majors
+                .iter()
+                .map(|(symbol, address)| TokenEntry {
+                    symbol: symbol.to_string(),
+                    address: address.parse().unwrap(),
+                })
+                .collect(),
+        )
+    }
+
+    /// All tokens whose symbol matches (case-insensitive)
+    pub fn find_by_symbol(&self, symbol: &str) -> Vec<&TokenEntry> {
+        self.tokens
+            .iter()
+            .filter(|token| token.symbol.eq_ignore_ascii_case(symbol))
+            .collect()
+    }
+
+    /// The token at `address`, if it is in the registry
+    pub fn find_by_address(&self, address: Address) -> Option<&TokenEntry> {
+        self.tokens.iter().find(|token| token.address == address)
+    }
+
+    /// Resolve a symbol to a single address, erroring when unknown or ambiguous
+    pub fn resolve_symbol(&self, symbol: &str) -> Result<Address> {
+        match self.find_by_symbol(symbol).as_slice() {
+            [] => anyhow::bail!(
+                "Unknown token symbol: {}. Pass token_address instead",
+                symbol
+            ),
+            [token] => Ok(token.address),
+            candidates => {
+                let addresses: Vec<String> = candidates
+                    .iter()
+                    .map(|token| format!("{:?}", token.address))
+                    .collect();
+                anyhow::bail!(
+                    "Ambiguous token symbol {}: matches {}. Pass token_address to disambiguate",
+                    symbol,
+                    addresses.join(", ")
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_symbol() {
+        let registry = TokenRegistry::mainnet();
+        let usdc = registry.resolve_symbol("usdc").unwrap();
+        assert_eq!(
+            usdc,
+            "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"
+                .parse::<Address>()
+                .unwrap()
+        );
+
+        assert!(registry.resolve_symbol("NOTATOKEN").is_err());
+    }
+
+    #[test]
+    fn test_normalize_eth_sentinels() {
+        assert_eq!(normalize_eth(Address::zero()), Address::zero());
+        for sentinel in [
+            ETH_SENTINEL_ADDRESS.to_string(),
+            ETH_SENTINEL_ADDRESS.to_lowercase(),
+        ] {
+            let sentinel = sentinel.parse::<Address>().unwrap();
+            assert!(is_native_eth(sentinel));
+            assert_eq!(normalize_eth(sentinel), Address::zero());
+        }
+
+        let usdc = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"
+            .parse::<Address>()
+            .unwrap();
+        assert!(!is_native_eth(usdc));
+        assert_eq!(normalize_eth(usdc), usdc);
+    }
+
+    #[test]
+    fn test_ambiguous_symbol_lists_candidates() {
+        let registry = TokenRegistry::new(vec![
+            TokenEntry {
+                symbol: "FOO".to_string(),
+                address: Address::from_low_u64_be(1),
+            },
+            TokenEntry {
+                symbol: "FOO".to_string(),
+                address: Address::from_low_u64_be(2),
+            },
+        ]);
+
+        let err = registry.resolve_symbol("foo").unwrap_err().to_string();
+        assert!(err.contains("Ambiguous"));
+        assert!(err.contains(&format!("{:?}", Address::from_low_u64_be(1))));
+        assert!(err.contains(&format!("{:?}", Address::from_low_u64_be(2))));
+    }
+}
@@ -0,0 +1,157 @@
+use anyhow::{Context, Result};
+use ethers::prelude::*;
+use rust_decimal::Decimal;
+
+use super::abi::{UniswapV3Pool, ERC20};
+use super::client::EthClient;
+use super::error::{require_found, RpcCallContext};
+
+#[derive(Debug)]
+pub struct TwapInfo {
+    pub token0: Address,
+    pub token1: Address,
+    pub window_secs: u32,
+    /// Time-weighted price of token0 denominated in token1
+    pub twap: Decimal,
+    /// Current spot price of token0 denominated in token1
+    pub spot: Decimal,
+}
+
+/// Compute the TWAP of a Uniswap V3 pool over `window_secs` using its observation oracle
+pub async fn get_twap(
+    provider: &EthClient,
+    pool_address: Address,
+    window_secs: u32,
+) -> Result<TwapInfo> {
+    if window_secs == 0 {
+        anyhow::bail!("window_secs must be greater than zero");
+    }
+
+    let pool = UniswapV3Pool::new(pool_address, provider.clone());
+
+    let (_, spot_tick, observation_index, cardinality, _, _, _) = pool
+        .slot_0()
+        .call()
+        .await
+        .rpc_context("slot0", Some(pool_address))?;
+
+    if cardinality < 2 {
+        anyhow::bail!(
+            "Pool observation cardinality is {}; the oracle keeps no history. Increase it with increaseObservationCardinalityNext",
+            cardinality
+        );
+    }
+
+    // The slot after the current index is the oldest one once the ring buffer has wrapped
+    let oldest_index = (observation_index + 1) % cardinality;
+    let (mut oldest_timestamp, _, _, initialized) = pool
+        .observations(U256::from(oldest_index))
+        .call()
+        .await
+        .rpc_context("observations", Some(pool_address))?;
+    if !initialized {
+        // Buffer hasn't wrapped yet, so history starts at slot 0
+        let (timestamp, _, _, _) = pool
+            .observations(U256::zero())
+            .call()
+            .await
+            .rpc_context("observations", Some(pool_address))?;
+        oldest_timestamp = timestamp;
+    }
+
+    let latest_block = provider
+        .get_block(BlockNumber::Latest)
+        .await
+        .context("Failed to get latest block")?;
+    let latest_block = require_found(latest_block, "Block", BlockNumber::Latest)?;
+    let now = latest_block.timestamp.as_u64();
+    let available = now.saturating_sub(oldest_timestamp as u64);
+    if (window_secs as u64) > available {
+        anyhow::bail!(
+            "Requested window of {}s exceeds the pool's available oracle history of {}s",
+            window_secs,
+            available
+        );
+    }
+
+    let (tick_cumulatives, _) = pool
+        .observe(vec![window_secs, 0])
+        .call()
+        .await
+        .rpc_context("observe", Some(pool_address))?;
+    if tick_cumulatives.len() != 2 {
+        anyhow::bail!("Unexpected observe() response length");
+    }
+
+    let average_tick = average_tick(tick_cumulatives[0], tick_cumulatives[1], window_secs);
+
+    let token0 = pool
+        .token_0()
+        .call()
+        .await
+        .rpc_context("token0", Some(pool_address))?;
+    let token1 = pool
+        .token_1()
+        .call()
+        .await
+        .rpc_context("token1", Some(pool_address))?;
+    let decimals0 = ERC20::new(token0, provider.clone())
+        .decimals()
+        .call()
+        .await
+        .rpc_context("decimals", Some(token0))?;
+    let decimals1 = ERC20::new(token1, provider.clone())
+        .decimals()
+        .call()
+        .await
+        .rpc_context("decimals", Some(token1))?;
+
+    Ok(TwapInfo {
+        token0,
+        token1,
+        window_secs,
+        twap: tick_to_price(average_tick, decimals0, decimals1)?,
+        spot: tick_to_price(spot_tick, decimals0, decimals1)?,
+    })
+}
+
+/// Arithmetic mean tick over the window, rounded toward negative infinity like the V3 OracleLibrary
+fn average_tick(cumulative_start: i64, cumulative_end: i64, window_secs: u32) -> i32 {
+    let delta = cumulative_end - cumulative_start;
+    let window = window_secs as i64;
+
+    let mut tick = delta / window;
+    if delta < 0 && delta % window != 0 {
+        tick -= 1;
+    }
+    tick as i32
+}
+
+/// Price of token0 in token1 for a tick: 1.0001^tick scaled by the decimals difference
+fn tick_to_price(tick: i32, decimals0: u8, decimals1: u8) -> Result<Decimal> {
+    let raw = 1.0001f64.powi(tick) * 10f64.powi(decimals0 as i32 - decimals1 as i32);
+    Decimal::from_f64_retain(raw).context("Price out of range for Decimal")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_average_tick_rounds_down() {
+        assert_eq!(average_tick(0, 1800 * 100, 1800), 100);
+        assert_eq!(average_tick(0, -1800 * 100, 1800), -100);
+        // -1 over 1800s must round toward negative infinity
+        assert_eq!(average_tick(0, -1, 1800), -1);
+        assert_eq!(average_tick(1000, 1001, 1800), 0);
+    }
+
+    #[test]
+    fn test_tick_to_price() {
+        assert_eq!(tick_to_price(0, 18, 18).unwrap(), Decimal::ONE);
+
+        // Same tick with 6 vs 18 decimals shifts the price by 1e12
+        let price = tick_to_price(0, 18, 6).unwrap();
+        assert_eq!(price, Decimal::from(1_000_000_000_000u64));
+    }
+}
@@ -0,0 +1,148 @@
+use anyhow::Result;
+use ethers::prelude::*;
+use std::time::{Duration, Instant};
+
+use super::client::EthClient;
+use super::error::{require_found, RpcCallContext};
+
+/// Longest a single call may wait for confirmations
+pub const MAX_CONFIRMATION_WAIT_SECS: u64 = 600;
+
+/// Reasons a sent transaction never reached the requested confirmations
+#[derive(Debug, thiserror::Error)]
+pub enum TxError {
+    #[error(
+        "Transaction {tx_hash:?} was replaced: nonce {nonce} was mined by a different transaction"
+    )]
+    Replaced { tx_hash: H256, nonce: U256 },
+    #[error("Transaction {tx_hash:?} was dropped from the mempool")]
+    Dropped { tx_hash: H256 },
+    #[error(
+        "Timed out after {timeout:?} waiting for {confirmations} confirmations of {tx_hash:?}"
+    )]
+    Timeout {
+        tx_hash: H256,
+        confirmations: u64,
+        timeout: Duration,
+    },
+}
+
+/// A mined transaction's receipt and how deep it is buried
+#[derive(Debug)]
+pub struct ConfirmedTx {
+    pub receipt: TransactionReceipt,
+    pub confirmations: u64,
+}
+
+/// Poll for a receipt until the transaction has `confirmations` blocks on top of it
+///
+/// A transaction the node no longer knows is reported as replaced when its sender's nonce has
+/// moved past it, and as dropped otherwise.
+pub async fn wait_for_confirmations(
+    provider: &EthClient,
+    tx_hash: H256,
+    confirmations: u64,
+    timeout: Duration,
+) -> Result<ConfirmedTx> {
+    // Remember sender and nonce so a replacement can be detected once the tx disappears
+    let tx = provider
+        .get_transaction(tx_hash)
+        .await
+        .rpc_context("eth_getTransactionByHash", None)?;
+    let tx = require_found(tx, "Transaction", tx_hash)?;
+
+    let deadline = Instant::now() + timeout;
+    let interval = provider.get_interval();
+
+    loop {
+        let receipt = provider
+            .get_transaction_receipt(tx_hash)
+            .await
+            .rpc_context("eth_getTransactionReceipt", None)?;
+
+        match receipt {
+            Some(receipt) => {
+                if let Some(mined_block) = receipt.block_number {
+                    let current_block = provider
+                        .get_block_number()
+                        .await
+                        .rpc_context("eth_blockNumber", None)?;
+
+                    let count = confirmation_count(mined_block.as_u64(), current_block.as_u64());
+                    if count >= confirmations {
+                        return Ok(ConfirmedTx {
+                            receipt,
+                            confirmations: count,
+                        });
+                    }
+                }
+            }
+            None => {
+                let pending = provider
+                    .get_transaction(tx_hash)
+                    .await
+                    .rpc_context("eth_getTransactionByHash", None)?;
+                if pending.is_none() {
+                    let mined_nonce = provider
+                        .get_transaction_count(tx.from, None)
+                        .await
+                        .rpc_context("eth_getTransactionCount", None)?;
+                    return Err(vanished(tx_hash, tx.nonce, mined_nonce).into());
+                }
+            }
+        }
+
+        if Instant::now() >= deadline {
+            return Err(TxError::Timeout {
+                tx_hash,
+                confirmations,
+                timeout,
+            }
+            .into());
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Number of confirmations a tx mined in `mined_block` has at `current_block`
+fn confirmation_count(mined_block: u64, current_block: u64) -> u64 {
+    if current_block < mined_block {
+        // Lagging load-balanced node; treat as just mined
+        return 1;
+    }
+    current_block - mined_block + 1
+}
+
+/// Why an unmined transaction disappeared: its nonce was used by another one, or it was dropped
+fn vanished(tx_hash: H256, nonce: U256, mined_nonce: U256) -> TxError {
+    if mined_nonce > nonce {
+        TxError::Replaced { tx_hash, nonce }
+    } else {
+        TxError::Dropped { tx_hash }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confirmation_count() {
+        assert_eq!(confirmation_count(100, 100), 1);
+        assert_eq!(confirmation_count(100, 102), 3);
+        assert_eq!(confirmation_count(100, 99), 1);
+    }
+
+    #[test]
+    fn test_vanished_transaction() {
+        let tx_hash = H256::from_low_u64_be(1);
+
+        // The sender has mined nonce 5, so another transaction took it
+        let replaced = vanished(tx_hash, U256::from(5), U256::from(6));
+        assert!(matches!(replaced, TxError::Replaced { nonce, .. } if nonce == U256::from(5)));
+
+        let dropped = vanished(tx_hash, U256::from(5), U256::from(5));
+        assert!(matches!(dropped, TxError::Dropped { .. }));
+    }
+}
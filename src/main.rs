@@ -1,98 +1,694 @@
+mod config;
 mod ethereum;
 mod mcp;
 mod tools;
 mod types;
 
 use anyhow::{Context, Result};
-use ethers::prelude::Middleware;
+use ethers::signers::Signer;
+use reqwest::header::USER_AGENT;
+use std::future::Future;
 use std::io::{self, BufRead, Write};
-use tracing_subscriber::EnvFilter;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Semaphore};
+use tokio::task::JoinSet;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{fmt, reload, EnvFilter};
 
-use ethereum::create_provider;
+use config::Config;
+use ethereum::{
+    create_http_client, create_provider, fetch_chain_info, user_agent_header, ChainInfo,
+    ChainProviders, MetadataCache, RpcLimits, RpcLogging,
+};
 use mcp::McpServer;
-use types::JsonRpcRequest;
+use serde_json::Value;
+
+/// Largest `Content-Length` accepted for one framed message
+const MAX_FRAME_BYTES: usize = 16 * 1024 * 1024;
+
+/// How JSON-RPC messages are delimited on stdio
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Framing {
+    /// One message per line
+    Lines,
+    /// LSP-style `Content-Length` headers, a blank line, then the body
+    ContentLength,
+}
+
+/// Reads messages in whichever framing the client's first bytes use, for the whole session
+struct MessageReader<R> {
+    reader: R,
+    framing: Option<Framing>,
+}
+
+impl<R: BufRead> MessageReader<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader,
+            framing: None,
+        }
+    }
+
+    /// Framing detected so far; line-delimited until the first message arrives
+    fn framing(&self) -> Framing {
+        self.framing.unwrap_or(Framing::Lines)
+    }
+
+    /// Next message body, or `None` at end of input
+    fn read_message(&mut self) -> io::Result<Option<String>> {
+        let framing = match self.framing {
+            Some(framing) => framing,
+            None => match self.detect_framing()? {
+                Some(framing) => {
+                    tracing::debug!("Using {:?} framing on stdio", framing);
+                    self.framing = Some(framing);
+                    framing
+                }
+                None => return Ok(None),
+            },
+        };
+
+        match framing {
+            Framing::Lines => self.read_line_message(),
+            Framing::ContentLength => self.read_framed_message(),
+        }
+    }
+
+    /// Skip leading whitespace and look at the first byte: JSON never starts with a header name
+    fn detect_framing(&mut self) -> io::Result<Option<Framing>> {
+        loop {
+            let buf = self.reader.fill_buf()?;
+            let Some(&first) = buf.first() else {
+                return Ok(None);
+            };
+            if first.is_ascii_whitespace() {
+                self.reader.consume(1);
+                continue;
+            }
+            return Ok(Some(if first.eq_ignore_ascii_case(&b'c') {
+                Framing::ContentLength
+            } else {
+                Framing::Lines
+            }));
+        }
+    }
+
+    fn read_line_message(&mut self) -> io::Result<Option<String>> {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            if !line.trim().is_empty() {
+                return Ok(Some(line.trim_end().to_string()));
+            }
+        }
+    }
+
+    fn read_framed_message(&mut self) -> io::Result<Option<String>> {
+        let mut content_length = None;
+        let mut saw_header = false;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self.reader.read_line(&mut line)? == 0 {
+                if saw_header {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "stdin closed inside message headers",
+                    ));
+                }
+                return Ok(None);
+            }
+            let header = line.trim();
+            if header.is_empty() {
+                // Blank lines between messages are tolerated; after headers they end them
+                if saw_header {
+                    break;
+                }
+                continue;
+            }
+            saw_header = true;
+
+            // Other headers, such as Content-Type, are ignored
+            let (name, value) = header.split_once(':').ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Malformed message header: {}", header),
+                )
+            })?;
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                let length = value.trim().parse::<usize>().map_err(|e| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("Invalid Content-Length {}: {}", value.trim(), e),
+                    )
+                })?;
+                content_length = Some(length);
+            }
+        }
+
+        let length = content_length.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Message headers have no Content-Length",
+            )
+        })?;
+        if length > MAX_FRAME_BYTES {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Content-Length {} exceeds the {} byte limit",
+                    length, MAX_FRAME_BYTES
+                ),
+            ));
+        }
+
+        let mut body = vec![0; length];
+        self.reader.read_exact(&mut body)?;
+        String::from_utf8(body)
+            .map(Some)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+/// Handle one message body, returning the serialized response (`None` for notifications)
+async fn process_message(server: &McpServer, line: &str) -> Result<Option<String>> {
+    tracing::debug!("Received: {}", line);
+
+    // Parse message; structural validation happens in the server
+    let message: Value = match serde_json::from_str(line) {
+        Ok(message) => message,
+        Err(e) => {
+            tracing::error!("Failed to parse request: {}", e);
+            let error_response = types::JsonRpcResponse {
+                jsonrpc: "2.0".to_string(),
+                id: serde_json::Value::Null,
+                result: None,
+                error: Some(types::JsonRpcError {
+                    code: -32700,
+                    message: format!("Parse error: {}", e),
+                    data: None,
+                }),
+            };
+            return Ok(Some(serde_json::to_string(&error_response)?));
+        }
+    };
+
+    // Handle a single request or a batch; notifications must never be answered
+    let response_json = match message {
+        Value::Array(batch) => {
+            let responses = server.handle_batch(batch).await;
+            if responses.is_empty() {
+                return Ok(None);
+            }
+            serde_json::to_string(&responses)?
+        }
+        message => match server.handle_message(message).await {
+            Some(response) => serde_json::to_string(&response)?,
+            None => return Ok(None),
+        },
+    };
+    Ok(Some(response_json))
+}
+
+/// The -32603 response for a message whose handler panicked; `None` for notifications
+///
+/// A batch can't be answered request by request, so it gets a single error with a null id.
+fn internal_error_response(message: &str) -> Result<Option<String>> {
+    let id = match serde_json::from_str::<Value>(message) {
+        Ok(Value::Object(request)) => match request.get("id") {
+            Some(id) => id.clone(),
+            None => return Ok(None),
+        },
+        _ => Value::Null,
+    };
+    let response = types::JsonRpcResponse {
+        jsonrpc: "2.0".to_string(),
+        id,
+        result: None,
+        error: Some(types::JsonRpcError {
+            code: -32603,
+            message: "Internal error: request handler panicked".to_string(),
+            data: None,
+        }),
+    };
+    Ok(Some(serde_json::to_string(&response)?))
+}
+
+/// Write one message in the session's framing and flush it
+fn write_message(out: &mut impl Write, framing: Framing, message: &str) -> io::Result<()> {
+    match framing {
+        Framing::Lines => writeln!(out, "{}", message)?,
+        Framing::ContentLength => {
+            write!(out, "Content-Length: {}\r\n\r\n{}", message.len(), message)?
+        }
+    }
+    out.flush()
+}
+
+/// Handle `requests` up to `max_inflight` at a time, writing each response to `out` as its
+/// request completes
+///
+/// One writer task owns `out`, so request tasks only queue their responses and never wait on
+/// each other. When input ends or `shutdown` resolves, no further requests are accepted;
+/// requests in flight run to completion, the writer drains the queue and flushes, and `out` is
+/// handed back.
+async fn serve<W, F, Fut>(
+    mut requests: mpsc::Receiver<(Framing, String)>,
+    mut out: W,
+    max_inflight: usize,
+    handle: F,
+    shutdown: impl Future<Output = ()>,
+) -> Result<W>
+where
+    W: Write + Send + 'static,
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<Option<String>>> + Send + 'static,
+{
+    let (responses, mut queued) = mpsc::channel::<(Framing, String)>(max_inflight);
+    let writer = tokio::task::spawn_blocking(move || -> io::Result<W> {
+        while let Some((framing, response)) = queued.blocking_recv() {
+            write_message(&mut out, framing, &response)?;
+        }
+        out.flush()?;
+        Ok(out)
+    });
+
+    let inflight = Arc::new(Semaphore::new(max_inflight));
+    let mut tasks = JoinSet::new();
+    tokio::pin!(shutdown);
+    loop {
+        let (framing, message) = tokio::select! {
+            request = requests.recv() => match request {
+                Some(request) => request,
+                None => break,
+            },
+            _ = &mut shutdown => {
+                tracing::info!("Shutdown requested, finishing {} requests in flight", tasks.len());
+                break;
+            }
+        };
+        let permit = inflight.clone().acquire_owned().await?;
+        // Run apart from the task below, so a panicking tool call fails only its own request
+        let response = tokio::spawn(handle(message.clone()));
+        let responses = responses.clone();
+        tasks.spawn(async move {
+            let _permit = permit;
+            let response = match response.await {
+                Ok(response) => response?,
+                Err(e) => {
+                    tracing::error!("Request handler panicked: {}", e);
+                    internal_error_response(&message)?
+                }
+            };
+            let Some(response) = response else {
+                return Ok(());
+            };
+            tracing::debug!("Sending: {}", response);
+            responses
+                .send((framing, response))
+                .await
+                .map_err(|_| anyhow::anyhow!("Response writer stopped"))
+        });
+
+        // Surface write failures (e.g. the client went away) without waiting for shutdown
+        while let Some(joined) = tasks.try_join_next() {
+            joined.context("Request task panicked")??;
+        }
+    }
+    // Tells the stdin reader to stop
+    drop(requests);
+
+    while let Some(joined) = tasks.join_next().await {
+        joined.context("Request task panicked")??;
+    }
+    // With every sender gone, the writer exits once the queue is empty
+    drop(responses);
+    writer
+        .await
+        .context("Response writer panicked")?
+        .context("Failed to write response")
+}
+
+/// Resolves on Ctrl-C, or SIGTERM on Unix
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = terminate.recv() => {}
+                }
+                return;
+            }
+            Err(e) => tracing::warn!("Failed to listen for SIGTERM: {}", e),
+        }
+    }
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        tracing::warn!("Failed to listen for Ctrl-C: {}", e);
+        std::future::pending::<()>().await;
+    }
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // Load .env file if present
     dotenv::dotenv().ok();
 
-    // Initialize logging
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
-        )
-        .with_writer(std::io::stderr)
+    // Initialize logging; the filter is reloadable so clients can change it via logging/setLevel
+    let (filter, log_filter) = reload::Layer::new(
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+    );
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_writer(std::io::stderr))
         .init();
 
     tracing::info!("Starting Ethereum Trading MCP Server");
 
+    let config = Config::from_env().context("Failed to load configuration")?;
+    tracing::info!("Chainlink price feeds: {}", config.chainlink_feeds.len());
+    let wallet = config::load_wallet().context("Failed to load wallet")?;
+    if let Some(wallet) = &wallet {
+        tracing::info!("Wallet configured: {:?}", wallet.address());
+    }
+    let watched_wallets =
+        config::load_watched_wallets().context("Failed to load watched wallets")?;
+    if !watched_wallets.is_empty() {
+        tracing::info!("Watching {} wallets", watched_wallets.len());
+    }
+    let token_denylist = config::load_token_denylist().context("Failed to load token denylist")?;
+    if !token_denylist.is_empty() {
+        tracing::info!("Denylisted tokens: {}", token_denylist.len());
+    }
+    let response_signer =
+        config::load_response_signer().context("Failed to load response signing key")?;
+    if let Some(signer) = &response_signer {
+        tracing::info!("Signing tool results as {:?}", signer.address());
+    }
+
     // Get Ethereum RPC URL from environment
-    let rpc_url = std::env::var("ETH_RPC_URL")
-        .unwrap_or_else(|_| "https://eth.llamarpc.com".to_string());
+    let rpc_url =
+        std::env::var("ETH_RPC_URL").unwrap_or_else(|_| "https://eth.llamarpc.com".to_string());
 
-    tracing::info!("Connecting to Ethereum RPC: {}", rpc_url);
+    // Create provider; it is never called in offline mode
+    let rpc_logging = RpcLogging {
+        enabled: config.log_rpc_calls,
+        redact: config.log_rpc_redact,
+    };
+    let rpc_limits = RpcLimits {
+        max_in_flight: config.rpc_max_in_flight,
+        max_requests_per_sec: config.rpc_rate_limit,
+        busy_threshold: config.rpc_busy_threshold,
+        busy_window: Duration::from_secs(config.rpc_busy_window_secs),
+        busy_cooldown: Duration::from_secs(config.rpc_busy_cooldown_secs),
+    };
+    let provider = create_provider(
+        &rpc_url,
+        rpc_logging,
+        rpc_limits,
+        &config.http_user_agent,
+        Duration::from_millis(config.rpc_poll_interval_ms),
+    )
+    .await
+    .context("Failed to create Ethereum provider")?;
 
-    // Create provider
-    let provider = create_provider(&rpc_url)
-        .await
-        .context("Failed to create Ethereum provider")?;
+    // Test connection and capture network details
+    let chain_info = if config.offline_mode {
+        tracing::warn!("OFFLINE_MODE enabled: tools return canned mock data, no RPC connection");
+        ChainInfo::offline()
+    } else {
+        tracing::info!("Connecting to Ethereum RPC: {}", rpc_url);
+        fetch_chain_info(&provider, &rpc_url)
+            .await
+            .context("Failed to query connected chain")?
+    };
+    tracing::info!(
+        "Connected to chain ID: {} ({}) at block {}",
+        chain_info.chain_id,
+        chain_info.chain_name,
+        chain_info.block_number
+    );
+    if !chain_info.has_known_addresses() {
+        tracing::warn!(
+            "Chain ID {} has no known contract addresses; swap and price tools are disabled",
+            chain_info.chain_id
+        );
+    }
+
+    // Other chains are connected on their first tool call, so a down endpoint can't block startup
+    let cache_dir = config.cache_dir.as_ref().map(PathBuf::from);
+    let chain_providers = ChainProviders::new(
+        config::load_rpc_urls()?,
+        rpc_logging,
+        rpc_limits,
+        &config.http_user_agent,
+        Duration::from_millis(config.rpc_poll_interval_ms),
+    )
+    .with_cache_dir(cache_dir.clone());
 
-    // Test connection
-    let chain_id = provider.get_chainid().await?;
-    tracing::info!("Connected to chain ID: {}", chain_id);
+    // Token metadata never changes, so a persisted cache spares lookups after a restart
+    let metadata_cache =
+        MetadataCache::with_decimals_overrides(config.token_decimals_overrides.clone());
+    let metadata_cache = match &cache_dir {
+        Some(dir) if !config.offline_mode => metadata_cache
+            .persisted_in(dir, chain_info.chain_id)
+            .context("Failed to load metadata cache from CACHE_DIR")?,
+        _ => metadata_cache,
+    };
+    if !chain_providers.chain_ids().is_empty() {
+        tracing::info!(
+            "Additional chains from RPC_URLS: {:?}",
+            chain_providers.chain_ids()
+        );
+    }
+
+    // Shared client for external HTTP APIs (CoinGecko, etc.)
+    let mut http_headers = config::load_http_headers()?;
+    if !http_headers.is_empty() {
+        tracing::info!(
+            "Sending {} extra headers to external APIs",
+            http_headers.len()
+        );
+    }
+    http_headers.insert(USER_AGENT, user_agent_header(&config.http_user_agent)?);
+    let http = create_http_client(
+        Duration::from_millis(config.coingecko_timeout_ms),
+        config.https_proxy.as_deref(),
+        http_headers,
+    )?;
 
     // Create MCP server
-    let server = McpServer::new(provider);
-
-    tracing::info!("MCP Server ready, listening on stdio");
-
-    // Read from stdin and write to stdout
-    let stdin = io::stdin();
-    let mut stdout = io::stdout();
-
-    for line in stdin.lock().lines() {
-        let line = line?;
-
-        if line.trim().is_empty() {
-            continue;
-        }
-
-        tracing::debug!("Received: {}", line);
-
-        // Parse request
-        let request: JsonRpcRequest = match serde_json::from_str(&line) {
-            Ok(req) => req,
-            Err(e) => {
-                tracing::error!("Failed to parse request: {}", e);
-                let error_response = types::JsonRpcResponse {
-                    jsonrpc: "2.0".to_string(),
-                    id: serde_json::Value::Null,
-                    result: None,
-                    error: Some(types::JsonRpcError {
-                        code: -32700,
-                        message: format!("Parse error: {}", e),
-                        data: None,
-                    }),
-                };
-                let response_json = serde_json::to_string(&error_response)?;
-                writeln!(stdout, "{}", response_json)?;
-                stdout.flush()?;
-                continue;
+    let max_inflight_requests = config.max_inflight_requests.max(1);
+    let server = McpServer::new(provider, chain_info, config, http, wallet, watched_wallets)
+        .with_log_filter(log_filter)
+        .with_token_denylist(token_denylist)
+        .with_metadata_cache(metadata_cache)
+        .with_chain_providers(chain_providers)
+        .with_response_signer(response_signer);
+    let server = Arc::new(server);
+
+    tracing::info!(
+        "MCP Server ready, listening on stdio ({} requests at a time)",
+        max_inflight_requests
+    );
+
+    // stdin is read on its own thread so a slow tool call never stalls reading; the bounded
+    // channel pushes back on the client once enough requests are queued
+    let (sender, receiver) = mpsc::channel(max_inflight_requests);
+    let reader_thread = std::thread::spawn(move || -> io::Result<()> {
+        let mut reader = MessageReader::new(io::stdin().lock());
+        while let Some(message) = reader.read_message()? {
+            if sender.blocking_send((reader.framing(), message)).is_err() {
+                break;
             }
-        };
+        }
+        Ok(())
+    });
 
-        // Handle request
-        let response = server.handle_request(request).await;
+    // Responses carry their request's id, so they may be written in completion order
+    let handle = |line: String| {
+        let server = server.clone();
+        async move { process_message(&server, &line).await }
+    };
+    serve(
+        receiver,
+        io::stdout(),
+        max_inflight_requests,
+        handle,
+        shutdown_signal(),
+    )
+    .await?;
 
-        // Send response
-        let response_json = serde_json::to_string(&response)?;
-        tracing::debug!("Sending: {}", response_json);
-        writeln!(stdout, "{}", response_json)?;
-        stdout.flush()?;
+    // After a signal the reader may still be blocked on stdin; it ends with the process
+    if reader_thread.is_finished() {
+        reader_thread
+            .join()
+            .map_err(|_| anyhow::anyhow!("stdin reader thread panicked"))??;
     }
 
     tracing::info!("MCP Server shutting down");
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REQUESTS: [&str; 2] = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#,
+        r#"{"jsonrpc":"2.0","id":"é","method":"tools/list"}"#,
+    ];
+
+    fn read_all(input: &str) -> (Vec<String>, Framing) {
+        let mut reader = MessageReader::new(input.as_bytes());
+        let mut messages = Vec::new();
+        while let Some(message) = reader.read_message().unwrap() {
+            messages.push(message);
+        }
+        (messages, reader.framing())
+    }
+
+    #[test]
+    fn test_line_framing() {
+        let input = format!("\n{}\r\n\n{}\n", REQUESTS[0], REQUESTS[1]);
+        let (messages, framing) = read_all(&input);
+        assert_eq!(messages, REQUESTS);
+        assert_eq!(framing, Framing::Lines);
+    }
+
+    #[test]
+    fn test_content_length_framing() {
+        // Bodies have no trailing newline, and lengths count bytes rather than characters
+        let input: String = REQUESTS
+            .iter()
+            .map(|body| {
+                format!(
+                    "Content-Length: {}\r\nContent-Type: application/vscode-jsonrpc; charset=utf-8\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+            })
+            .collect();
+        let (messages, framing) = read_all(&input);
+        assert_eq!(messages, REQUESTS);
+        assert_eq!(framing, Framing::ContentLength);
+
+        let mut reader = MessageReader::new("content-length: 5\r\n\r\n{}".as_bytes());
+        assert!(reader.read_message().is_err());
+        let mut reader = MessageReader::new("Content-Type: json\r\n\r\n{}".as_bytes());
+        assert!(reader.read_message().is_err());
+    }
+
+    async fn slow_echo(message: String) -> Result<Option<String>> {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        Ok(Some(message))
+    }
+
+    #[tokio::test]
+    async fn test_eof_flushes_inflight_response() {
+        let (sender, receiver) = mpsc::channel(4);
+        sender
+            .send((Framing::Lines, REQUESTS[0].to_string()))
+            .await
+            .unwrap();
+        // Input ends while the request is still being handled
+        drop(sender);
+
+        let out = serve(receiver, Vec::new(), 4, slow_echo, std::future::pending())
+            .await
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!("{}\n", REQUESTS[0])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_signal_flushes_inflight_response() {
+        let (sender, receiver) = mpsc::channel(4);
+        sender
+            .send((Framing::ContentLength, REQUESTS[1].to_string()))
+            .await
+            .unwrap();
+
+        // The client keeps stdin open; the signal arrives mid-request
+        let shutdown = tokio::time::sleep(Duration::from_millis(10));
+        let out = serve(receiver, Vec::new(), 4, slow_echo, shutdown)
+            .await
+            .unwrap();
+        let (messages, _) = read_all(std::str::from_utf8(&out).unwrap());
+        assert_eq!(messages, [REQUESTS[1]]);
+        drop(sender);
+    }
+
+    #[tokio::test]
+    async fn test_panicking_request_fails_alone() {
+        let (sender, receiver) = mpsc::channel(4);
+        for request in REQUESTS {
+            sender
+                .send((Framing::Lines, request.to_string()))
+                .await
+                .unwrap();
+        }
+        drop(sender);
+
+        let handle = |message: String| async move {
+            if message.contains("tools/list") {
+                panic!("tool call blew up");
+            }
+            slow_echo(message).await
+        };
+        let out = serve(receiver, Vec::new(), 4, handle, std::future::pending())
+            .await
+            .unwrap();
+        let (messages, _) = read_all(std::str::from_utf8(&out).unwrap());
+
+        // The other request is still answered, and the panicking one gets an error for its id
+        assert_eq!(messages.len(), 2);
+        assert!(messages.contains(&REQUESTS[0].to_string()));
+        let error = messages
+            .iter()
+            .find(|message| *message != REQUESTS[0])
+            .unwrap();
+        let error: Value = serde_json::from_str(error).unwrap();
+        assert_eq!(error["id"], "é");
+        assert_eq!(error["error"]["code"], -32603);
+    }
+
+    #[test]
+    fn test_internal_error_response() {
+        let error: Value =
+            serde_json::from_str(&internal_error_response(REQUESTS[1]).unwrap().unwrap()).unwrap();
+        assert_eq!(error["id"], "é");
+        assert_eq!(error["error"]["code"], -32603);
+
+        // Notifications stay unanswered; a batch gets one error with a null id
+        let notification = r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#;
+        assert_eq!(internal_error_response(notification).unwrap(), None);
+        let batch = format!("[{}]", REQUESTS[0]);
+        let error: Value =
+            serde_json::from_str(&internal_error_response(&batch).unwrap().unwrap()).unwrap();
+        assert_eq!(error["id"], Value::Null);
+    }
+
+    #[test]
+    fn test_write_message_round_trip() {
+        for framing in [Framing::Lines, Framing::ContentLength] {
+            let mut out = Vec::new();
+            for request in REQUESTS {
+                write_message(&mut out, framing, request).unwrap();
+            }
+            let (messages, detected) = read_all(std::str::from_utf8(&out).unwrap());
+            assert_eq!(messages, REQUESTS);
+            assert_eq!(detected, framing);
+        }
+    }
+}
@@ -1,24 +1,231 @@
 use anyhow::Result;
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::Address;
+use serde::de::DeserializeOwned;
 use serde_json::{json, Value};
+use std::collections::{HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::Instrument;
+use tracing_subscriber::{reload, EnvFilter, Registry};
 
-use crate::ethereum::EthClient;
+use crate::config::Config;
+use crate::ethereum::{
+    parse_chain, rpc_backpressure, ChainClient, ChainGuard, ChainInfo, ChainProviders, EthClient,
+    MetadataCache, NotFound, PriceAlerts, RpcCallError, TokenDenylist, TokenRegistry,
+};
 use crate::tools;
-use crate::types::{JsonRpcError, JsonRpcRequest, JsonRpcResponse, ToolResult, MCP_VERSION};
+use crate::types::{
+    JsonRpcError, JsonRpcRequest, JsonRpcResponse, ResponseFormat, ToolResult, MCP_VERSION,
+};
+
+/// Handle for swapping the global log filter at runtime
+pub type LogFilterHandle = reload::Handle<EnvFilter, Registry>;
+
+/// How many recent request ids duplicate detection remembers
+const MAX_SEEN_IDS: usize = 1024;
+
+/// The most recent request ids, forgetting the oldest once `capacity` is reached
+#[derive(Debug)]
+struct RecentIds {
+    capacity: usize,
+    /// Ids in arrival order, oldest first
+    order: VecDeque<String>,
+    ids: HashSet<String>,
+}
+
+impl RecentIds {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            order: VecDeque::with_capacity(capacity),
+            ids: HashSet::with_capacity(capacity),
+        }
+    }
+
+    /// Remember `id`, returning false when it is still among the recent ones
+    fn insert(&mut self, id: String) -> bool {
+        if self.ids.contains(&id) {
+            return false;
+        }
+        if self.order.len() == self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.ids.remove(&oldest);
+            }
+        }
+        self.ids.insert(id.clone());
+        self.order.push_back(id);
+        true
+    }
+}
 
 pub struct McpServer {
     provider: EthClient,
+    chain_info: ChainInfo,
+    /// Catches the endpoint moving to another network after startup
+    chain_guard: ChainGuard,
+    config: Config,
+    registry: TokenRegistry,
+    /// Resolves no symbols; used on chains the built-in registry doesn't cover
+    empty_registry: TokenRegistry,
+    http: reqwest::Client,
+    wallet: Option<LocalWallet>,
+    /// Wallets preloaded from `WATCH_WALLETS_PATH`
+    watched_wallets: Vec<Address>,
+    cache: Arc<MetadataCache>,
+    /// Providers for the other chains in `RPC_URLS`, selected by a tool call's `chain` argument
+    chain_providers: Arc<ChainProviders>,
+    /// Price alerts registered by the client this session
+    alerts: PriceAlerts,
+    /// Tokens loaded from `TOKEN_DENYLIST_PATH`; any tool call mentioning one is refused
+    token_denylist: TokenDenylist,
+    log_filter: Option<LogFilterHandle>,
+    /// Most recent request ids, for duplicate detection
+    seen_ids: Mutex<RecentIds>,
+    /// Counter behind the trace id given to each request's log span
+    next_trace_id: AtomicU64,
+    /// Key signing every tool result into its `_meta`, when `SIGN_RESPONSES` is on
+    response_signer: Option<LocalWallet>,
 }
 
 impl McpServer {
-    pub fn new(provider: EthClient) -> Self {
-        Self { provider }
+    pub fn new(
+        provider: EthClient,
+        chain_info: ChainInfo,
+        config: Config,
+        http: reqwest::Client,
+        wallet: Option<LocalWallet>,
+        watched_wallets: Vec<Address>,
+    ) -> Self {
+        let chain_guard = ChainGuard::new(
+            chain_info.chain_id,
+            Duration::from_secs(config.chain_check_interval_secs),
+        );
+        let cache = Arc::new(MetadataCache::with_decimals_overrides(
+            config.token_decimals_overrides.clone(),
+        ));
+        Self {
+            provider,
+            chain_info,
+            chain_guard,
+            config,
+            registry: TokenRegistry::mainnet(),
+            empty_registry: TokenRegistry::new(Vec::new()),
+            http,
+            wallet,
+            watched_wallets,
+            cache,
+            chain_providers: Arc::new(ChainProviders::default()),
+            alerts: PriceAlerts::new(),
+            token_denylist: TokenDenylist::default(),
+            log_filter: None,
+            seen_ids: Mutex::new(RecentIds::new(MAX_SEEN_IDS)),
+            next_trace_id: AtomicU64::new(1),
+            response_signer: None,
+        }
+    }
+
+    /// Enable `logging/setLevel` by giving the server control of the log filter
+    pub fn with_log_filter(mut self, log_filter: LogFilterHandle) -> Self {
+        self.log_filter = Some(log_filter);
+        self
+    }
+
+    /// Use this metadata cache for the `ETH_RPC_URL` chain, e.g. one persisted to `CACHE_DIR`
+    pub fn with_metadata_cache(mut self, cache: MetadataCache) -> Self {
+        self.cache = Arc::new(cache);
+        self
     }
 
+    /// Serve tool calls whose `chain` argument names another chain from these providers
+    pub fn with_chain_providers(mut self, chain_providers: ChainProviders) -> Self {
+        self.chain_providers = Arc::new(chain_providers);
+        self
+    }
+
+    /// Refuse tool calls whose arguments mention any of these tokens
+    pub fn with_token_denylist(mut self, token_denylist: TokenDenylist) -> Self {
+        self.token_denylist = token_denylist;
+        self
+    }
+
+    /// Sign every tool result with this key, for tamper-evident audit logs
+    pub fn with_response_signer(mut self, response_signer: Option<LocalWallet>) -> Self {
+        self.response_signer = response_signer;
+        self
+    }
+
+    /// Handle a batch (JSON array) of messages, returning one response per non-notification
+    ///
+    /// Responses keep the order and ids of their requests; an empty batch is itself invalid.
+    pub async fn handle_batch(&self, batch: Vec<Value>) -> Vec<JsonRpcResponse> {
+        if batch.is_empty() {
+            return vec![invalid_request(Value::Null, "empty batch".to_string())];
+        }
+
+        let mut responses = Vec::new();
+        for message in batch {
+            if let Some(response) = self.handle_message(message).await {
+                responses.push(response);
+            }
+        }
+        responses
+    }
+
+    /// Handle a single message, returning `None` for notifications
+    pub async fn handle_message(&self, message: Value) -> Option<JsonRpcResponse> {
+        // Echo the id even when the rest of the request is malformed
+        let id = message.get("id").cloned().unwrap_or(Value::Null);
+        let request: JsonRpcRequest = match serde_json::from_value(message) {
+            Ok(request) => request,
+            Err(e) => return Some(invalid_request(id, e.to_string())),
+        };
+
+        if request.is_notification() {
+            self.handle_request(request).await;
+            tracing::debug!("Notification handled, no response sent");
+            return None;
+        }
+
+        Some(self.handle_request(request).await)
+    }
+
+    /// Handle a request inside a span carrying its id and a trace id, so every log line of
+    /// the call (including nested RPC logs) can be told apart from interleaved requests
     pub async fn handle_request(&self, request: JsonRpcRequest) -> JsonRpcResponse {
+        let trace_id = format!("{:08x}", self.next_trace_id.fetch_add(1, Ordering::Relaxed));
+        let request_id = request.id.clone().unwrap_or(Value::Null);
+        let span = tracing::info_span!("request", id = %request_id, trace = %trace_id);
+
+        let mut response = self.dispatch_request(request).instrument(span).await;
+        if let Some(error) = &mut response.error {
+            tag_error(error, &request_id, &trace_id);
+        }
+        response
+    }
+
+    async fn dispatch_request(&self, request: JsonRpcRequest) -> JsonRpcResponse {
         tracing::info!("Handling request: {}", request.method);
 
+        if request.jsonrpc != "2.0" {
+            return invalid_request(
+                request.id.unwrap_or(Value::Null),
+                format!("jsonrpc must be \"2.0\", got {:?}", request.jsonrpc),
+            );
+        }
+
+        if let Some(id) = &request.id {
+            if self.config.warn_duplicate_ids && !self.record_id(id) {
+                tracing::warn!("Request id {} was already used in this session", id);
+            }
+        }
+
         let result = match request.method.as_str() {
             "initialize" => self.handle_initialize(&request.params).await,
+            "notifications/initialized" => Ok(Value::Null),
+            "server/info" => self.handle_server_info().await,
+            "logging/setLevel" => self.handle_set_log_level(&request.params),
             "tools/list" => self.handle_tools_list().await,
             "tools/call" => self.handle_tool_call(&request.params).await,
             _ => Err(JsonRpcError {
@@ -28,16 +235,17 @@ impl McpServer {
             }),
         };
 
+        let id = request.id.unwrap_or(Value::Null);
         match result {
             Ok(result) => JsonRpcResponse {
                 jsonrpc: "2.0".to_string(),
-                id: request.id,
+                id,
                 result: Some(result),
                 error: None,
             },
             Err(error) => JsonRpcResponse {
                 jsonrpc: "2.0".to_string(),
-                id: request.id,
+                id,
                 result: None,
                 error: Some(error),
             },
@@ -47,16 +255,75 @@ impl McpServer {
     async fn handle_initialize(&self, _params: &Value) -> Result<Value, JsonRpcError> {
         Ok(json!({
             "protocolVersion": MCP_VERSION,
-            "serverInfo": {
-                "name": "eth-trading-mcp-server",
-                "version": "0.1.0"
-            },
+            "serverInfo": self.server_info(),
             "capabilities": {
-                "tools": {}
+                "tools": {},
+                "logging": {},
+                "experimental": {
+                    "trading": self.trading_enabled()
+                }
             }
         }))
     }
 
+    /// Remember a request id, returning false when it was seen recently
+    fn record_id(&self, id: &Value) -> bool {
+        self.seen_ids
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(id.to_string())
+    }
+
+    /// Trades can only be executed when explicitly enabled and a signing wallet is configured
+    fn trading_enabled(&self) -> bool {
+        self.config.enable_trading && self.wallet.is_some()
+    }
+
+    fn handle_set_log_level(&self, params: &Value) -> Result<Value, JsonRpcError> {
+        let invalid = |message: String| JsonRpcError {
+            code: -32602,
+            message,
+            data: None,
+        };
+
+        let level = params
+            .get("level")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| invalid("Missing log level".to_string()))?;
+        let directive = tracing_directive(level)
+            .ok_or_else(|| invalid(format!("Unknown log level: {}", level)))?;
+
+        let log_filter = self.log_filter.as_ref().ok_or_else(|| JsonRpcError {
+            code: -32603,
+            message: "Log level cannot be changed in this server".to_string(),
+            data: None,
+        })?;
+        log_filter
+            .reload(EnvFilter::new(directive))
+            .map_err(|e| JsonRpcError {
+                code: -32603,
+                message: format!("Failed to set log level: {}", e),
+                data: None,
+            })?;
+
+        tracing::info!("Log level set to {} ({})", level, directive);
+        Ok(json!({}))
+    }
+
+    async fn handle_server_info(&self) -> Result<Value, JsonRpcError> {
+        Ok(self.server_info())
+    }
+
+    fn server_info(&self) -> Value {
+        json!({
+            "name": "eth-trading-mcp-server",
+            "version": env!("CARGO_PKG_VERSION"),
+            "chain": self.chain_info,
+            "configured_chains": self.chain_providers.chain_ids(),
+            "offline_mode": self.config.offline_mode
+        })
+    }
+
     async fn handle_tools_list(&self) -> Result<Value, JsonRpcError> {
         let tools = tools::get_all_tools();
         Ok(json!({
@@ -79,48 +346,367 @@ impl McpServer {
 
         tracing::info!("Calling tool: {} with args: {}", tool_name, arguments);
 
-        let result = self.execute_tool(tool_name, arguments).await?;
+        let format = self.response_format(arguments)?;
+        let mut result = self.execute_tool(tool_name, arguments).await?;
+        result.apply_format(format);
+        // Only the prose is cut; the JSON block, all a `json` format call gets, stays parseable
+        result.truncate_text(self.config.max_result_chars);
 
-        Ok(json!(result))
+        match &self.response_signer {
+            Some(signer) => sign_tool_result(signer, &result)
+                .await
+                .map_err(|e| self.error_to_json_rpc_error(e)),
+            None => Ok(json!(result)),
+        }
+    }
+
+    /// The call's `format` argument, accepted by every tool, else `RESPONSE_FORMAT`
+    fn response_format(&self, args: &Value) -> Result<ResponseFormat, JsonRpcError> {
+        let format = match args.get("format") {
+            None | Some(Value::Null) => return Ok(self.config.response_format),
+            Some(format) => format
+                .as_str()
+                .map_or_else(|| format.to_string(), str::to_string),
+        };
+        format.parse().map_err(|e| JsonRpcError {
+            code: -32602,
+            message: format!("Invalid parameters: format: {}", e),
+            data: None,
+        })
     }
 
     async fn execute_tool(&self, name: &str, args: &Value) -> Result<ToolResult, JsonRpcError> {
-        let result = match name {
+        // Checked before any tool runs, so denied tokens are never priced, quoted or called
+        if let Some(token) = self.token_denylist.find_in_args(args) {
+            tracing::warn!("Refused {} call: token {:?} is denylisted", name, token);
+            return Err(JsonRpcError {
+                code: -32000,
+                message: format!("token is denylisted: {:?}", token),
+                data: None,
+            });
+        }
+
+        if self.config.offline_mode {
+            return self.execute_mock_tool(name, args);
+        }
+
+        let target = self.select_chain(args).await?;
+        self.check_backpressure(&target.provider)?;
+        if self.is_primary(&target) {
+            self.chain_guard.refresh(&self.provider).await;
+        }
+
+        match name {
             "get_balance" => {
-                let params: tools::get_balance::GetBalanceParams =
-                    serde_json::from_value(args.clone()).map_err(|e| JsonRpcError {
-                        code: -32602,
-                        message: format!("Invalid parameters: {}", e),
-                        data: None,
-                    })?;
+                let params: tools::get_balance::GetBalanceParams = parse_params(args)?;
 
-                tools::get_balance::execute(&self.provider, params)
-                    .await
-                    .map_err(|e| self.error_to_json_rpc_error(e))
+                // Valuing the balance needs the mainnet price sources
+                if params.quote_currency.is_some() {
+                    self.require_known_addresses(&target)?;
+                }
+                tools::get_balance::execute(
+                    &target.provider,
+                    &self.http,
+                    &target.cache,
+                    self.registry_for(&target),
+                    &self.config,
+                    params,
+                )
+                .await
+                .or_else(|e| self.tool_error(e))
             }
             "get_token_price" => {
-                let params: tools::get_token_price::GetTokenPriceParams =
-                    serde_json::from_value(args.clone()).map_err(|e| JsonRpcError {
-                        code: -32602,
-                        message: format!("Invalid parameters: {}", e),
-                        data: None,
-                    })?;
+                let params: tools::get_token_price::GetTokenPriceParams = parse_params(args)?;
 
-                tools::get_token_price::execute(&self.provider, params)
+                self.require_known_addresses(&target)?;
+                tools::get_token_price::execute(&target.provider, &self.http, &self.config, params)
                     .await
-                    .map_err(|e| self.error_to_json_rpc_error(e))
+                    .or_else(|e| self.tool_error(e))
+            }
+            "get_eth_price" => {
+                self.require_known_addresses(&target)?;
+                tools::get_eth_price::execute(&target.provider)
+                    .await
+                    .or_else(|e| self.tool_error(e))
             }
             "swap_tokens" => {
-                let params: tools::swap_tokens::SwapTokensParams =
-                    serde_json::from_value(args.clone()).map_err(|e| JsonRpcError {
-                        code: -32602,
-                        message: format!("Invalid parameters: {}", e),
-                        data: None,
-                    })?;
+                let params: tools::swap_tokens::SwapTokensParams = parse_params(args)?;
+
+                self.require_known_addresses(&target)?;
+                tools::swap_tokens::execute(
+                    &target.provider,
+                    &target.cache,
+                    &self.http,
+                    &self.config,
+                    target.info.chain_id,
+                    self.wallet.as_ref().map(|wallet| wallet.address()),
+                    params,
+                )
+                .await
+                .or_else(|e| self.tool_error(e))
+            }
+            "build_swap_transaction" => {
+                let params: tools::swap_tokens::SwapTokensParams = parse_params(args)?;
+
+                self.require_known_addresses(&target)?;
+                tools::build_swap_transaction::execute(
+                    &target.provider,
+                    &target.cache,
+                    &self.http,
+                    &self.config,
+                    target.info.chain_id,
+                    self.wallet.as_ref().map(|wallet| wallet.address()),
+                    params,
+                )
+                .await
+                .or_else(|e| self.tool_error(e))
+            }
+            "simulate_swap_effects" => {
+                let params: tools::swap_tokens::SwapTokensParams = parse_params(args)?;
+
+                self.require_known_addresses(&target)?;
+                tools::simulate_swap_effects::execute(
+                    &target.provider,
+                    &target.cache,
+                    &self.http,
+                    &self.config,
+                    self.wallet.as_ref().map(|wallet| wallet.address()),
+                    params,
+                )
+                .await
+                .or_else(|e| self.tool_error(e))
+            }
+            "get_pair_address" => {
+                let params: tools::get_pair_address::GetPairAddressParams = parse_params(args)?;
+
+                self.require_known_addresses(&target)?;
+                tools::get_pair_address::execute(&target.provider, params)
+                    .await
+                    .or_else(|e| self.tool_error(e))
+            }
+            "call_contract" => {
+                let params: tools::call_contract::CallContractParams = parse_params(args)?;
 
-                tools::swap_tokens::execute(&self.provider, params)
+                tools::call_contract::execute(&target.provider, params)
                     .await
-                    .map_err(|e| self.error_to_json_rpc_error(e))
+                    .or_else(|e| self.tool_error(e))
+            }
+            "get_logs" => {
+                let params: tools::get_logs::GetLogsParams = parse_params(args)?;
+
+                tools::get_logs::execute(&target.provider, params)
+                    .await
+                    .or_else(|e| self.tool_error(e))
+            }
+            "wait_for_transaction" => {
+                let params: tools::wait_for_transaction::WaitForTransactionParams =
+                    parse_params(args)?;
+
+                tools::wait_for_transaction::execute(&target.provider, params)
+                    .await
+                    .or_else(|e| self.tool_error(e))
+            }
+            "get_average_price" => {
+                let params: tools::get_average_price::GetAveragePriceParams = parse_params(args)?;
+
+                tools::get_average_price::execute(&target.provider, params)
+                    .await
+                    .or_else(|e| self.tool_error(e))
+            }
+            "get_gas_price" => {
+                let params: tools::get_gas_price::GetGasPriceParams = parse_params(args)?;
+
+                tools::get_gas_price::execute(&target.provider, &self.http, &self.config, params)
+                    .await
+                    .or_else(|e| self.tool_error(e))
+            }
+            "get_portfolio_value" => {
+                let params: tools::get_portfolio_value::GetPortfolioValueParams =
+                    parse_params(args)?;
+
+                self.require_known_addresses(&target)?;
+                tools::get_portfolio_value::execute(
+                    &target.provider,
+                    &self.http,
+                    &target.cache,
+                    &self.config,
+                    params,
+                )
+                .await
+                .or_else(|e| self.tool_error(e))
+            }
+            "set_price_alert" => {
+                let params: tools::set_price_alert::SetPriceAlertParams = parse_params(args)?;
+
+                self.require_known_addresses(&target)?;
+                tools::set_price_alert::execute(&self.alerts, params)
+                    .or_else(|e| self.tool_error(e))
+            }
+            "check_price_alerts" => {
+                self.require_known_addresses(&target)?;
+                tools::check_price_alerts::execute(
+                    &target.provider,
+                    &self.http,
+                    &self.config,
+                    &self.alerts,
+                )
+                .await
+                .or_else(|e| self.tool_error(e))
+            }
+            "get_swap_route_graph" => {
+                let params: tools::get_swap_route_graph::GetSwapRouteGraphParams =
+                    parse_params(args)?;
+
+                self.require_known_addresses(&target)?;
+                tools::get_swap_route_graph::execute(
+                    &target.provider,
+                    &target.cache,
+                    &self.config,
+                    params,
+                )
+                .await
+                .or_else(|e| self.tool_error(e))
+            }
+            "get_max_swap_size" => {
+                let params: tools::get_max_swap_size::GetMaxSwapSizeParams = parse_params(args)?;
+
+                self.require_known_addresses(&target)?;
+                tools::get_max_swap_size::execute(
+                    &target.provider,
+                    &target.cache,
+                    &self.config,
+                    params,
+                )
+                .await
+                .or_else(|e| self.tool_error(e))
+            }
+            "compare_dex_quotes" => {
+                let params: tools::compare_dex_quotes::CompareDexQuotesParams = parse_params(args)?;
+
+                self.require_known_addresses(&target)?;
+                tools::compare_dex_quotes::execute(
+                    &target.provider,
+                    &target.cache,
+                    &self.config,
+                    params,
+                )
+                .await
+                .or_else(|e| self.tool_error(e))
+            }
+            "get_mempool_flow" => {
+                let params: tools::get_mempool_flow::GetMempoolFlowParams = parse_params(args)?;
+
+                self.require_known_addresses(&target)?;
+                tools::get_mempool_flow::execute(&target.provider, &target.cache, params)
+                    .await
+                    .or_else(|e| self.tool_error(e))
+            }
+            "get_transaction_count_by_block" => {
+                let params: tools::get_transaction_count_by_block::GetTransactionCountByBlockParams =
+                    parse_params(args)?;
+
+                tools::get_transaction_count_by_block::execute(&target.provider, params)
+                    .await
+                    .or_else(|e| self.tool_error(e))
+            }
+            "get_storage_at" => {
+                let params: tools::get_storage_at::GetStorageAtParams = parse_params(args)?;
+
+                tools::get_storage_at::execute(&target.provider, params)
+                    .await
+                    .or_else(|e| self.tool_error(e))
+            }
+            "get_eip1559_fee_history" => {
+                let params: tools::get_eip1559_fee_history::GetFeeHistoryParams =
+                    parse_params(args)?;
+
+                tools::get_eip1559_fee_history::execute(&target.provider, params)
+                    .await
+                    .or_else(|e| self.tool_error(e))
+            }
+            "get_watched_balances" => {
+                let params: tools::get_watched_balances::GetWatchedBalancesParams =
+                    parse_params(args)?;
+
+                tools::get_watched_balances::execute(
+                    &target.provider,
+                    &target.cache,
+                    &self.watched_wallets,
+                    params,
+                )
+                .await
+                .or_else(|e| self.tool_error(e))
+            }
+            "ens_resolve" => {
+                let params: tools::ens_resolve::EnsResolveParams = parse_params(args)?;
+
+                tools::ens_resolve::execute(&target.provider, params)
+                    .await
+                    .or_else(|e| self.tool_error(e))
+            }
+            "ens_reverse" => {
+                let params: tools::ens_reverse::EnsReverseParams = parse_params(args)?;
+
+                tools::ens_reverse::execute(&target.provider, params)
+                    .await
+                    .or_else(|e| self.tool_error(e))
+            }
+            "decode_calldata" => {
+                let params: tools::decode_calldata::DecodeCalldataParams = parse_params(args)?;
+
+                tools::decode_calldata::execute(self.registry_for(&target), params)
+                    .or_else(|e| self.tool_error(e))
+            }
+            "get_pool_swaps" => {
+                let params: tools::get_pool_swaps::GetPoolSwapsParams = parse_params(args)?;
+
+                tools::get_pool_swaps::execute(&target.provider, &target.cache, params)
+                    .await
+                    .or_else(|e| self.tool_error(e))
+            }
+            "is_contract" => {
+                let params: tools::is_contract::IsContractParams = parse_params(args)?;
+
+                tools::is_contract::execute(&target.provider, params)
+                    .await
+                    .or_else(|e| self.tool_error(e))
+            }
+            "compare_gas_across_chains" => tools::compare_gas_across_chains::execute(
+                &self.provider,
+                &self.chain_info,
+                &self.chain_providers,
+                &self.http,
+                &self.config,
+            )
+            .await
+            .or_else(|e| self.tool_error(e)),
+            "get_wallet_address" => tools::get_wallet_address::execute(self.wallet.as_ref())
+                .or_else(|e| self.tool_error(e)),
+            _ => Err(JsonRpcError {
+                code: -32601,
+                message: format!("Unknown tool: {}", name),
+                data: None,
+            }),
+        }
+    }
+
+    /// Answer from canned data in offline mode; tools without a mock are refused
+    fn execute_mock_tool(&self, name: &str, args: &Value) -> Result<ToolResult, JsonRpcError> {
+        let result = match name {
+            "get_balance" => tools::mock::get_balance(parse_params(args)?),
+            "get_token_price" => tools::mock::get_token_price(parse_params(args)?),
+            "get_eth_price" => tools::mock::get_eth_price(),
+            "swap_tokens" => tools::mock::swap_tokens(parse_params(args)?),
+            _ if tools::get_all_tools().iter().any(|tool| tool.name == name) => {
+                return Err(JsonRpcError {
+                    code: -32000,
+                    message: format!(
+                        "{} is not available in OFFLINE_MODE (mocked tools: {})",
+                        name,
+                        tools::mock::MOCK_TOOLS.join(", ")
+                    ),
+                    data: None,
+                })
             }
             _ => {
                 return Err(JsonRpcError {
@@ -131,14 +717,1208 @@ impl McpServer {
             }
         };
 
-        result
+        result.or_else(|e| self.tool_error(e))
+    }
+
+    /// Turn tool calls away while the RPC endpoint is rate limiting us, so clients back off
+    /// instead of adding load; `data.retry_after_secs` suggests when to try again
+    fn check_backpressure(&self, provider: &EthClient) -> Result<(), JsonRpcError> {
+        let Some(retry_after) = rpc_backpressure(provider).retry_after() else {
+            return Ok(());
+        };
+        // Round up, so a client waiting the suggested time finds the breaker closed
+        let secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+        Err(JsonRpcError {
+            code: -32000,
+            message: format!("server busy, retry after {}s", secs),
+            data: Some(json!({ "retry_after_secs": secs })),
+        })
+    }
+
+    /// The chain a tool call runs against: the one its `chain` argument names, or else the
+    /// `ETH_RPC_URL` chain
+    async fn select_chain(&self, args: &Value) -> Result<ChainClient, JsonRpcError> {
+        let chain_id = match args.get("chain") {
+            None | Some(Value::Null) => None,
+            Some(chain) => {
+                let chain = chain
+                    .as_str()
+                    .map_or_else(|| chain.to_string(), str::to_string);
+                Some(parse_chain(&chain).map_err(|e| JsonRpcError {
+                    code: -32602,
+                    message: format!("Invalid parameters: chain: {}", e),
+                    data: None,
+                })?)
+            }
+        };
+
+        match chain_id {
+            None => Ok(self.primary_chain()),
+            Some(chain_id) if chain_id == self.chain_info.chain_id => Ok(self.primary_chain()),
+            Some(chain_id) if !self.chain_providers.chain_ids().contains(&chain_id) => {
+                Err(JsonRpcError {
+                    code: -32602,
+                    message: format!(
+                        "Invalid parameters: chain: chain ID {} is not configured; add it to RPC_URLS (connected: {}, configured: {:?})",
+                        chain_id,
+                        self.chain_info.chain_id,
+                        self.chain_providers.chain_ids()
+                    ),
+                    data: None,
+                })
+            }
+            Some(chain_id) => self.chain_providers.get(chain_id).await.map_err(|e| JsonRpcError {
+                code: -32000,
+                message: format!("{:#}", e),
+                data: None,
+            }),
+        }
+    }
+
+    fn primary_chain(&self) -> ChainClient {
+        ChainClient {
+            provider: self.provider.clone(),
+            info: self.chain_info.clone(),
+            cache: self.cache.clone(),
+        }
+    }
+
+    fn is_primary(&self, target: &ChainClient) -> bool {
+        Arc::ptr_eq(&target.provider, &self.provider)
+    }
+
+    /// The built-in symbols are mainnet addresses, so other chains resolve none of them
+    fn registry_for(&self, target: &ChainClient) -> &TokenRegistry {
+        if target.info.has_known_addresses() {
+            &self.registry
+        } else {
+            &self.empty_registry
+        }
+    }
+
+    /// Refuse tools that rely on hardcoded mainnet addresses when `target` is another chain
+    fn require_known_addresses(&self, target: &ChainClient) -> Result<(), JsonRpcError> {
+        if self.is_primary(target) {
+            if let Some(chain_id) = self.chain_guard.mismatch() {
+                return Err(JsonRpcError {
+                    code: -32000,
+                    message: format!(
+                        "RPC endpoint switched from chain ID {} to {} since startup; refusing until it serves chain ID {} again",
+                        self.chain_info.chain_id, chain_id, self.chain_info.chain_id
+                    ),
+                    data: None,
+                });
+            }
+        }
+        if target.info.has_known_addresses() {
+            return Ok(());
+        }
+
+        Err(JsonRpcError {
+            code: -32000,
+            message: format!(
+                "unsupported chain for this feature: chain ID {} ({})",
+                target.info.chain_id, target.info.chain_name
+            ),
+            data: None,
+        })
+    }
+
+    /// Report a block or transaction the node answered with null as an `isError` tool result,
+    /// so it reads as an answer rather than a failed call; other errors become JSON-RPC errors
+    fn tool_error(&self, error: anyhow::Error) -> Result<ToolResult, JsonRpcError> {
+        let Some(not_found) = error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<NotFound>())
+        else {
+            return Err(self.error_to_json_rpc_error(error));
+        };
+
+        let data = json!({
+            "found": false,
+            "what": not_found.what,
+            "id": not_found.id,
+        });
+        ToolResult::error(not_found.to_string(), &data)
+            .map_err(|e| self.error_to_json_rpc_error(e.into()))
     }
 
     fn error_to_json_rpc_error(&self, error: anyhow::Error) -> JsonRpcError {
+        // Surface which RPC call failed, and how, so agents can decide whether to retry
+        let data = error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<RpcCallError>())
+            .and_then(|rpc_error| serde_json::to_value(rpc_error).ok());
+
         JsonRpcError {
             code: -32000,
             message: error.to_string(),
+            data,
+        }
+    }
+}
+
+/// Serialize a tool result with an EIP-191 signature over its compact JSON in `_meta.signature`
+///
+/// The signed payload is the result exactly as returned minus `_meta`, serialized without
+/// whitespace and in field order, so verifiers can rebuild it from the response they received.
+async fn sign_tool_result(signer: &LocalWallet, result: &ToolResult) -> Result<Value> {
+    let payload = serde_json::to_string(result)?;
+    let signature = signer.sign_message(payload.as_bytes()).await?;
+
+    let mut value = json!(result);
+    value["_meta"] = json!({
+        "signature": {
+            "scheme": "eip191",
+            "signer": format!("{:?}", signer.address()),
+            "signature": format!("0x{}", signature),
+        }
+    });
+    Ok(value)
+}
+
+/// Response for a message that is not a valid JSON-RPC 2.0 request
+fn invalid_request(id: Value, reason: String) -> JsonRpcResponse {
+    JsonRpcResponse {
+        jsonrpc: "2.0".to_string(),
+        id,
+        result: None,
+        error: Some(JsonRpcError {
+            code: -32600,
+            message: format!("Invalid Request: {}", reason),
             data: None,
+        }),
+    }
+}
+
+/// Add the request and trace ids to an error's `data`, for matching it to the server logs
+///
+/// Object data gains `request_id` and `trace_id` fields; errors without data get an object of
+/// just those two.
+fn tag_error(error: &mut JsonRpcError, request_id: &Value, trace_id: &str) {
+    match &mut error.data {
+        None => {
+            error.data = Some(json!({ "request_id": request_id, "trace_id": trace_id }));
+        }
+        Some(Value::Object(data)) => {
+            data.insert("request_id".to_string(), request_id.clone());
+            data.insert("trace_id".to_string(), json!(trace_id));
+        }
+        Some(_) => {}
+    }
+}
+
+/// Deserialize tool arguments, reporting failures as invalid params
+fn parse_params<T: DeserializeOwned>(args: &Value) -> Result<T, JsonRpcError> {
+    serde_json::from_value(args.clone()).map_err(|e| JsonRpcError {
+        code: -32602,
+        message: format!("Invalid parameters: {}", e),
+        data: None,
+    })
+}
+
+/// Map an MCP (syslog-style) log level onto the nearest `tracing` level
+fn tracing_directive(level: &str) -> Option<&'static str> {
+    match level {
+        "debug" => Some("debug"),
+        "info" | "notice" => Some("info"),
+        "warning" => Some("warn"),
+        "error" | "critical" | "alert" | "emergency" => Some("error"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ethereum::gas::is_op_stack;
+    use crate::ethereum::{
+        create_http_client, create_provider, fetch_chain_info, PriceSource, RpcLimits, RpcLogging,
+        DEFAULT_USER_AGENT,
+    };
+    use reqwest::header::HeaderMap;
+    use std::collections::HashMap;
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::{TcpListener, TcpStream};
+
+    /// Canned answers of the fake node; 1 ETH for every balance
+    fn fake_node_result(method: &str, chain_id: u64) -> Option<Value> {
+        match method {
+            "eth_chainId" => Some(json!(format!("{:#x}", chain_id))),
+            "eth_blockNumber" => Some(json!("0x112a880")),
+            "eth_getBalance" => Some(json!("0xde0b6b3a7640000")),
+            "eth_gasPrice" => Some(json!("0x3b9aca00")),
+            "eth_getTransactionByHash" => Some(fake_transaction()),
+            "eth_getTransactionReceipt" => Some(fake_receipt()),
+            // Pruned or future blocks: the node answers null
+            "eth_getBlockByNumber" => Some(Value::Null),
+            _ => None,
+        }
+    }
+
+    const FAKE_TX_HASH: &str = "0x5c504ed432cb51138bcf09aa5e8a410dd4a1e204ef84bfed1be16dfba1b22060";
+
+    /// Every transaction is a plain transfer mined two blocks before the fake head
+    fn fake_transaction() -> Value {
+        json!({
+            "hash": FAKE_TX_HASH,
+            "nonce": "0x5",
+            "blockHash": format!("0x{}", "11".repeat(32)),
+            "blockNumber": "0x112a87e",
+            "transactionIndex": "0x0",
+            "from": "0xd8da6bf26964af9d7eed9e03e53415d37aa96045",
+            "to": "0x0000000000000000000000000000000000000001",
+            "value": "0x0",
+            "gasPrice": "0x3b9aca00",
+            "gas": "0x5208",
+            "input": "0x",
+            "v": "0x25",
+            "r": "0x1",
+            "s": "0x1"
+        })
+    }
+
+    fn fake_receipt() -> Value {
+        json!({
+            "transactionHash": FAKE_TX_HASH,
+            "transactionIndex": "0x0",
+            "blockHash": format!("0x{}", "11".repeat(32)),
+            "blockNumber": "0x112a87e",
+            "from": "0xd8da6bf26964af9d7eed9e03e53415d37aa96045",
+            "to": "0x0000000000000000000000000000000000000001",
+            "cumulativeGasUsed": "0x5208",
+            "gasUsed": "0x5208",
+            "contractAddress": null,
+            "logs": [],
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "status": "0x1",
+            "effectiveGasPrice": "0x3b9aca00"
+        })
+    }
+
+    /// Contract calls the fake node answers; any other call reverts
+    fn fake_call_result(call: &Value, chain_id: u64) -> Option<Value> {
+        use ethers::abi::{encode, Token};
+        use ethers::utils::{hex, id};
+
+        let to = call["to"].as_str()?.to_lowercase();
+        let data = call["data"].as_str().or(call["input"].as_str())?;
+        let calls =
+            |signature: &str| data.starts_with(&format!("0x{}", hex::encode(id(signature))));
+
+        // OP-stack GasPriceOracle.getL1Fee quotes 1e12 wei (0.000001 ETH) for any transaction
+        if is_op_stack(chain_id) && to == "0x420000000000000000000000000000000000000f" {
+            return Some(json!(format!("0x{:064x}", 1_000_000_000_000u64)));
+        }
+
+        // A Uniswap V2 router whose getAmountsOut prices one hop more than it was asked for
+        if to == "0x7a250d5630b4cf539739df2c5dacb4c659f2488d" {
+            let weth: Address = "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2".parse().ok()?;
+            let factory: Address = "0x5C69bEe701ef814a2B6a3EDD4B1652CB9cc5aA6f".parse().ok()?;
+            let amounts = [1_000_000_000_000_000_000u64, 2_000_000_000, 1_999_000_000]
+                .map(|amount| Token::Uint(amount.into()));
+            let result = if calls("WETH()") {
+                encode(&[Token::Address(weth)])
+            } else if calls("factory()") {
+                encode(&[Token::Address(factory)])
+            } else if calls("getAmountsOut(uint256,address[])") {
+                encode(&[Token::Array(amounts.to_vec())])
+            } else {
+                return None;
+            };
+            return Some(json!(format!("0x{}", hex::encode(result))));
+        }
+        None
+    }
+
+    const ZERO_ADDRESS_REVERT: &str = "ERC20: balance query for the zero address";
+
+    /// `Error(string)` revert data carrying `ZERO_ADDRESS_REVERT`
+    fn zero_address_revert_data() -> String {
+        let mut data = vec![0x08, 0xc3, 0x79, 0xa0];
+        data.extend(ethers::abi::encode(&[ethers::abi::Token::String(
+            ZERO_ADDRESS_REVERT.to_string(),
+        )]));
+        format!("0x{}", ethers::utils::hex::encode(data))
+    }
+
+    /// Serve JSON-RPC over keep-alive HTTP/1.1 until the client hangs up
+    async fn serve_fake_node_connection(socket: TcpStream, chain_id: u64) {
+        let mut reader = BufReader::new(socket);
+        loop {
+            let mut content_length = 0;
+            let mut line = String::new();
+            loop {
+                line.clear();
+                if reader.read_line(&mut line).await.unwrap_or(0) == 0 {
+                    return;
+                }
+                let header = line.trim_end();
+                if header.is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = header.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap_or(0);
+                    }
+                }
+            }
+
+            let mut body = vec![0; content_length];
+            if reader.read_exact(&mut body).await.is_err() {
+                return;
+            }
+            let request: Value = serde_json::from_slice(&body).unwrap_or(Value::Null);
+            let method = request["method"].as_str().unwrap_or_default();
+            let result = match method {
+                "eth_call" => fake_call_result(&request["params"][0], chain_id),
+                _ => fake_node_result(method, chain_id),
+            };
+            let response = match result {
+                Some(result) => json!({"jsonrpc": "2.0", "id": request["id"], "result": result}),
+                // Other contract calls revert like pre-4.0 OpenZeppelin tokens asked about the zero address
+                None if method == "eth_call" => json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"],
+                    "error": {
+                        "code": 3,
+                        "message": format!("execution reverted: {}", ZERO_ADDRESS_REVERT),
+                        "data": zero_address_revert_data()
+                    }
+                }),
+                None => json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"],
+                    "error": {"code": -32601, "message": format!("method {} not supported", method)}
+                }),
+            };
+
+            let payload = response.to_string();
+            let http = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                payload.len(),
+                payload
+            );
+            if reader.get_mut().write_all(http.as_bytes()).await.is_err() {
+                return;
+            }
         }
     }
+
+    /// Start a local fake node, returning its RPC URL
+    async fn spawn_fake_node() -> String {
+        spawn_fake_node_for_chain(1).await
+    }
+
+    /// Start a local fake node serving `chain_id`, returning its RPC URL
+    async fn spawn_fake_node_for_chain(chain_id: u64) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let rpc_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                tokio::spawn(serve_fake_node_connection(socket, chain_id));
+            }
+        });
+        rpc_url
+    }
+
+    /// Build a server wired to a local fake node, as main does against a real RPC
+    async fn test_server() -> McpServer {
+        let rpc_url = spawn_fake_node().await;
+        let provider = create_provider(
+            &rpc_url,
+            RpcLogging::default(),
+            RpcLimits::default(),
+            DEFAULT_USER_AGENT,
+            Duration::from_millis(500),
+        )
+        .await
+        .unwrap();
+        let chain_info = fetch_chain_info(&provider, &rpc_url).await.unwrap();
+        let http = create_http_client(Duration::from_secs(1), None, HeaderMap::new()).unwrap();
+
+        McpServer::new(
+            provider,
+            chain_info,
+            Config::default(),
+            http,
+            None,
+            Vec::new(),
+        )
+    }
+
+    async fn call(server: &McpServer, request: Value) -> Value {
+        let request: JsonRpcRequest = serde_json::from_value(request).unwrap();
+        serde_json::to_value(server.handle_request(request).await).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_initialize_list_and_get_balance() {
+        let server = test_server().await;
+
+        let init = call(
+            &server,
+            json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}),
+        )
+        .await;
+        assert_eq!(init["id"], 1);
+        assert_eq!(init["result"]["protocolVersion"], MCP_VERSION);
+        assert_eq!(init["result"]["serverInfo"]["chain"]["chain_id"], 1);
+        assert_eq!(
+            init["result"]["serverInfo"]["chain"]["block_number"],
+            18_000_000
+        );
+        assert_eq!(
+            init["result"]["capabilities"]["experimental"]["trading"],
+            false
+        );
+
+        let list = call(
+            &server,
+            json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"}),
+        )
+        .await;
+        let tools = list["result"]["tools"].as_array().unwrap();
+        assert_eq!(tools.len(), tools::get_all_tools().len());
+        assert!(tools.iter().any(|tool| tool["name"] == "get_balance"));
+
+        let balance = call(
+            &server,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 3,
+                "method": "tools/call",
+                "params": {
+                    "name": "get_balance",
+                    "arguments": {"wallet_address": "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"}
+                }
+            }),
+        )
+        .await;
+        assert_eq!(balance["id"], 3);
+        assert!(balance.get("error").is_none());
+        let content = balance["result"]["content"].as_array().unwrap();
+        assert_eq!(content.len(), 3);
+        let summary = content[0]["text"].as_str().unwrap();
+        assert!(summary.starts_with("Balance: 1"));
+        assert!(!summary.contains('\n'));
+        let details = content[1]["text"].as_str().unwrap();
+        assert!(details.contains(" ETH\n"));
+        assert!(details.contains("Raw balance: 1000000000000000000"));
+        let data: Value = serde_json::from_str(content[2]["text"].as_str().unwrap()).unwrap();
+        assert!(data["balance"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_result_size_limit_spares_json() {
+        let mut server = test_server().await;
+        server.config.max_result_chars = 20;
+        let get_balance = |format: &str| {
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/call",
+                "params": {"name": "get_balance", "arguments": {
+                    "wallet_address": "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+                    "format": format
+                }}
+            })
+        };
+
+        let both = call(&server, get_balance("both")).await;
+        let content = both["result"]["content"].as_array().unwrap();
+        assert!(content[1]["text"]
+            .as_str()
+            .unwrap()
+            .contains("...(truncated, "));
+        let data: Value = serde_json::from_str(content[2]["text"].as_str().unwrap()).unwrap();
+        assert!(data["balance"].is_string());
+
+        let json_only = call(&server, get_balance("json")).await;
+        let content = json_only["result"]["content"].as_array().unwrap();
+        assert_eq!(content.len(), 1);
+        let data: Value = serde_json::from_str(content[0]["text"].as_str().unwrap()).unwrap();
+        assert!(data["balance"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_get_balance_of_eth_token_address() {
+        let server = test_server().await;
+
+        // The fake node reverts every eth_call, so a balanceOf attempt would fail the call
+        for eth in [
+            "0x0000000000000000000000000000000000000000",
+            "0xEeeeeEeeeEeEeeEeEeEeeEEEeeeeEeeeeeeeEEeE",
+        ] {
+            let balance = call(
+                &server,
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "method": "tools/call",
+                    "params": {
+                        "name": "get_balance",
+                        "arguments": {
+                            "wallet_address": "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+                            "token_address": eth
+                        }
+                    }
+                }),
+            )
+            .await;
+            assert!(balance.get("error").is_none());
+            assert!(balance["result"].get("isError").is_none(), "{}", balance);
+            let content = balance["result"]["content"].as_array().unwrap();
+            let details = content[1]["text"].as_str().unwrap();
+            assert!(details.contains("Raw balance: 1000000000000000000"));
+            let data: Value = serde_json::from_str(content[2]["text"].as_str().unwrap()).unwrap();
+            assert_eq!(data["symbol"], "ETH");
+            assert_eq!(data["token_address"], Value::Null);
+        }
+    }
+
+    #[test]
+    fn test_tag_error() {
+        let mut error = JsonRpcError {
+            code: -32601,
+            message: "Method not found: nope".to_string(),
+            data: None,
+        };
+        tag_error(&mut error, &json!("abc"), "0000002a");
+        assert_eq!(
+            error.data,
+            Some(json!({"request_id": "abc", "trace_id": "0000002a"}))
+        );
+
+        error.data = Some(json!({"retry_after_secs": 5}));
+        tag_error(&mut error, &Value::Null, "0000002b");
+        let data = error.data.unwrap();
+        assert_eq!(data["retry_after_secs"], 5);
+        assert_eq!(data["request_id"], Value::Null);
+        assert_eq!(data["trace_id"], "0000002b");
+    }
+
+    #[test]
+    fn test_tracing_directive() {
+        assert_eq!(tracing_directive("debug"), Some("debug"));
+        assert_eq!(tracing_directive("notice"), Some("info"));
+        assert_eq!(tracing_directive("warning"), Some("warn"));
+        assert_eq!(tracing_directive("emergency"), Some("error"));
+        assert_eq!(tracing_directive("verbose"), None);
+    }
+
+    #[tokio::test]
+    async fn test_set_log_level() {
+        let server = test_server().await;
+        let set_level = |level: &str| {
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "logging/setLevel",
+                "params": {"level": level}
+            })
+        };
+
+        let invalid = call(&server, set_level("verbose")).await;
+        assert_eq!(invalid["error"]["code"], -32602);
+
+        // Without a reload handle the level can't be changed
+        let unsupported = call(&server, set_level("debug")).await;
+        assert_eq!(unsupported["error"]["code"], -32603);
+
+        let (_filter, handle) = reload::Layer::<EnvFilter, Registry>::new(EnvFilter::new("info"));
+        let server = server.with_log_filter(handle);
+        let ok = call(&server, set_level("debug")).await;
+        assert_eq!(ok["result"], json!({}));
+    }
+
+    #[tokio::test]
+    async fn test_batch_and_request_validation() {
+        let server = test_server().await;
+
+        let responses = server
+            .handle_batch(vec![
+                json!({"jsonrpc": "2.0", "id": "a", "method": "tools/list"}),
+                json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+                json!({"jsonrpc": "1.0", "id": 7, "method": "tools/list"}),
+                json!({"jsonrpc": "2.0", "id": 8}),
+            ])
+            .await;
+        let responses = serde_json::to_value(responses).unwrap();
+        let responses = responses.as_array().unwrap();
+
+        // The notification gets no response; everything else keeps its id and order
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0]["id"], "a");
+        assert!(responses[0]["result"]["tools"].is_array());
+        assert_eq!(responses[1]["id"], 7);
+        assert_eq!(responses[1]["error"]["code"], -32600);
+        assert_eq!(responses[2]["id"], 8);
+        assert_eq!(responses[2]["error"]["code"], -32600);
+
+        let empty = server.handle_batch(Vec::new()).await;
+        assert_eq!(empty[0].error.as_ref().unwrap().code, -32600);
+
+        assert!(server.record_id(&json!(42)));
+        assert!(!server.record_id(&json!(42)));
+        // The same digits as a string are a different id
+        assert!(server.record_id(&json!("42")));
+    }
+
+    #[test]
+    fn test_recent_ids_evicts_oldest() {
+        let mut recent = RecentIds::new(2);
+        assert!(recent.insert("1".to_string()));
+        assert!(recent.insert("2".to_string()));
+        assert!(!recent.insert("1".to_string()));
+
+        // A third id pushes out the oldest, which then counts as new again
+        assert!(recent.insert("3".to_string()));
+        assert_eq!(recent.order.len(), 2);
+        assert_eq!(recent.ids.len(), 2);
+        assert!(!recent.insert("2".to_string()));
+        assert!(recent.insert("1".to_string()));
+        assert!(!recent.insert("3".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_offline_mode() {
+        // Nothing listens here; offline mode must never reach the provider
+        let provider = create_provider(
+            "http://127.0.0.1:9",
+            RpcLogging::default(),
+            RpcLimits::default(),
+            DEFAULT_USER_AGENT,
+            Duration::from_millis(500),
+        )
+        .await
+        .unwrap();
+        let http = create_http_client(Duration::from_secs(1), None, HeaderMap::new()).unwrap();
+        let config = Config {
+            offline_mode: true,
+            ..Config::default()
+        };
+        let server = McpServer::new(
+            provider,
+            ChainInfo::offline(),
+            config,
+            http,
+            None,
+            Vec::new(),
+        );
+        let tool_call = |name: &str, arguments: Value| {
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/call",
+                "params": {"name": name, "arguments": arguments}
+            })
+        };
+
+        let balance = call(
+            &server,
+            tool_call(
+                "get_balance",
+                json!({"wallet_address": "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"}),
+            ),
+        )
+        .await;
+        let text = balance["result"]["content"][0]["text"].as_str().unwrap();
+        assert!(text.starts_with(tools::mock::MOCK_LABEL));
+        assert!(text.contains("Balance: 10 ETH"));
+
+        let unmocked = call(&server, tool_call("get_logs", json!({}))).await;
+        assert_eq!(unmocked["error"]["code"], -32000);
+        let unknown = call(&server, tool_call("no_such_tool", json!({}))).await;
+        assert_eq!(unknown["error"]["code"], -32601);
+        let invalid = call(&server, tool_call("get_balance", json!({}))).await;
+        assert_eq!(invalid["error"]["code"], -32602);
+
+        // Mock results have no JSON block, so asking for one keeps the labeled text
+        let json_only = call(
+            &server,
+            tool_call(
+                "get_balance",
+                json!({"wallet_address": "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045", "format": "json"}),
+            ),
+        )
+        .await;
+        assert_eq!(json_only["result"]["content"].as_array().unwrap().len(), 1);
+        let bad_format = call(
+            &server,
+            tool_call(
+                "get_balance",
+                json!({"wallet_address": "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045", "format": "xml"}),
+            ),
+        )
+        .await;
+        assert_eq!(bad_format["error"]["code"], -32602);
+    }
+
+    #[tokio::test]
+    async fn test_busy_rpc_rejects_tool_calls() {
+        // Nothing listens here; the busy check must answer before any RPC call
+        let provider = create_provider(
+            "http://127.0.0.1:9",
+            RpcLogging::default(),
+            RpcLimits::default(),
+            DEFAULT_USER_AGENT,
+            Duration::from_millis(500),
+        )
+        .await
+        .unwrap();
+        for _ in 0..RpcLimits::default().busy_threshold {
+            rpc_backpressure(&provider).record_rate_limited();
+        }
+        let http = create_http_client(Duration::from_secs(1), None, HeaderMap::new()).unwrap();
+        let server = McpServer::new(
+            provider,
+            ChainInfo::offline(),
+            Config::default(),
+            http,
+            None,
+            Vec::new(),
+        );
+
+        let busy = call(
+            &server,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/call",
+                "params": {"name": "get_gas_price", "arguments": {}}
+            }),
+        )
+        .await;
+        assert_eq!(busy["error"]["code"], -32000);
+        assert!(busy["error"]["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("server busy, retry after "));
+        assert_eq!(busy["error"]["data"]["retry_after_secs"], 30);
+        assert_eq!(busy["error"]["data"]["request_id"], 1);
+        assert!(busy["error"]["data"]["trace_id"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_tool_call_errors() {
+        let server = test_server().await;
+
+        let unknown = call(
+            &server,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/call",
+                "params": {"name": "no_such_tool", "arguments": {}}
+            }),
+        )
+        .await;
+        assert_eq!(unknown["error"]["code"], -32601);
+
+        let invalid = call(
+            &server,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "tools/call",
+                "params": {"name": "get_balance", "arguments": {}}
+            }),
+        )
+        .await;
+        assert_eq!(invalid["error"]["code"], -32602);
+
+        let bad_address = call(
+            &server,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 3,
+                "method": "tools/call",
+                "params": {"name": "get_balance", "arguments": {"wallet_address": "0x123"}}
+            }),
+        )
+        .await;
+        assert_eq!(bad_address["error"]["code"], -32000);
+
+        let zero_holder = call(
+            &server,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 4,
+                "method": "tools/call",
+                "params": {"name": "get_balance", "arguments": {
+                    "wallet_address": "0x0000000000000000000000000000000000000000",
+                    "token_address": "0x6B175474E89094C44Da98b954EedeAC495271d0F"
+                }}
+            }),
+        )
+        .await;
+        let message = zero_holder["error"]["message"].as_str().unwrap();
+        assert!(message.contains("does not support balance query for address"));
+        assert!(message.contains(ZERO_ADDRESS_REVERT));
+        assert_eq!(zero_holder["error"]["data"]["method"], "balanceOf");
+        assert_eq!(zero_holder["error"]["data"]["kind"], "revert");
+    }
+
+    #[tokio::test]
+    async fn test_not_found_is_a_tool_result() {
+        let server = test_server().await;
+
+        let not_found = NotFound {
+            what: "Transaction",
+            id: "0x01".to_string(),
+        };
+        let result = server
+            .tool_error(anyhow::Error::new(not_found).context("Failed to trace"))
+            .unwrap();
+        assert_eq!(result.is_error, Some(true));
+        assert_eq!(result.content[0].text, "Transaction 0x01 not found");
+        let data: Value = serde_json::from_str(&result.content[1].text).unwrap();
+        assert_eq!(data["found"], false);
+        assert_eq!(data["id"], "0x01");
+
+        // Failed lookups stay JSON-RPC errors
+        let error = server.tool_error(anyhow::anyhow!("connection refused"));
+        assert_eq!(error.unwrap_err().code, -32000);
+
+        // The fake node answers null for every block, and clients must see the MCP `isError` flag
+        let missing_block = call(
+            &server,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/call",
+                "params": {"name": "get_transaction_count_by_block", "arguments": {"block_count": 1}}
+            }),
+        )
+        .await;
+        assert!(missing_block.get("error").is_none(), "{}", missing_block);
+        assert_eq!(missing_block["result"]["isError"], true);
+        assert!(missing_block["result"].get("is_error").is_none());
+        assert_eq!(
+            missing_block["result"]["content"][0]["text"],
+            "Block 18000000 not found"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_chain_argument_selects_provider() {
+        // The fake node serves chain ID 1, so configuring it for Optimism must be refused
+        let optimism_url = spawn_fake_node().await;
+        let chain_providers = ChainProviders::new(
+            HashMap::from([(10, optimism_url)]),
+            RpcLogging::default(),
+            RpcLimits::default(),
+            DEFAULT_USER_AGENT,
+            Duration::from_millis(500),
+        );
+        let server = test_server().await.with_chain_providers(chain_providers);
+        let get_balance = |id: u64, chain: Value| {
+            json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": "tools/call",
+                "params": {"name": "get_balance", "arguments": {
+                    "wallet_address": "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+                    "chain": chain
+                }}
+            })
+        };
+
+        let primary = call(&server, get_balance(1, json!("mainnet"))).await;
+        assert!(primary["error"].is_null());
+
+        let unconfigured = call(&server, get_balance(2, json!(8453))).await;
+        assert_eq!(unconfigured["error"]["code"], -32602);
+        assert!(unconfigured["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("RPC_URLS"));
+
+        let unknown = call(&server, get_balance(3, json!("nowhere"))).await;
+        assert_eq!(unknown["error"]["code"], -32602);
+
+        let mismatched = call(&server, get_balance(4, json!("optimism"))).await;
+        assert_eq!(mismatched["error"]["code"], -32000);
+        assert!(mismatched["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("serves chain ID 1"));
+    }
+
+    #[tokio::test]
+    async fn test_compare_gas_marks_failing_chain_unavailable() {
+        // Serves chain ID 1, so as Optimism's endpoint it fails the chain check
+        let optimism_url = spawn_fake_node().await;
+        let chain_providers = ChainProviders::new(
+            HashMap::from([(10, optimism_url)]),
+            RpcLogging::default(),
+            RpcLimits::default(),
+            DEFAULT_USER_AGENT,
+            Duration::from_millis(500),
+        );
+        let mut server = test_server().await.with_chain_providers(chain_providers);
+        // Chainlink reverts on the fake node, which leaves only the unusable fallback ETH price
+        server.config.price_sources = vec![PriceSource::Chainlink];
+
+        let result = call(
+            &server,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/call",
+                "params": {"name": "compare_gas_across_chains", "arguments": {}}
+            }),
+        )
+        .await;
+        assert!(result.get("error").is_none(), "{}", result);
+        let content = result["result"]["content"].as_array().unwrap();
+        assert!(content[0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("1 of 2 chains unavailable"));
+
+        let data: Value = serde_json::from_str(content[2]["text"].as_str().unwrap()).unwrap();
+        let primary = &data["chains"][0];
+        assert_eq!(primary["chain_id"], 1);
+        assert_eq!(primary["available"], true);
+        assert_eq!(primary["gas_price_gwei"], "1");
+        assert_eq!(primary["transfer_cost"], "0.000021");
+        assert_eq!(primary["transfer_cost_usd"], Value::Null);
+
+        let optimism = &data["chains"][1];
+        assert_eq!(optimism["chain_id"], 10);
+        assert_eq!(optimism["available"], false);
+        assert!(optimism["error"]
+            .as_str()
+            .unwrap()
+            .contains("serves chain ID 1"));
+        assert_eq!(data["cheapest_chain_id"], Value::Null);
+    }
+
+    #[tokio::test]
+    async fn test_compare_gas_includes_op_stack_l1_fee() {
+        let optimism_url = spawn_fake_node_for_chain(10).await;
+        let chain_providers = ChainProviders::new(
+            HashMap::from([(10, optimism_url)]),
+            RpcLogging::default(),
+            RpcLimits::default(),
+            DEFAULT_USER_AGENT,
+            Duration::from_millis(500),
+        );
+        let server = test_server().await.with_chain_providers(chain_providers);
+
+        let result = call(
+            &server,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/call",
+                "params": {"name": "compare_gas_across_chains", "arguments": {}}
+            }),
+        )
+        .await;
+        assert!(result.get("error").is_none(), "{}", result);
+        let content = result["result"]["content"].as_array().unwrap();
+        let data: Value = serde_json::from_str(content[2]["text"].as_str().unwrap()).unwrap();
+
+        // Mainnet pays execution gas only
+        let primary = &data["chains"][0];
+        assert_eq!(primary["l1_data_fee"], Value::Null);
+        assert_eq!(primary["transfer_cost"], "0.000021");
+
+        // Optimism adds the GasPriceOracle's L1 data fee on top of 21000 gas at 1 gwei
+        let optimism = &data["chains"][1];
+        assert_eq!(optimism["chain_id"], 10);
+        assert_eq!(optimism["available"], true, "{}", optimism);
+        assert_eq!(optimism["l1_data_fee"], "0.000001");
+        assert_eq!(optimism["transfer_cost"], "0.000022");
+    }
+
+    #[tokio::test]
+    async fn test_swap_quote_checks_route_after_quoting() {
+        let server = test_server().await;
+
+        // ETH in is quoted from the router's WETH; the fake router answers for a longer route
+        let result = call(
+            &server,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/call",
+                "params": {"name": "swap_tokens", "arguments": {
+                    "from_token": "0xEeeeeEeeeEeEeeEeEeEeeEEEeeeeEeeeeeeeEEeE",
+                    "to_token": "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+                    "amount": "1",
+                    "wallet_address": "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+                }}
+            }),
+        )
+        .await;
+        assert_eq!(result["error"]["code"], -32000, "{}", result);
+        let message = result["error"]["message"].as_str().unwrap();
+        assert!(
+            message.contains("Router quoted 3 amounts for the 2-token route"),
+            "{}",
+            message
+        );
+        assert!(message.contains("[0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2, 0xa0b8"));
+    }
+
+    #[tokio::test]
+    async fn test_chain_switch_refuses_mainnet_tools() {
+        let server = test_server().await;
+        server.chain_guard.record(10);
+
+        let price = call(
+            &server,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/call",
+                "params": {"name": "get_eth_price", "arguments": {}}
+            }),
+        )
+        .await;
+        assert_eq!(price["error"]["code"], -32000);
+        assert!(price["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("switched from chain ID 1 to 10"));
+    }
+
+    /// Clients cache tools/list, so any change to names, descriptions, schemas or their order must be deliberate.
+    /// Run with UPDATE_SNAPSHOTS=1 to rewrite the snapshot after an intended change.
+    #[tokio::test]
+    async fn test_tools_list_snapshot() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/src/snapshots/tools_list.json");
+        let server = test_server().await;
+        let list = call(
+            &server,
+            json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}),
+        )
+        .await;
+        let actual = serde_json::to_string_pretty(&list).unwrap();
+
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::write(path, format!("{}\n", actual)).unwrap();
+            return;
+        }
+        let expected = std::fs::read_to_string(path).unwrap();
+        assert_eq!(
+            actual,
+            expected.trim_end(),
+            "tools/list changed; rerun with UPDATE_SNAPSHOTS=1 if intended"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_transaction() {
+        let server = test_server().await;
+        let wait = |confirmations: u64| {
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/call",
+                "params": {"name": "wait_for_transaction", "arguments": {
+                    "tx_hash": FAKE_TX_HASH,
+                    "confirmations": confirmations,
+                    "timeout_secs": 1
+                }}
+            })
+        };
+
+        // Mined two blocks before the head, so it already has 3 confirmations
+        let confirmed = call(&server, wait(3)).await;
+        assert!(confirmed.get("error").is_none(), "{}", confirmed);
+        let content = confirmed["result"]["content"].as_array().unwrap();
+        let data: Value = serde_json::from_str(content[2]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(data["status"], "success");
+        assert_eq!(data["block_number"], 17_999_998);
+        assert_eq!(data["confirmations"], 3);
+        assert_eq!(data["gas_used"], "21000");
+
+        // The fake head never moves, so a fourth confirmation never comes
+        let timed_out = call(&server, wait(4)).await;
+        assert_eq!(timed_out["error"]["code"], -32000);
+        assert!(timed_out["error"]["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("Timed out after 1s waiting for 4 confirmations"));
+    }
+
+    #[tokio::test]
+    async fn test_token_denylist() {
+        let scam = "0x1111111111111111111111111111111111111111";
+        let server = test_server()
+            .await
+            .with_token_denylist(TokenDenylist::parse(scam));
+        let tool_call = |name: &str, arguments: Value| {
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/call",
+                "params": {"name": name, "arguments": arguments}
+            })
+        };
+
+        let denied = call(
+            &server,
+            tool_call(
+                "get_balance",
+                json!({
+                    "wallet_address": "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+                    "token_address": scam
+                }),
+            ),
+        )
+        .await;
+        assert_eq!(denied["error"]["code"], -32000);
+        assert!(denied["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("token is denylisted"));
+
+        let allowed = call(
+            &server,
+            tool_call(
+                "get_balance",
+                json!({"wallet_address": "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"}),
+            ),
+        )
+        .await;
+        assert!(allowed["error"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_sign_tool_result() {
+        let signer: LocalWallet =
+            "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+                .parse()
+                .unwrap();
+        let result = ToolResult {
+            content: vec![crate::types::ToolContent::text("Balance: 1 ETH")],
+            is_error: None,
+        };
+
+        let signed = sign_tool_result(&signer, &result).await.unwrap();
+        let meta = &signed["_meta"]["signature"];
+        assert_eq!(meta["signer"], format!("{:?}", signer.address()));
+
+        // The verification recipe: drop _meta, re-serialize compactly, recover the signer
+        let mut unsigned = signed.clone();
+        unsigned.as_object_mut().unwrap().remove("_meta");
+        let payload = serde_json::to_string(&unsigned).unwrap();
+        let signature: ethers::types::Signature = meta["signature"]
+            .as_str()
+            .unwrap()
+            .trim_start_matches("0x")
+            .parse()
+            .unwrap();
+        assert_eq!(
+            signature.recover(payload.as_str()).unwrap(),
+            signer.address()
+        );
+        assert!(signature.recover("Balance: 2 ETH").unwrap() != signer.address());
+    }
 }
@@ -0,0 +1,149 @@
+use anyhow::Result;
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use serde::Serialize;
+
+use super::swap_tokens::{
+    self, confirmation_request, refresh_metadata, require_wallet, resolve_amount, swap_request,
+    version_label, SwapTokensParams,
+};
+use crate::config::Config;
+use crate::ethereum::{build_swap_transaction, EthClient, MetadataCache};
+use crate::types::{Amount, Tool, ToolResult};
+
+/// Unsigned EIP-1559 transaction fields, hex-encoded as wallets expect them
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsignedTransactionResponse {
+    #[serde(rename = "type")]
+    pub tx_type: String,
+    pub chain_id: String,
+    pub from: String,
+    pub to: String,
+    pub data: String,
+    pub value: String,
+    pub gas: String,
+    pub nonce: String,
+    pub max_fee_per_gas: String,
+    pub max_priority_fee_per_gas: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BuildSwapTransactionResponse {
+    pub transaction: UnsignedTransactionResponse,
+    /// RLP of the unsigned typed transaction (0x02 || rlp), as signed by hardware/MPC wallets
+    pub unsigned_rlp: String,
+    pub estimated_output: Amount,
+    pub minimum_output: Amount,
+    pub version: String,
+    pub fee_tier: Option<u32>,
+    pub route: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+pub fn get_tool_definition() -> Tool {
+    // Same inputs as swap_tokens, minus options that don't map to a single transaction
+    let mut input_schema = swap_tokens::get_tool_definition().input_schema;
+    if let Some(properties) = input_schema["properties"].as_object_mut() {
+        // retain rather than remove, which would reorder the remaining keys
+        properties.retain(|key, _| key != "split" && key != "compare_aggregator");
+    }
+    input_schema["properties"]["wallet_address"]["description"] =
+        "Address that will sign and send the transaction (0x...)".into();
+    // Only swap_tokens can fall back to the default simulation wallet
+    if let Some(required) = input_schema["required"].as_array_mut() {
+        required.push("wallet_address".into());
+    }
+
+    Tool {
+        name: "build_swap_transaction".to_string(),
+        description: "Quote a Uniswap swap and return the fully-populated unsigned EIP-1559 transaction (to, data, value, gas, nonce, chainId, fee fields) as hex, for signing in an external wallet. No private key needed."
+            .to_string(),
+        input_schema,
+    }
+}
+
+pub async fn execute(
+    provider: &EthClient,
+    cache: &MetadataCache,
+    http: &reqwest::Client,
+    config: &Config,
+    chain_id: u64,
+    signer: Option<Address>,
+    params: SwapTokensParams,
+) -> Result<ToolResult> {
+    require_wallet(&params)?;
+    let (amount, _) = resolve_amount(provider, http, config, &params).await?;
+    let request = swap_request(&params, amount, config, signer)?;
+    if params.refresh {
+        refresh_metadata(cache, &request);
+    }
+    let built = match build_swap_transaction(provider, cache, chain_id, request).await {
+        Ok(built) => built,
+        Err(e) => return confirmation_request("build_swap_transaction", e),
+    };
+
+    let tx = &built.tx;
+    let hex_u256 = |value: Option<&U256>| format!("{:#x}", value.copied().unwrap_or_default());
+    let (max_fee_per_gas, max_priority_fee_per_gas) = match tx {
+        TypedTransaction::Eip1559(inner) => (inner.max_fee_per_gas, inner.max_priority_fee_per_gas),
+        _ => (None, None),
+    };
+
+    let transaction = UnsignedTransactionResponse {
+        tx_type: "0x2".to_string(),
+        chain_id: format!("{:#x}", chain_id),
+        from: format!("{:?}", tx.from().copied().unwrap_or_default()),
+        to: tx
+            .to_addr()
+            .map(|to| format!("{:?}", to))
+            .unwrap_or_default(),
+        data: tx.data().map(|data| data.to_string()).unwrap_or_default(),
+        value: hex_u256(tx.value()),
+        gas: hex_u256(tx.gas()),
+        nonce: hex_u256(tx.nonce()),
+        max_fee_per_gas: hex_u256(max_fee_per_gas.as_ref()),
+        max_priority_fee_per_gas: hex_u256(max_priority_fee_per_gas.as_ref()),
+    };
+
+    let simulation = &built.simulation;
+    let response = BuildSwapTransactionResponse {
+        transaction,
+        unsigned_rlp: tx.rlp().to_string(),
+        estimated_output: Amount::new(simulation.estimated_output),
+        minimum_output: Amount::new(simulation.minimum_output),
+        version: version_label(simulation.version),
+        fee_tier: simulation.fee_tier,
+        route: simulation
+            .route
+            .iter()
+            .map(|addr| format!("{:?}", addr))
+            .collect(),
+        warnings: simulation.warnings.clone(),
+    };
+
+    let mut text = format!(
+        "Unsigned swap transaction ({}{})\nEstimated output: {}\nMinimum output: {}\nRoute: {}\n\nTransaction:\n{}\n\nUnsigned RLP: {}",
+        response.version.to_uppercase(),
+        response
+            .fee_tier
+            .map(|fee| format!(", fee tier {}", fee))
+            .unwrap_or_default(),
+        response.estimated_output,
+        response.minimum_output,
+        response.route.join(" -> "),
+        serde_json::to_string_pretty(&response.transaction)?,
+        response.unsigned_rlp
+    );
+    for warning in &response.warnings {
+        text.push_str(&format!("\nWarning: {}", warning));
+    }
+
+    let summary = format!(
+        "Unsigned Uniswap {} swap transaction: {} out, minimum {}",
+        response.version.to_uppercase(),
+        response.estimated_output,
+        response.minimum_output
+    );
+    Ok(ToolResult::blocks(summary, text, &response)?)
+}
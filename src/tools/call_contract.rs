@@ -0,0 +1,131 @@
+use anyhow::Result;
+use ethers::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::ethereum::{
+    call_contract, param_types, parse_signature, token_to_json, CallArgs, EthClient,
+};
+use crate::types::{Tool, ToolResult};
+
+#[derive(Debug, Deserialize)]
+pub struct CallContractParams {
+    pub contract_address: String,
+    /// e.g. "balanceOf(address)(uint256)" or "function balanceOf(address) view returns (uint256)"
+    pub signature: String,
+    /// Plain argument values, one per input
+    pub args: Option<Vec<Value>>,
+    /// ABI-encoded arguments (hex, without the selector), instead of `args`
+    pub data: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DecodedOutput {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub value: Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CallContractResponse {
+    pub contract_address: String,
+    pub function: String,
+    pub raw_result: String,
+    pub decoded: Vec<DecodedOutput>,
+}
+
+pub fn get_tool_definition() -> Tool {
+    Tool {
+        name: "call_contract".to_string(),
+        description: "Call any read-only contract function via eth_call and decode the result from the function signature. For contracts the built-in tools don't cover."
+            .to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "contract_address": {
+                    "type": "string",
+                    "description": "Contract address to call (0x...)"
+                },
+                "signature": {
+                    "type": "string",
+                    "description": "Function signature with return types, e.g. 'balanceOf(address)(uint256)' or 'function balanceOf(address) view returns (uint256)'. Without return types the raw result is returned undecoded."
+                },
+                "args": {
+                    "type": "array",
+                    "description": "Argument values in order, e.g. [\"0x...\", \"1000\"]. Arrays and tuples use the '[a,b]' / '(a,b)' string form."
+                },
+                "data": {
+                    "type": "string",
+                    "description": "ABI-encoded arguments as hex (without the 4-byte selector), instead of args"
+                }
+            },
+            "required": ["contract_address", "signature"]
+        }),
+    }
+}
+
+pub async fn execute(provider: &EthClient, params: CallContractParams) -> Result<ToolResult> {
+    let contract = params
+        .contract_address
+        .parse::<Address>()
+        .map_err(|e| anyhow::anyhow!("Invalid contract address: {}", e))?;
+
+    let function = parse_signature(&params.signature)?;
+
+    let args = match (params.args, params.data) {
+        (Some(_), Some(_)) => anyhow::bail!("Provide either args or data, not both"),
+        (None, Some(data)) => CallArgs::Encoded(
+            data.parse::<Bytes>()
+                .map_err(|e| anyhow::anyhow!("Invalid data hex: {}", e))?,
+        ),
+        (args, None) => CallArgs::Values(args.unwrap_or_default()),
+    };
+
+    let result = call_contract(provider, contract, function, args).await?;
+
+    let function = &result.function;
+    let response = CallContractResponse {
+        contract_address: format!("{:?}", contract),
+        function: format!(
+            "{}({}) returns ({})",
+            function.name,
+            param_types(&function.inputs),
+            param_types(&function.outputs)
+        ),
+        raw_result: result.raw.to_string(),
+        decoded: function
+            .outputs
+            .iter()
+            .zip(&result.decoded)
+            .map(|(param, token)| DecodedOutput {
+                name: param.name.clone(),
+                kind: param.kind.to_string(),
+                value: token_to_json(token),
+            })
+            .collect(),
+    };
+
+    let mut text = format!(
+        "Contract: {}\nFunction: {}\nRaw result: {}",
+        response.contract_address, response.function, response.raw_result
+    );
+    if function.outputs.is_empty() {
+        text.push_str("\n(no return types in signature; result not decoded)");
+    }
+    for (i, output) in response.decoded.iter().enumerate() {
+        let label = if output.name.is_empty() {
+            format!("[{}]", i)
+        } else {
+            output.name.clone()
+        };
+        let value = match &output.value {
+            Value::String(value) => value.clone(),
+            value => value.to_string(),
+        };
+        text.push_str(&format!("\n{} ({}): {}", label, output.kind, value));
+    }
+
+    let summary = format!("Called {} on {}", function.name, response.contract_address);
+    Ok(ToolResult::blocks(summary, text, &response)?)
+}
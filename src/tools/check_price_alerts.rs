@@ -0,0 +1,110 @@
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashMap;
+
+use crate::config::Config;
+use crate::ethereum::{get_token_price, AlertDirection, EthClient, PriceAlerts, PriceSource};
+use crate::types::{Amount, Tool, ToolResult};
+
+#[derive(Debug, Serialize)]
+pub struct TriggeredAlertResponse {
+    pub alert_id: u64,
+    pub token_address: String,
+    pub direction: AlertDirection,
+    pub threshold_usd: Amount,
+    pub price_usd: Amount,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CheckPriceAlertsResponse {
+    pub triggered: Vec<TriggeredAlertResponse>,
+    pub pending_alerts: usize,
+    /// Tokens that couldn't be priced this check; their alerts stay pending
+    pub unpriced: Vec<String>,
+}
+
+pub fn get_tool_definition() -> Tool {
+    Tool {
+        name: "check_price_alerts".to_string(),
+        description: "Fetch current prices for tokens with pending alerts (see set_price_alert) and return the alerts that triggered since the last check."
+            .to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {}
+        }),
+    }
+}
+
+pub async fn execute(
+    provider: &EthClient,
+    http: &reqwest::Client,
+    config: &Config,
+    alerts: &PriceAlerts,
+) -> Result<ToolResult> {
+    // One price lookup per token, however many alerts watch it
+    let mut prices = HashMap::new();
+    let mut unpriced = Vec::new();
+    let oracles = config.price_oracles(http);
+    for token in alerts.pending_tokens() {
+        let price = get_token_price(provider, &oracles, token, PriceSource::Auto).await;
+        match price {
+            // The hardcoded fallback ETH price must never trigger an alert
+            Ok(price) if !price.fallback => match price.price_usd {
+                Some(price_usd) => {
+                    prices.insert(token, price_usd);
+                }
+                None => unpriced.push(format!("{:?}: no USD price", token)),
+            },
+            Ok(price) => unpriced.push(format!("{:?}: {} price unreliable", token, price.source)),
+            Err(e) => unpriced.push(format!("{:?}: {}", token, e)),
+        }
+    }
+
+    let triggered = alerts.take_triggered(&prices);
+
+    let response = CheckPriceAlertsResponse {
+        triggered: triggered
+            .into_iter()
+            .map(|triggered| TriggeredAlertResponse {
+                alert_id: triggered.alert.id,
+                token_address: format!("{:?}", triggered.alert.token),
+                direction: triggered.alert.direction,
+                threshold_usd: Amount::new(triggered.alert.threshold_usd),
+                price_usd: Amount::new(triggered.price_usd),
+            })
+            .collect(),
+        pending_alerts: alerts.pending_count(),
+        unpriced,
+    };
+
+    let mut text = if response.triggered.is_empty() {
+        "No alerts triggered".to_string()
+    } else {
+        format!("Triggered alerts: {}", response.triggered.len())
+    };
+    for alert in &response.triggered {
+        text.push_str(&format!(
+            "\n- #{} {}: ${} is {} ${}",
+            alert.alert_id,
+            alert.token_address,
+            alert.price_usd,
+            match alert.direction {
+                AlertDirection::Above => "at or above",
+                AlertDirection::Below => "at or below",
+            },
+            alert.threshold_usd
+        ));
+    }
+    text.push_str(&format!("\nPending alerts: {}", response.pending_alerts));
+    for note in &response.unpriced {
+        text.push_str(&format!("\nUnpriced: {}", note));
+    }
+
+    let summary = format!(
+        "{} alerts triggered, {} pending",
+        response.triggered.len(),
+        response.pending_alerts
+    );
+    Ok(ToolResult::blocks(summary, text, &response)?)
+}
@@ -0,0 +1,235 @@
+use anyhow::Result;
+use ethers::prelude::*;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::config::Config;
+use crate::ethereum::{
+    compare_v2_venues, normalize_eth, EthClient, MetadataCache, SwapRequest, UniswapVersion,
+    VenueQuote,
+};
+use crate::types::{Amount, Tool, ToolResult};
+
+#[derive(Debug, Deserialize)]
+pub struct CompareDexQuotesParams {
+    pub from_token: String,
+    pub to_token: String,
+    /// Amount in from-token units
+    pub amount: Amount,
+    #[serde(default = "default_slippage")]
+    pub slippage_bps: u32,
+    pub wallet_address: String,
+    /// Quote with a flat gas figure when gas estimation fails
+    #[serde(default)]
+    pub allow_gas_fallback: bool,
+}
+
+fn default_slippage() -> u32 {
+    50 // 0.5% default slippage
+}
+
+#[derive(Debug, Serialize)]
+pub struct VenueQuoteResponse {
+    pub venue: String,
+    pub router: String,
+    pub estimated_output: Option<Amount>,
+    pub minimum_output: Option<Amount>,
+    pub price_impact: Option<Amount>,
+    pub estimated_gas: Option<String>,
+    pub route: Option<Vec<String>>,
+    pub warnings: Vec<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CompareDexQuotesResponse {
+    pub from_token: String,
+    pub to_token: String,
+    pub amount_in: Amount,
+    pub best_venue: String,
+    /// How much more the best venue returns than the other, in percent
+    pub spread_pct: Option<Amount>,
+    /// Best output first
+    pub quotes: Vec<VenueQuoteResponse>,
+}
+
+pub fn get_tool_definition() -> Tool {
+    Tool {
+        name: "compare_dex_quotes".to_string(),
+        description: "Quote the same swap on Uniswap V2 and Sushiswap and report which returns more, with both quotes side by side and the spread between them. Returns estimates only, without executing."
+            .to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "from_token": {
+                    "type": "string",
+                    "description": "Source token address (0x...). Use 0x0000000000000000000000000000000000000000 for ETH."
+                },
+                "to_token": {
+                    "type": "string",
+                    "description": "Destination token address (0x...)"
+                },
+                "amount": {
+                    "type": "string",
+                    "description": "Amount to swap (in token units, e.g., '1.5')"
+                },
+                "slippage_bps": {
+                    "type": "number",
+                    "description": "Slippage tolerance in basis points (e.g., 50 = 0.5%). Default: 50",
+                    "default": 50
+                },
+                "wallet_address": {
+                    "type": "string",
+                    "description": "Wallet address for simulation (0x...)"
+                },
+                "allow_gas_fallback": {
+                    "type": "boolean",
+                    "description": "Set to true to quote with a flat 300000 gas estimate when gas estimation fails (e.g. the wallet lacks the balance), instead of reporting the revert error. Default: false",
+                    "default": false
+                }
+            },
+            "required": ["from_token", "to_token", "amount", "wallet_address"]
+        }),
+    }
+}
+
+pub async fn execute(
+    provider: &EthClient,
+    cache: &MetadataCache,
+    config: &Config,
+    params: CompareDexQuotesParams,
+) -> Result<ToolResult> {
+    let from_token = params
+        .from_token
+        .parse::<Address>()
+        .map(normalize_eth)
+        .map_err(|e| anyhow::anyhow!("Invalid from_token address: {}", e))?;
+
+    let to_token = params
+        .to_token
+        .parse::<Address>()
+        .map(normalize_eth)
+        .map_err(|e| anyhow::anyhow!("Invalid to_token address: {}", e))?;
+
+    let wallet_address = params
+        .wallet_address
+        .parse::<Address>()
+        .map_err(|e| anyhow::anyhow!("Invalid wallet address: {}", e))?;
+
+    let amount = params.amount.value();
+    if amount <= Decimal::ZERO {
+        anyhow::bail!("Amount must be positive");
+    }
+
+    let request = SwapRequest {
+        from_token,
+        to_token,
+        amount_in: amount,
+        slippage_bps: params.slippage_bps,
+        auto_slippage: false,
+        wallet_address,
+        recipient: None,
+        version: UniswapVersion::V2,
+        fee_tier: None,
+        max_price_impact_bps: config.max_price_impact_bps,
+        confirm_impact_bps: config.confirm_impact_bps,
+        // Comparing quotes commits to nothing; swap_tokens asks before a high-impact trade
+        high_impact_confirmed: true,
+        max_hops: config.max_hops,
+        rounding: config.wei_rounding,
+        path: None,
+        split: false,
+        v2_router: None,
+        strict_balance: false,
+        min_slippage_bps: config.min_slippage_bps,
+        slippage_warn_bps: config.slippage_warn_bps,
+        allow_gas_fallback: params.allow_gas_fallback,
+        max_gas_estimate: config.max_gas_estimate,
+    };
+    let quotes = compare_v2_venues(provider, cache, request).await;
+
+    let outputs: Vec<Decimal> = quotes
+        .iter()
+        .filter_map(|quote| quote.simulation.as_ref().ok())
+        .map(|simulation| simulation.estimated_output)
+        .collect();
+    if outputs.is_empty() {
+        let errors: Vec<String> = quotes
+            .iter()
+            .filter_map(|quote| {
+                let error = quote.simulation.as_ref().err()?;
+                Some(format!("{}: {:#}", quote.venue, error))
+            })
+            .collect();
+        anyhow::bail!("No venue could quote the swap: {}", errors.join("; "));
+    }
+    let spread_pct = match outputs.as_slice() {
+        [best, other] if !other.is_zero() => Some(Amount::with_decimals(
+            (*best - *other) / *other * Decimal::from(100),
+            4,
+        )),
+        _ => None,
+    };
+
+    let response = CompareDexQuotesResponse {
+        from_token: params.from_token,
+        to_token: params.to_token,
+        amount_in: params.amount,
+        best_venue: quotes[0].venue.to_string(),
+        spread_pct,
+        quotes: quotes.iter().map(venue_response).collect(),
+    };
+
+    let mut text = format!(
+        "Best venue for {} {} -> {}: {}",
+        response.amount_in, response.from_token, response.to_token, response.best_venue
+    );
+    if let Some(spread) = &response.spread_pct {
+        text.push_str(&format!(" ({}% more output)", spread));
+    }
+    for quote in &response.quotes {
+        match (&quote.estimated_output, &quote.price_impact, &quote.error) {
+            (Some(output), Some(impact), _) => text.push_str(&format!(
+                "\n{}: {} out, minimum {} (price impact {}%, router {})",
+                quote.venue,
+                output,
+                quote
+                    .minimum_output
+                    .map_or("N/A".to_string(), |minimum| minimum.to_string()),
+                impact,
+                quote.router
+            )),
+            (_, _, Some(error)) => text.push_str(&format!("\n{}: failed: {}", quote.venue, error)),
+            _ => text.push_str(&format!("\n{}: no quote", quote.venue)),
+        }
+        for warning in &quote.warnings {
+            text.push_str(&format!("\n{} warning: {}", quote.venue, warning));
+        }
+    }
+
+    let summary = format!(
+        "Best venue: {}{}",
+        response.best_venue,
+        response
+            .spread_pct
+            .map(|spread| format!(" ({}% more output)", spread))
+            .unwrap_or_default()
+    );
+    Ok(ToolResult::blocks(summary, text, &response)?)
+}
+
+fn venue_response(quote: &VenueQuote) -> VenueQuoteResponse {
+    let simulation = quote.simulation.as_ref().ok();
+    VenueQuoteResponse {
+        venue: quote.venue.to_string(),
+        router: format!("{:?}", quote.router),
+        estimated_output: simulation.map(|s| Amount::new(s.estimated_output)),
+        minimum_output: simulation.map(|s| Amount::new(s.minimum_output)),
+        price_impact: simulation.map(|s| Amount::with_decimals(s.price_impact, 4)),
+        estimated_gas: simulation.map(|s| s.estimated_gas.to_string()),
+        route: simulation.map(|s| s.route.iter().map(|addr| format!("{:?}", addr)).collect()),
+        warnings: simulation.map(|s| s.warnings.clone()).unwrap_or_default(),
+        error: quote.simulation.as_ref().err().map(|e| format!("{:#}", e)),
+    }
+}
@@ -0,0 +1,248 @@
+use anyhow::{Context, Result};
+use ethers::prelude::*;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinSet;
+
+use crate::config::Config;
+use crate::ethereum::{
+    get_coin_price_usd, get_token_price, get_transfer_cost, native_currency, ChainInfo,
+    ChainProviders, EthClient, PriceSource, TransferCost, TRANSFER_GAS,
+};
+use crate::types::{Amount, Tool, ToolResult};
+
+/// Longest wait for one chain's RPC before it is reported unavailable
+const CHAIN_QUERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Serialize)]
+pub struct ChainGasResponse {
+    pub chain_id: u64,
+    pub chain_name: Option<String>,
+    /// False when the chain's RPC failed or timed out; `error` says why
+    pub available: bool,
+    pub native_symbol: String,
+    pub gas_price_gwei: Option<Amount>,
+    /// Cost of a plain transfer, in the native token
+    pub transfer_cost: Option<Amount>,
+    /// OP-stack L1 data fee included in `transfer_cost`
+    pub l1_data_fee: Option<Amount>,
+    pub native_price_usd: Option<Amount>,
+    pub transfer_cost_usd: Option<Amount>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CompareGasResponse {
+    pub transfer_gas: u64,
+    /// The `ETH_RPC_URL` chain first, then the `RPC_URLS` chains by ID
+    pub chains: Vec<ChainGasResponse>,
+    /// Chain with the lowest USD transfer cost among those priced
+    pub cheapest_chain_id: Option<u64>,
+}
+
+pub fn get_tool_definition() -> Tool {
+    Tool {
+        name: "compare_gas_across_chains".to_string(),
+        description: "Compare current gas prices across every configured chain (ETH_RPC_URL and RPC_URLS), with the cost of a plain 21000-gas transfer in the native token and in USD. Chains whose RPC is down are listed as unavailable. Use to decide which chain to trade on."
+            .to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {}
+        }),
+    }
+}
+
+pub async fn execute(
+    provider: &EthClient,
+    chain_info: &ChainInfo,
+    chain_providers: &Arc<ChainProviders>,
+    http: &reqwest::Client,
+    config: &Config,
+) -> Result<ToolResult> {
+    let mut chain_ids = vec![chain_info.chain_id];
+    chain_ids.extend(
+        chain_providers
+            .chain_ids()
+            .into_iter()
+            .filter(|chain_id| *chain_id != chain_info.chain_id),
+    );
+
+    // A chain whose RPC is down is reported, not fatal, and doesn't hold up the others
+    let mut tasks = JoinSet::new();
+    for (index, chain_id) in chain_ids.iter().copied().enumerate() {
+        let primary = (index == 0).then(|| (provider.clone(), chain_info.chain_name.clone()));
+        let chain_providers = chain_providers.clone();
+        tasks.spawn(async move {
+            let query = async {
+                let (provider, chain_name) = match primary {
+                    Some(primary) => primary,
+                    None => {
+                        let client = chain_providers.get(chain_id).await?;
+                        (client.provider, client.info.chain_name)
+                    }
+                };
+                let cost = get_transfer_cost(&provider, chain_id).await?;
+                Ok::<_, anyhow::Error>((chain_name, cost))
+            };
+            let result = tokio::time::timeout(CHAIN_QUERY_TIMEOUT, query)
+                .await
+                .unwrap_or_else(|_| {
+                    Err(anyhow::anyhow!(
+                        "RPC did not answer within {}s",
+                        CHAIN_QUERY_TIMEOUT.as_secs()
+                    ))
+                });
+            (index, chain_id, result)
+        });
+    }
+
+    let mut results = Vec::with_capacity(chain_ids.len());
+    while let Some(joined) = tasks.join_next().await {
+        results.push(joined.context("Gas lookup task failed")?);
+    }
+    results.sort_by_key(|(index, _, _)| *index);
+
+    // One price per native token, only for chains that answered
+    let mut prices: HashMap<&str, Result<Decimal, String>> = HashMap::new();
+    for (_, chain_id, result) in &results {
+        let coin_id = native_currency(*chain_id).1;
+        if result.is_ok() && !prices.contains_key(coin_id) {
+            let price = native_price_usd(provider, chain_info, http, config, coin_id).await;
+            prices.insert(coin_id, price.map_err(|e| format!("{:#}", e)));
+        }
+    }
+
+    let chains: Vec<ChainGasResponse> = results
+        .into_iter()
+        .map(|(_, chain_id, result)| {
+            let (symbol, coin_id) = native_currency(chain_id);
+            match result {
+                Ok((chain_name, cost)) => {
+                    to_response(chain_id, chain_name, symbol, cost, &prices[coin_id])
+                }
+                Err(e) => ChainGasResponse {
+                    chain_id,
+                    chain_name: None,
+                    available: false,
+                    native_symbol: symbol.to_string(),
+                    gas_price_gwei: None,
+                    transfer_cost: None,
+                    l1_data_fee: None,
+                    native_price_usd: None,
+                    transfer_cost_usd: None,
+                    error: Some(format!("{:#}", e)),
+                },
+            }
+        })
+        .collect();
+
+    let cheapest = chains
+        .iter()
+        .filter_map(|chain| Some((chain.chain_id, chain.transfer_cost_usd?)))
+        .min_by_key(|(_, cost_usd)| cost_usd.value());
+    let response = CompareGasResponse {
+        transfer_gas: TRANSFER_GAS,
+        cheapest_chain_id: cheapest.map(|(chain_id, _)| chain_id),
+        chains,
+    };
+
+    let mut text = format!("Transfer Gas: {}", response.transfer_gas);
+    for chain in &response.chains {
+        let label = match &chain.chain_name {
+            Some(name) => format!("{} ({})", name, chain.chain_id),
+            None => format!("Chain ID {}", chain.chain_id),
+        };
+        match (&chain.gas_price_gwei, &chain.transfer_cost) {
+            (Some(gas_price), Some(cost)) => {
+                text.push_str(&format!(
+                    "\n{}: {} gwei, transfer {} {}",
+                    label, gas_price, cost, chain.native_symbol
+                ));
+                match &chain.transfer_cost_usd {
+                    Some(cost_usd) => text.push_str(&format!(" (${})", cost_usd)),
+                    None => text.push_str(&format!(
+                        " (USD N/A: {})",
+                        chain.error.as_deref().unwrap_or("no price")
+                    )),
+                }
+            }
+            _ => text.push_str(&format!(
+                "\n{}: unavailable ({})",
+                label,
+                chain.error.as_deref().unwrap_or("no response")
+            )),
+        }
+    }
+
+    let unavailable = response.chains.iter().filter(|c| !c.available).count();
+    let mut summary = match cheapest {
+        Some((chain_id, cost_usd)) => {
+            format!("Cheapest transfer: chain ID {} at ${}", chain_id, cost_usd)
+        }
+        None => "No chain's transfer cost could be priced in USD".to_string(),
+    };
+    if unavailable > 0 {
+        summary.push_str(&format!(
+            "; {} of {} chains unavailable",
+            unavailable,
+            response.chains.len()
+        ));
+    }
+    Ok(ToolResult::blocks(summary, text, &response)?)
+}
+
+fn to_response(
+    chain_id: u64,
+    chain_name: String,
+    symbol: &str,
+    cost: TransferCost,
+    price: &Result<Decimal, String>,
+) -> ChainGasResponse {
+    let (native_price_usd, error) = match price {
+        Ok(price) => (Some(*price), None),
+        Err(e) => (None, Some(format!("{} price unavailable: {}", symbol, e))),
+    };
+    ChainGasResponse {
+        chain_id,
+        chain_name: Some(chain_name),
+        available: true,
+        native_symbol: symbol.to_string(),
+        gas_price_gwei: Some(Amount::new(cost.gas_price.normalize())),
+        transfer_cost: Some(Amount::new(cost.cost.normalize())),
+        l1_data_fee: cost.l1_data_fee.map(|fee| Amount::new(fee.normalize())),
+        native_price_usd: native_price_usd.map(Amount::new),
+        transfer_cost_usd: native_price_usd
+            .map(|price| Amount::with_decimals(cost.cost * price, 4)),
+        error,
+    }
+}
+
+/// USD price of a chain's native token; ETH goes through the configured price sources on mainnet
+async fn native_price_usd(
+    provider: &EthClient,
+    chain_info: &ChainInfo,
+    http: &reqwest::Client,
+    config: &Config,
+    coin_id: &str,
+) -> Result<Decimal> {
+    if coin_id != "ethereum" || !chain_info.has_known_addresses() {
+        return get_coin_price_usd(&config.coingecko(http), coin_id).await;
+    }
+
+    let price = get_token_price(
+        provider,
+        &config.price_oracles(http),
+        Address::zero(),
+        PriceSource::Auto,
+    )
+    .await?;
+    // The hardcoded fallback ETH price would report a made-up cost
+    if price.fallback {
+        anyhow::bail!("only the fallback ETH price is available");
+    }
+    price.price_usd.context("no USD price for ETH")
+}
@@ -0,0 +1,160 @@
+use anyhow::Result;
+use ethers::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::ethereum::{decode_calldata, known_contract, token_to_json, DecodedCall, TokenRegistry};
+use crate::types::{Tool, ToolResult};
+
+#[derive(Debug, Deserialize)]
+pub struct DecodeCalldataParams {
+    /// Address the transaction is sent to
+    pub to: String,
+    /// Transaction input data as hex, selector included
+    pub data: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DecodedArgResponse {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub value: Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DecodedCallResponse {
+    pub selector: String,
+    pub function: Option<String>,
+    pub abi: Option<String>,
+    pub args: Vec<DecodedArgResponse>,
+    pub inner_calls: Vec<DecodedCallResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DecodeCalldataResponse {
+    pub to: String,
+    /// Known router or registry token at `to`
+    pub contract: Option<String>,
+    pub recognized: bool,
+    pub call: DecodedCallResponse,
+    pub warnings: Vec<String>,
+}
+
+pub fn get_tool_definition() -> Tool {
+    Tool {
+        name: "decode_calldata".to_string(),
+        description: "Decode raw transaction calldata against known ABIs (ERC20, ERC721 approvals, WETH, Uniswap V2/V3 routers and their multicalls) into the function name and arguments, and flag unlimited approvals. Unknown selectors are reported as unrecognized. Use before signing a transaction to see what it actually does."
+            .to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "to": {
+                    "type": "string",
+                    "description": "Address the transaction is sent to (0x...)"
+                },
+                "data": {
+                    "type": "string",
+                    "description": "Transaction input data as hex, starting with the 4-byte function selector (0x...)"
+                }
+            },
+            "required": ["to", "data"]
+        }),
+    }
+}
+
+fn call_response(call: &DecodedCall) -> DecodedCallResponse {
+    DecodedCallResponse {
+        selector: call.selector.clone(),
+        function: call.function.clone(),
+        abi: call.abi.map(str::to_string),
+        args: call
+            .args
+            .iter()
+            .map(|arg| DecodedArgResponse {
+                name: arg.name.clone(),
+                kind: arg.kind.clone(),
+                value: token_to_json(&arg.token),
+            })
+            .collect(),
+        inner_calls: call.inner_calls.iter().map(call_response).collect(),
+    }
+}
+
+/// Append `call` to `text`, indenting inner calls under their multicall
+fn describe_call(text: &mut String, call: &DecodedCallResponse, indent: usize) {
+    let pad = "  ".repeat(indent);
+    match (&call.function, &call.abi) {
+        (Some(function), Some(abi)) => {
+            text.push_str(&format!("\n{}Function: {} [{}]", pad, function, abi))
+        }
+        _ if call.selector.is_empty() => {
+            text.push_str(&format!("\n{}No calldata: a plain ETH transfer", pad))
+        }
+        _ => text.push_str(&format!("\n{}Unrecognized selector {}", pad, call.selector)),
+    }
+    for (i, arg) in call.args.iter().enumerate() {
+        let label = if arg.name.is_empty() {
+            format!("[{}]", i)
+        } else {
+            arg.name.clone()
+        };
+        let value = match &arg.value {
+            Value::String(value) => value.clone(),
+            value => value.to_string(),
+        };
+        text.push_str(&format!("\n{}  {} ({}): {}", pad, label, arg.kind, value));
+    }
+    for inner in &call.inner_calls {
+        describe_call(text, inner, indent + 1);
+    }
+}
+
+pub fn execute(registry: &TokenRegistry, params: DecodeCalldataParams) -> Result<ToolResult> {
+    let to = params
+        .to
+        .parse::<Address>()
+        .map_err(|e| anyhow::anyhow!("Invalid to address: {}", e))?;
+    let data = params
+        .data
+        .parse::<Bytes>()
+        .map_err(|e| anyhow::anyhow!("Invalid data hex: {}", e))?;
+
+    let decoded = decode_calldata(&data)?;
+    let contract = known_contract(to).map(str::to_string).or_else(|| {
+        registry
+            .find_by_address(to)
+            .map(|token| token.symbol.clone())
+    });
+    let response = DecodeCalldataResponse {
+        to: format!("{:?}", to),
+        contract,
+        recognized: decoded.function.is_some() || decoded.selector.is_empty(),
+        call: call_response(&decoded),
+        warnings: decoded.warnings.clone(),
+    };
+
+    let mut text = match &response.contract {
+        Some(contract) => format!("To: {} ({})", response.to, contract),
+        None => format!("To: {}", response.to),
+    };
+    describe_call(&mut text, &response.call, 0);
+    if !response.recognized {
+        text.push_str(
+            "\nThe selector is not in the known ABIs; look it up in a signature database before signing",
+        );
+    }
+    for warning in &response.warnings {
+        text.push_str(&format!("\nWarning: {}", warning));
+    }
+
+    let summary = match (&response.call.function, response.recognized) {
+        (Some(function), _) => format!("Calls {} on {}", function, response.to),
+        (None, true) => format!("Plain ETH transfer to {}", response.to),
+        (None, false) => format!(
+            "Unrecognized selector {} on {}",
+            response.call.selector, response.to
+        ),
+    };
+    Ok(ToolResult::blocks(summary, text, &response)?)
+}
@@ -0,0 +1,58 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::ethereum::{normalize_name, resolve_ens_name, EthClient};
+use crate::types::{Tool, ToolResult};
+
+#[derive(Debug, Deserialize)]
+pub struct EnsResolveParams {
+    pub name: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EnsResolveResponse {
+    pub name: String,
+    /// Null when the name has no resolver or address record
+    pub address: Option<String>,
+}
+
+pub fn get_tool_definition() -> Tool {
+    Tool {
+        name: "ens_resolve".to_string(),
+        description: "Resolve an ENS name (e.g. vitalik.eth) to its Ethereum address. A name without an address record returns a null address rather than an error."
+            .to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "ENS name to resolve, e.g. vitalik.eth"
+                }
+            },
+            "required": ["name"]
+        }),
+    }
+}
+
+pub async fn execute(provider: &EthClient, params: EnsResolveParams) -> Result<ToolResult> {
+    let name = normalize_name(&params.name)?;
+    let address = resolve_ens_name(provider, &name).await?;
+
+    let response = EnsResolveResponse {
+        name,
+        address: address.map(|address| format!("{:?}", address)),
+    };
+
+    let summary = match &response.address {
+        Some(address) => format!("{} resolves to {}", response.name, address),
+        None => format!("{} has no address record", response.name),
+    };
+    let text = format!(
+        "Name: {}\nAddress: {}",
+        response.name,
+        response.address.as_deref().unwrap_or("none")
+    );
+
+    Ok(ToolResult::blocks(summary, text, &response)?)
+}
@@ -0,0 +1,62 @@
+use anyhow::Result;
+use ethers::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::ethereum::{lookup_ens_name, EthClient};
+use crate::types::{Tool, ToolResult};
+
+#[derive(Debug, Deserialize)]
+pub struct EnsReverseParams {
+    pub address: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EnsReverseResponse {
+    pub address: String,
+    /// Null when the address has no primary name, or it doesn't resolve back to the address
+    pub name: Option<String>,
+}
+
+pub fn get_tool_definition() -> Tool {
+    Tool {
+        name: "ens_reverse".to_string(),
+        description: "Look up the primary ENS name of an Ethereum address, for labeling addresses. The name is only returned when it resolves back to the same address; otherwise the name is null."
+            .to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "address": {
+                    "type": "string",
+                    "description": "Address to look up (0x...)"
+                }
+            },
+            "required": ["address"]
+        }),
+    }
+}
+
+pub async fn execute(provider: &EthClient, params: EnsReverseParams) -> Result<ToolResult> {
+    let address = params
+        .address
+        .parse::<Address>()
+        .map_err(|e| anyhow::anyhow!("Invalid address: {}", e))?;
+    let name = lookup_ens_name(provider, address).await?;
+
+    let response = EnsReverseResponse {
+        address: format!("{:?}", address),
+        name,
+    };
+
+    let summary = match &response.name {
+        Some(name) => format!("{} is {}", response.address, name),
+        None => format!("{} has no primary ENS name", response.address),
+    };
+    let text = format!(
+        "Address: {}\nPrimary Name: {}",
+        response.address,
+        response.name.as_deref().unwrap_or("none")
+    );
+
+    Ok(ToolResult::blocks(summary, text, &response)?)
+}
@@ -0,0 +1,94 @@
+use anyhow::Result;
+use ethers::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::ethereum::{get_twap, EthClient};
+use crate::types::{Tool, ToolResult};
+
+#[derive(Debug, Deserialize)]
+pub struct GetAveragePriceParams {
+    pub pool_address: String,
+    #[serde(default = "default_window")]
+    pub window_secs: u32,
+}
+
+fn default_window() -> u32 {
+    1800 // 30 minutes
+}
+
+#[derive(Debug, Serialize)]
+pub struct AveragePriceResponse {
+    pub pool_address: String,
+    pub token0: String,
+    pub token1: String,
+    pub window_secs: u32,
+    pub twap: String,
+    pub spot: String,
+    pub deviation_pct: Option<String>,
+}
+
+pub fn get_tool_definition() -> Tool {
+    Tool {
+        name: "get_average_price".to_string(),
+        description: "Get a manipulation-resistant time-weighted average price (TWAP) from a Uniswap V3 pool oracle, alongside the current spot price"
+            .to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "pool_address": {
+                    "type": "string",
+                    "description": "Uniswap V3 pool address (0x...)"
+                },
+                "window_secs": {
+                    "type": "number",
+                    "description": "Averaging window in seconds. Default: 1800 (30 minutes)",
+                    "default": 1800
+                }
+            },
+            "required": ["pool_address"]
+        }),
+    }
+}
+
+pub async fn execute(provider: &EthClient, params: GetAveragePriceParams) -> Result<ToolResult> {
+    let pool_address = params
+        .pool_address
+        .parse::<Address>()
+        .map_err(|e| anyhow::anyhow!("Invalid pool address: {}", e))?;
+
+    let twap = get_twap(provider, pool_address, params.window_secs).await?;
+
+    let deviation_pct = if twap.twap.is_zero() {
+        None
+    } else {
+        Some(((twap.spot - twap.twap) / twap.twap * rust_decimal::Decimal::from(100)).round_dp(4))
+    };
+
+    let response = AveragePriceResponse {
+        pool_address: params.pool_address,
+        token0: format!("{:?}", twap.token0),
+        token1: format!("{:?}", twap.token1),
+        window_secs: twap.window_secs,
+        twap: twap.twap.to_string(),
+        spot: twap.spot.to_string(),
+        deviation_pct: deviation_pct.map(|d| d.to_string()),
+    };
+
+    let text = format!(
+        "Pool: {}\nPrice of token0 ({}) in token1 ({})\nTWAP ({}s): {}\nSpot: {}\nSpot vs TWAP: {}%",
+        response.pool_address,
+        response.token0,
+        response.token1,
+        response.window_secs,
+        response.twap,
+        response.spot,
+        response.deviation_pct.as_deref().unwrap_or("N/A")
+    );
+
+    let summary = format!(
+        "TWAP over {}s: {} (spot {})",
+        response.window_secs, response.twap, response.spot
+    );
+    Ok(ToolResult::blocks(summary, text, &response)?)
+}
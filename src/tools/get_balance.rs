@@ -1,30 +1,48 @@
 use anyhow::Result;
 use ethers::prelude::*;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
-use crate::ethereum::{get_eth_balance, get_token_balance, EthClient};
-use crate::types::{Tool, ToolContent, ToolResult};
+use crate::config::Config;
+use crate::ethereum::{
+    display_amount, get_eth_balance, get_token_balance, get_token_price, is_tiny_amount,
+    normalize_eth, EthClient, MetadataCache, PriceSource, QuoteCurrency, TokenRegistry,
+};
+use crate::types::{Amount, Tool, ToolResult};
 
 #[derive(Debug, Deserialize)]
 pub struct GetBalanceParams {
     pub wallet_address: String,
     pub token_address: Option<String>,
+    pub token_symbol: Option<String>,
+    /// Also value the balance in "USD" or "ETH"
+    pub quote_currency: Option<String>,
+    /// Re-read the token's decimals and symbol instead of using cached values
+    #[serde(default)]
+    pub refresh: bool,
 }
 
 #[derive(Debug, Serialize)]
 pub struct BalanceResponse {
-    pub balance: String,
+    pub balance: Amount,
     pub symbol: String,
     pub decimals: u8,
     pub wallet_address: String,
     pub token_address: Option<String>,
+    pub underlying_balance: Option<Amount>,
+    pub underlying_symbol: Option<String>,
+    pub underlying_asset: Option<String>,
+    pub quote_currency: Option<String>,
+    /// Balance value in `quote_currency`; null when the token couldn't be priced
+    pub value: Option<Amount>,
+    pub value_note: Option<String>,
 }
 
 pub fn get_tool_definition() -> Tool {
     Tool {
         name: "get_balance".to_string(),
-        description: "Query ETH or ERC20 token balance for a wallet address".to_string(),
+        description: "Query ETH or ERC20 token balance for a wallet address. ERC-4626 vault shares also report their underlying asset value".to_string(),
         input_schema: json!({
             "type": "object",
             "properties": {
@@ -35,6 +53,20 @@ pub fn get_tool_definition() -> Tool {
                 "token_address": {
                     "type": "string",
                     "description": "Optional ERC20 token contract address. If not provided, returns ETH balance."
+                },
+                "token_symbol": {
+                    "type": "string",
+                    "description": "Optional token symbol (e.g. USDC) resolved via the built-in token registry. Use instead of token_address."
+                },
+                "quote_currency": {
+                    "type": "string",
+                    "enum": ["USD", "ETH"],
+                    "description": "Optional currency to also value the balance in, using the current token price. Mainnet only."
+                },
+                "refresh": {
+                    "type": "boolean",
+                    "description": "Re-read the token's decimals and symbol from the chain instead of using cached metadata. Default: false",
+                    "default": false
                 }
             },
             "required": ["wallet_address"]
@@ -42,41 +74,164 @@ pub fn get_tool_definition() -> Tool {
     }
 }
 
-pub async fn execute(provider: &EthClient, params: GetBalanceParams) -> Result<ToolResult> {
+pub async fn execute(
+    provider: &EthClient,
+    http: &reqwest::Client,
+    cache: &MetadataCache,
+    registry: &TokenRegistry,
+    config: &Config,
+    params: GetBalanceParams,
+) -> Result<ToolResult> {
     let wallet_address = params
         .wallet_address
         .parse::<Address>()
         .map_err(|e| anyhow::anyhow!("Invalid wallet address: {}", e))?;
 
-    let balance_info = if let Some(token_addr_str) = &params.token_address {
-        let token_address = token_addr_str
-            .parse::<Address>()
-            .map_err(|e| anyhow::anyhow!("Invalid token address: {}", e))?;
+    let quote_currency = params
+        .quote_currency
+        .as_deref()
+        .map(str::parse::<QuoteCurrency>)
+        .transpose()?;
 
-        get_token_balance(provider, token_address, wallet_address).await?
+    let token_address = match (&params.token_address, &params.token_symbol) {
+        (Some(_), Some(_)) => {
+            return Err(anyhow::anyhow!(
+                "Provide either token_address or token_symbol, not both"
+            ))
+        }
+        (Some(token_addr_str), None) => Some(
+            token_addr_str
+                .parse::<Address>()
+                .map(normalize_eth)
+                .map_err(|e| anyhow::anyhow!("Invalid token address: {}", e))?,
+        ),
+        // Native ETH is not an ERC20, so it bypasses the registry
+        (None, Some(symbol)) if symbol.eq_ignore_ascii_case("ETH") => None,
+        (None, Some(symbol)) => Some(registry.resolve_symbol(symbol)?),
+        (None, None) => None,
+    };
+    // Either native-ETH sentinel asks for the ETH balance
+    let token_address = token_address.filter(|token| !token.is_zero());
+
+    let balance_info = if let Some(token_address) = token_address {
+        if params.refresh {
+            cache.invalidate(token_address);
+        }
+        get_token_balance(provider, cache, token_address, wallet_address).await?
     } else {
         get_eth_balance(provider, wallet_address).await?
     };
 
+    let (value, value_note) = match quote_currency {
+        Some(currency) => {
+            let token = token_address.unwrap_or_else(Address::zero);
+            let value = quote_balance(
+                provider,
+                http,
+                config,
+                token,
+                balance_info.balance,
+                currency,
+            )
+            .await;
+            match value {
+                Ok(value) => (Some(Amount::new(value.round_dp(8).normalize())), None),
+                Err(note) => (None, Some(note)),
+            }
+        }
+        None => (None, None),
+    };
+
+    let underlying = balance_info.underlying.as_ref();
     let response = BalanceResponse {
-        balance: balance_info.balance.to_string(),
+        balance: Amount::with_decimals(balance_info.balance, balance_info.decimals.into()),
         symbol: balance_info.symbol,
         decimals: balance_info.decimals,
         wallet_address: params.wallet_address,
-        token_address: params.token_address,
+        token_address: token_address.map(|addr| format!("{:?}", addr)),
+        underlying_balance: underlying.map(|u| Amount::new(u.amount)),
+        underlying_symbol: underlying.map(|u| u.symbol.clone()),
+        underlying_asset: underlying.map(|u| format!("{:?}", u.asset)),
+        quote_currency: quote_currency.map(|currency| currency.symbol().to_string()),
+        value,
+        value_note,
+    };
+
+    // Dust reads as scientific notation plus the exact base-unit count; `balance` keeps full precision
+    let shown_balance = if is_tiny_amount(balance_info.balance) {
+        format!(
+            "{} {} ({} base units)",
+            display_amount(balance_info.balance),
+            response.symbol,
+            balance_info.raw_balance
+        )
+    } else {
+        format!("{} {}", response.balance, response.symbol)
     };
 
-    let text = format!(
-        "Balance: {} {}\nDecimals: {}\nWallet: {}\nRaw balance: {}",
-        response.balance,
-        response.symbol,
-        response.decimals,
-        response.wallet_address,
-        balance_info.raw_balance
+    let mut text = format!(
+        "Balance: {}\nDecimals: {}\nWallet: {}\nRaw balance: {}",
+        shown_balance, response.decimals, response.wallet_address, balance_info.raw_balance
     );
+    if let (Some(amount), Some(symbol), Some(asset)) = (
+        &response.underlying_balance,
+        &response.underlying_symbol,
+        &response.underlying_asset,
+    ) {
+        text.push_str(&format!(
+            "\nUnderlying (ERC-4626): {} {} ({})",
+            amount, symbol, asset
+        ));
+    }
+    if let Some(currency) = &response.quote_currency {
+        match (&response.value, &response.value_note) {
+            (Some(value), _) => text.push_str(&format!("\nValue: {} {}", value, currency)),
+            (None, note) => text.push_str(&format!(
+                "\nValue: N/A ({})",
+                note.as_deref().unwrap_or("no price")
+            )),
+        }
+    }
+
+    let summary = format!("Balance: {}", shown_balance);
+    Ok(ToolResult::blocks(summary, text, &response)?)
+}
+
+/// Value a balance in `currency`, or say why the token can't be priced
+async fn quote_balance(
+    provider: &EthClient,
+    http: &reqwest::Client,
+    config: &Config,
+    token: Address,
+    balance: Decimal,
+    currency: QuoteCurrency,
+) -> Result<Decimal, String> {
+    // ETH needs no lookup to be valued in ETH
+    if token == Address::zero() && currency == QuoteCurrency::Eth {
+        return Ok(balance);
+    }
+
+    let price = get_token_price(
+        provider,
+        &config.price_oracles(http),
+        token,
+        PriceSource::Auto,
+    )
+    .await
+    .map_err(|e| format!("price unavailable: {}", e))?;
+    // The hardcoded fallback ETH price would report a made-up value
+    if price.fallback {
+        return Err("only the fallback ETH price is available".to_string());
+    }
 
-    Ok(ToolResult {
-        content: vec![ToolContent::text(text)],
-        is_error: None,
-    })
+    currency
+        .unit_price(&price)
+        .map(|unit_price| balance * unit_price)
+        .ok_or_else(|| {
+            format!(
+                "{} has no {} price for this token",
+                price.source,
+                currency.symbol()
+            )
+        })
 }
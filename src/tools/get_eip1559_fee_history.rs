@@ -0,0 +1,129 @@
+use anyhow::Result;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::ethereum::{get_fee_history, EthClient, MAX_FEE_HISTORY_BLOCKS};
+use crate::types::{Tool, ToolResult};
+
+#[derive(Debug, Deserialize)]
+pub struct GetFeeHistoryParams {
+    #[serde(default = "default_block_count")]
+    pub block_count: u64,
+    /// Priority-fee percentiles to report per block, ascending
+    #[serde(default = "default_percentiles")]
+    pub percentiles: Vec<f64>,
+}
+
+fn default_block_count() -> u64 {
+    20
+}
+
+fn default_percentiles() -> Vec<f64> {
+    vec![10.0, 50.0, 90.0]
+}
+
+#[derive(Debug, Serialize)]
+pub struct FeeHistoryResponse {
+    pub oldest_block: Option<u64>,
+    pub newest_block: Option<u64>,
+    pub percentiles: Vec<f64>,
+    pub blocks: Vec<FeeHistoryBlockResponse>,
+    pub next_base_fee_gwei: String,
+    pub suggested_max_fee_per_gas_gwei: String,
+    pub suggested_max_priority_fee_per_gas_gwei: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FeeHistoryBlockResponse {
+    pub block_number: u64,
+    pub base_fee_gwei: String,
+    pub gas_used_ratio: f64,
+    /// Priority fee at each requested percentile, in the same order
+    pub priority_fees_gwei: Vec<String>,
+}
+
+pub fn get_tool_definition() -> Tool {
+    Tool {
+        name: "get_eip1559_fee_history".to_string(),
+        description: "Get raw EIP-1559 fee history (eth_feeHistory) for recent blocks: each block's base fee, how full it was, and the priority fees paid at the requested percentiles, plus a suggested maxFeePerGas and maxPriorityFeePerGas. For building custom gas bidding strategies."
+            .to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "block_count": {
+                    "type": "integer",
+                    "description": format!("Number of most recent blocks to cover (1-{}). Default: 20", MAX_FEE_HISTORY_BLOCKS),
+                    "minimum": 1,
+                    "maximum": MAX_FEE_HISTORY_BLOCKS,
+                    "default": 20
+                },
+                "percentiles": {
+                    "type": "array",
+                    "items": {"type": "number", "minimum": 0, "maximum": 100},
+                    "description": "Priority-fee percentiles (0-100, ascending) to report per block, weighted by gas used. The suggested priority fee is the median across blocks of the highest one. Default: [10, 50, 90]",
+                    "default": [10, 50, 90]
+                }
+            }
+        }),
+    }
+}
+
+pub async fn execute(provider: &EthClient, params: GetFeeHistoryParams) -> Result<ToolResult> {
+    let history = get_fee_history(provider, params.block_count, &params.percentiles).await?;
+
+    let gwei = |value: Decimal| value.round_dp(4).to_string();
+    let response = FeeHistoryResponse {
+        oldest_block: history.blocks.first().map(|block| block.number),
+        newest_block: history.blocks.last().map(|block| block.number),
+        percentiles: params.percentiles,
+        blocks: history
+            .blocks
+            .iter()
+            .map(|block| FeeHistoryBlockResponse {
+                block_number: block.number,
+                base_fee_gwei: gwei(block.base_fee),
+                gas_used_ratio: block.gas_used_ratio,
+                priority_fees_gwei: block.priority_fees.iter().copied().map(gwei).collect(),
+            })
+            .collect(),
+        next_base_fee_gwei: gwei(history.next_base_fee),
+        suggested_max_fee_per_gas_gwei: gwei(history.suggested_max_fee_per_gas),
+        suggested_max_priority_fee_per_gas_gwei: gwei(history.suggested_max_priority_fee_per_gas),
+    };
+
+    let percentile_labels = response
+        .percentiles
+        .iter()
+        .map(|p| format!("p{}", p))
+        .collect::<Vec<_>>()
+        .join("/");
+    let mut text = format!(
+        "EIP-1559 Fee History ({} blocks):\n\
+        Next Base Fee: {} gwei\n\
+        Suggested Max Fee: {} gwei\n\
+        Suggested Max Priority Fee: {} gwei",
+        response.blocks.len(),
+        response.next_base_fee_gwei,
+        response.suggested_max_fee_per_gas_gwei,
+        response.suggested_max_priority_fee_per_gas_gwei
+    );
+    for block in &response.blocks {
+        text.push_str(&format!(
+            "\nBlock {}: base fee {} gwei, {:.1}% full, priority {}: {} gwei",
+            block.block_number,
+            block.base_fee_gwei,
+            block.gas_used_ratio * 100.0,
+            percentile_labels,
+            block.priority_fees_gwei.join(" / ")
+        ));
+    }
+
+    let summary = format!(
+        "Next base fee {} gwei; suggested max fee {} gwei, priority fee {} gwei",
+        response.next_base_fee_gwei,
+        response.suggested_max_fee_per_gas_gwei,
+        response.suggested_max_priority_fee_per_gas_gwei
+    );
+    Ok(ToolResult::blocks(summary, text, &response)?)
+}
@@ -0,0 +1,77 @@
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::ethereum::{get_eth_price_from_chainlink, EthClient};
+use crate::types::{Tool, ToolResult};
+
+/// Chainlink's ETH/USD feed updates at least hourly; older answers are stale
+const STALE_AFTER_SECS: u64 = 3600;
+
+#[derive(Debug, Serialize)]
+pub struct EthPriceResponse {
+    pub price_usd: Option<String>,
+    pub source: String,
+    pub updated_at: Option<u64>,
+    pub age_secs: Option<u64>,
+    pub stale: bool,
+    pub warnings: Vec<String>,
+}
+
+pub fn get_tool_definition() -> Tool {
+    Tool {
+        name: "get_eth_price".to_string(),
+        description: "Get the current ETH/USD price from the Chainlink oracle, with the feed's last update time and staleness"
+            .to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {}
+        }),
+    }
+}
+
+pub async fn execute(provider: &EthClient) -> Result<ToolResult> {
+    let price_info = get_eth_price_from_chainlink(provider).await?;
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_secs();
+    let age_secs = price_info
+        .updated_at
+        .map(|updated_at| now.saturating_sub(updated_at));
+
+    let response = EthPriceResponse {
+        price_usd: price_info.price_usd.map(|p| p.to_string()),
+        source: price_info.source,
+        updated_at: price_info.updated_at,
+        age_secs,
+        // Without a round timestamp (fallback price) freshness can't be vouched for
+        stale: age_secs.is_none_or(|age| age > STALE_AFTER_SECS),
+        warnings: price_info.warnings,
+    };
+
+    let mut text = format!(
+        "ETH Price (USD): {}\nSource: {}",
+        response.price_usd.as_deref().unwrap_or("N/A"),
+        response.source
+    );
+    match (response.updated_at, response.age_secs) {
+        (Some(updated_at), Some(age)) => {
+            text.push_str(&format!("\nUpdated At: {} ({}s ago)", updated_at, age))
+        }
+        _ => text.push_str("\nUpdated At: N/A"),
+    }
+    if response.stale {
+        text.push_str("\nWarning: price is stale or not from a live oracle round");
+    }
+    for warning in &response.warnings {
+        text.push_str(&format!("\nWarning: {}", warning));
+    }
+
+    let summary = format!(
+        "ETH: ${} ({})",
+        response.price_usd.as_deref().unwrap_or("N/A"),
+        response.source
+    );
+    Ok(ToolResult::blocks(summary, text, &response)?)
+}
@@ -0,0 +1,128 @@
+use anyhow::Result;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::config::Config;
+use crate::ethereum::{get_gas_estimate, EthClient, GasEstimate, GasOracleKeys, GasSource};
+use crate::types::{Tool, ToolResult};
+
+#[derive(Debug, Deserialize)]
+pub struct GetGasPriceParams {
+    #[serde(default = "default_gas_source")]
+    pub gas_source: String,
+}
+
+fn default_gas_source() -> String {
+    "node".to_string()
+}
+
+#[derive(Debug, Serialize)]
+pub struct GasEstimateResponse {
+    pub source: String,
+    pub gas_price_gwei: Option<String>,
+    pub base_fee_gwei: Option<String>,
+    pub max_fee_per_gas_gwei: Option<String>,
+    pub max_priority_fee_per_gas_gwei: Option<String>,
+    pub safe_gwei: Option<String>,
+    pub fast_gwei: Option<String>,
+    pub error: Option<String>,
+}
+
+pub fn get_tool_definition() -> Tool {
+    Tool {
+        name: "get_gas_price".to_string(),
+        description: "Get current gas prices in gwei from the node (eth_gasPrice / fee history) or external oracles (Etherscan, Blocknative)"
+            .to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "gas_source": {
+                    "type": "string",
+                    "enum": ["node", "etherscan", "blocknative", "all"],
+                    "description": "Where to get gas prices from. 'all' returns every configured source side by side. Default: node",
+                    "default": "node"
+                }
+            }
+        }),
+    }
+}
+
+pub async fn execute(
+    provider: &EthClient,
+    http: &reqwest::Client,
+    config: &Config,
+    params: GetGasPriceParams,
+) -> Result<ToolResult> {
+    let keys = GasOracleKeys {
+        etherscan: config.etherscan_api_key.as_deref(),
+        blocknative: config.blocknative_api_key.as_deref(),
+    };
+
+    let mut estimates = Vec::new();
+    if params.gas_source.eq_ignore_ascii_case("all") {
+        // Compare every source; a failing external oracle is reported, not fatal
+        for source in [
+            GasSource::Node,
+            GasSource::Etherscan,
+            GasSource::Blocknative,
+        ] {
+            let response = match get_gas_estimate(provider, http, keys, source).await {
+                Ok(estimate) => to_response(source, estimate),
+                Err(e) => GasEstimateResponse {
+                    error: Some(e.to_string()),
+                    ..to_response(source, GasEstimate::default())
+                },
+            };
+            estimates.push(response);
+        }
+    } else {
+        let source: GasSource = params.gas_source.parse()?;
+        let estimate = get_gas_estimate(provider, http, keys, source).await?;
+        estimates.push(to_response(source, estimate));
+    }
+
+    let na = |value: &Option<String>| value.clone().unwrap_or_else(|| "N/A".to_string());
+    let text = estimates
+        .iter()
+        .map(|e| match &e.error {
+            Some(error) => format!("Source: {}\nUnavailable: {}", e.source, error),
+            None => format!(
+                "Source: {}\nGas Price: {} gwei\nBase Fee: {} gwei\nMax Fee: {} gwei\nMax Priority Fee: {} gwei\nSafe: {} gwei\nFast: {} gwei",
+                e.source,
+                na(&e.gas_price_gwei),
+                na(&e.base_fee_gwei),
+                na(&e.max_fee_per_gas_gwei),
+                na(&e.max_priority_fee_per_gas_gwei),
+                na(&e.safe_gwei),
+                na(&e.fast_gwei)
+            ),
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    let summary = estimates
+        .iter()
+        .map(|e| match (&e.error, &e.gas_price_gwei) {
+            (None, Some(gas_price)) => format!("{}: {} gwei", e.source, gas_price),
+            _ => format!("{}: unavailable", e.source),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    Ok(ToolResult::blocks(summary, text, &estimates)?)
+}
+
+fn to_response(source: GasSource, estimate: GasEstimate) -> GasEstimateResponse {
+    let gwei = |value: Option<Decimal>| value.map(|v| v.round_dp(4).to_string());
+
+    GasEstimateResponse {
+        source: source.name().to_string(),
+        gas_price_gwei: gwei(estimate.gas_price),
+        base_fee_gwei: gwei(estimate.base_fee),
+        max_fee_per_gas_gwei: gwei(estimate.max_fee_per_gas),
+        max_priority_fee_per_gas_gwei: gwei(estimate.max_priority_fee_per_gas),
+        safe_gwei: gwei(estimate.safe),
+        fast_gwei: gwei(estimate.fast),
+        error: None,
+    }
+}
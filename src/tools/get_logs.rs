@@ -0,0 +1,147 @@
+use anyhow::{Context, Result};
+use ethers::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::ethereum::{query_logs, EthClient, MAX_LOG_BLOCK_RANGE};
+use crate::types::{Tool, ToolResult};
+
+#[derive(Debug, Deserialize)]
+pub struct GetLogsParams {
+    pub address: Option<String>,
+    #[serde(default)]
+    pub topics: Vec<Option<String>>,
+    pub from_block: Option<u64>,
+    pub to_block: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LogResponse {
+    pub address: String,
+    pub block_number: Option<u64>,
+    pub transaction_hash: Option<String>,
+    pub log_index: Option<String>,
+    pub topics: Vec<String>,
+    pub data: String,
+    pub event: Option<String>,
+    pub decoded: Option<Value>,
+}
+
+pub fn get_tool_definition() -> Tool {
+    Tool {
+        name: "get_logs".to_string(),
+        description: "Query raw event logs by contract address and topics over a block range. Known ERC20 and Uniswap V2 events are decoded."
+            .to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "address": {
+                    "type": "string",
+                    "description": "Optional contract address emitting the logs (0x...)"
+                },
+                "topics": {
+                    "type": "array",
+                    "items": { "type": ["string", "null"] },
+                    "description": "Up to 4 topic filters as 32-byte hex. Use null as a wildcard for a position."
+                },
+                "from_block": {
+                    "type": "number",
+                    "description": "First block to search (inclusive). Default: 100 blocks before to_block"
+                },
+                "to_block": {
+                    "type": "number",
+                    "description": "Last block to search (inclusive). Default: latest block"
+                }
+            }
+        }),
+    }
+}
+
+pub async fn execute(provider: &EthClient, params: GetLogsParams) -> Result<ToolResult> {
+    let address = params
+        .address
+        .as_deref()
+        .map(|addr| {
+            addr.parse::<Address>()
+                .map_err(|e| anyhow::anyhow!("Invalid address: {}", e))
+        })
+        .transpose()?;
+
+    let topics = params
+        .topics
+        .iter()
+        .map(|topic| {
+            topic
+                .as_deref()
+                .map(|t| {
+                    t.parse::<H256>()
+                        .map_err(|e| anyhow::anyhow!("Invalid topic {}: {}", t, e))
+                })
+                .transpose()
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let to_block = match params.to_block {
+        Some(block) => block,
+        None => provider
+            .get_block_number()
+            .await
+            .context("Failed to get latest block number")?
+            .as_u64(),
+    };
+    let from_block = params
+        .from_block
+        .unwrap_or_else(|| to_block.saturating_sub(100));
+
+    let logs = query_logs(provider, address, topics, from_block, to_block).await?;
+
+    let entries: Vec<LogResponse> = logs
+        .into_iter()
+        .map(|log| LogResponse {
+            address: format!("{:?}", log.address),
+            block_number: log.block_number,
+            transaction_hash: log.transaction_hash.map(|hash| format!("{:?}", hash)),
+            log_index: log.log_index.map(|index| index.to_string()),
+            topics: log.topics.iter().map(|t| format!("{:?}", t)).collect(),
+            data: log.data.to_string(),
+            event: log.event,
+            decoded: log.decoded,
+        })
+        .collect();
+
+    let mut text = format!(
+        "Logs: {} entries in blocks {}..={} (max range {})",
+        entries.len(),
+        from_block,
+        to_block,
+        MAX_LOG_BLOCK_RANGE
+    );
+    for entry in &entries {
+        text.push_str(&format!(
+            "\n- Block {} | {} | {} | tx {}",
+            entry
+                .block_number
+                .map(|n| n.to_string())
+                .unwrap_or_else(|| "pending".to_string()),
+            entry.address,
+            entry.event.as_deref().unwrap_or("unknown event"),
+            entry.transaction_hash.as_deref().unwrap_or("N/A")
+        ));
+        match &entry.decoded {
+            Some(decoded) => text.push_str(&format!("\n  {}", decoded)),
+            None => text.push_str(&format!(
+                "\n  topics: [{}] data: {}",
+                entry.topics.join(", "),
+                entry.data
+            )),
+        }
+    }
+
+    let summary = format!(
+        "{} log entries in blocks {}..={}",
+        entries.len(),
+        from_block,
+        to_block
+    );
+    Ok(ToolResult::blocks(summary, text, &entries)?)
+}
@@ -0,0 +1,117 @@
+use anyhow::Result;
+use ethers::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::config::Config;
+use crate::ethereum::{max_trade_size, normalize_eth, EthClient, MetadataCache};
+use crate::types::{Amount, Tool, ToolResult};
+
+#[derive(Debug, Deserialize)]
+pub struct GetMaxSwapSizeParams {
+    pub from_token: String,
+    pub to_token: String,
+    /// Largest acceptable price impact, in basis points
+    pub max_price_impact_bps: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MaxSwapSizeResponse {
+    pub from_token: String,
+    pub to_token: String,
+    pub pair_address: String,
+    pub max_price_impact_bps: u32,
+    pub amount_in: Amount,
+    pub estimated_output: Amount,
+    pub price_impact: Amount,
+}
+
+pub fn get_tool_definition() -> Tool {
+    Tool {
+        name: "get_max_swap_size".to_string(),
+        description: "Find the largest amount that can be swapped on the direct Uniswap V2 pair while keeping price impact within a limit, by binary-searching against the pair's reserves. Returns that amount and its estimated output, for sizing orders in thin markets."
+            .to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "from_token": {
+                    "type": "string",
+                    "description": "Source token address (0x...). Use 0x0000000000000000000000000000000000000000 for ETH."
+                },
+                "to_token": {
+                    "type": "string",
+                    "description": "Destination token address (0x...)"
+                },
+                "max_price_impact_bps": {
+                    "type": "number",
+                    "description": "Largest acceptable price impact in basis points (e.g., 100 = 1%)"
+                }
+            },
+            "required": ["from_token", "to_token", "max_price_impact_bps"]
+        }),
+    }
+}
+
+pub async fn execute(
+    provider: &EthClient,
+    cache: &MetadataCache,
+    config: &Config,
+    params: GetMaxSwapSizeParams,
+) -> Result<ToolResult> {
+    let from_token = params
+        .from_token
+        .parse::<Address>()
+        .map(normalize_eth)
+        .map_err(|e| anyhow::anyhow!("Invalid from_token address: {}", e))?;
+
+    let to_token = params
+        .to_token
+        .parse::<Address>()
+        .map(normalize_eth)
+        .map_err(|e| anyhow::anyhow!("Invalid to_token address: {}", e))?;
+
+    // A size swap_tokens would refuse to quote is no use to the caller
+    if params.max_price_impact_bps > config.max_price_impact_bps {
+        anyhow::bail!(
+            "max_price_impact_bps {} exceeds the server limit of {} (MAX_PRICE_IMPACT_BPS)",
+            params.max_price_impact_bps,
+            config.max_price_impact_bps
+        );
+    }
+
+    let size = max_trade_size(
+        provider,
+        cache,
+        from_token,
+        to_token,
+        params.max_price_impact_bps,
+    )
+    .await?;
+
+    let response = MaxSwapSizeResponse {
+        from_token: params.from_token,
+        to_token: params.to_token,
+        pair_address: format!("{:?}", size.pair),
+        max_price_impact_bps: params.max_price_impact_bps,
+        amount_in: Amount::new(size.amount_in.normalize()),
+        estimated_output: Amount::new(size.estimated_output.normalize()),
+        price_impact: Amount::with_decimals(size.price_impact, 4),
+    };
+
+    let text = format!(
+        "Largest swap within {} bps price impact on Uniswap V2 pair {}:\nAmount In: {} {}\nEstimated Output: {} {}\nPrice Impact: {}%",
+        response.max_price_impact_bps,
+        response.pair_address,
+        response.amount_in,
+        response.from_token,
+        response.estimated_output,
+        response.to_token,
+        response.price_impact
+    );
+
+    let summary = format!(
+        "Max swap within {} bps impact: {} in, {} out",
+        response.max_price_impact_bps, response.amount_in, response.estimated_output
+    );
+    Ok(ToolResult::blocks(summary, text, &response)?)
+}
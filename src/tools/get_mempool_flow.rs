@@ -0,0 +1,116 @@
+use anyhow::Result;
+use ethers::prelude::*;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::ethereum::{normalize_eth, sample_mempool_flow, EthClient, MetadataCache};
+use crate::types::{Amount, Tool, ToolResult};
+
+#[derive(Debug, Deserialize)]
+pub struct GetMempoolFlowParams {
+    pub token_address: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MempoolFlowResponse {
+    pub token_address: String,
+    /// False when the endpoint exposes neither `txpool_content` nor pending filters
+    pub available: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    pub inspected: usize,
+    pub buys: usize,
+    pub sells: usize,
+    pub buy_volume: Amount,
+    pub sell_volume: Amount,
+    /// Buy volume minus sell volume
+    pub net_volume: Amount,
+}
+
+pub fn get_tool_definition() -> Tool {
+    Tool {
+        name: "get_mempool_flow".to_string(),
+        description: "Summarize pending (not yet mined) Uniswap buy and sell swaps of a token from the mempool: counts and volumes on each side. Uses txpool_content or a short pending-transaction filter sample; reports when the RPC endpoint exposes neither."
+            .to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "token_address": {
+                    "type": "string",
+                    "description": "Token contract address (0x...). Use 0x0000000000000000000000000000000000000000 for ETH."
+                }
+            },
+            "required": ["token_address"]
+        }),
+    }
+}
+
+pub async fn execute(
+    provider: &EthClient,
+    cache: &MetadataCache,
+    params: GetMempoolFlowParams,
+) -> Result<ToolResult> {
+    let token = params
+        .token_address
+        .parse::<Address>()
+        .map(normalize_eth)
+        .map_err(|e| anyhow::anyhow!("Invalid token address: {}", e))?;
+
+    let response = match sample_mempool_flow(provider, cache, token).await {
+        Ok(flow) => MempoolFlowResponse {
+            token_address: params.token_address,
+            available: true,
+            reason: None,
+            source: Some(flow.source.to_string()),
+            inspected: flow.inspected,
+            buys: flow.buys,
+            sells: flow.sells,
+            buy_volume: Amount::new(flow.buy_volume.normalize()),
+            sell_volume: Amount::new(flow.sell_volume.normalize()),
+            net_volume: Amount::new((flow.buy_volume - flow.sell_volume).normalize()),
+        },
+        Err(reason) => MempoolFlowResponse {
+            token_address: params.token_address,
+            available: false,
+            reason: Some(reason),
+            source: None,
+            inspected: 0,
+            buys: 0,
+            sells: 0,
+            buy_volume: Amount::new(Decimal::ZERO),
+            sell_volume: Amount::new(Decimal::ZERO),
+            net_volume: Amount::new(Decimal::ZERO),
+        },
+    };
+
+    let text = match (&response.source, &response.reason) {
+        (Some(source), _) => format!(
+            "Pending swaps of {} ({} transactions inspected via {}):\nBuys: {} ({} tokens, at least)\nSells: {} ({} tokens)\nNet: {} tokens",
+            response.token_address,
+            response.inspected,
+            source,
+            response.buys,
+            response.buy_volume,
+            response.sells,
+            response.sell_volume,
+            response.net_volume
+        ),
+        (None, reason) => format!(
+            "Mempool access not available: {}",
+            reason.as_deref().unwrap_or("unknown reason")
+        ),
+    };
+
+    let summary = if response.available {
+        format!(
+            "{} pending buys, {} pending sells, net {} tokens",
+            response.buys, response.sells, response.net_volume
+        )
+    } else {
+        "Mempool access not available".to_string()
+    };
+    Ok(ToolResult::blocks(summary, text, &response)?)
+}
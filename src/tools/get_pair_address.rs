@@ -0,0 +1,112 @@
+use anyhow::Result;
+use ethers::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::ethereum::{get_pool_addresses, normalize_eth, EthClient};
+use crate::types::{Tool, ToolResult};
+
+#[derive(Debug, Deserialize)]
+pub struct GetPairAddressParams {
+    pub token_a: String,
+    pub token_b: String,
+    pub fee_tier: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct V3PoolResponse {
+    pub fee_tier: u32,
+    pub pool_address: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PairAddressResponse {
+    pub token_a: String,
+    pub token_b: String,
+    pub v2_pair_address: Option<String>,
+    pub v3_pools: Vec<V3PoolResponse>,
+}
+
+pub fn get_tool_definition() -> Tool {
+    Tool {
+        name: "get_pair_address".to_string(),
+        description: "Look up the Uniswap V2 pair address and V3 pool addresses for two tokens via the factories. Missing pools are returned as null."
+            .to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "token_a": {
+                    "type": "string",
+                    "description": "First token address (0x...). Use 0x0000000000000000000000000000000000000000 for ETH (looked up as WETH)."
+                },
+                "token_b": {
+                    "type": "string",
+                    "description": "Second token address (0x...)"
+                },
+                "fee_tier": {
+                    "type": "number",
+                    "enum": [100, 500, 3000, 10000],
+                    "description": "Optional V3 fee tier. If omitted, pools for all standard tiers are returned."
+                }
+            },
+            "required": ["token_a", "token_b"]
+        }),
+    }
+}
+
+pub async fn execute(provider: &EthClient, params: GetPairAddressParams) -> Result<ToolResult> {
+    let token_a = params
+        .token_a
+        .parse::<Address>()
+        .map(normalize_eth)
+        .map_err(|e| anyhow::anyhow!("Invalid token_a address: {}", e))?;
+
+    let token_b = params
+        .token_b
+        .parse::<Address>()
+        .map(normalize_eth)
+        .map_err(|e| anyhow::anyhow!("Invalid token_b address: {}", e))?;
+
+    let pools = get_pool_addresses(provider, token_a, token_b, params.fee_tier).await?;
+
+    let format_address = |address: Option<Address>| address.map(|a| format!("{:?}", a));
+    let response = PairAddressResponse {
+        token_a: format!("{:?}", pools.token_a),
+        token_b: format!("{:?}", pools.token_b),
+        v2_pair_address: format_address(pools.v2_pair),
+        v3_pools: pools
+            .v3_pools
+            .iter()
+            .map(|(fee, pool)| V3PoolResponse {
+                fee_tier: *fee,
+                pool_address: format_address(*pool),
+            })
+            .collect(),
+    };
+
+    let mut text = format!(
+        "Token A: {}\nToken B: {}\nUniswap V2 Pair: {}",
+        response.token_a,
+        response.token_b,
+        response.v2_pair_address.as_deref().unwrap_or("none")
+    );
+    for pool in &response.v3_pools {
+        text.push_str(&format!(
+            "\nUniswap V3 Pool ({} fee): {}",
+            pool.fee_tier,
+            pool.pool_address.as_deref().unwrap_or("none")
+        ));
+    }
+
+    let summary = format!(
+        "Uniswap V2 pair: {}, {} of {} V3 pools exist",
+        response.v2_pair_address.as_deref().unwrap_or("none"),
+        response
+            .v3_pools
+            .iter()
+            .filter(|pool| pool.pool_address.is_some())
+            .count(),
+        response.v3_pools.len()
+    );
+    Ok(ToolResult::blocks(summary, text, &response)?)
+}
@@ -0,0 +1,215 @@
+use anyhow::Result;
+use ethers::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::swap_tokens::version_label;
+use crate::ethereum::{
+    get_pool_swaps, summarize_swaps, EthClient, MetadataCache, MAX_POOL_SWAP_BLOCKS,
+};
+use crate::types::{Amount, Tool, ToolResult};
+
+/// About an hour of mainnet blocks
+const DEFAULT_POOL_SWAP_BLOCKS: u64 = 300;
+
+/// Most recent swaps listed individually; the summary covers every swap in the window
+const MAX_LISTED_SWAPS: usize = 25;
+
+#[derive(Debug, Deserialize)]
+pub struct GetPoolSwapsParams {
+    pub pool_address: String,
+    /// Latest blocks to read, up to `MAX_POOL_SWAP_BLOCKS`
+    pub blocks: Option<u64>,
+    /// Token whose buys and sells are counted; the pool's token0 when omitted
+    pub base_token: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PoolSwapResponse {
+    pub block_number: Option<u64>,
+    pub transaction_hash: Option<String>,
+    pub amount0_in: Amount,
+    pub amount1_in: Amount,
+    pub amount0_out: Amount,
+    pub amount1_out: Amount,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PoolSwapsResponse {
+    pub pool_address: String,
+    pub version: String,
+    pub token0: String,
+    pub token1: String,
+    pub base_token: String,
+    pub quote_token: String,
+    pub from_block: u64,
+    pub to_block: u64,
+    pub swap_count: usize,
+    /// Swaps taking the base token out of the pool
+    pub buys: usize,
+    pub sells: usize,
+    pub buy_volume: Amount,
+    pub sell_volume: Amount,
+    /// Buy volume minus sell volume
+    pub net_volume: Amount,
+    /// Quote token paid for buys and received for sells
+    pub quote_volume: Amount,
+    /// Quote token per base token, weighted by volume
+    pub average_price: Option<Amount>,
+    /// Most recent first, in token units
+    pub recent_swaps: Vec<PoolSwapResponse>,
+}
+
+pub fn get_tool_definition() -> Tool {
+    Tool {
+        name: "get_pool_swaps".to_string(),
+        description: "Read recent Swap events of a Uniswap V2 pair or V3 pool and summarize the trading activity: buy and sell counts and volumes of the base token, and the volume-weighted average price. Lists the most recent swaps individually. Use for a sense of market activity before entering a position."
+            .to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "pool_address": {
+                    "type": "string",
+                    "description": "Uniswap V2 pair or V3 pool address (0x...); see get_pair_address"
+                },
+                "blocks": {
+                    "type": "integer",
+                    "description": format!("Latest blocks to read, 1 to {} (default: {}, about an hour on mainnet)", MAX_POOL_SWAP_BLOCKS, DEFAULT_POOL_SWAP_BLOCKS)
+                },
+                "base_token": {
+                    "type": "string",
+                    "description": "Token whose buys and sells to count, and which the average price is quoted per: the pool's token0 or token1. Default: token0"
+                }
+            },
+            "required": ["pool_address"]
+        }),
+    }
+}
+
+pub async fn execute(
+    provider: &EthClient,
+    cache: &MetadataCache,
+    params: GetPoolSwapsParams,
+) -> Result<ToolResult> {
+    let pool = params
+        .pool_address
+        .parse::<Address>()
+        .map_err(|e| anyhow::anyhow!("Invalid pool address: {}", e))?;
+    let base_token = params
+        .base_token
+        .as_ref()
+        .map(|token| {
+            token
+                .parse::<Address>()
+                .map_err(|e| anyhow::anyhow!("Invalid base_token address: {}", e))
+        })
+        .transpose()?;
+
+    let blocks = params.blocks.unwrap_or(DEFAULT_POOL_SWAP_BLOCKS);
+    let pool_swaps = get_pool_swaps(provider, cache, pool, blocks).await?;
+    let base_is_token1 = match base_token {
+        None => false,
+        Some(token) if token == pool_swaps.token0 => false,
+        Some(token) if token == pool_swaps.token1 => true,
+        Some(token) => anyhow::bail!(
+            "base_token {:?} is not in the pool, which trades {:?} and {:?}",
+            token,
+            pool_swaps.token0,
+            pool_swaps.token1
+        ),
+    };
+    let volume = summarize_swaps(
+        &pool_swaps.swaps,
+        base_is_token1,
+        pool_swaps.decimals0,
+        pool_swaps.decimals1,
+    )?;
+
+    let recent_swaps = pool_swaps
+        .swaps
+        .iter()
+        .rev()
+        .take(MAX_LISTED_SWAPS)
+        .map(|swap| {
+            let [amount0_in, amount1_in, amount0_out, amount1_out] =
+                pool_swaps.token_amounts(swap)?;
+            Ok(PoolSwapResponse {
+                block_number: swap.block_number,
+                transaction_hash: swap.transaction_hash.map(|hash| format!("{:?}", hash)),
+                amount0_in: Amount::new(amount0_in),
+                amount1_in: Amount::new(amount1_in),
+                amount0_out: Amount::new(amount0_out),
+                amount1_out: Amount::new(amount1_out),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let (token0, token1) = (
+        format!("{:?}", pool_swaps.token0),
+        format!("{:?}", pool_swaps.token1),
+    );
+    let (base, quote) = if base_is_token1 {
+        (token1.clone(), token0.clone())
+    } else {
+        (token0.clone(), token1.clone())
+    };
+    let response = PoolSwapsResponse {
+        pool_address: format!("{:?}", pool_swaps.pool),
+        version: version_label(pool_swaps.version),
+        token0,
+        token1,
+        base_token: base,
+        quote_token: quote,
+        from_block: pool_swaps.from_block,
+        to_block: pool_swaps.to_block,
+        swap_count: pool_swaps.swaps.len(),
+        buys: volume.buys,
+        sells: volume.sells,
+        buy_volume: Amount::new(volume.buy_volume),
+        sell_volume: Amount::new(volume.sell_volume),
+        net_volume: Amount::new(volume.buy_volume - volume.sell_volume),
+        quote_volume: Amount::new(volume.quote_volume),
+        average_price: volume.average_price.map(Amount::new),
+        recent_swaps,
+    };
+
+    let mut text = format!(
+        "Pool: {} (Uniswap {})\nBlocks: {} to {}\nSwaps: {}\nBase Token: {}\nQuote Token: {}\nBuys: {} ({} base tokens)\nSells: {} ({} base tokens)\nNet: {} base tokens\nQuote Volume: {}",
+        response.pool_address,
+        response.version.to_uppercase(),
+        response.from_block,
+        response.to_block,
+        response.swap_count,
+        response.base_token,
+        response.quote_token,
+        response.buys,
+        response.buy_volume,
+        response.sells,
+        response.sell_volume,
+        response.net_volume,
+        response.quote_volume
+    );
+    if let Some(price) = &response.average_price {
+        text.push_str(&format!("\nAverage Price: {} quote per base", price));
+    }
+    if !response.recent_swaps.is_empty() {
+        text.push_str("\nRecent Swaps (token0 in/out, token1 in/out):");
+        for swap in &response.recent_swaps {
+            text.push_str(&format!(
+                "\n  Block {}: {}/{}, {}/{}",
+                swap.block_number
+                    .map_or("pending".to_string(), |block| block.to_string()),
+                swap.amount0_in,
+                swap.amount0_out,
+                swap.amount1_in,
+                swap.amount1_out
+            ));
+        }
+    }
+
+    let summary = format!(
+        "{} swaps in {} blocks: {} buys, {} sells, net {} base tokens",
+        response.swap_count, blocks, response.buys, response.sells, response.net_volume
+    );
+    Ok(ToolResult::blocks(summary, text, &response)?)
+}
@@ -0,0 +1,173 @@
+use anyhow::Result;
+use ethers::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::ethereum::{
+    discover_tokens, get_portfolio, normalize_eth, EthClient, Holding, MetadataCache,
+    MAX_LOG_BLOCK_RANGE, MAX_PORTFOLIO_TOKENS,
+};
+use crate::types::{Tool, ToolResult};
+
+#[derive(Debug, Deserialize)]
+pub struct GetPortfolioValueParams {
+    pub wallet_address: String,
+    /// Tokens to value; discovered from recent Transfer logs when omitted
+    pub token_addresses: Option<Vec<String>>,
+    #[serde(default = "default_include_eth")]
+    pub include_eth: bool,
+}
+
+fn default_include_eth() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize)]
+pub struct HoldingResponse {
+    pub token_address: String,
+    pub symbol: String,
+    pub balance: String,
+    pub price_usd: Option<String>,
+    pub value_usd: Option<String>,
+    pub price_source: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PortfolioResponse {
+    pub wallet_address: String,
+    pub total_value_usd: String,
+    pub holdings: Vec<HoldingResponse>,
+    pub unpriced: Vec<HoldingResponse>,
+    pub failed: Vec<String>,
+}
+
+pub fn get_tool_definition() -> Tool {
+    Tool {
+        name: "get_portfolio_value".to_string(),
+        description: "Estimate the USD value of a wallet's portfolio: fetches each token balance and price concurrently and returns per-token values plus a total. Tokens without a price are listed as unpriced."
+            .to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "wallet_address": {
+                    "type": "string",
+                    "description": "The wallet address to value (0x...)"
+                },
+                "token_addresses": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "maxItems": MAX_PORTFOLIO_TOKENS,
+                    "description": format!(
+                        "ERC20 token addresses to include. If omitted, tokens are discovered from Transfer logs to the wallet in the last {} blocks.",
+                        MAX_LOG_BLOCK_RANGE
+                    )
+                },
+                "include_eth": {
+                    "type": "boolean",
+                    "description": "Include the native ETH balance. Default: true",
+                    "default": true
+                }
+            },
+            "required": ["wallet_address"]
+        }),
+    }
+}
+
+pub async fn execute(
+    provider: &EthClient,
+    http: &reqwest::Client,
+    cache: &Arc<MetadataCache>,
+    config: &Config,
+    params: GetPortfolioValueParams,
+) -> Result<ToolResult> {
+    let wallet_address = params
+        .wallet_address
+        .parse::<Address>()
+        .map_err(|e| anyhow::anyhow!("Invalid wallet address: {}", e))?;
+
+    let mut tokens = match &params.token_addresses {
+        Some(addresses) => addresses
+            .iter()
+            .map(|addr| {
+                addr.parse::<Address>()
+                    .map(normalize_eth)
+                    .map_err(|e| anyhow::anyhow!("Invalid token address {}: {}", addr, e))
+            })
+            .collect::<Result<Vec<_>>>()?,
+        None => discover_tokens(provider, wallet_address).await?,
+    };
+    if params.include_eth && !tokens.contains(&Address::zero()) {
+        tokens.insert(0, Address::zero());
+    }
+
+    let portfolio = get_portfolio(
+        provider,
+        &config.price_oracles(http),
+        cache,
+        wallet_address,
+        &tokens,
+    )
+    .await?;
+
+    let response = PortfolioResponse {
+        wallet_address: params.wallet_address,
+        total_value_usd: portfolio.total_usd.round_dp(2).to_string(),
+        holdings: portfolio.priced.iter().map(to_response).collect(),
+        unpriced: portfolio.unpriced.iter().map(to_response).collect(),
+        failed: portfolio
+            .failed
+            .iter()
+            .map(|f| format!("{:?}: {}", f.token, f.error))
+            .collect(),
+    };
+
+    let mut text = format!(
+        "Wallet: {}\nTotal Value (USD): {}",
+        response.wallet_address, response.total_value_usd
+    );
+    for holding in &response.holdings {
+        text.push_str(&format!(
+            "\n- {} {} @ ${} = ${} ({})",
+            holding.balance,
+            holding.symbol,
+            holding.price_usd.as_deref().unwrap_or("N/A"),
+            holding.value_usd.as_deref().unwrap_or("N/A"),
+            holding.token_address
+        ));
+    }
+    if !response.unpriced.is_empty() {
+        text.push_str("\nUnpriced:");
+        for holding in &response.unpriced {
+            text.push_str(&format!(
+                "\n- {} {} ({})",
+                holding.balance, holding.symbol, holding.token_address
+            ));
+        }
+    }
+    if !response.failed.is_empty() {
+        text.push_str("\nFailed:");
+        for failure in &response.failed {
+            text.push_str(&format!("\n- {}", failure));
+        }
+    }
+
+    let summary = format!(
+        "Total value: ${} across {} priced holdings",
+        response.total_value_usd,
+        response.holdings.len()
+    );
+    Ok(ToolResult::blocks(summary, text, &response)?)
+}
+
+fn to_response(holding: &Holding) -> HoldingResponse {
+    HoldingResponse {
+        token_address: format!("{:?}", holding.token),
+        symbol: holding.symbol.clone(),
+        balance: holding.balance.to_string(),
+        price_usd: holding.price_usd.map(|p| p.to_string()),
+        value_usd: holding.value_usd.map(|v| v.round_dp(2).to_string()),
+        price_source: holding.price_source.clone(),
+    }
+}
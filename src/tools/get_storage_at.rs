@@ -0,0 +1,132 @@
+use anyhow::Result;
+use ethers::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::ethereum::{get_storage, mapping_slot, parse_slot, EthClient};
+use crate::types::{Tool, ToolResult};
+
+#[derive(Debug, Deserialize)]
+pub struct GetStorageAtParams {
+    pub contract_address: String,
+    /// Hex, decimal, or an EIP-1967 slot name; the mapping's own slot when `key` is given
+    pub slot: String,
+    /// Mapping key whose entry to read instead of `slot` itself
+    pub key: Option<String>,
+    /// Read the slot as of this past block (needs an archive node)
+    pub block_number: Option<u64>,
+    /// Bytes from the right of the word where a packed field starts
+    pub packed_offset: Option<usize>,
+    /// Width of the packed field in bytes
+    pub packed_size: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StorageAtResponse {
+    pub contract_address: String,
+    pub slot: String,
+    pub block_number: Option<u64>,
+    pub raw: String,
+    pub as_uint: String,
+    pub as_address: String,
+    pub as_bool: Option<bool>,
+    pub packed_value: Option<String>,
+}
+
+pub fn get_tool_definition() -> Tool {
+    Tool {
+        name: "get_storage_at".to_string(),
+        description: "Read a raw 32-byte contract storage slot via eth_getStorageAt and decode it as uint, address and bool. Slots can be given directly, by EIP-1967 name for proxy implementation/admin/beacon slots, or computed from a mapping key. For debugging contract state the other tools don't expose."
+            .to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "contract_address": {
+                    "type": "string",
+                    "description": "Contract address to read (0x...)"
+                },
+                "slot": {
+                    "type": "string",
+                    "description": "Storage slot as hex (0x...) or decimal, or one of 'eip1967.implementation', 'eip1967.admin', 'eip1967.beacon'. With key, the slot the mapping is declared at."
+                },
+                "key": {
+                    "type": "string",
+                    "description": "Optional mapping key (address, or uint as hex or decimal). Reads mapping[key], at keccak256(key . slot)."
+                },
+                "block_number": {
+                    "type": "integer",
+                    "description": "Read the slot as of this past block instead of now; requires an archive node for old blocks."
+                },
+                "packed_offset": {
+                    "type": "integer",
+                    "description": "For packed storage: bytes from the right of the word where the field starts. Default: 0"
+                },
+                "packed_size": {
+                    "type": "integer",
+                    "description": "For packed storage: width of the field in bytes (e.g. 1 for uint8/bool, 20 for address)"
+                }
+            },
+            "required": ["contract_address", "slot"]
+        }),
+    }
+}
+
+pub async fn execute(provider: &EthClient, params: GetStorageAtParams) -> Result<ToolResult> {
+    let contract = params
+        .contract_address
+        .parse::<Address>()
+        .map_err(|e| anyhow::anyhow!("Invalid contract address: {}", e))?;
+
+    let base_slot = parse_slot(&params.slot)?;
+    let slot = match &params.key {
+        Some(key) => mapping_slot(key, base_slot)?,
+        None => base_slot,
+    };
+
+    if params.packed_offset.is_some() && params.packed_size.is_none() {
+        anyhow::bail!("packed_offset requires packed_size");
+    }
+
+    let value = get_storage(provider, contract, slot, params.block_number).await?;
+    let packed_value = params
+        .packed_size
+        .map(|size| value.packed(params.packed_offset.unwrap_or(0), size))
+        .transpose()?;
+
+    let response = StorageAtResponse {
+        contract_address: format!("{:?}", contract),
+        slot: format!("{:?}", slot),
+        block_number: params.block_number,
+        raw: format!("{:?}", value.raw),
+        as_uint: value.as_uint.to_string(),
+        as_address: format!("{:?}", value.as_address),
+        as_bool: value.as_bool,
+        packed_value: packed_value.map(|packed| packed.to_string()),
+    };
+
+    let mut text = format!(
+        "Contract: {}\nSlot: {}\nRaw: {}\nAs uint: {}\nAs address: {}",
+        response.contract_address,
+        response.slot,
+        response.raw,
+        response.as_uint,
+        response.as_address
+    );
+    if let Some(flag) = response.as_bool {
+        text.push_str(&format!("\nAs bool: {}", flag));
+    }
+    if let Some(packed) = &response.packed_value {
+        text.push_str(&format!(
+            "\nPacked field ({} bytes at offset {}): {}",
+            params.packed_size.unwrap_or_default(),
+            params.packed_offset.unwrap_or(0),
+            packed
+        ));
+    }
+    if let Some(block_number) = response.block_number {
+        text.push_str(&format!("\nBlock: {}", block_number));
+    }
+
+    let summary = format!("Slot {}: {}", response.slot, response.raw);
+    Ok(ToolResult::blocks(summary, text, &response)?)
+}
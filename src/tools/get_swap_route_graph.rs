@@ -0,0 +1,161 @@
+use anyhow::Result;
+use ethers::prelude::*;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::swap_tokens::version_label;
+use crate::config::Config;
+use crate::ethereum::{explore_routes, normalize_eth, EthClient, MetadataCache};
+use crate::types::{Amount, Tool, ToolResult};
+
+#[derive(Debug, Deserialize)]
+pub struct GetSwapRouteGraphParams {
+    pub from_token: String,
+    pub to_token: String,
+    /// Amount in from-token units
+    pub amount: Amount,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RouteResponse {
+    pub version: String,
+    pub fee_tier: Option<u32>,
+    /// Token addresses, usable as swap_tokens `path`
+    pub path: Vec<String>,
+    pub estimated_output: Option<Amount>,
+    pub price_impact: Option<Amount>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RouteGraphResponse {
+    pub from_token: String,
+    pub to_token: String,
+    pub amount_in: Amount,
+    pub routes: Vec<RouteResponse>,
+}
+
+pub fn get_tool_definition() -> Tool {
+    Tool {
+        name: "get_swap_route_graph".to_string(),
+        description: "Quote every candidate Uniswap route for a swap (direct V2, two-hop V2 via WETH/USDC/USDT/DAI, and each V3 fee tier) and list them best to worst with estimated output and price impact. For debugging a surprising swap_tokens quote or choosing a path to pass to swap_tokens."
+            .to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "from_token": {
+                    "type": "string",
+                    "description": "Source token address (0x...). Use 0x0000000000000000000000000000000000000000 for ETH."
+                },
+                "to_token": {
+                    "type": "string",
+                    "description": "Destination token address (0x...)"
+                },
+                "amount": {
+                    "type": "string",
+                    "description": "Amount to swap (in token units, e.g., '1.5')"
+                }
+            },
+            "required": ["from_token", "to_token", "amount"]
+        }),
+    }
+}
+
+pub async fn execute(
+    provider: &EthClient,
+    cache: &MetadataCache,
+    config: &Config,
+    params: GetSwapRouteGraphParams,
+) -> Result<ToolResult> {
+    let from_token = params
+        .from_token
+        .parse::<Address>()
+        .map(normalize_eth)
+        .map_err(|e| anyhow::anyhow!("Invalid from_token address: {}", e))?;
+
+    let to_token = params
+        .to_token
+        .parse::<Address>()
+        .map(normalize_eth)
+        .map_err(|e| anyhow::anyhow!("Invalid to_token address: {}", e))?;
+
+    let amount = params.amount.value();
+    if amount <= Decimal::ZERO {
+        anyhow::bail!("Amount must be positive");
+    }
+
+    let candidates = explore_routes(
+        provider,
+        cache,
+        from_token,
+        to_token,
+        amount,
+        config.max_hops,
+        config.wei_rounding,
+    )
+    .await?;
+
+    let response = RouteGraphResponse {
+        from_token: params.from_token,
+        to_token: params.to_token,
+        amount_in: params.amount,
+        routes: candidates
+            .into_iter()
+            .map(|candidate| RouteResponse {
+                version: version_label(candidate.version),
+                fee_tier: candidate.fee_tier,
+                path: candidate
+                    .path
+                    .iter()
+                    .map(|token| format!("{:?}", token))
+                    .collect(),
+                estimated_output: candidate.estimated_output.map(Amount::new),
+                price_impact: candidate
+                    .price_impact
+                    .map(|impact| Amount::with_decimals(impact, 4)),
+                error: candidate.error,
+            })
+            .collect(),
+    };
+
+    let mut text = format!(
+        "Routes for {} {} -> {} (best first):",
+        response.amount_in, response.from_token, response.to_token
+    );
+    for (i, route) in response.routes.iter().enumerate() {
+        let venue = match route.fee_tier {
+            Some(fee) => format!("{} ({} fee)", route.version, fee),
+            None => route.version.clone(),
+        };
+        let outcome = match (&route.estimated_output, &route.price_impact, &route.error) {
+            (Some(output), Some(impact), _) => format!("{} (price impact {}%)", output, impact),
+            (_, _, Some(error)) => format!("failed: {}", error),
+            _ => "no quote".to_string(),
+        };
+        text.push_str(&format!(
+            "\n{}. {} {}: {}",
+            i + 1,
+            venue,
+            route.path.join(" -> "),
+            outcome
+        ));
+    }
+
+    let quoted = response
+        .routes
+        .iter()
+        .filter(|route| route.estimated_output.is_some())
+        .count();
+    let summary = match response.routes.first() {
+        Some(best) if best.estimated_output.is_some() => format!(
+            "{} of {} routes quoted, best: {} {}",
+            quoted,
+            response.routes.len(),
+            best.version,
+            best.path.join(" -> ")
+        ),
+        _ => format!("No route quoted out of {}", response.routes.len()),
+    };
+    Ok(ToolResult::blocks(summary, text, &response)?)
+}
@@ -1,14 +1,25 @@
 use anyhow::Result;
 use ethers::prelude::*;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
+use serde_json::{json, Map, Value};
 
-use crate::ethereum::{get_token_price, EthClient};
-use crate::types::{Tool, ToolContent, ToolResult};
+use crate::config::Config;
+use crate::ethereum::{
+    get_coingecko_prices, get_token_price, get_token_price_at_block, normalize_eth, EthClient,
+    PriceSource,
+};
+use crate::types::{Tool, ToolResult};
 
 #[derive(Debug, Deserialize)]
 pub struct GetTokenPriceParams {
     pub token_address: String,
+    /// Pin a price source ("chainlink", "coingecko", "uniswap" or "auto")
+    pub source: Option<String>,
+    /// Read the price as of this past block (needs an archive node)
+    pub block_number: Option<u64>,
+    /// Comma-separated CoinGecko currency codes to price the token in, e.g. "eur,gbp,btc"
+    pub vs_currencies: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -17,6 +28,29 @@ pub struct PriceResponse {
     pub price_usd: Option<String>,
     pub price_eth: Option<String>,
     pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+    /// USD value of the pool a Uniswap price was derived from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pool_liquidity_usd: Option<String>,
+    /// Price per requested `vs_currencies` code, null where CoinGecko has none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prices: Option<Map<String, Value>>,
+    /// Chainlink aggregator and round a Chainlink price was read from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feed: Option<FeedResponse>,
+    /// Assumptions the price rests on, such as a Chainlink feed's decimals
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FeedResponse {
+    pub feed_address: String,
+    /// uint80 round ids, as strings since they overflow JSON numbers
+    pub round_id: String,
+    pub answered_in_round: String,
+    pub updated_at: u64,
 }
 
 pub fn get_tool_definition() -> Tool {
@@ -30,6 +64,19 @@ pub fn get_tool_definition() -> Tool {
                 "token_address": {
                     "type": "string",
                     "description": "The token contract address (0x...). Use 0x0000000000000000000000000000000000000000 for ETH."
+                },
+                "source": {
+                    "type": "string",
+                    "enum": ["auto", "chainlink", "coingecko", "uniswap"],
+                    "description": "Price source to use. 'auto' (default) tries the server's configured sources in order (Chainlink, CoinGecko, Uniswap unless reconfigured) until one prices the token; any other value uses only that source and fails if it cannot price the token."
+                },
+                "block_number": {
+                    "type": "integer",
+                    "description": "Get the price as of this past block instead of now. Uses Chainlink for ETH and tokens with a feed, otherwise Uniswap V2 reserves; requires an archive node for old blocks."
+                },
+                "vs_currencies": {
+                    "type": "string",
+                    "description": "Comma-separated currency codes to price the token in via CoinGecko, e.g. 'eur,gbp,btc'. Returns a prices map of currency to price. Not combinable with block_number or a non-CoinGecko source."
                 }
             },
             "required": ["token_address"]
@@ -37,39 +84,155 @@ pub fn get_tool_definition() -> Tool {
     }
 }
 
-pub async fn execute(provider: &EthClient, params: GetTokenPriceParams) -> Result<ToolResult> {
+pub async fn execute(
+    provider: &EthClient,
+    http: &reqwest::Client,
+    config: &Config,
+    params: GetTokenPriceParams,
+) -> Result<ToolResult> {
     let token_address = params
         .token_address
         .parse::<Address>()
+        .map(normalize_eth)
         .map_err(|e| anyhow::anyhow!("Invalid token address: {}", e))?;
 
-    let price_info = get_token_price(provider, token_address).await?;
+    let source = params
+        .source
+        .as_deref()
+        .map(str::parse::<PriceSource>)
+        .transpose()?
+        .unwrap_or_default();
+
+    if let Some(vs_currencies) = params.vs_currencies.as_deref() {
+        if params.block_number.is_some() {
+            anyhow::bail!("vs_currencies cannot be combined with block_number");
+        }
+        if !matches!(source, PriceSource::Auto | PriceSource::CoinGecko) {
+            anyhow::bail!("vs_currencies is only supported with the coingecko source");
+        }
+        let coingecko = config.coingecko(http);
+        let prices = get_coingecko_prices(&coingecko, token_address, vs_currencies).await?;
+        return currency_prices_result(params.token_address, prices);
+    }
+
+    let price_info = match params.block_number {
+        Some(block_number) => {
+            get_token_price_at_block(
+                provider,
+                &config.chainlink_feeds,
+                token_address,
+                source,
+                block_number,
+                config.min_pool_liquidity_usd,
+            )
+            .await?
+        }
+        None => {
+            get_token_price(provider, &config.price_oracles(http), token_address, source).await?
+        }
+    };
 
     let response = PriceResponse {
         token_address: params.token_address,
         price_usd: price_info.price_usd.map(|p| p.to_string()),
         price_eth: price_info.price_eth.map(|p| p.to_string()),
         source: price_info.source,
+        block_number: price_info.block_number,
+        pool_liquidity_usd: price_info
+            .pool_liquidity_usd
+            .map(|liquidity| liquidity.round_dp(2).to_string()),
+        prices: None,
+        feed: price_info.round.map(|round| FeedResponse {
+            feed_address: format!("{:?}", round.feed),
+            round_id: round.round_id.to_string(),
+            answered_in_round: round.answered_in_round.to_string(),
+            updated_at: round.updated_at,
+        }),
+        warnings: price_info.warnings,
     };
 
-    let text = format!(
+    let mut text = format!(
         "Token: {}\nPrice (USD): {}\nPrice (ETH): {}\nSource: {}",
         response.token_address,
-        response
-            .price_usd
-            .as_ref()
-            .map(|s| s.as_str())
-            .unwrap_or("N/A"),
-        response
-            .price_eth
-            .as_ref()
-            .map(|s| s.as_str())
-            .unwrap_or("N/A"),
+        response.price_usd.as_deref().unwrap_or("N/A"),
+        response.price_eth.as_deref().unwrap_or("N/A"),
+        response.source
+    );
+    if let Some(block_number) = response.block_number {
+        text.push_str(&format!("\nBlock: {}", block_number));
+    }
+    if let Some(liquidity) = &response.pool_liquidity_usd {
+        text.push_str(&format!("\nPool Liquidity (USD): {}", liquidity));
+    }
+    if let Some(feed) = &response.feed {
+        text.push_str(&format!(
+            "\nChainlink Feed: {} (round {}, updated at {})",
+            feed.feed_address, feed.round_id, feed.updated_at
+        ));
+    }
+    for warning in &response.warnings {
+        text.push_str(&format!("\nWarning: {}", warning));
+    }
+
+    let summary = format!(
+        "Price: ${} ({})",
+        response.price_usd.as_deref().unwrap_or("N/A"),
         response.source
     );
+    Ok(ToolResult::blocks(summary, text, &response)?)
+}
+
+/// Result listing the token's CoinGecko price in each requested currency
+fn currency_prices_result(
+    token_address: String,
+    prices: Vec<(String, Option<Decimal>)>,
+) -> Result<ToolResult> {
+    let price_in = |code: &str| {
+        prices
+            .iter()
+            .find(|(currency, _)| currency == code)
+            .and_then(|(_, price)| price.map(|p| p.to_string()))
+    };
+    let response = PriceResponse {
+        price_usd: price_in("usd"),
+        price_eth: price_in("eth"),
+        token_address,
+        source: "CoinGecko".to_string(),
+        block_number: None,
+        pool_liquidity_usd: None,
+        prices: Some(
+            prices
+                .iter()
+                .map(|(currency, price)| {
+                    let price = price.map_or(Value::Null, |p| Value::String(p.to_string()));
+                    (currency.clone(), price)
+                })
+                .collect(),
+        ),
+        feed: None,
+        warnings: Vec::new(),
+    };
+
+    let mut text = format!("Token: {}", response.token_address);
+    for (currency, price) in &prices {
+        text.push_str(&format!(
+            "\nPrice ({}): {}",
+            currency.to_uppercase(),
+            price.map_or("N/A".to_string(), |p| p.to_string())
+        ));
+    }
+    text.push_str(&format!("\nSource: {}", response.source));
 
-    Ok(ToolResult {
-        content: vec![ToolContent::text(text)],
-        is_error: None,
-    })
+    let summary = prices
+        .iter()
+        .map(|(currency, price)| {
+            format!(
+                "{} {}",
+                price.map_or("N/A".to_string(), |p| p.to_string()),
+                currency.to_uppercase()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    Ok(ToolResult::blocks(summary, text, &response)?)
 }
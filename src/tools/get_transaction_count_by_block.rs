@@ -0,0 +1,119 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::ethereum::{get_block_congestion, EthClient, MAX_CONGESTION_BLOCKS};
+use crate::types::{Tool, ToolResult};
+
+#[derive(Debug, Deserialize)]
+pub struct GetTransactionCountByBlockParams {
+    /// How many of the latest blocks to inspect
+    #[serde(default = "default_block_count")]
+    pub block_count: u64,
+}
+
+fn default_block_count() -> u64 {
+    10
+}
+
+#[derive(Debug, Serialize)]
+pub struct BlockActivityResponse {
+    pub block_number: u64,
+    pub timestamp: u64,
+    pub transaction_count: usize,
+    pub gas_used: String,
+    pub gas_limit: String,
+    pub utilization_pct: String,
+    pub base_fee_gwei: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CongestionResponse {
+    /// Oldest block first
+    pub blocks: Vec<BlockActivityResponse>,
+    pub average_utilization_pct: String,
+    pub utilization_trend: String,
+    pub base_fee_trend: Option<String>,
+    pub next_base_fee_gwei: Option<String>,
+}
+
+pub fn get_tool_definition() -> Tool {
+    Tool {
+        name: "get_transaction_count_by_block".to_string(),
+        description: "Get the transaction count and gas utilization (gasUsed/gasLimit) of the latest blocks, with whether congestion and the base fee are rising or falling and the next block's base fee. A cheap congestion signal for deciding whether to wait for cheaper gas."
+            .to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "block_count": {
+                    "type": "integer",
+                    "description": format!("Number of latest blocks to inspect (1-{}). Default: 10", MAX_CONGESTION_BLOCKS),
+                    "default": 10
+                }
+            }
+        }),
+    }
+}
+
+pub async fn execute(
+    provider: &EthClient,
+    params: GetTransactionCountByBlockParams,
+) -> Result<ToolResult> {
+    let report = get_block_congestion(provider, params.block_count).await?;
+
+    let response = CongestionResponse {
+        blocks: report
+            .blocks
+            .iter()
+            .map(|block| BlockActivityResponse {
+                block_number: block.number,
+                timestamp: block.timestamp,
+                transaction_count: block.transaction_count,
+                gas_used: block.gas_used.to_string(),
+                gas_limit: block.gas_limit.to_string(),
+                utilization_pct: block.utilization_pct.to_string(),
+                base_fee_gwei: block.base_fee_gwei.map(|fee| fee.round_dp(4).to_string()),
+            })
+            .collect(),
+        average_utilization_pct: report.average_utilization_pct.to_string(),
+        utilization_trend: report.utilization_trend.name().to_string(),
+        base_fee_trend: report.base_fee_trend.map(|trend| trend.name().to_string()),
+        next_base_fee_gwei: report
+            .next_base_fee_gwei
+            .map(|fee| fee.round_dp(4).to_string()),
+    };
+
+    let mut text = format!(
+        "Block Congestion (last {} blocks):\nAverage Utilization: {}%\nCongestion Trend: {}",
+        response.blocks.len(),
+        response.average_utilization_pct,
+        response.utilization_trend
+    );
+    if let (Some(trend), Some(next)) = (&response.base_fee_trend, &response.next_base_fee_gwei) {
+        text.push_str(&format!(
+            "\nBase Fee Trend: {}\nNext Block Base Fee: {} gwei",
+            trend, next
+        ));
+    }
+    for block in &response.blocks {
+        text.push_str(&format!(
+            "\n- Block {}: {} txs, {}% gas used{}",
+            block.block_number,
+            block.transaction_count,
+            block.utilization_pct,
+            block
+                .base_fee_gwei
+                .as_ref()
+                .map(|fee| format!(", base fee {} gwei", fee))
+                .unwrap_or_default()
+        ));
+    }
+
+    let summary = format!(
+        "Average utilization {}% over {} blocks, trend: {}",
+        response.average_utilization_pct,
+        response.blocks.len(),
+        response.utilization_trend
+    );
+    Ok(ToolResult::blocks(summary, text, &response)?)
+}
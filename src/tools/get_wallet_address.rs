@@ -0,0 +1,34 @@
+use anyhow::Result;
+use ethers::signers::{LocalWallet, Signer};
+use ethers::utils::to_checksum;
+use serde_json::json;
+
+use crate::types::{Tool, ToolResult};
+
+pub fn get_tool_definition() -> Tool {
+    Tool {
+        name: "get_wallet_address".to_string(),
+        description: "Return the address of the wallet configured via PRIVATE_KEY or MNEMONIC. Never returns the key itself."
+            .to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {}
+        }),
+    }
+}
+
+pub fn execute(wallet: Option<&LocalWallet>) -> Result<ToolResult> {
+    let wallet = wallet.ok_or_else(|| {
+        anyhow::anyhow!("No wallet configured. Set PRIVATE_KEY or MNEMONIC to enable this tool")
+    })?;
+
+    let address = to_checksum(&wallet.address(), None);
+    let summary = format!("Wallet address: {}", address);
+    let text = format!("Address: {}\nSigner: local key", address);
+
+    Ok(ToolResult::blocks(
+        summary,
+        text,
+        &json!({ "wallet_address": address }),
+    )?)
+}
@@ -0,0 +1,126 @@
+use anyhow::{Context, Result};
+use ethers::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use tokio::task::JoinSet;
+
+use crate::ethereum::{
+    get_eth_balance, get_token_balance, normalize_eth, EthClient, MetadataCache,
+};
+use crate::types::{Tool, ToolResult};
+
+#[derive(Debug, Deserialize)]
+pub struct GetWatchedBalancesParams {
+    pub token_address: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WatchedBalanceResponse {
+    pub wallet_address: String,
+    pub balance: Option<String>,
+    pub symbol: Option<String>,
+    pub error: Option<String>,
+}
+
+pub fn get_tool_definition() -> Tool {
+    Tool {
+        name: "get_watched_balances".to_string(),
+        description: "Return current ETH or ERC20 balances for every wallet preloaded from WATCH_WALLETS_PATH"
+            .to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "token_address": {
+                    "type": "string",
+                    "description": "Optional ERC20 token contract address. If not provided, returns ETH balances."
+                }
+            }
+        }),
+    }
+}
+
+pub async fn execute(
+    provider: &EthClient,
+    cache: &Arc<MetadataCache>,
+    watched_wallets: &[Address],
+    params: GetWatchedBalancesParams,
+) -> Result<ToolResult> {
+    if watched_wallets.is_empty() {
+        anyhow::bail!(
+            "No watched wallets configured. Set WATCH_WALLETS_PATH to a file of addresses"
+        );
+    }
+
+    let token_address = params
+        .token_address
+        .as_deref()
+        .map(|addr| {
+            addr.parse::<Address>()
+                .map(normalize_eth)
+                .map_err(|e| anyhow::anyhow!("Invalid token address: {}", e))
+        })
+        .transpose()?;
+    // Either native-ETH sentinel asks for the ETH balance
+    let token_address = token_address.filter(|token| !token.is_zero());
+
+    let mut tasks = JoinSet::new();
+    for (index, wallet) in watched_wallets.iter().copied().enumerate() {
+        let provider = provider.clone();
+        let cache = cache.clone();
+        tasks.spawn(async move {
+            let balance = match token_address {
+                Some(token) => get_token_balance(&provider, &cache, token, wallet).await,
+                None => get_eth_balance(&provider, wallet).await,
+            };
+            (index, wallet, balance)
+        });
+    }
+
+    let mut results = Vec::with_capacity(watched_wallets.len());
+    while let Some(joined) = tasks.join_next().await {
+        results.push(joined.context("Balance lookup task failed")?);
+    }
+    results.sort_by_key(|(index, _, _)| *index);
+
+    // One failing wallet shouldn't hide the others' balances
+    let balances: Vec<WatchedBalanceResponse> = results
+        .into_iter()
+        .map(|(_, wallet, balance)| match balance {
+            Ok(info) => WatchedBalanceResponse {
+                wallet_address: format!("{:?}", wallet),
+                balance: Some(info.balance.to_string()),
+                symbol: Some(info.symbol),
+                error: None,
+            },
+            Err(e) => WatchedBalanceResponse {
+                wallet_address: format!("{:?}", wallet),
+                balance: None,
+                symbol: None,
+                error: Some(format!("{:#}", e)),
+            },
+        })
+        .collect();
+
+    let mut text = format!("Watched wallets: {}", balances.len());
+    for entry in &balances {
+        match (&entry.balance, &entry.symbol, &entry.error) {
+            (Some(balance), Some(symbol), _) => text.push_str(&format!(
+                "\n- {}: {} {}",
+                entry.wallet_address, balance, symbol
+            )),
+            (_, _, error) => text.push_str(&format!(
+                "\n- {}: unavailable ({})",
+                entry.wallet_address,
+                error.as_deref().unwrap_or("unknown error")
+            )),
+        }
+    }
+
+    let failed = balances
+        .iter()
+        .filter(|entry| entry.error.is_some())
+        .count();
+    let summary = format!("{} watched wallets, {} unavailable", balances.len(), failed);
+    Ok(ToolResult::blocks(summary, text, &balances)?)
+}
@@ -0,0 +1,98 @@
+use anyhow::Result;
+use ethers::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::ethereum::{inspect_address, ContractKind, EthClient, ProxyKind};
+use crate::types::{Tool, ToolResult};
+
+#[derive(Debug, Deserialize)]
+pub struct IsContractParams {
+    pub address: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProxyResponse {
+    pub kind: ProxyKind,
+    pub target: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IsContractResponse {
+    pub address: String,
+    pub is_contract: bool,
+    pub code_size: usize,
+    pub contract_type: ContractKind,
+    pub interfaces: Vec<&'static str>,
+    pub proxy: Option<ProxyResponse>,
+    pub delegate: Option<String>,
+}
+
+pub fn get_tool_definition() -> Tool {
+    Tool {
+        name: "is_contract".to_string(),
+        description: "Check whether an address is a contract or an externally owned account (EOA), with its code size and a best-effort guess at the contract type (ERC20, ERC721, ERC1155, proxy) from eth_call probes. Use before sending funds or approvals to an unfamiliar address."
+            .to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "address": {
+                    "type": "string",
+                    "description": "Address to inspect (0x...)"
+                }
+            },
+            "required": ["address"]
+        }),
+    }
+}
+
+pub async fn execute(provider: &EthClient, params: IsContractParams) -> Result<ToolResult> {
+    let address = params
+        .address
+        .parse::<Address>()
+        .map_err(|e| anyhow::anyhow!("Invalid address: {}", e))?;
+
+    let info = inspect_address(provider, address).await?;
+    let response = IsContractResponse {
+        address: format!("{:?}", info.address),
+        is_contract: info.is_contract(),
+        code_size: info.code_size,
+        contract_type: info.kind,
+        interfaces: info.interfaces.clone(),
+        proxy: info.proxy.map(|proxy| ProxyResponse {
+            kind: proxy.kind,
+            target: format!("{:?}", proxy.target),
+        }),
+        delegate: info.delegate.map(|delegate| format!("{:?}", delegate)),
+    };
+
+    let mut text = format!(
+        "Address: {}\nContract: {}\nCode Size: {} bytes\nType: {}",
+        response.address,
+        if response.is_contract { "yes" } else { "no" },
+        response.code_size,
+        response.contract_type.label()
+    );
+    if !response.interfaces.is_empty() {
+        text.push_str(&format!("\nInterfaces: {}", response.interfaces.join(", ")));
+    }
+    if let Some(proxy) = &response.proxy {
+        let role = match proxy.kind {
+            ProxyKind::Eip1967Beacon => "beacon",
+            ProxyKind::Eip1967 | ProxyKind::Eip1167 => "implementation",
+        };
+        text.push_str(&format!(
+            "\nProxy: {:?}, {} {}",
+            proxy.kind, role, proxy.target
+        ));
+    }
+    if let Some(delegate) = &response.delegate {
+        text.push_str(&format!("\nDelegates To: {}", delegate));
+    }
+    if response.is_contract && response.contract_type == ContractKind::Unknown {
+        text.push_str("\nNote: the type is a guess from standard interfaces; none answered");
+    }
+
+    let summary = format!("{}: {}", response.address, response.contract_type.label());
+    Ok(ToolResult::blocks(summary, text, &response)?)
+}
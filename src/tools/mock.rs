@@ -0,0 +1,265 @@
+use anyhow::Result;
+use ethers::prelude::*;
+use rust_decimal::Decimal;
+
+use super::get_balance::GetBalanceParams;
+use super::get_token_price::GetTokenPriceParams;
+use super::swap_tokens::{SwapTokensParams, DEFAULT_SLIPPAGE_BPS};
+use crate::ethereum::{normalize_eth, QuoteCurrency};
+use crate::types::{ToolContent, ToolResult};
+
+/// First line of every offline response, so canned values are never mistaken for chain data
+pub const MOCK_LABEL: &str = "[MOCK DATA - OFFLINE_MODE, not from the blockchain]";
+
+/// Tools that return canned values in offline mode; all others are refused
+pub const MOCK_TOOLS: &[&str] = &[
+    "get_balance",
+    "get_token_price",
+    "get_eth_price",
+    "swap_tokens",
+];
+
+/// Fixed prices so offline quotes are deterministic
+const MOCK_ETH_PRICE_USD: u64 = 2000;
+const MOCK_TOKEN_PRICE_USD: u64 = 1;
+
+/// Canned balances: 10 ETH, or 1000 of any token
+const MOCK_ETH_BALANCE: u64 = 10;
+const MOCK_TOKEN_BALANCE: u64 = 1000;
+
+const MOCK_SWAP_GAS: u64 = 150_000;
+
+fn mock_result(text: String) -> ToolResult {
+    ToolResult {
+        content: vec![ToolContent::text(format!("{}\n{}", MOCK_LABEL, text))],
+        is_error: None,
+    }
+}
+
+fn parse_address(value: &str, what: &str) -> Result<Address> {
+    value
+        .parse::<Address>()
+        .map_err(|e| anyhow::anyhow!("Invalid {}: {}", what, e))
+}
+
+/// A token address, with either native-ETH sentinel mapped to `Address::zero()`
+fn parse_token(value: &str, what: &str) -> Result<Address> {
+    parse_address(value, what).map(normalize_eth)
+}
+
+fn mock_price_usd(token: Address) -> Decimal {
+    if token == Address::zero() {
+        Decimal::from(MOCK_ETH_PRICE_USD)
+    } else {
+        Decimal::from(MOCK_TOKEN_PRICE_USD)
+    }
+}
+
+pub fn get_balance(params: GetBalanceParams) -> Result<ToolResult> {
+    parse_address(&params.wallet_address, "wallet address")?;
+    let token_address = params
+        .token_address
+        .as_deref()
+        .map(|token| parse_token(token, "token address"))
+        .transpose()?
+        .filter(|token| !token.is_zero());
+
+    let is_eth = token_address.is_none()
+        && params
+            .token_symbol
+            .as_deref()
+            .is_none_or(|symbol| symbol.eq_ignore_ascii_case("ETH"));
+    let (balance, symbol) = if is_eth {
+        (MOCK_ETH_BALANCE, "ETH".to_string())
+    } else {
+        let symbol = params
+            .token_symbol
+            .map(|symbol| symbol.to_uppercase())
+            .unwrap_or_else(|| "MOCK".to_string());
+        (MOCK_TOKEN_BALANCE, symbol)
+    };
+
+    let mut text = format!(
+        "Balance: {} {}\nDecimals: 18\nWallet: {}\nRaw balance: {}",
+        balance,
+        symbol,
+        params.wallet_address,
+        U256::from(balance) * U256::exp10(18)
+    );
+    if let Some(currency) = params.quote_currency.as_deref() {
+        let price_usd = if is_eth {
+            MOCK_ETH_PRICE_USD
+        } else {
+            MOCK_TOKEN_PRICE_USD
+        };
+        let value_usd = Decimal::from(balance * price_usd);
+        let value = match currency.parse::<QuoteCurrency>()? {
+            QuoteCurrency::Usd => value_usd,
+            QuoteCurrency::Eth => value_usd / Decimal::from(MOCK_ETH_PRICE_USD),
+        };
+        text.push_str(&format!(
+            "\nValue: {} {}",
+            value.normalize(),
+            currency.to_uppercase()
+        ));
+    }
+
+    Ok(mock_result(text))
+}
+
+pub fn get_token_price(params: GetTokenPriceParams) -> Result<ToolResult> {
+    let token = parse_token(&params.token_address, "token address")?;
+    let price_usd = mock_price_usd(token);
+
+    Ok(mock_result(format!(
+        "Token: {}\nPrice (USD): ${}\nPrice (ETH): {}\nSource: Mock",
+        params.token_address,
+        price_usd,
+        (price_usd / Decimal::from(MOCK_ETH_PRICE_USD)).normalize()
+    )))
+}
+
+pub fn get_eth_price() -> Result<ToolResult> {
+    Ok(mock_result(format!(
+        "ETH Price (USD): ${}\nSource: Mock",
+        MOCK_ETH_PRICE_USD
+    )))
+}
+
+/// Quote at the fixed mock prices with no fees or price impact
+pub fn swap_tokens(params: SwapTokensParams) -> Result<ToolResult> {
+    let from_token = parse_token(&params.from_token, "from_token address")?;
+    let to_token = parse_token(&params.to_token, "to_token address")?;
+    if let Some(wallet) = &params.wallet_address {
+        parse_address(wallet, "wallet address")?;
+    }
+    if let Some(recipient) = &params.recipient {
+        parse_address(recipient, "recipient address")?;
+    }
+    // Mock pools have no history to suggest slippage from
+    let slippage_bps = params.slippage_bps.unwrap_or(DEFAULT_SLIPPAGE_BPS);
+    if slippage_bps >= 10_000 {
+        anyhow::bail!(
+            "Slippage of {} bps must be below 100% (10000 bps)",
+            slippage_bps
+        );
+    }
+
+    let from_price = mock_price_usd(from_token);
+    let amount: Decimal = match (params.amount, params.amount_usd) {
+        (Some(amount), None) => amount.value(),
+        (None, Some(usd)) => (usd.value() / from_price).round_dp(18).normalize(),
+        (Some(_), Some(_)) => anyhow::bail!("Provide either amount or amount_usd, not both"),
+        (None, None) => anyhow::bail!("Missing amount: provide amount or amount_usd"),
+    };
+
+    let output = (amount * from_price / mock_price_usd(to_token))
+        .round_dp(18)
+        .normalize();
+    let minimum_output = (output * Decimal::from(10_000 - slippage_bps) / Decimal::from(10_000))
+        .round_dp(18)
+        .normalize();
+
+    Ok(mock_result(format!(
+        "Swap Simulation:\n\
+        From: {}\n\
+        To: {}\n\
+        Amount In: {}\n\
+        Estimated Output: {}\n\
+        Minimum Output (with slippage): {}\n\
+        Price Impact: 0%\n\
+        Estimated Gas: {}\n\
+        Slippage Tolerance: {} bps ({}%)\n\
+        Route: {} -> {}\n\
+        Venue: Mock",
+        params.from_token,
+        params.to_token,
+        amount,
+        output,
+        minimum_output,
+        MOCK_SWAP_GAS,
+        slippage_bps,
+        (slippage_bps as f64) / 100.0,
+        params.from_token,
+        params.to_token
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ETH: &str = "0x0000000000000000000000000000000000000000";
+    const ETH_SENTINEL: &str = "0xEeeeeEeeeEeEeeEeEeEeeEEEeeeeEeeeeeeeEEeE";
+    const USDC: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+    const WALLET: &str = "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045";
+
+    fn text(result: ToolResult) -> String {
+        result.content[0].text.clone()
+    }
+
+    #[test]
+    fn test_mock_swap_is_deterministic_and_labeled() {
+        let params: SwapTokensParams = serde_json::from_value(serde_json::json!({
+            "from_token": ETH,
+            "to_token": USDC,
+            "amount": "1.5",
+            "slippage_bps": 100,
+            "wallet_address": WALLET
+        }))
+        .unwrap();
+        let text = text(swap_tokens(params).unwrap());
+
+        assert!(text.starts_with(MOCK_LABEL));
+        assert!(text.contains("Estimated Output: 3000\n"));
+        assert!(text.contains("Minimum Output (with slippage): 2970\n"));
+    }
+
+    #[test]
+    fn test_mock_swap_accepts_both_eth_sentinels() {
+        for eth in [ETH, ETH_SENTINEL] {
+            let params: SwapTokensParams = serde_json::from_value(serde_json::json!({
+                "from_token": eth,
+                "to_token": USDC,
+                "amount": "1",
+                "wallet_address": WALLET
+            }))
+            .unwrap();
+            assert!(text(swap_tokens(params).unwrap()).contains("Estimated Output: 2000\n"));
+        }
+    }
+
+    #[test]
+    fn test_mock_swap_without_wallet() {
+        let params: SwapTokensParams = serde_json::from_value(serde_json::json!({
+            "from_token": ETH,
+            "to_token": USDC,
+            "amount": "1"
+        }))
+        .unwrap();
+        assert!(params.wallet_address.is_none());
+        assert!(text(swap_tokens(params).unwrap()).contains("Estimated Output: 2000\n"));
+    }
+
+    #[test]
+    fn test_mock_balance() {
+        let params: GetBalanceParams =
+            serde_json::from_value(serde_json::json!({ "wallet_address": WALLET })).unwrap();
+        let text = text(get_balance(params).unwrap());
+        assert!(text.contains("Balance: 10 ETH"));
+
+        let params: GetBalanceParams = serde_json::from_value(serde_json::json!({
+            "wallet_address": WALLET,
+            "quote_currency": "usd"
+        }))
+        .unwrap();
+        let valued = get_balance(params).unwrap();
+        assert!(valued.content[0].text.contains("Value: 20000 USD"));
+
+        let params: GetBalanceParams = serde_json::from_value(serde_json::json!({
+            "wallet_address": "0x123"
+        }))
+        .unwrap();
+        assert!(get_balance(params).is_err());
+    }
+}
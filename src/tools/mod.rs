@@ -1,6 +1,34 @@
+pub mod build_swap_transaction;
+pub mod call_contract;
+pub mod check_price_alerts;
+pub mod compare_dex_quotes;
+pub mod compare_gas_across_chains;
+pub mod decode_calldata;
+pub mod ens_resolve;
+pub mod ens_reverse;
+pub mod get_average_price;
 pub mod get_balance;
+pub mod get_eip1559_fee_history;
+pub mod get_eth_price;
+pub mod get_gas_price;
+pub mod get_logs;
+pub mod get_max_swap_size;
+pub mod get_mempool_flow;
+pub mod get_pair_address;
+pub mod get_pool_swaps;
+pub mod get_portfolio_value;
+pub mod get_storage_at;
+pub mod get_swap_route_graph;
 pub mod get_token_price;
+pub mod get_transaction_count_by_block;
+pub mod get_wallet_address;
+pub mod get_watched_balances;
+pub mod is_contract;
+pub mod mock;
+pub mod set_price_alert;
+pub mod simulate_swap_effects;
 pub mod swap_tokens;
+pub mod wait_for_transaction;
 
 use crate::types::Tool;
 
@@ -8,6 +36,33 @@ pub fn get_all_tools() -> Vec<Tool> {
     vec![
         get_balance::get_tool_definition(),
         get_token_price::get_tool_definition(),
+        get_eth_price::get_tool_definition(),
         swap_tokens::get_tool_definition(),
+        get_logs::get_tool_definition(),
+        wait_for_transaction::get_tool_definition(),
+        get_average_price::get_tool_definition(),
+        get_wallet_address::get_tool_definition(),
+        get_watched_balances::get_tool_definition(),
+        get_gas_price::get_tool_definition(),
+        get_portfolio_value::get_tool_definition(),
+        build_swap_transaction::get_tool_definition(),
+        get_pair_address::get_tool_definition(),
+        call_contract::get_tool_definition(),
+        set_price_alert::get_tool_definition(),
+        check_price_alerts::get_tool_definition(),
+        get_swap_route_graph::get_tool_definition(),
+        get_max_swap_size::get_tool_definition(),
+        get_mempool_flow::get_tool_definition(),
+        get_transaction_count_by_block::get_tool_definition(),
+        get_storage_at::get_tool_definition(),
+        compare_dex_quotes::get_tool_definition(),
+        simulate_swap_effects::get_tool_definition(),
+        ens_resolve::get_tool_definition(),
+        ens_reverse::get_tool_definition(),
+        get_eip1559_fee_history::get_tool_definition(),
+        decode_calldata::get_tool_definition(),
+        is_contract::get_tool_definition(),
+        get_pool_swaps::get_tool_definition(),
+        compare_gas_across_chains::get_tool_definition(),
     ]
 }
@@ -0,0 +1,90 @@
+use anyhow::Result;
+use ethers::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::ethereum::{normalize_eth, AlertDirection, PriceAlerts};
+use crate::types::{Amount, Tool, ToolResult};
+
+#[derive(Debug, Deserialize)]
+pub struct SetPriceAlertParams {
+    pub token_address: String,
+    /// USD price to compare against
+    pub threshold_usd: Amount,
+    /// "above" or "below"
+    pub direction: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SetPriceAlertResponse {
+    pub alert_id: u64,
+    pub token_address: String,
+    pub direction: AlertDirection,
+    pub threshold_usd: Amount,
+    pub pending_alerts: usize,
+}
+
+pub fn get_tool_definition() -> Tool {
+    Tool {
+        name: "set_price_alert".to_string(),
+        description: "Register a price alert on a token. Poll check_price_alerts to receive alerts once their condition is met; each alert fires once."
+            .to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "token_address": {
+                    "type": "string",
+                    "description": "Token to watch (0x...). Use 0x0000000000000000000000000000000000000000 for ETH."
+                },
+                "threshold_usd": {
+                    "type": "string",
+                    "description": "USD price threshold (e.g., '3000')"
+                },
+                "direction": {
+                    "type": "string",
+                    "enum": ["above", "below"],
+                    "description": "Trigger when the price is at or above, or at or below, the threshold"
+                }
+            },
+            "required": ["token_address", "threshold_usd", "direction"]
+        }),
+    }
+}
+
+pub fn execute(alerts: &PriceAlerts, params: SetPriceAlertParams) -> Result<ToolResult> {
+    let token = params
+        .token_address
+        .parse::<Address>()
+        .map(normalize_eth)
+        .map_err(|e| anyhow::anyhow!("Invalid token address: {}", e))?;
+
+    let direction: AlertDirection = params.direction.parse()?;
+
+    let alert = alerts.add(token, direction, params.threshold_usd.value())?;
+
+    let response = SetPriceAlertResponse {
+        alert_id: alert.id,
+        token_address: format!("{:?}", alert.token),
+        direction: alert.direction,
+        threshold_usd: Amount::new(alert.threshold_usd),
+        pending_alerts: alerts.pending_count(),
+    };
+
+    let text = format!(
+        "Alert #{} set: {} {} ${}\nPending alerts: {}",
+        response.alert_id,
+        response.token_address,
+        params.direction.to_lowercase(),
+        response.threshold_usd,
+        response.pending_alerts
+    );
+
+    let summary = format!(
+        "Alert #{} set: {} {} ${}",
+        response.alert_id,
+        response.token_address,
+        params.direction.to_lowercase(),
+        response.threshold_usd
+    );
+    Ok(ToolResult::blocks(summary, text, &response)?)
+}
@@ -0,0 +1,170 @@
+use anyhow::Result;
+use ethers::prelude::*;
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+use super::swap_tokens::{
+    self, confirmation_request, refresh_metadata, require_wallet, resolve_amount, swap_request,
+    version_label, SwapTokensParams,
+};
+use crate::config::Config;
+use crate::ethereum::{simulate_swap_effects, EthClient, MetadataCache};
+use crate::types::{Amount, Tool, ToolResult};
+
+#[derive(Debug, Serialize)]
+pub struct BalanceChangeResponse {
+    pub token: String,
+    pub symbol: String,
+    pub before: Amount,
+    pub after: Amount,
+    pub change: Amount,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SwapEffectsResponse {
+    pub wallet_address: String,
+    pub amount_in: Amount,
+    pub estimated_output: Amount,
+    pub minimum_output: Amount,
+    pub price_impact: Amount,
+    pub estimated_cost_eth: Amount,
+    pub version: String,
+    pub fee_tier: Option<u32>,
+    pub route: Vec<String>,
+    /// From-token, to-token, then ETH; ETH appears once when it is one side of the swap
+    pub balances: Vec<BalanceChangeResponse>,
+    pub warnings: Vec<String>,
+}
+
+pub fn get_tool_definition() -> Tool {
+    // Same inputs as swap_tokens, minus the aggregator cross-check, which doesn't move balances
+    let mut input_schema = swap_tokens::get_tool_definition().input_schema;
+    if let Some(properties) = input_schema["properties"].as_object_mut() {
+        properties.retain(|key, _| key != "compare_aggregator");
+    }
+    input_schema["properties"]["wallet_address"]["description"] =
+        "Wallet whose balances to project (0x...)".into();
+    // Only swap_tokens can fall back to the default simulation wallet
+    if let Some(required) = input_schema["required"].as_array_mut() {
+        required.push("wallet_address".into());
+    }
+
+    Tool {
+        name: "simulate_swap_effects".to_string(),
+        description: "Simulate a Uniswap swap and show its net effect on the wallet: current and projected balances of the from-token, the to-token, and ETH after gas. Returns estimates only, without executing."
+            .to_string(),
+        input_schema,
+    }
+}
+
+pub async fn execute(
+    provider: &EthClient,
+    cache: &MetadataCache,
+    http: &reqwest::Client,
+    config: &Config,
+    signer: Option<Address>,
+    params: SwapTokensParams,
+) -> Result<ToolResult> {
+    let wallet_address = require_wallet(&params)?.to_string();
+    let (amount, _) = resolve_amount(provider, http, config, &params).await?;
+    let request = swap_request(&params, amount, config, signer)?;
+    if params.refresh {
+        refresh_metadata(cache, &request);
+    }
+    let effects = match simulate_swap_effects(provider, cache, request).await {
+        Ok(effects) => effects,
+        Err(e) => return confirmation_request("simulate_swap_effects", e),
+    };
+
+    let simulation = &effects.simulation;
+    let mut warnings = simulation.warnings.clone();
+    for change in effects
+        .changes
+        .iter()
+        .filter(|change| change.after < Decimal::ZERO)
+    {
+        warnings.push(format!(
+            "Projected {} balance is negative: the wallet holds {} but the swap needs {}",
+            change.symbol,
+            change.before,
+            -change.change()
+        ));
+    }
+
+    let response = SwapEffectsResponse {
+        wallet_address,
+        amount_in: Amount::new(amount),
+        estimated_output: Amount::new(simulation.estimated_output),
+        minimum_output: Amount::new(simulation.minimum_output),
+        price_impact: Amount::with_decimals(simulation.price_impact, 4),
+        estimated_cost_eth: Amount::with_decimals(simulation.estimated_cost_eth, 8),
+        version: version_label(simulation.version),
+        fee_tier: simulation.fee_tier,
+        route: simulation
+            .route
+            .iter()
+            .map(|addr| format!("{:?}", addr))
+            .collect(),
+        balances: effects
+            .changes
+            .iter()
+            .map(|change| BalanceChangeResponse {
+                token: format!("{:?}", change.token),
+                symbol: change.symbol.clone(),
+                before: Amount::new(change.before),
+                after: Amount::new(change.after),
+                change: Amount::new(change.change()),
+            })
+            .collect(),
+        warnings,
+    };
+
+    let mut text = format!(
+        "Swap Effects for {}:\n\
+        Amount In: {}\n\
+        Estimated Output: {}\n\
+        Minimum Output (with slippage): {}\n\
+        Price Impact: {}%\n\
+        Estimated Cost: {} ETH\n\
+        Route: {}\n\
+        Venue: Uniswap {}{}\n\
+        Balances:",
+        response.wallet_address,
+        response.amount_in,
+        response.estimated_output,
+        response.minimum_output,
+        response.price_impact,
+        response.estimated_cost_eth,
+        response.route.join(" -> "),
+        response.version.to_uppercase(),
+        response
+            .fee_tier
+            .map(|fee| format!(" (fee tier {})", fee))
+            .unwrap_or_default()
+    );
+    for balance in &response.balances {
+        text.push_str(&format!(
+            "\n- {}: {} -> {} ({}{})",
+            balance.symbol,
+            balance.before,
+            balance.after,
+            if balance.change.value() < Decimal::ZERO {
+                ""
+            } else {
+                "+"
+            },
+            balance.change
+        ));
+    }
+    for warning in &response.warnings {
+        text.push_str(&format!("\nWarning: {}", warning));
+    }
+
+    let summary = format!(
+        "{} in, {} out, {} warnings",
+        response.amount_in,
+        response.estimated_output,
+        response.warnings.len()
+    );
+    Ok(ToolResult::blocks(summary, text, &response)?)
+}
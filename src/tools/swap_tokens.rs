@@ -1,42 +1,122 @@
 use anyhow::Result;
 use ethers::prelude::*;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
-use crate::ethereum::{simulate_swap, EthClient};
-use crate::types::{Tool, ToolContent, ToolResult};
+use crate::config::Config;
+use crate::ethereum::{
+    get_aggregator_quote, get_token_price, normalize_eth, simulate_swap, EthClient, MetadataCache,
+    PriceSource, SwapError, SwapRequest, UniswapVersion,
+};
+use crate::types::{Amount, Tool, ToolResult};
 
 #[derive(Debug, Deserialize)]
 pub struct SwapTokensParams {
     pub from_token: String,
     pub to_token: String,
-    pub amount: String,
-    #[serde(default = "default_slippage")]
-    pub slippage_bps: u32,
-    pub wallet_address: String,
+    /// Amount in from-token units; exactly one of `amount` and `amount_usd` is required
+    pub amount: Option<Amount>,
+    /// Amount in US dollars, converted to from-token units at the current price
+    pub amount_usd: Option<Amount>,
+    /// Slippage tolerance; `DEFAULT_SLIPPAGE_BPS` unless given or suggested by `auto_slippage`
+    pub slippage_bps: Option<u32>,
+    /// Suggest a slippage from the pair's recent volatility when `slippage_bps` is omitted
+    #[serde(default)]
+    pub auto_slippage: bool,
+    /// Wallet to simulate from; `swap_tokens` falls back to `SIMULATION_FROM` when omitted
+    pub wallet_address: Option<String>,
+    /// Account to receive the output, when it isn't the wallet paying for the swap
+    pub recipient: Option<String>,
+    /// Uniswap version to quote against ("v2" or "v3")
+    pub version: Option<String>,
+    /// Pin a V3 fee tier (100, 500, 3000, 10000) instead of searching all tiers
+    pub fee_tier: Option<u32>,
+    /// Split the input across V2 and V3 to minimize price impact
+    #[serde(default)]
+    pub split: bool,
+    /// Cross-check the quote against the 0x aggregator
+    #[serde(default)]
+    pub compare_aggregator: bool,
+    /// Explicit route (token addresses, from_token first) overriding automatic path building
+    pub path: Option<Vec<String>>,
+    /// Acknowledge a price impact above the confirmation threshold
+    #[serde(default)]
+    pub confirm_high_impact: bool,
+    /// Quote with a flat gas figure when gas estimation fails
+    #[serde(default)]
+    pub allow_gas_fallback: bool,
+    /// Re-read the tokens' decimals and symbols instead of using cached values
+    #[serde(default)]
+    pub refresh: bool,
 }
 
-fn default_slippage() -> u32 {
-    50 // 0.5% default slippage
-}
+/// 0.5%, used when neither `slippage_bps` nor `auto_slippage` is given
+pub const DEFAULT_SLIPPAGE_BPS: u32 = 50;
 
 #[derive(Debug, Serialize)]
 pub struct SwapResponse {
     pub from_token: String,
     pub to_token: String,
-    pub amount_in: String,
-    pub estimated_output: String,
-    pub minimum_output: String,
+    pub amount_in: Amount,
+    /// USD figure the input amount was derived from, when given as `amount_usd`
+    pub amount_usd: Option<Amount>,
+    pub estimated_output: Amount,
+    /// To-token received per from-token, `estimated_output / amount_in`
+    pub exchange_rate: Amount,
+    /// From-token paid per to-token
+    pub inverse_rate: Amount,
+    /// From-token's USD price implied by the quote and the to-token's market price
+    pub implied_from_price_usd: Option<Amount>,
+    /// To-token's USD price implied by the quote and the from-token's market price
+    pub implied_to_price_usd: Option<Amount>,
+    pub minimum_output: Amount,
+    pub price_impact: Amount,
     pub estimated_gas: String,
+    /// Gas for the approve transactions needed before the swap, when the allowance is short
+    pub approval_gas: Option<String>,
+    /// Swap and approval gas at the current gas price
+    pub estimated_cost_eth: Amount,
     pub slippage_bps: u32,
+    /// Why `slippage_bps` was suggested, when `auto_slippage` picked it
+    pub auto_slippage: Option<AutoSlippageResponse>,
     pub route: Vec<String>,
+    pub version: String,
+    pub fee_tier: Option<u32>,
+    pub split: Option<Vec<SplitLegResponse>>,
+    /// 0x aggregator output for the same trade, when requested and available
+    pub reference_output: Option<Amount>,
+    pub reference_note: Option<String>,
+    /// Default wallet gas was estimated from, when the call gave no `wallet_address`
+    pub simulation_from: Option<String>,
+    /// Account the output is sent to, when the call named one
+    pub recipient: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AutoSlippageResponse {
+    /// Suggestion before `MIN_SLIPPAGE_BPS` is applied
+    pub suggested_bps: u32,
+    /// How far the route's rate moved over recent blocks, if they could be sampled
+    pub movement_bps: Option<String>,
+    pub reasoning: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SplitLegResponse {
+    pub version: String,
+    pub fee_tier: Option<u32>,
+    pub share_pct: u32,
+    pub amount_in: Amount,
+    pub estimated_output: Amount,
 }
 
 pub fn get_tool_definition() -> Tool {
     Tool {
         name: "swap_tokens".to_string(),
         description:
-            "Simulate a token swap on Uniswap V2. Returns estimated output and gas costs without executing."
+            "Simulate a token swap on Uniswap V2 or V3. Returns estimated output and gas costs without executing."
                 .to_string(),
         input_schema: json!({
             "type": "object",
@@ -51,88 +131,558 @@ pub fn get_tool_definition() -> Tool {
                 },
                 "amount": {
                     "type": "string",
-                    "description": "Amount to swap (in token units, e.g., '1.5' for 1.5 tokens)"
+                    "description": "Amount to swap (in token units, e.g., '1.5' for 1.5 tokens). Provide this or amount_usd."
+                },
+                "amount_usd": {
+                    "type": "string",
+                    "description": "Amount to swap in US dollars (e.g., '250'), converted to from_token units at the current price. Provide this or amount."
                 },
                 "slippage_bps": {
                     "type": "number",
-                    "description": "Slippage tolerance in basis points (e.g., 50 = 0.5%). Default: 50",
+                    "description": "Slippage tolerance in basis points (e.g., 50 = 0.5%). Default: 50, or a suggestion when auto_slippage is set",
                     "default": 50
                 },
+                "auto_slippage": {
+                    "type": "boolean",
+                    "description": "Set to true to suggest slippage from the pair's recent rate movement and price impact when slippage_bps is omitted: stable pairs get less, volatile or thin pairs more. The suggestion and its reasoning are returned. An explicit slippage_bps always wins. Default: false",
+                    "default": false
+                },
                 "wallet_address": {
                     "type": "string",
-                    "description": "Wallet address for simulation (0x...)"
+                    "description": "Wallet address for simulation (0x...). Optional: when omitted, gas is estimated from the SIMULATION_FROM default wallet (0x...dEaD) and the response's simulation_from says so"
+                },
+                "recipient": {
+                    "type": "string",
+                    "description": "Address to receive the swap output (0x...), e.g. a smart wallet or another account. Passed as the router call's recipient. Default: wallet_address"
+                },
+                "version": {
+                    "type": "string",
+                    "enum": ["v2", "v3"],
+                    "description": "Uniswap version to quote against. Default: v2 (v3 when fee_tier is set)"
+                },
+                "fee_tier": {
+                    "type": "number",
+                    "enum": [100, 500, 3000, 10000],
+                    "description": "Optional V3 fee tier. If omitted, all standard tiers are quoted and the best is used."
+                },
+                "split": {
+                    "type": "boolean",
+                    "description": "Evaluate splitting the input across Uniswap V2 and V3 to minimize price impact. Default: false",
+                    "default": false
+                },
+                "compare_aggregator": {
+                    "type": "boolean",
+                    "description": "Also fetch a reference quote from the 0x aggregator to check the route is competitive. Requires ZEROX_API_KEY. Default: false",
+                    "default": false
+                },
+                "path": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Optional explicit route as token addresses, starting at from_token (WETH when selling ETH) and ending at to_token (WETH when buying ETH). Multi-hop paths are quoted on Uniswap V2 only."
+                },
+                "confirm_high_impact": {
+                    "type": "boolean",
+                    "description": "Set to true to proceed when price impact exceeds the high-impact confirmation threshold. Without it, such a swap returns a confirmation request instead of a quote. Default: false",
+                    "default": false
+                },
+                "allow_gas_fallback": {
+                    "type": "boolean",
+                    "description": "Set to true to quote with a flat 300000 gas estimate when gas estimation fails (e.g. the wallet lacks the balance), instead of returning the revert error. Default: false",
+                    "default": false
+                },
+                "refresh": {
+                    "type": "boolean",
+                    "description": "Re-read the decimals and symbols of the tokens on the route from the chain instead of using cached metadata. Default: false",
+                    "default": false
                 }
             },
-            "required": ["from_token", "to_token", "amount", "wallet_address"]
+            "required": ["from_token", "to_token"]
         }),
     }
 }
 
-pub async fn execute(provider: &EthClient, params: SwapTokensParams) -> Result<ToolResult> {
-    let from_token = params
-        .from_token
-        .parse::<Address>()
-        .map_err(|e| anyhow::anyhow!("Invalid from_token address: {}", e))?;
+pub async fn execute(
+    provider: &EthClient,
+    cache: &MetadataCache,
+    http: &reqwest::Client,
+    config: &Config,
+    chain_id: u64,
+    signer: Option<Address>,
+    params: SwapTokensParams,
+) -> Result<ToolResult> {
+    let (amount, usd_conversion) = resolve_amount(provider, http, config, &params).await?;
+    let request = swap_request(&params, amount, config, signer)?;
+    if params.refresh {
+        refresh_metadata(cache, &request);
+    }
+    let (from_token, to_token, recipient) =
+        (request.from_token, request.to_token, request.recipient);
 
-    let to_token = params
-        .to_token
-        .parse::<Address>()
-        .map_err(|e| anyhow::anyhow!("Invalid to_token address: {}", e))?;
+    let simulation = match simulate_swap(provider, cache, request).await {
+        Ok(simulation) => simulation,
+        Err(e) => return confirmation_request("swap_tokens", e),
+    };
 
-    let wallet_address = params
-        .wallet_address
-        .parse::<Address>()
-        .map_err(|e| anyhow::anyhow!("Invalid wallet address: {}", e))?;
+    // The reference quote is best-effort; failures are reported, never fatal
+    let (reference_output, reference_note) = if !params.compare_aggregator {
+        (None, None)
+    } else if let Some(api_key) = &config.zerox_api_key {
+        match get_aggregator_quote(
+            http,
+            api_key,
+            chain_id,
+            from_token,
+            to_token,
+            simulation.amount_in_raw,
+            simulation.to_decimals,
+        )
+        .await
+        {
+            Ok(output) => {
+                let diff_pct = if output.is_zero() {
+                    None
+                } else {
+                    Some(
+                        ((simulation.estimated_output - output) / output
+                            * rust_decimal::Decimal::from(100))
+                        .round_dp(2),
+                    )
+                };
+                (
+                    Some(Amount::new(output)),
+                    diff_pct.map(|d| format!("Our route is {}% vs 0x", d)),
+                )
+            }
+            Err(e) => {
+                tracing::warn!("0x reference quote unavailable: {}", e);
+                (None, Some(format!("0x reference unavailable: {}", e)))
+            }
+        }
+    } else {
+        (
+            None,
+            Some("0x reference unavailable: ZEROX_API_KEY not configured".to_string()),
+        )
+    };
 
-    let amount = params
-        .amount
-        .parse()
-        .map_err(|e| anyhow::anyhow!("Invalid amount: {}", e))?;
+    // Zero amounts and outputs are rejected before here; checked_div only keeps that from panicking
+    let exchange_rate = simulation
+        .estimated_output
+        .checked_div(amount)
+        .unwrap_or_default();
+    let inverse_rate = amount
+        .checked_div(simulation.estimated_output)
+        .unwrap_or_default();
 
-    let simulation = simulate_swap(
-        provider,
-        from_token,
-        to_token,
-        amount,
-        params.slippage_bps,
-        wallet_address,
-    )
-    .await?;
+    // Each token is valued through the other's market price; either may be unavailable. A
+    // dollar amount was already converted at the from-token's price, so that one is reused
+    let from_price = async {
+        match usd_conversion {
+            Some(conversion) => Some(conversion.price_usd),
+            None => market_price_usd(provider, http, config, from_token).await,
+        }
+    };
+    let (from_price, to_price) = tokio::join!(
+        from_price,
+        market_price_usd(provider, http, config, to_token)
+    );
+    let implied_price =
+        |price: Option<Decimal>, rate: Decimal| price.map(|p| Amount::with_decimals(p * rate, 6));
 
     let response = SwapResponse {
         from_token: params.from_token,
         to_token: params.to_token,
-        amount_in: params.amount,
-        estimated_output: simulation.estimated_output.to_string(),
-        minimum_output: simulation.minimum_output.to_string(),
+        amount_in: Amount::new(amount),
+        amount_usd: usd_conversion.map(|conversion| Amount::new(conversion.amount_usd)),
+        estimated_output: Amount::new(simulation.estimated_output),
+        exchange_rate: Amount::new(exchange_rate.round_dp(18).normalize()),
+        inverse_rate: Amount::new(inverse_rate.round_dp(18).normalize()),
+        implied_from_price_usd: implied_price(to_price, exchange_rate),
+        implied_to_price_usd: implied_price(from_price, inverse_rate),
+        minimum_output: Amount::new(simulation.minimum_output),
+        price_impact: Amount::with_decimals(simulation.price_impact, 4),
         estimated_gas: simulation.estimated_gas.to_string(),
-        slippage_bps: params.slippage_bps,
-        route: simulation.route.iter().map(|addr| format!("{:?}", addr)).collect(),
+        approval_gas: simulation.approval_gas.map(|gas| gas.to_string()),
+        estimated_cost_eth: Amount::with_decimals(simulation.estimated_cost_eth, 8),
+        slippage_bps: simulation.slippage_bps,
+        auto_slippage: simulation
+            .slippage_suggestion
+            .map(|suggestion| AutoSlippageResponse {
+                suggested_bps: suggestion.slippage_bps,
+                movement_bps: suggestion.movement_bps.map(|bps| bps.to_string()),
+                reasoning: suggestion.reasoning,
+            }),
+        route: simulation
+            .route
+            .iter()
+            .map(|addr| format!("{:?}", addr))
+            .collect(),
+        version: version_label(simulation.version),
+        fee_tier: simulation.fee_tier,
+        split: simulation.split.map(|legs| {
+            legs.into_iter()
+                .map(|leg| SplitLegResponse {
+                    version: version_label(leg.version),
+                    fee_tier: leg.fee_tier,
+                    share_pct: leg.share_pct,
+                    amount_in: Amount::new(leg.amount_in),
+                    estimated_output: Amount::new(leg.estimated_output),
+                })
+                .collect()
+        }),
+        reference_output,
+        reference_note,
+        simulation_from: params
+            .wallet_address
+            .is_none()
+            .then(|| format!("{:?}", config.simulation_from)),
+        recipient: recipient.map(|recipient| format!("{:?}", recipient)),
+        warnings: simulation.warnings,
     };
 
-    let text = format!(
+    let mut text = format!(
         "Swap Simulation:\n\
         From: {}\n\
         To: {}\n\
         Amount In: {}\n\
         Estimated Output: {}\n\
         Minimum Output (with slippage): {}\n\
-        Estimated Gas: {}\n\
+        Price Impact: {}%\n\
+        Estimated Gas: {}{}\n\
+        Estimated Cost: {} ETH\n\
         Slippage Tolerance: {} bps ({}%)\n\
-        Route: {}",
+        Route: {}\n\
+        Venue: Uniswap {}{}",
         response.from_token,
         response.to_token,
         response.amount_in,
         response.estimated_output,
         response.minimum_output,
+        response.price_impact,
         response.estimated_gas,
+        response
+            .approval_gas
+            .as_ref()
+            .map(|gas| format!(" (+{} for the approval)", gas))
+            .unwrap_or_default(),
+        response.estimated_cost_eth,
         response.slippage_bps,
         (response.slippage_bps as f64) / 100.0,
-        response.route.join(" -> ")
+        response.route.join(" -> "),
+        response.version.to_uppercase(),
+        response
+            .fee_tier
+            .map(|fee| format!(" (fee tier {})", fee))
+            .unwrap_or_default()
     );
 
-    Ok(ToolResult {
-        content: vec![ToolContent::text(text)],
-        is_error: None,
+    if response.reference_output.is_some() || response.reference_note.is_some() {
+        text.push_str(&format!(
+            "\nReference (0x): {}",
+            response
+                .reference_output
+                .map_or("N/A".to_string(), |output| output.to_string())
+        ));
+        if let Some(note) = &response.reference_note {
+            text.push_str(&format!(" ({})", note));
+        }
+    }
+
+    if let Some(usd) = &response.amount_usd {
+        text.push_str(&format!("\nAmount In (USD): ${}", usd));
+    }
+
+    text.push_str(&format!(
+        "\nExchange Rate: {} to_token per from_token ({} from_token per to_token)",
+        response.exchange_rate, response.inverse_rate
+    ));
+    if response.implied_from_price_usd.is_some() || response.implied_to_price_usd.is_some() {
+        let usd = |price: &Option<Amount>| {
+            price
+                .as_ref()
+                .map_or("N/A".to_string(), |price| format!("${}", price))
+        };
+        text.push_str(&format!(
+            "\nImplied Prices: from_token {}, to_token {}",
+            usd(&response.implied_from_price_usd),
+            usd(&response.implied_to_price_usd)
+        ));
+    }
+
+    if let Some(wallet) = &response.simulation_from {
+        text.push_str(&format!(
+            "\nSimulated From: {} (default wallet for gas estimation; pass wallet_address to simulate your own)",
+            wallet
+        ));
+    }
+
+    if let Some(recipient) = &response.recipient {
+        text.push_str(&format!("\nRecipient: {}", recipient));
+    }
+
+    if let Some(auto) = &response.auto_slippage {
+        text.push_str(&format!("\nAuto Slippage: {}", auto.reasoning));
+    }
+
+    for warning in &response.warnings {
+        text.push_str(&format!("\nWarning: {}", warning));
+    }
+
+    if let Some(legs) = &response.split {
+        text.push_str("\nSplit Plan:");
+        for leg in legs {
+            text.push_str(&format!(
+                "\n- {}% via Uniswap {}{}: {} in -> {} out",
+                leg.share_pct,
+                leg.version.to_uppercase(),
+                leg.fee_tier
+                    .map(|fee| format!(" (fee tier {})", fee))
+                    .unwrap_or_default(),
+                leg.amount_in,
+                leg.estimated_output
+            ));
+        }
+    }
+
+    let summary = format!(
+        "Swap {} for an estimated {} via Uniswap {}",
+        response.amount_in,
+        response.estimated_output,
+        response.version.to_uppercase()
+    );
+    Ok(ToolResult::blocks(summary, text, &response)?)
+}
+
+/// A dollar `amount_usd` and the from-token price it was converted at
+#[derive(Debug, Clone, Copy)]
+pub struct UsdConversion {
+    pub amount_usd: Decimal,
+    pub price_usd: Decimal,
+}
+
+/// Work out the input amount in from-token units, converting `amount_usd` at the current price
+///
+/// Returns the token amount and, when the input was given in dollars, how it was converted.
+pub async fn resolve_amount(
+    provider: &EthClient,
+    http: &reqwest::Client,
+    config: &Config,
+    params: &SwapTokensParams,
+) -> Result<(Decimal, Option<UsdConversion>)> {
+    match (params.amount, params.amount_usd) {
+        (Some(amount), None) => Ok((amount.value(), None)),
+        (None, Some(amount_usd)) => {
+            let usd = amount_usd.value();
+            if usd <= Decimal::ZERO {
+                anyhow::bail!("amount_usd must be positive");
+            }
+
+            let from_token = params
+                .from_token
+                .parse::<Address>()
+                .map(normalize_eth)
+                .map_err(|e| anyhow::anyhow!("Invalid from_token address: {}", e))?;
+            let price = get_token_price(
+                provider,
+                &config.price_oracles(http),
+                from_token,
+                PriceSource::Auto,
+            )
+            .await?;
+
+            // The hardcoded fallback ETH price and ETH-only pool estimates can't size a dollar amount
+            let Some(price_usd) = price.market_price_usd() else {
+                anyhow::bail!(
+                    "No reliable USD price for {:?} (source: {}); pass amount in token units instead",
+                    from_token,
+                    price.source
+                );
+            };
+
+            let conversion = UsdConversion {
+                amount_usd: usd,
+                price_usd,
+            };
+            Ok(((usd / price_usd).round_dp(18), Some(conversion)))
+        }
+        (Some(_), Some(_)) => anyhow::bail!("Provide either amount or amount_usd, not both"),
+        (None, None) => anyhow::bail!("Missing amount: provide amount or amount_usd"),
+    }
+}
+
+/// A token's USD market price for implied swap prices, if there is a reliable one
+///
+/// Like `amount_usd`, this skips the hardcoded fallback ETH price and ETH-only pool estimates.
+async fn market_price_usd(
+    provider: &EthClient,
+    http: &reqwest::Client,
+    config: &Config,
+    token: Address,
+) -> Option<Decimal> {
+    let price = get_token_price(
+        provider,
+        &config.price_oracles(http),
+        token,
+        PriceSource::Auto,
+    )
+    .await;
+
+    match price {
+        Ok(price) => price.market_price_usd(),
+        Err(e) => {
+            tracing::debug!("No USD price for {:?}: {:#}", token, e);
+            None
+        }
+    }
+}
+
+/// Parse swap parameters into a simulation request
+pub fn swap_request(
+    params: &SwapTokensParams,
+    amount: Decimal,
+    config: &Config,
+    signer: Option<Address>,
+) -> Result<SwapRequest> {
+    let from_token = params
+        .from_token
+        .parse::<Address>()
+        .map(normalize_eth)
+        .map_err(|e| anyhow::anyhow!("Invalid from_token address: {}", e))?;
+
+    let to_token = params
+        .to_token
+        .parse::<Address>()
+        .map(normalize_eth)
+        .map_err(|e| anyhow::anyhow!("Invalid to_token address: {}", e))?;
+
+    // A quote doesn't need a real wallet; the default one only stands in for gas estimation
+    let wallet_address = match &params.wallet_address {
+        Some(wallet) => wallet
+            .parse::<Address>()
+            .map_err(|e| anyhow::anyhow!("Invalid wallet address: {}", e))?,
+        None => config.simulation_from,
+    };
+    let recipient = params
+        .recipient
+        .as_ref()
+        .map(|recipient| {
+            recipient
+                .parse::<Address>()
+                .map_err(|e| anyhow::anyhow!("Invalid recipient address: {}", e))
+        })
+        .transpose()?;
+
+    let version = match (params.version.as_deref(), params.fee_tier) {
+        (None, None) => UniswapVersion::V2,
+        (None, Some(_)) => UniswapVersion::V3,
+        (Some(v), fee_tier) => match v.to_lowercase().as_str() {
+            "v2" if fee_tier.is_some() => {
+                return Err(anyhow::anyhow!("fee_tier is only supported for Uniswap V3"))
+            }
+            "v2" => UniswapVersion::V2,
+            "v3" => UniswapVersion::V3,
+            other => {
+                return Err(anyhow::anyhow!(
+                    "Invalid version: {} (expected v2 or v3)",
+                    other
+                ))
+            }
+        },
+    };
+
+    let path = params
+        .path
+        .as_ref()
+        .map(|path| {
+            path.iter()
+                .map(|token| {
+                    token
+                        .parse::<Address>()
+                        .map(normalize_eth)
+                        .map_err(|e| anyhow::anyhow!("Invalid path address {}: {}", token, e))
+                })
+                .collect::<Result<Vec<_>>>()
+        })
+        .transpose()?;
+
+    Ok(SwapRequest {
+        from_token,
+        to_token,
+        amount_in: amount,
+        slippage_bps: params.slippage_bps.unwrap_or(DEFAULT_SLIPPAGE_BPS),
+        // An explicit tolerance always wins over the suggestion
+        auto_slippage: params.auto_slippage && params.slippage_bps.is_none(),
+        wallet_address,
+        recipient,
+        version,
+        fee_tier: params.fee_tier,
+        max_price_impact_bps: config.max_price_impact_bps,
+        confirm_impact_bps: config.confirm_impact_bps,
+        high_impact_confirmed: params.confirm_high_impact,
+        max_hops: config.max_hops,
+        rounding: config.wei_rounding,
+        path,
+        split: params.split,
+        v2_router: None,
+        // Only the server's own signing wallet would actually trade; other wallets are read-only targets
+        strict_balance: signer == Some(wallet_address),
+        min_slippage_bps: config.min_slippage_bps,
+        slippage_warn_bps: config.slippage_warn_bps,
+        // The default wallet's balances and approvals shouldn't decide whether a quote succeeds
+        allow_gas_fallback: params.allow_gas_fallback || params.wallet_address.is_none(),
+        max_gas_estimate: config.max_gas_estimate,
     })
 }
+
+/// The caller's `wallet_address`, for tools that act on a specific wallet rather than just quoting
+pub fn require_wallet(params: &SwapTokensParams) -> Result<&str> {
+    params
+        .wallet_address
+        .as_deref()
+        .ok_or_else(|| anyhow::anyhow!("Missing wallet_address"))
+}
+
+/// Drop the cached metadata of every token `request` trades through
+pub fn refresh_metadata(cache: &MetadataCache, request: &SwapRequest) {
+    let path = request.path.iter().flatten();
+    for token in [request.from_token, request.to_token].iter().chain(path) {
+        if !token.is_zero() {
+            cache.invalidate(*token);
+        }
+    }
+}
+
+/// Turn a high-impact confirmation failure into a result telling the agent how to re-call `tool`
+///
+/// This is the first step of a two-step flow, so it is not reported as an error; any other
+/// failure is passed through unchanged.
+pub fn confirmation_request(tool: &str, error: anyhow::Error) -> Result<ToolResult> {
+    let Some(SwapError::ConfirmationRequired { impact, threshold }) = error.downcast_ref() else {
+        return Err(error);
+    };
+
+    let request = json!({
+        "confirmation_required": true,
+        "reason": "high_price_impact",
+        "price_impact": impact.to_string(),
+        "threshold": threshold.to_string(),
+        "retry_with": { "confirm_high_impact": true }
+    });
+    let summary = format!(
+        "Confirmation required: price impact {}% exceeds the {}% high-impact threshold.",
+        impact, threshold
+    );
+    let text = format!(
+        "To proceed, call {} again with the same arguments plus \"confirm_high_impact\": true.",
+        tool
+    );
+
+    Ok(ToolResult::blocks(summary, text, &request)?)
+}
+
+pub fn version_label(version: UniswapVersion) -> String {
+    match version {
+        UniswapVersion::V2 => "v2".to_string(),
+        UniswapVersion::V3 => "v3".to_string(),
+    }
+}
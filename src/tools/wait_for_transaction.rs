@@ -0,0 +1,122 @@
+use anyhow::Result;
+use ethers::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::Duration;
+
+use crate::ethereum::{wait_for_confirmations, EthClient, MAX_CONFIRMATION_WAIT_SECS};
+use crate::types::{Tool, ToolResult};
+
+#[derive(Debug, Deserialize)]
+pub struct WaitForTransactionParams {
+    pub tx_hash: String,
+    #[serde(default = "default_confirmations")]
+    pub confirmations: u64,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_confirmations() -> u64 {
+    1
+}
+
+fn default_timeout_secs() -> u64 {
+    120
+}
+
+#[derive(Debug, Serialize)]
+pub struct WaitForTransactionResponse {
+    pub tx_hash: String,
+    /// `success` or `reverted`; null for pre-Byzantium receipts without a status
+    pub status: Option<String>,
+    pub block_number: Option<u64>,
+    pub confirmations: u64,
+    pub gas_used: Option<String>,
+}
+
+pub fn get_tool_definition() -> Tool {
+    Tool {
+        name: "wait_for_transaction".to_string(),
+        description: "Wait until a broadcast transaction, such as one built with build_swap_transaction and signed externally, has the requested number of confirmations, then return its receipt status. Reports transactions that were replaced (their nonce mined by another transaction) or dropped from the mempool."
+            .to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "tx_hash": {
+                    "type": "string",
+                    "description": "Transaction hash (0x...)"
+                },
+                "confirmations": {
+                    "type": "integer",
+                    "description": "Blocks, including the one it was mined in, the transaction must have. Default: 1",
+                    "minimum": 1,
+                    "default": 1
+                },
+                "timeout_secs": {
+                    "type": "integer",
+                    "description": format!("How long to wait before giving up (1-{}). Default: 120", MAX_CONFIRMATION_WAIT_SECS),
+                    "minimum": 1,
+                    "maximum": MAX_CONFIRMATION_WAIT_SECS,
+                    "default": 120
+                }
+            },
+            "required": ["tx_hash"]
+        }),
+    }
+}
+
+pub async fn execute(provider: &EthClient, params: WaitForTransactionParams) -> Result<ToolResult> {
+    let tx_hash = params
+        .tx_hash
+        .parse::<H256>()
+        .map_err(|e| anyhow::anyhow!("Invalid transaction hash: {}", e))?;
+    if params.confirmations == 0 {
+        anyhow::bail!("confirmations must be at least 1");
+    }
+    if params.timeout_secs == 0 || params.timeout_secs > MAX_CONFIRMATION_WAIT_SECS {
+        anyhow::bail!(
+            "timeout_secs must be between 1 and {}",
+            MAX_CONFIRMATION_WAIT_SECS
+        );
+    }
+
+    let confirmed = wait_for_confirmations(
+        provider,
+        tx_hash,
+        params.confirmations,
+        Duration::from_secs(params.timeout_secs),
+    )
+    .await?;
+    let receipt = confirmed.receipt;
+
+    let response = WaitForTransactionResponse {
+        tx_hash: format!("{:?}", tx_hash),
+        status: receipt.status.map(|status| {
+            if status.is_zero() {
+                "reverted".to_string()
+            } else {
+                "success".to_string()
+            }
+        }),
+        block_number: receipt.block_number.map(|number| number.as_u64()),
+        confirmations: confirmed.confirmations,
+        gas_used: receipt.gas_used.map(|gas| gas.to_string()),
+    };
+
+    let status = response.status.as_deref().unwrap_or("unknown");
+    let text = format!(
+        "Transaction: {}\nStatus: {}\nBlock: {}\nConfirmations: {}\nGas Used: {}",
+        response.tx_hash,
+        status,
+        response
+            .block_number
+            .map_or_else(|| "N/A".to_string(), |number| number.to_string()),
+        response.confirmations,
+        response.gas_used.as_deref().unwrap_or("N/A")
+    );
+    let summary = format!(
+        "{} {} with {} confirmations",
+        response.tx_hash, status, response.confirmations
+    );
+    Ok(ToolResult::blocks(summary, text, &response)?)
+}
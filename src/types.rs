@@ -1,5 +1,9 @@
-use serde::{Deserialize, Serialize};
+use rust_decimal::Decimal;
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value;
+use std::fmt;
+use std::str::FromStr;
 
 /// MCP Protocol version
 pub const MCP_VERSION: &str = "2024-11-05";
@@ -8,12 +12,29 @@ pub const MCP_VERSION: &str = "2024-11-05";
 #[derive(Debug, Deserialize)]
 pub struct JsonRpcRequest {
     pub jsonrpc: String,
-    pub id: Value,
+    /// Absent for notifications; an explicit `null` is still a request
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub id: Option<Value>,
     pub method: String,
     #[serde(default)]
     pub params: Value,
 }
 
+impl JsonRpcRequest {
+    /// Notifications carry no id and must not receive a response
+    pub fn is_notification(&self) -> bool {
+        self.id.is_none()
+    }
+}
+
+/// Map a present field (even `null`) to `Some`, leaving `None` for a missing one
+fn deserialize_present<'de, D>(deserializer: D) -> Result<Option<Value>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Value::deserialize(deserializer).map(Some)
+}
+
 /// JSON-RPC 2.0 response
 #[derive(Debug, Serialize)]
 pub struct JsonRpcResponse {
@@ -43,11 +64,158 @@ pub struct Tool {
     pub input_schema: Value,
 }
 
+/// A token or currency amount in tool params and responses
+///
+/// Serialized as a decimal string so JSON clients never round it through a float. `decimals`, when
+/// known, is the precision the amount is shown with (e.g. 4 for a percentage, 18 for a token).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Amount {
+    value: Decimal,
+    decimals: Option<u32>,
+}
+
+impl Amount {
+    pub fn new(value: Decimal) -> Self {
+        Self {
+            value,
+            decimals: None,
+        }
+    }
+
+    /// Amount shown rounded to `decimals` places
+    pub fn with_decimals(value: Decimal, decimals: u32) -> Self {
+        Self {
+            value,
+            decimals: Some(decimals),
+        }
+    }
+
+    pub fn value(self) -> Decimal {
+        self.value
+    }
+}
+
+impl From<Decimal> for Amount {
+    fn from(value: Decimal) -> Self {
+        Self::new(value)
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.decimals {
+            Some(decimals) => fmt::Display::fmt(&self.value.round_dp(decimals), f),
+            None => fmt::Display::fmt(&self.value, f),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("invalid amount {0:?} (expected a decimal number such as \"1.5\" or \"1.5e6\")")]
+pub struct ParseAmountError(String);
+
+impl FromStr for Amount {
+    type Err = ParseAmountError;
+
+    /// Plain decimals, or scientific notation such as `1e-9` and `1.5E6`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        Decimal::from_str(trimmed)
+            .ok()
+            .or_else(|| parse_scientific(trimmed))
+            .map(Amount::new)
+            .ok_or_else(|| ParseAmountError(s.to_string()))
+    }
+}
+
+/// Scientific notation, exactly where the mantissa and exponent fit a `Decimal`
+///
+/// Falls back to `f64` for the rest (e.g. a mantissa with more than 28 digits), but never lets
+/// a nonzero value underflow to zero.
+fn parse_scientific(s: &str) -> Option<Decimal> {
+    if !s.contains(['e', 'E']) {
+        return None;
+    }
+    if let Ok(value) = Decimal::from_scientific(s) {
+        return Some(value);
+    }
+    let float = s.parse::<f64>().ok().filter(|float| float.is_finite())?;
+    // Via the shortest round-trip string, as for JSON numbers
+    let value = Decimal::from_str(&float.to_string()).ok()?;
+    (!value.is_zero() || float == 0.0).then_some(value)
+}
+
+impl Serialize for Amount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Amount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(AmountVisitor)
+    }
+}
+
+/// Accepts decimal strings, the documented form, and plain JSON numbers from lenient clients
+struct AmountVisitor;
+
+impl<'de> Visitor<'de> for AmountVisitor {
+    type Value = Amount;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a decimal amount as a string, e.g. \"1.5\"")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Amount, E> {
+        value.parse().map_err(E::custom)
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Amount, E> {
+        Ok(Amount::new(Decimal::from(value)))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Amount, E> {
+        Ok(Amount::new(Decimal::from(value)))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Amount, E> {
+        // Via the shortest round-trip string, so 1.1 stays 1.1 rather than its binary expansion
+        value.to_string().parse().map_err(E::custom)
+    }
+}
+
+/// Which content blocks tool results carry: the prose, the JSON, or both
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResponseFormat {
+    Text,
+    Json,
+    #[default]
+    Both,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Unknown response format {0} (expected text, json or both)")]
+pub struct ParseResponseFormatError(String);
+
+impl FromStr for ResponseFormat {
+    type Err = ParseResponseFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(ResponseFormat::Text),
+            "json" => Ok(ResponseFormat::Json),
+            "both" => Ok(ResponseFormat::Both),
+            _ => Err(ParseResponseFormatError(s.to_string())),
+        }
+    }
+}
+
 /// Tool result
 #[derive(Debug, Serialize)]
 pub struct ToolResult {
     pub content: Vec<ToolContent>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "isError", skip_serializing_if = "Option::is_none")]
     pub is_error: Option<bool>,
 }
 
@@ -57,6 +225,9 @@ pub struct ToolContent {
     #[serde(rename = "type")]
     pub content_type: String,
     pub text: String,
+    /// Whether the text is the machine-readable JSON block rather than prose
+    #[serde(skip)]
+    is_json: bool,
 }
 
 impl ToolContent {
@@ -64,6 +235,262 @@ impl ToolContent {
         Self {
             content_type: "text".to_string(),
             text: text.into(),
+            is_json: false,
+        }
+    }
+
+    fn json(text: String) -> Self {
+        Self {
+            is_json: true,
+            ..Self::text(text)
+        }
+    }
+
+    /// Cut text longer than `max_chars` at a line boundary, noting how many lines were dropped
+    pub fn truncate(&mut self, max_chars: usize) {
+        if self.text.chars().count() <= max_chars {
+            return;
+        }
+
+        let total_lines = self.text.lines().count();
+        let mut kept = String::new();
+        let mut kept_chars = 0;
+        let mut kept_lines = 0;
+        for line in self.text.lines() {
+            let line_chars = line.chars().count() + 1;
+            if kept_chars + line_chars > max_chars {
+                break;
+            }
+            kept.push_str(line);
+            kept.push('\n');
+            kept_chars += line_chars;
+            kept_lines += 1;
+        }
+
+        // A single oversized first line is cut mid-line rather than dropped entirely
+        if kept_lines == 0 {
+            kept.extend(self.text.chars().take(max_chars));
+            kept.push('\n');
+            kept_lines = 1;
+        }
+
+        kept.push_str(&format!(
+            "...(truncated, {} more lines)",
+            total_lines - kept_lines
+        ));
+        self.text = kept;
+    }
+}
+
+impl ToolResult {
+    /// The standard result layout: a one-line summary, the human-readable details, then `data`
+    /// as JSON for clients that parse the result
+    pub fn blocks(
+        summary: impl Into<String>,
+        details: impl Into<String>,
+        data: &impl Serialize,
+    ) -> serde_json::Result<Self> {
+        Ok(Self {
+            content: vec![
+                ToolContent::text(summary),
+                ToolContent::text(details),
+                ToolContent::json(serde_json::to_string_pretty(data)?),
+            ],
+            is_error: None,
+        })
+    }
+
+    /// A result flagged `isError`: the tool ran, but what was asked for isn't there
+    pub fn error(message: impl Into<String>, data: &impl Serialize) -> serde_json::Result<Self> {
+        Ok(Self {
+            content: vec![
+                ToolContent::text(message),
+                ToolContent::json(serde_json::to_string_pretty(data)?),
+            ],
+            is_error: Some(true),
+        })
+    }
+
+    /// Keep only the blocks `format` asks for
+    ///
+    /// A result with nothing in the requested form, like a single-block mock result, is left
+    /// whole rather than emptied.
+    pub fn apply_format(&mut self, format: ResponseFormat) {
+        let keep_json = match format {
+            ResponseFormat::Both => return,
+            ResponseFormat::Json => true,
+            ResponseFormat::Text => false,
+        };
+        if self.content.iter().any(|block| block.is_json == keep_json) {
+            self.content.retain(|block| block.is_json == keep_json);
+        }
+    }
+
+    /// Apply the result size limit to the prose blocks; 0 disables truncation
+    ///
+    /// The JSON block is left whole, since a cut-off document no longer parses.
+    pub fn truncate_text(&mut self, max_chars: usize) {
+        if max_chars == 0 {
+            return;
+        }
+        for content in self.content.iter_mut().filter(|block| !block.is_json) {
+            content.truncate(max_chars);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notification_has_no_id() {
+        let notification: JsonRpcRequest =
+            serde_json::from_str(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#)
+                .unwrap();
+        assert!(notification.is_notification());
+
+        let null_id: JsonRpcRequest =
+            serde_json::from_str(r#"{"jsonrpc":"2.0","id":null,"method":"tools/list"}"#).unwrap();
+        assert!(!null_id.is_notification());
+        assert_eq!(null_id.id, Some(Value::Null));
+
+        let request: JsonRpcRequest =
+            serde_json::from_str(r#"{"jsonrpc":"2.0","id":7,"method":"tools/list"}"#).unwrap();
+        assert_eq!(request.id, Some(Value::from(7)));
+    }
+
+    #[test]
+    fn test_amount_serde() {
+        let amount: Amount = serde_json::from_value(serde_json::json!("1.50")).unwrap();
+        assert_eq!(amount.value(), Decimal::new(150, 2));
+        assert_eq!(serde_json::to_value(amount).unwrap(), "1.50");
+
+        let from_number: Amount = serde_json::from_value(serde_json::json!(1.1)).unwrap();
+        assert_eq!(from_number.value(), Decimal::new(11, 1));
+        let from_integer: Amount = serde_json::from_value(serde_json::json!(3)).unwrap();
+        assert_eq!(from_integer.value(), Decimal::from(3));
+
+        assert!(serde_json::from_value::<Amount>(serde_json::json!("1.5 ETH")).is_err());
+        assert!(serde_json::from_value::<Amount>(serde_json::json!(true)).is_err());
+
+        let impact = Amount::with_decimals(Decimal::new(123456, 5), 4);
+        assert_eq!(serde_json::to_value(impact).unwrap(), "1.2346");
+        assert_eq!(impact.value(), Decimal::new(123456, 5));
+    }
+
+    #[test]
+    fn test_amount_scientific_notation() {
+        let parse = |s: &str| s.parse::<Amount>().map(|amount| amount.value());
+
+        assert_eq!(parse("1e18").unwrap(), Decimal::from(10u64.pow(18)));
+        assert_eq!(parse("1.5E6").unwrap(), Decimal::from(1_500_000));
+        assert_eq!(parse("1e-9").unwrap(), Decimal::new(1, 9));
+        assert_eq!(parse("2.5e+3").unwrap(), Decimal::from(2_500));
+        assert_eq!(parse(" 0.0 ").unwrap(), Decimal::ZERO);
+        assert_eq!(parse("0e5").unwrap(), Decimal::ZERO);
+
+        let malformed = ["", "1e", "e5", "1.5.2", "1e5e2", "1e1.5", "abc", "NaN"];
+        for input in malformed {
+            assert!(parse(input).is_err(), "{:?} should be rejected", input);
         }
+        // Too small for a Decimal, rather than silently zero
+        assert!(parse("1e-40").is_err());
+
+        let error = parse("1e").unwrap_err().to_string();
+        assert!(error.contains("1.5e6"), "{}", error);
+
+        let from_json: Amount = serde_json::from_value(serde_json::json!("1e-9")).unwrap();
+        assert_eq!(from_json.value(), Decimal::new(1, 9));
+    }
+
+    #[test]
+    fn test_result_blocks() {
+        let result = ToolResult::blocks(
+            "Balance: 1 ETH",
+            "Balance: 1 ETH\nRaw balance: 1000000000000000000",
+            &serde_json::json!({ "balance": "1" }),
+        )
+        .unwrap();
+
+        assert_eq!(result.content.len(), 3);
+        assert_eq!(result.content[0].text, "Balance: 1 ETH");
+        assert!(result.content[1].text.contains("Raw balance"));
+        let data: Value = serde_json::from_str(&result.content[2].text).unwrap();
+        assert_eq!(data["balance"], "1");
+    }
+
+    #[test]
+    fn test_apply_format() {
+        let blocks = || {
+            ToolResult::blocks("Balance: 1 ETH", "Raw balance: 1", &serde_json::json!({})).unwrap()
+        };
+
+        let mut json = blocks();
+        json.apply_format("JSON".parse().unwrap());
+        assert_eq!(json.content.len(), 1);
+        assert_eq!(json.content[0].text, "{}");
+
+        let mut text = blocks();
+        text.apply_format(ResponseFormat::Text);
+        assert_eq!(text.content.len(), 2);
+        assert_eq!(text.content[1].text, "Raw balance: 1");
+
+        let mut mock = ToolResult {
+            content: vec![ToolContent::text("[MOCK] Balance: 1 ETH")],
+            is_error: None,
+        };
+        mock.apply_format(ResponseFormat::Json);
+        assert_eq!(mock.content.len(), 1);
+
+        assert!("prose".parse::<ResponseFormat>().is_err());
+    }
+
+    #[test]
+    fn test_truncate_text() {
+        let mut result = ToolResult {
+            content: vec![ToolContent::text(
+                "line one\nline two\nline three\nline four",
+            )],
+            is_error: None,
+        };
+        result.truncate_text(20);
+        assert_eq!(
+            result.content[0].text,
+            "line one\nline two\n...(truncated, 2 more lines)"
+        );
+
+        // Short text and a zero limit are left alone
+        let mut short = ToolContent::text("ok");
+        short.truncate(20);
+        assert_eq!(short.text, "ok");
+
+        let mut unlimited = ToolResult {
+            content: vec![ToolContent::text("x".repeat(50))],
+            is_error: None,
+        };
+        unlimited.truncate_text(0);
+        assert_eq!(unlimited.content[0].text.len(), 50);
+
+        // An oversized first line is cut mid-line
+        let mut long_line = ToolContent::text(format!("{}\nnext", "y".repeat(30)));
+        long_line.truncate(10);
+        assert_eq!(
+            long_line.text,
+            format!("{}\n...(truncated, 1 more lines)", "y".repeat(10))
+        );
+
+        // The JSON block keeps parsing however far the prose is cut
+        let logs: Vec<String> = (0..50).map(|i| format!("log {}", i)).collect();
+        let mut blocks = ToolResult::blocks(
+            "50 logs",
+            logs.join("\n"),
+            &serde_json::json!({ "logs": logs }),
+        )
+        .unwrap();
+        blocks.truncate_text(20);
+        assert!(blocks.content[1].text.ends_with("more lines)"));
+        let data: Value = serde_json::from_str(&blocks.content[2].text).unwrap();
+        assert_eq!(data["logs"].as_array().unwrap().len(), 50);
     }
 }